@@ -1,9 +1,52 @@
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 pub struct Command {
     #[arg(long)]
     pub output_path: PathBuf,
+
+    /// Json file with keyboard layout, instead of the built-in one
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+
+    /// Print diagnostics of mesh operations, filtered like `RUST_LOG`, e.g.
+    /// `geometry=debug`. Needs `tracing` feature.
+    #[arg(long)]
+    pub log: Option<String>,
+
+    #[command(subcommand)]
+    pub action: Option<Action>,
+}
+
+/// Whole case with hotswap modules is generated, if no action is given
+#[derive(Subcommand)]
+pub enum Action {
+    /// Only key plate with switch cutouts in draft quality: quick print to test switch
+    /// spacing before the whole case
+    PlateOnly,
+    /// Every printable part of the keyboard as stl, with `manifest.json` of them. Parts
+    /// are built in parallel. Both halves are made, if no half is given.
+    Full {
+        #[arg(long)]
+        left: bool,
+        #[arg(long)]
+        right: bool,
+        /// Accessories, which use their settings from config
+        #[arg(long, value_delimiter = ',')]
+        accessories: Vec<Accessory>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Accessory {
+    /// Tenting adapter plate
+    Stand,
+    /// Flexible bumper ring
+    Bumper,
+    /// Plug of detachable module for pogo dock
+    Plug,
+    /// Screw-in tenting feet of all lengths
+    Feet,
 }
@@ -0,0 +1,188 @@
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::Path,
+    time::Instant,
+};
+
+use anyhow::anyhow;
+use geometry::{
+    decimal::Dec,
+    indexes::{aabb::Aabb, geo_index::index::GeoIndex},
+};
+use itertools::Itertools;
+use keyboard::{chok_hotswap::ChokHotswap, KeyboardBuilder, RightKeyboardConfig};
+use nalgebra::Vector3;
+use rayon::prelude::*;
+use rust_decimal_macros::dec;
+use serde_json::json;
+
+use crate::cli::Accessory;
+
+/// Building of hull goes deep into recursion, default stack of worker threads is too small
+const STACK_SIZE: usize = 512 * 1024 * 1024;
+
+#[derive(Clone, Copy, Debug)]
+enum Side {
+    Left,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Part {
+    Hull,
+    Bottom,
+    Accessory(Accessory),
+    HotswapTop,
+    HotswapBottom,
+}
+
+/// One printable file
+#[derive(Clone, Copy, Debug)]
+struct Artifact {
+    part: Part,
+    /// Hotswap modules are the same for both halves
+    side: Option<Side>,
+}
+
+impl Artifact {
+    fn name(&self) -> String {
+        let part = match self.part {
+            Part::Hull => "hull",
+            Part::Bottom => "bottom",
+            Part::Accessory(Accessory::Stand) => "stand",
+            Part::Accessory(Accessory::Bumper) => "bumper",
+            Part::Accessory(Accessory::Plug) => "plug",
+            Part::Accessory(Accessory::Feet) => "feet",
+            Part::HotswapTop => "chok_hotswap_top",
+            Part::HotswapBottom => "chok_hotswap_bottom",
+        };
+        match self.side {
+            Some(Side::Left) => format!("left_{part}"),
+            Some(Side::Right) => format!("right_{part}"),
+            None => part.to_string(),
+        }
+    }
+
+    /// Builds the part into new index. Config is not shared between threads, so each
+    /// artifact builds its own.
+    fn build(&self, keyboard: &RightKeyboardConfig) -> anyhow::Result<GeoIndex> {
+        let size = Dec::from(150);
+        let mut index = GeoIndex::new(Aabb::from_points(&[
+            Vector3::new(-size, -size, -size),
+            Vector3::new(size, size, size),
+        ]))
+        .input_polygon_min_rib_length(dec!(0.05))
+        .points_precision(dec!(0.001));
+
+        match self.part {
+            Part::Hull => {
+                keyboard.buttons_hull(&mut index)?;
+            }
+            Part::Bottom => {
+                keyboard.bottom_pad(&mut index)?;
+            }
+            Part::Accessory(Accessory::Stand) => {
+                keyboard.tenting_adapter(&mut index)?;
+            }
+            Part::Accessory(Accessory::Bumper) => {
+                keyboard.bumper(&mut index)?;
+            }
+            Part::Accessory(Accessory::Plug) => {
+                keyboard.pogo_plug(&mut index)?;
+            }
+            Part::Accessory(Accessory::Feet) => {
+                keyboard.tenting_feet(&mut index)?;
+            }
+            Part::HotswapTop => ChokHotswap::new().top_mesh(&mut index)?,
+            Part::HotswapBottom => ChokHotswap::new().bottom_mesh(&mut index)?,
+        }
+        if let Some(Side::Left) = self.side {
+            index.mirror_x()?;
+        }
+        Ok(index)
+    }
+}
+
+/// Builds all parts of the keyboard in parallel and writes them as stl with manifest.
+/// Failed parts are listed in manifest with their errors, other parts are still written.
+pub fn full(
+    keyboard: &(dyn Fn() -> anyhow::Result<KeyboardBuilder> + Sync),
+    output_path: &Path,
+    left: bool,
+    right: bool,
+    accessories: &[Accessory],
+) -> anyhow::Result<()> {
+    let sides = match (left, right) {
+        (false, false) | (true, true) => vec![Side::Left, Side::Right],
+        (true, false) => vec![Side::Left],
+        (false, true) => vec![Side::Right],
+    };
+    let per_side = [Part::Hull, Part::Bottom]
+        .into_iter()
+        .chain(accessories.iter().copied().map(Part::Accessory))
+        .collect_vec();
+    let artifacts = sides
+        .iter()
+        .cartesian_product(&per_side)
+        .map(|(side, part)| Artifact {
+            part: *part,
+            side: Some(*side),
+        })
+        .chain([Part::HotswapTop, Part::HotswapBottom].map(|part| Artifact { part, side: None }))
+        .collect_vec();
+
+    std::fs::create_dir_all(output_path)?;
+    let pool = rayon::ThreadPoolBuilder::new()
+        .stack_size(STACK_SIZE)
+        .build()?;
+    let results = pool.install(|| {
+        artifacts
+            .par_iter()
+            .map(|artifact| {
+                let started = Instant::now();
+                let file = format!("{}.stl", artifact.name());
+                // Panic in one part must not take down the others
+                let result = catch_unwind(AssertUnwindSafe(|| {
+                    let index = artifact.build(&keyboard()?.try_build()?)?;
+                    let triangles = index.stl_triangles()?;
+                    let mut stl = std::fs::File::create(output_path.join(&file))?;
+                    stl_io::write_stl(&mut stl, triangles.iter())?;
+                    Ok(triangles.len())
+                }))
+                .unwrap_or_else(|_| Err(anyhow!("Panicked while building")));
+                let seconds = started.elapsed().as_secs_f64();
+                println!("{} done in {seconds:.1}s", artifact.name());
+                (artifact, file, seconds, result)
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let failed = results.iter().filter(|(.., r)| r.is_err()).count();
+    let manifest = results
+        .iter()
+        .map(|(artifact, file, seconds, result)| {
+            let mut entry = json!({
+                "name": artifact.name(),
+                "side": artifact.side.map(|s| format!("{s:?}").to_lowercase()),
+                "seconds": (seconds * 10.0).round() / 10.0,
+            });
+            match result {
+                Ok(triangles) => {
+                    entry["file"] = json!(file);
+                    entry["triangles"] = json!(triangles);
+                }
+                Err(e) => entry["error"] = json!(e.to_string()),
+            }
+            entry
+        })
+        .collect_vec();
+    std::fs::write(
+        output_path.join("manifest.json"),
+        serde_json::to_string_pretty(&json!({ "artifacts": manifest }))?,
+    )?;
+
+    if failed > 0 {
+        return Err(anyhow!("{failed} parts failed, see manifest.json"));
+    }
+    Ok(())
+}
@@ -18,15 +18,176 @@ use geometry::{
     origin::Origin,
 };
 use keyboard::{
-    chok_hotswap::ChokHotswap, Angle, Button, ButtonsCollection, ButtonsColumn, RightKeyboardConfig,
+    chok_hotswap::ChokHotswap, Angle, Button, ButtonsCollection, ButtonsColumn, KeyboardBuilder,
+    KeyboardFile, RightKeyboardConfig, SurfaceQuality,
 };
 
+use crate::cli::{Action, Command};
+
 mod cli;
+mod full;
 
 fn main() -> Result<(), anyhow::Error> {
-    let cli = cli::Command::parse();
+    let cli = Command::parse();
+
+    if let Some(filter) = &cli.log {
+        geometry::log::init(filter)?;
+    }
+
+    let keyboard = || match &cli.config {
+        Some(path) => KeyboardFile::load(path)?.builder(),
+        None => Ok(default_keyboard()),
+    };
+    let builder = keyboard()?;
+    match &cli.action {
+        Some(Action::PlateOnly) => {
+            return plate_only(builder.quality(SurfaceQuality::draft()).try_build()?, &cli);
+        }
+        Some(Action::Full {
+            left,
+            right,
+            accessories,
+        }) => {
+            return full::full(&keyboard, &cli.output_path, *left, *right, accessories);
+        }
+        None => {}
+    }
+    let keyboard = builder.try_build()?;
+
+    let mut buttons_hull = GeoIndex::new(Aabb::from_points(&[
+        Vector3::new(Dec::from(-150), Dec::from(-150), Dec::from(-150)),
+        Vector3::new(Dec::from(150), Dec::from(150), Dec::from(150)),
+    ]))
+    .input_polygon_min_rib_length(dec!(0.05))
+    .points_precision(dec!(0.001));
+    /*
 
-    let keyboard = RightKeyboardConfig::build()
+    let some_basis = PolygonBasis {
+        center: Vector3::new(
+            //
+            dec!(22.82445866667124051206245548).into(),
+            dec!(-19.779570592522108815492239509).into(),
+            dec!(22.15891213079591064257514847).into(),
+        ),
+        //x
+        x: Vector3::new(
+            dec!(-0.8374019817599564937384033586).into(),
+            dec!(0.5312522329518458348708607207).into(),
+            dec!(0.1285651038508320043182247472).into(),
+        ),
+        //y
+        y: Vector3::new(
+            dec!(0.4412129513248363290765072231).into(),
+            dec!(0.7958346018056754586727604878).into(),
+            dec!(-0.4147028070221246913099825422).into(),
+        ),
+    };
+    */
+
+    buttons_hull.face_debug(23, Some(FaceId(23)));
+
+    keyboard.buttons_hull(&mut buttons_hull).unwrap();
+
+    let main_button_hull_scad_path = cli.output_path.join("main_button_hull.scad");
+    let scad = buttons_hull.scad();
+
+    std::fs::write(main_button_hull_scad_path, scad)?;
+
+    let mut chok_hotswap_top = GeoIndex::new(Aabb::from_points(&[
+        Vector3::new(Dec::from(-15), Dec::from(-15), Dec::from(-15)),
+        Vector3::new(Dec::from(15), Dec::from(15), Dec::from(16)),
+    ]))
+    .debug_svg_path(cli.output_path.clone())
+    .input_polygon_min_rib_length(dec!(0.05))
+    .points_precision(dec!(0.001));
+
+    let mut chok_hotswap_bottom = GeoIndex::new(Aabb::from_points(&[
+        Vector3::new(Dec::from(-15), Dec::from(-15), Dec::from(-10)),
+        Vector3::new(Dec::from(15), Dec::from(15), Dec::from(16)),
+    ]))
+    .debug_svg_path(cli.output_path.clone())
+    .input_polygon_min_rib_length(dec!(0.05))
+    .points_precision(dec!(0.001));
+
+    let mut chok_hotswap_mount = GeoIndex::new(Aabb::from_points(&[
+        Vector3::new(Dec::from(-20), Dec::from(-20), Dec::from(-20)),
+        Vector3::new(Dec::from(20), Dec::from(20), Dec::from(20)),
+    ]))
+    .debug_svg_path(cli.output_path.clone())
+    .input_polygon_min_rib_length(dec!(0.05))
+    .points_precision(dec!(0.001));
+
+    let chok = ChokHotswap::new();
+    /*
+    let xy = PolygonBasis {
+        center: Vector3::zeros(),
+        x: Vector3::x(),
+        y: Vector3::y(),
+    };
+    let xz = PolygonBasis {
+        center: Vector3::zeros(),
+        x: Vector3::x(),
+        y: Vector3::z(),
+    };
+
+    let yz = PolygonBasis {
+        center: Vector3::zeros(),
+        x: Vector3::y(),
+        y: Vector3::z(),
+    };
+    */
+
+    //chok_hotswap_top.poly_split_debug(64, xy.clone());
+    //chok_hotswap_top.poly_split_debug(67, xy.clone());
+    //chok_hotswap_top.poly_split_debug(911, xz.clone());
+    //chok_hotswap_top.poly_split_debug(913, xz);
+
+    chok_hotswap_top.face_debug(333, None);
+    chok.top_mesh(&mut chok_hotswap_top)?;
+    chok.bottom_mesh(&mut chok_hotswap_bottom)?;
+    chok.outer_mount(Origin::new(), &mut chok_hotswap_mount)?;
+
+    let scad_path_all = cli.output_path.join("chok_hotswap_all.scad");
+    let scad_path_top = cli.output_path.join("chok_hotswap_top.scad");
+    let scad_path_bottom = cli.output_path.join("chok_hotswap_bottom.scad");
+    let scad_path_mount = cli.output_path.join("chok_hotswap_mount.scad");
+    let scad = chok_hotswap_top.scad();
+    let top = format!("translate(v=[0, 0, 0]) {{ {scad} }};");
+    let scad = chok_hotswap_bottom.scad();
+    let bottom = format!("translate(v=[0, 0, 0]) {{ {scad} }};");
+    let scad = chok_hotswap_mount.scad();
+    let mount = format!("translate(v=[0, 0, 7]) {{ {scad} }};");
+
+    let totals = format!("{top}\n{bottom}\n{mount}");
+
+    std::fs::write(scad_path_all, totals)?;
+    std::fs::write(scad_path_top, top)?;
+    std::fs::write(scad_path_bottom, bottom)?;
+    std::fs::write(scad_path_mount, mount)?;
+
+    Ok(())
+}
+
+fn plate_only(keyboard: RightKeyboardConfig, cli: &Command) -> anyhow::Result<()> {
+    let mut index = GeoIndex::new(Aabb::from_points(&[
+        Vector3::new(Dec::from(-150), Dec::from(-150), Dec::from(-150)),
+        Vector3::new(Dec::from(150), Dec::from(150), Dec::from(150)),
+    ]))
+    .input_polygon_min_rib_length(dec!(0.05))
+    .points_precision(dec!(0.001));
+
+    keyboard.key_plate(&mut index)?;
+
+    std::fs::create_dir_all(&cli.output_path)?;
+    std::fs::write(cli.output_path.join("key_plate.scad"), index.scad())?;
+    let mut file = std::fs::File::create(cli.output_path.join("key_plate.stl"))?;
+    stl_io::write_stl(&mut file, index.stl_triangles()?.iter())?;
+    Ok(())
+}
+
+/// Layout, used when no config file is given
+fn default_keyboard() -> KeyboardBuilder {
+    RightKeyboardConfig::build()
         .main(
             ButtonsCollection::build()
                 .column(
@@ -323,118 +484,4 @@ fn main() -> Result<(), anyhow::Error> {
                     ),
                 ),
         )
-        .build();
-
-    let mut buttons_hull = GeoIndex::new(Aabb::from_points(&[
-        Vector3::new(Dec::from(-150), Dec::from(-150), Dec::from(-150)),
-        Vector3::new(Dec::from(150), Dec::from(150), Dec::from(150)),
-    ]))
-    .input_polygon_min_rib_length(dec!(0.05))
-    .points_precision(dec!(0.001));
-    /*
-
-    let some_basis = PolygonBasis {
-        center: Vector3::new(
-            //
-            dec!(22.82445866667124051206245548).into(),
-            dec!(-19.779570592522108815492239509).into(),
-            dec!(22.15891213079591064257514847).into(),
-        ),
-        //x
-        x: Vector3::new(
-            dec!(-0.8374019817599564937384033586).into(),
-            dec!(0.5312522329518458348708607207).into(),
-            dec!(0.1285651038508320043182247472).into(),
-        ),
-        //y
-        y: Vector3::new(
-            dec!(0.4412129513248363290765072231).into(),
-            dec!(0.7958346018056754586727604878).into(),
-            dec!(-0.4147028070221246913099825422).into(),
-        ),
-    };
-    */
-
-    buttons_hull.face_debug(23, Some(FaceId(23)));
-
-    keyboard.buttons_hull(&mut buttons_hull).unwrap();
-
-    let main_button_hull_scad_path = cli.output_path.join("main_button_hull.scad");
-    let scad = buttons_hull.scad();
-
-    std::fs::write(main_button_hull_scad_path, scad)?;
-
-    let mut chok_hotswap_top = GeoIndex::new(Aabb::from_points(&[
-        Vector3::new(Dec::from(-15), Dec::from(-15), Dec::from(-15)),
-        Vector3::new(Dec::from(15), Dec::from(15), Dec::from(16)),
-    ]))
-    .debug_svg_path(cli.output_path.clone())
-    .input_polygon_min_rib_length(dec!(0.05))
-    .points_precision(dec!(0.001));
-
-    let mut chok_hotswap_bottom = GeoIndex::new(Aabb::from_points(&[
-        Vector3::new(Dec::from(-15), Dec::from(-15), Dec::from(-10)),
-        Vector3::new(Dec::from(15), Dec::from(15), Dec::from(16)),
-    ]))
-    .debug_svg_path(cli.output_path.clone())
-    .input_polygon_min_rib_length(dec!(0.05))
-    .points_precision(dec!(0.001));
-
-    let mut chok_hotswap_mount = GeoIndex::new(Aabb::from_points(&[
-        Vector3::new(Dec::from(-20), Dec::from(-20), Dec::from(-20)),
-        Vector3::new(Dec::from(20), Dec::from(20), Dec::from(20)),
-    ]))
-    .debug_svg_path(cli.output_path.clone())
-    .input_polygon_min_rib_length(dec!(0.05))
-    .points_precision(dec!(0.001));
-
-    let chok = ChokHotswap::new();
-    /*
-    let xy = PolygonBasis {
-        center: Vector3::zeros(),
-        x: Vector3::x(),
-        y: Vector3::y(),
-    };
-    let xz = PolygonBasis {
-        center: Vector3::zeros(),
-        x: Vector3::x(),
-        y: Vector3::z(),
-    };
-
-    let yz = PolygonBasis {
-        center: Vector3::zeros(),
-        x: Vector3::y(),
-        y: Vector3::z(),
-    };
-    */
-
-    //chok_hotswap_top.poly_split_debug(64, xy.clone());
-    //chok_hotswap_top.poly_split_debug(67, xy.clone());
-    //chok_hotswap_top.poly_split_debug(911, xz.clone());
-    //chok_hotswap_top.poly_split_debug(913, xz);
-
-    chok_hotswap_top.face_debug(333, None);
-    chok.top_mesh(&mut chok_hotswap_top)?;
-    chok.bottom_mesh(&mut chok_hotswap_bottom)?;
-    chok.outer_mount(Origin::new(), &mut chok_hotswap_mount)?;
-
-    let scad_path_all = cli.output_path.join("chok_hotswap_all.scad");
-    let scad_path_top = cli.output_path.join("chok_hotswap_top.scad");
-    let scad_path_bottom = cli.output_path.join("chok_hotswap_bottom.scad");
-    let scad_path_mount = cli.output_path.join("chok_hotswap_mount.scad");
-    let scad = chok_hotswap_top.scad();
-    let top = format!("translate(v=[0, 0, 0]) {{ {scad} }};");
-    let scad = chok_hotswap_bottom.scad();
-    let bottom = format!("translate(v=[0, 0, 0]) {{ {scad} }};");
-    let scad = chok_hotswap_mount.scad();
-    let mount = format!("translate(v=[0, 0, 7]) {{ {scad} }};");
-
-    let totals = format!("{top}\n{bottom}\n{mount}");
-
-    std::fs::write(scad_path_all, totals)?;
-    std::fs::write(scad_path_top, top)?;
-    std::fs::write(scad_path_bottom, bottom)?;
-    std::fs::write(scad_path_mount, mount)?;
-
-    Ok(())
 }
@@ -0,0 +1,51 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use geometry::{
+    decimal::Dec,
+    geometry::GeometryDyn,
+    indexes::{
+        aabb::Aabb,
+        geo_index::{geo_object::GeoObject, index::GeoIndex},
+    },
+    origin::Origin,
+    shapes::{Cylinder, Rect},
+};
+use nalgebra::Vector3;
+use rust_decimal_macros::dec;
+
+fn new_index() -> GeoIndex {
+    GeoIndex::new(Aabb::from_points(&[
+        Vector3::new(Dec::from(-50), Dec::from(-50), Dec::from(-50)),
+        Vector3::new(Dec::from(50), Dec::from(50), Dec::from(50)),
+    ]))
+    .input_polygon_min_rib_length(dec!(0.05))
+    .points_precision(dec!(0.001))
+}
+
+/// Cylinder, crossing the box - every wall polygon is split by box sides
+fn box_and_cylinder(steps: usize) {
+    let mut index = new_index();
+    let block = index.new_mesh();
+    Rect::centered(Origin::new(), 10.into(), 10.into(), 10.into())
+        .polygonize(block.make_mut_ref(&mut index), 0)
+        .unwrap();
+
+    let tool = index.new_mesh();
+    Cylinder::centered(Origin::new().offset_x(5), 20, 3)
+        .steps(steps)
+        .polygonize(tool.make_mut_ref(&mut index), 0)
+        .unwrap();
+}
+
+fn splits(c: &mut Criterion) {
+    let mut group = c.benchmark_group("splits");
+    group.sample_size(10);
+    for steps in [8, 16, 32] {
+        group.bench_function(format!("box_and_cylinder_{steps}"), |b| {
+            b.iter(|| box_and_cylinder(steps))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, splits);
+criterion_main!(benches);
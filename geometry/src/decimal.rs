@@ -1,30 +1,73 @@
+mod scalar;
+
 use std::{
     cmp::Ordering,
     fmt,
+    hash::{Hash, Hasher},
     iter::{Product, Sum},
     ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign},
 };
 
 use approx::{AbsDiffEq, UlpsEq};
 use nalgebra::{ComplexField, Field, RealField, SimdValue};
-use num_traits::{pow::Pow, Bounded, FromPrimitive, Num, Signed, ToPrimitive};
-use rust_decimal::{
-    prelude::{One, Zero},
-    Decimal, MathematicalOps,
-};
-use rust_decimal_macros::dec;
+use num_traits::{pow::Pow, Bounded, FromPrimitive, Num, One, Signed, Zero};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
 use simba::scalar::{SubsetOf, SupersetOf};
 
-#[derive(PartialEq, PartialOrd, Eq, Ord, Hash, Clone, Copy, Default)]
-pub struct Dec(Decimal);
+pub use scalar::Scalar;
+
+/// Number, which is used for all the geometry. `S` is what it is made of, everything
+/// else is the same for all kinds of scalars.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Number<S>(S);
+
+/// Exact decimal number
+#[cfg(not(feature = "f64"))]
+pub type Dec = Number<Decimal>;
+
+/// Fast, but inexact number, enabled with `f64` feature, so whole design can be built
+/// for drafts. Boolean operations rely on exact arithmetic, so final models should be
+/// built without this feature.
+#[cfg(feature = "f64")]
+pub type Dec = Number<f64>;
 
-impl fmt::Debug for Dec {
+impl<S: Scalar> fmt::Debug for Number<S> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Dec: {}", self.round_dp(5))
     }
 }
 
-pub const EPS: Dec = Dec(dec!(1e-8));
+/// Scalars are compared with [`Scalar::total_cmp`], so equality, order and hash agree
+/// with each other for floats too.
+impl<S: Scalar> PartialEq for Number<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<S: Scalar> Eq for Number<S> {}
+
+impl<S: Scalar> PartialOrd for Number<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S: Scalar> Ord for Number<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl<S: Scalar> Hash for Number<S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.total_hash(state)
+    }
+}
+
+pub const EPS: Dec = Dec::EPS;
 pub const STABILITY_ROUNDING: u32 = 14;
 pub const NORMAL_DOT_ROUNDING: u32 = 4;
 //pub const STABILITY_ROUNDING_F: u32 = 15;
@@ -33,59 +76,59 @@ pub trait Round {
     fn round(self, order: u32) -> Self;
 }
 
-impl Round for Dec {
+impl<S: Scalar> Round for Number<S> {
     fn round(self, order: u32) -> Self {
         self.round_dp(order)
     }
 }
 
-impl SubsetOf<Dec> for Dec {
-    fn to_superset(&self) -> Dec {
+impl<S: Scalar> SubsetOf<Number<S>> for Number<S> {
+    fn to_superset(&self) -> Self {
         *self
     }
 
-    fn from_superset_unchecked(element: &Dec) -> Self {
+    fn from_superset_unchecked(element: &Self) -> Self {
         *element
     }
 
-    fn is_in_subset(_element: &Dec) -> bool {
+    fn is_in_subset(_element: &Self) -> bool {
         true
     }
 }
 
-impl Pow<usize> for Dec {
-    type Output = Dec;
+impl<S: Scalar> Pow<usize> for Number<S> {
+    type Output = Number<S>;
 
     fn pow(self, rhs: usize) -> Self::Output {
-        Dec(self.0.powu(rhs as u64))
+        Number(self.0.powu(rhs as u64))
     }
 }
 
-impl Pow<u64> for Dec {
-    type Output = Dec;
+impl<S: Scalar> Pow<u64> for Number<S> {
+    type Output = Number<S>;
 
     fn pow(self, rhs: u64) -> Self::Output {
-        Dec(self.0.powu(rhs))
+        Number(self.0.powu(rhs))
     }
 }
 
-impl Pow<u16> for Dec {
-    type Output = Dec;
+impl<S: Scalar> Pow<u16> for Number<S> {
+    type Output = Number<S>;
 
     fn pow(self, rhs: u16) -> Self::Output {
-        Dec(self.0.powu(rhs as u64))
+        Number(self.0.powu(rhs as u64))
     }
 }
 
-impl Pow<i64> for Dec {
-    type Output = Dec;
+impl<S: Scalar> Pow<i64> for Number<S> {
+    type Output = Number<S>;
 
     fn pow(self, rhs: i64) -> Self::Output {
-        Dec(self.0.powi(rhs))
+        Number(self.0.powi(rhs))
     }
 }
 
-impl ComplexField for Dec {
+impl<S: Scalar> ComplexField for Number<S> {
     type RealField = Self;
 
     #[doc = r" Builds a pure-real complex number from the given value."]
@@ -134,11 +177,11 @@ impl ComplexField for Dec {
     }
 
     fn floor(self) -> Self {
-        todo!()
+        Self(self.0.floor())
     }
 
     fn ceil(self) -> Self {
-        todo!()
+        Self(self.0.ceil())
     }
 
     fn round(self) -> Self {
@@ -199,7 +242,7 @@ impl ComplexField for Dec {
 
     fn acos(self) -> Self {
         let inner: f64 = self.into();
-        Self(Decimal::from_f64(inner.acos()).expect("conversion must be ok"))
+        Self(S::from_f64(inner.acos()).expect("conversion must be ok"))
     }
 
     fn atan(self) -> Self {
@@ -251,8 +294,7 @@ impl ComplexField for Dec {
     }
 
     fn sqrt(self) -> Self {
-        // Self(self.0.powd(dec!(0.5)))
-        Self(self.0.sqrt().expect("aaa"))
+        Self(self.0.sqrt())
     }
 
     fn exp(self) -> Self {
@@ -292,7 +334,7 @@ impl ComplexField for Dec {
     }
 }
 
-impl RealField for Dec {
+impl<S: Scalar> RealField for Number<S> {
     fn is_sign_positive(&self) -> bool {
         self.0.is_sign_positive()
     }
@@ -302,19 +344,19 @@ impl RealField for Dec {
     }
 
     fn copysign(self, _sign: Self) -> Self {
-        (self.0 / self.0.abs()).into()
+        Self(self.0 / self.0.abs())
     }
 
     fn max(self, other: Self) -> Self {
-        self.0.max(other.0).into()
+        Ord::max(self, other)
     }
 
     fn min(self, other: Self) -> Self {
-        self.0.min(other.0).into()
+        Ord::min(self, other)
     }
 
     fn clamp(self, min: Self, max: Self) -> Self {
-        self.0.clamp(min.0, max.0).into()
+        Ord::clamp(self, min, max)
     }
 
     fn atan2(self, _other: Self) -> Self {
@@ -389,9 +431,9 @@ impl RealField for Dec {
         todo!()
     }
 }
-impl Signed for Dec {
+impl<S: Scalar> Signed for Number<S> {
     fn abs(&self) -> Self {
-        self.0.abs().into()
+        Self(self.0.abs())
     }
 
     fn abs_sub(&self, other: &Self) -> Self {
@@ -410,16 +452,16 @@ impl Signed for Dec {
         self.0.is_sign_negative()
     }
 }
-impl UlpsEq for Dec {
+impl<S: Scalar> UlpsEq for Number<S> {
     fn default_max_ulps() -> u32 {
         Self::EPSILON.into()
     }
 
     fn ulps_eq(&self, other: &Self, _epsilon: Self::Epsilon, _max_ulps: u32) -> bool {
-        self.0 == other.0
+        self == other
     }
 }
-impl AbsDiffEq for Dec {
+impl<S: Scalar> AbsDiffEq for Number<S> {
     type Epsilon = Self;
 
     fn default_epsilon() -> Self::Epsilon {
@@ -427,13 +469,13 @@ impl AbsDiffEq for Dec {
     }
 
     fn abs_diff_eq(&self, other: &Self, _epsilon: Self::Epsilon) -> bool {
-        self.0 == other.0
+        self == other
     }
 }
 
-impl approx::RelativeEq for Dec {
+impl<S: Scalar> approx::RelativeEq for Number<S> {
     fn default_max_relative() -> Self::Epsilon {
-        Dec::EPSILON
+        Self::EPSILON
     }
 
     fn relative_eq(
@@ -442,24 +484,24 @@ impl approx::RelativeEq for Dec {
         _epsilon: Self::Epsilon,
         _max_relative: Self::Epsilon,
     ) -> bool {
-        self.0 == other.0
+        self == other
     }
 }
 
-impl FromPrimitive for Dec {
+impl<S: Scalar> FromPrimitive for Number<S> {
     fn from_i64(n: i64) -> Option<Self> {
-        Decimal::from_i64(n).map(Self)
+        S::from_i64(n).map(Self)
     }
 
     fn from_u64(n: u64) -> Option<Self> {
-        Decimal::from_u64(n).map(Self)
+        S::from_u64(n).map(Self)
     }
 }
 
-impl Field for Dec {}
+impl<S: Scalar> Field for Number<S> {}
 
-impl SimdValue for Dec {
-    type Element = Dec;
+impl<S: Scalar> SimdValue for Number<S> {
+    type Element = Number<S>;
 
     type SimdBool = bool;
 
@@ -496,15 +538,15 @@ impl SimdValue for Dec {
     }
 }
 
-impl Num for Dec {
-    type FromStrRadixErr = <Decimal as Num>::FromStrRadixErr;
+impl<S: Scalar> Num for Number<S> {
+    type FromStrRadixErr = S::FromStrRadixErr;
 
     fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
-        let d = Decimal::from_str_radix(str, radix)?;
+        let d = S::from_str_radix(str, radix)?;
         Ok(Self(d))
     }
 }
-impl SupersetOf<f64> for Dec {
+impl<S: Scalar> SupersetOf<f64> for Number<S> {
     fn is_in_subset(&self) -> bool {
         true
     }
@@ -514,22 +556,22 @@ impl SupersetOf<f64> for Dec {
     }
 
     fn from_subset(element: &f64) -> Self {
-        Dec::from(*element)
+        Number::<S>::from(*element)
     }
 }
 
-impl Sum for Dec {
+impl<S: Scalar> Sum for Number<S> {
     fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
-        let mut d = Self(Decimal::zero());
+        let mut d = Self(S::zero());
         for i in iter {
             d += i
         }
         d
     }
 }
-impl Product for Dec {
+impl<S: Scalar> Product for Number<S> {
     fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
-        let mut d = Self(Decimal::one());
+        let mut d = Self(S::one());
         for i in iter {
             d *= i
         }
@@ -537,25 +579,25 @@ impl Product for Dec {
     }
 }
 
-impl From<f32> for Dec {
+impl<S: Scalar> From<f32> for Number<S> {
     fn from(value: f32) -> Self {
-        Self(Decimal::from_f32_retain(value).unwrap_or_else(|| {
+        Self(S::from_f32_retain(value).unwrap_or_else(|| {
             println!("WARNING: Cannot convert float f32 to decimal `{value}`, setting 0");
 
-            Decimal::zero()
+            S::zero()
         }))
     }
 }
-impl From<Dec> for i128 {
-    fn from(value: Dec) -> Self {
+impl<S: Scalar> From<Number<S>> for i128 {
+    fn from(value: Number<S>) -> Self {
         value.0.to_i128().unwrap_or_else(|| {
             println!("WARNING: Cannot convert float f32 to decimal `{value}`, setting 0");
             0
         })
     }
 }
-impl From<Dec> for u32 {
-    fn from(value: Dec) -> Self {
+impl<S: Scalar> From<Number<S>> for u32 {
+    fn from(value: Number<S>) -> Self {
         value.0.to_u32().unwrap_or_else(|| {
             println!("WARNING: Cannot convert float u32 to decimal `{value}`, setting 0");
             0
@@ -563,8 +605,8 @@ impl From<Dec> for u32 {
     }
 }
 
-impl From<Dec> for f64 {
-    fn from(value: Dec) -> Self {
+impl<S: Scalar> From<Number<S>> for f64 {
+    fn from(value: Number<S>) -> Self {
         value.0.to_f64().unwrap_or_else(|| {
             println!("WARNING: Cannot convert float f32 to decimal `{value}`, setting 0");
             0.0
@@ -572,77 +614,77 @@ impl From<Dec> for f64 {
     }
 }
 
-impl From<Dec> for f32 {
-    fn from(value: Dec) -> Self {
+impl<S: Scalar> From<Number<S>> for f32 {
+    fn from(value: Number<S>) -> Self {
         value.0.to_f32().unwrap_or_else(|| {
             println!("WARNING: Cannot convert float f32 to decimal `{value}`, setting 0");
             0.0
         })
     }
 }
-impl From<f64> for Dec {
+impl<S: Scalar> From<f64> for Number<S> {
     fn from(value: f64) -> Self {
-        Self(Decimal::from_f64_retain(value).unwrap_or_else(|| {
+        Self(S::from_f64_retain(value).unwrap_or_else(|| {
             println!("WARNING: Cannot convert float f64 to decimal `{value}`, setting 0");
 
-            Decimal::zero()
+            S::zero()
         }))
     }
 }
 
-impl From<i128> for Dec {
+impl<S: Scalar> From<i128> for Number<S> {
     fn from(value: i128) -> Self {
-        Self(Decimal::from_i128(value).unwrap_or_else(|| {
+        Self(S::from_i128(value).unwrap_or_else(|| {
             println!("WARNING: Cannot convert integer i128 to decimal `{value}`, setting 0");
 
-            Decimal::zero()
+            S::zero()
         }))
     }
 }
-impl From<i64> for Dec {
+impl<S: Scalar> From<i64> for Number<S> {
     fn from(value: i64) -> Self {
-        Self(Decimal::from_i64(value).unwrap_or_else(|| {
+        Self(S::from_i64(value).unwrap_or_else(|| {
             println!("WARNING: Cannot convert integer i64 to decimal `{value}`, setting 0");
 
-            Decimal::zero()
+            S::zero()
         }))
     }
 }
-impl From<i32> for Dec {
+impl<S: Scalar> From<i32> for Number<S> {
     fn from(value: i32) -> Self {
-        Self(Decimal::from_i32(value).unwrap_or_else(|| {
+        Self(S::from_i32(value).unwrap_or_else(|| {
             println!("WARNING: Cannot convert integer i32 to decimal `{value}`, setting 0");
 
-            Decimal::zero()
+            S::zero()
         }))
     }
 }
-impl From<u32> for Dec {
+impl<S: Scalar> From<u32> for Number<S> {
     fn from(value: u32) -> Self {
-        Self(Decimal::from_u32(value).unwrap_or_else(|| {
+        Self(S::from_u32(value).unwrap_or_else(|| {
             println!("WARNING: Cannot convert integer u32 to decimal `{value}`, setting 0");
 
-            Decimal::zero()
+            S::zero()
         }))
     }
 }
 
-impl From<u16> for Dec {
+impl<S: Scalar> From<u16> for Number<S> {
     fn from(value: u16) -> Self {
-        Self(Decimal::from_u16(value).unwrap_or_else(|| {
+        Self(S::from_u16(value).unwrap_or_else(|| {
             println!("WARNING: Cannot convert integer u32 to decimal `{value}`, setting 0");
 
-            Decimal::zero()
+            S::zero()
         }))
     }
 }
 
-impl From<usize> for Dec {
+impl<S: Scalar> From<usize> for Number<S> {
     fn from(value: usize) -> Self {
-        Self(Decimal::from_usize(value).unwrap_or_else(|| {
+        Self(S::from_usize(value).unwrap_or_else(|| {
             println!("WARNING: Cannot convert integer usize to decimal `{value}`, setting 0");
 
-            Decimal::zero()
+            S::zero()
         }))
     }
 }
@@ -660,9 +702,9 @@ where
 }
 */
 
-impl Zero for Dec {
+impl<S: Scalar> Zero for Number<S> {
     fn zero() -> Self {
-        Self(Decimal::zero())
+        Self(S::zero())
     }
 
     fn is_zero(&self) -> bool {
@@ -670,14 +712,15 @@ impl Zero for Dec {
     }
 }
 
-impl One for Dec {
+impl<S: Scalar> One for Number<S> {
     fn one() -> Self {
-        Self(Decimal::one())
+        Self(S::one())
     }
 }
 
-impl Dec {
-    pub const EPSILON: Self = Self(Decimal::from_parts(1, 0, 0, false, 28));
+impl<S: Scalar> Number<S> {
+    pub const EPSILON: Self = Self(S::EPSILON);
+    const EPS: Self = Self(S::EPS);
 
     pub fn atan2(&self, other: &Self) -> Self {
         let f1 = self.0.to_f64().unwrap();
@@ -687,11 +730,11 @@ impl Dec {
     }
 
     pub fn two_pi() -> Self {
-        Self(rust_decimal::Decimal::TWO_PI)
+        Self(S::TWO_PI)
     }
 
     pub fn pi() -> Self {
-        Self(rust_decimal::Decimal::PI)
+        Self(S::PI)
     }
 
     pub fn round(&self) -> Self {
@@ -703,17 +746,17 @@ impl Dec {
     }
 
     pub fn total_cmp(&self, other: &Self) -> Ordering {
-        self.0.cmp(&other.0)
+        self.cmp(other)
     }
 }
 
-impl fmt::Display for Dec {
+impl<S: Scalar> fmt::Display for Number<S> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.0.fmt(f)
     }
 }
 
-impl DivAssign for Dec {
+impl<S: Scalar> DivAssign for Number<S> {
     fn div_assign(&mut self, rhs: Self) {
         if rhs == Self::zero() {
             dbg!("QQQQQQQ");
@@ -722,170 +765,212 @@ impl DivAssign for Dec {
     }
 }
 
-impl AddAssign for Dec {
+impl<S: Scalar> AddAssign for Number<S> {
     fn add_assign(&mut self, rhs: Self) {
         self.0 += rhs.0;
     }
 }
-impl SubAssign for Dec {
+impl<S: Scalar> SubAssign for Number<S> {
     fn sub_assign(&mut self, rhs: Self) {
         self.0 -= rhs.0;
     }
 }
 
-impl MulAssign for Dec {
+impl<S: Scalar> MulAssign for Number<S> {
     fn mul_assign(&mut self, rhs: Self) {
         self.0 *= rhs.0;
     }
 }
 
-impl RemAssign for Dec {
+impl<S: Scalar> RemAssign for Number<S> {
     fn rem_assign(&mut self, rhs: Self) {
         self.0 %= rhs.0;
     }
 }
 
-impl Neg for Dec {
+impl<S: Scalar> Neg for Number<S> {
     type Output = Self;
 
     fn neg(self) -> Self::Output {
         Self(-self.0)
     }
 }
-impl Rem for Dec {
+impl<S: Scalar> Rem for Number<S> {
     type Output = Self;
 
     fn rem(self, rhs: Self) -> Self::Output {
-        Dec(self.0 % rhs.0)
+        Number(self.0 % rhs.0)
     }
 }
-impl Div for Dec {
+impl<S: Scalar> Div for Number<S> {
     type Output = Self;
 
+    /// Exact scalar panics on division by zero, float one gives infinity
     fn div(self, rhs: Self) -> Self::Output {
-        if rhs == Self::zero() {
-            panic!("div by zero {self} / {rhs}")
-        }
-        Dec(self.0 / rhs.0)
+        Number(self.0 / rhs.0)
     }
 }
-impl Div<i64> for Dec {
+impl<S: Scalar> Div<i64> for Number<S> {
     type Output = Self;
 
     fn div(self, rhs: i64) -> Self::Output {
         if rhs == 0 {
             dbg!("IIII");
         }
-        self / Dec::from(rhs)
+        self / Number::<S>::from(rhs)
     }
 }
 
-impl Add for Dec {
+impl<S: Scalar> Add for Number<S> {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
         let d = self.0 + rhs.0;
 
-        Dec(d)
+        Number(d)
     }
 }
-impl Add<i64> for Dec {
+impl<S: Scalar> Add<i64> for Number<S> {
     type Output = Self;
 
     fn add(self, rhs: i64) -> Self::Output {
         dbg!("add i64");
-        self + Dec::from(rhs)
+        self + Number::<S>::from(rhs)
     }
 }
-impl Sub for Dec {
+impl<S: Scalar> Sub for Number<S> {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        Dec(self.0 - rhs.0)
+        Number(self.0 - rhs.0)
     }
 }
-impl Sub<i64> for Dec {
+impl<S: Scalar> Sub<i64> for Number<S> {
     type Output = Self;
 
     fn sub(self, rhs: i64) -> Self::Output {
-        self - Dec::from(rhs)
+        self - Number::<S>::from(rhs)
     }
 }
 
-impl Mul for Dec {
+impl<S: Scalar> Mul for Number<S> {
     type Output = Self;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        Dec(self.0 * rhs.0)
+        Number(self.0 * rhs.0)
     }
 }
 
-impl Mul<i64> for Dec {
+impl<S: Scalar> Mul<i64> for Number<S> {
     type Output = Self;
 
     fn mul(self, rhs: i64) -> Self::Output {
-        self * Dec::from(rhs)
+        self * Number::<S>::from(rhs)
     }
 }
 
-impl Mul<Dec> for i64 {
-    type Output = Dec;
+impl<S: Scalar> Mul<Number<S>> for i64 {
+    type Output = Number<S>;
 
-    fn mul(self, rhs: Dec) -> Self::Output {
-        Dec::from(self) * rhs
+    fn mul(self, rhs: Number<S>) -> Self::Output {
+        Number::<S>::from(self) * rhs
     }
 }
 
-impl Mul<Dec> for f64 {
-    type Output = Dec;
+impl<S: Scalar> Mul<Number<S>> for f64 {
+    type Output = Number<S>;
 
-    fn mul(self, rhs: Dec) -> Self::Output {
-        (Dec::from(self) * rhs).round_dp(8)
+    fn mul(self, rhs: Number<S>) -> Self::Output {
+        (Number::<S>::from(self) * rhs).round_dp(8)
     }
 }
 
-impl Mul<Dec> for f32 {
-    type Output = Dec;
+impl<S: Scalar> Mul<Number<S>> for f32 {
+    type Output = Number<S>;
 
-    fn mul(self, rhs: Dec) -> Self::Output {
-        (Dec::from(self) * rhs).round_dp(10)
+    fn mul(self, rhs: Number<S>) -> Self::Output {
+        (Number::<S>::from(self) * rhs).round_dp(10)
     }
 }
 
-impl Mul<f64> for Dec {
-    type Output = Dec;
+impl<S: Scalar> Mul<f64> for Number<S> {
+    type Output = Number<S>;
 
     fn mul(self, rhs: f64) -> Self::Output {
-        (Dec::from(rhs) * self).round_dp(8)
+        (Number::<S>::from(rhs) * self).round_dp(8)
     }
 }
 
-impl Mul<f32> for Dec {
-    type Output = Dec;
+impl<S: Scalar> Mul<f32> for Number<S> {
+    type Output = Number<S>;
 
     fn mul(self, rhs: f32) -> Self::Output {
-        (Dec::from(rhs) * self).round_dp(10)
+        (Number::<S>::from(rhs) * self).round_dp(10)
     }
 }
 
-impl From<Decimal> for Dec {
+impl<S: Scalar> From<Decimal> for Number<S> {
     fn from(value: Decimal) -> Self {
-        Dec(value)
+        Number(S::from_decimal(value))
     }
 }
 
-impl From<Dec> for Decimal {
-    fn from(value: Dec) -> Self {
-        value.0
+impl<S: Scalar> From<Number<S>> for Decimal {
+    fn from(value: Number<S>) -> Self {
+        value.0.to_decimal()
     }
 }
 
-impl Bounded for Dec {
+impl<S: Scalar> Bounded for Number<S> {
     fn min_value() -> Self {
-        Decimal::MIN.into()
+        Self(S::MIN)
     }
 
     fn max_value() -> Self {
-        Decimal::MAX.into()
+        Self(S::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cmp::Ordering,
+        collections::hash_map::DefaultHasher,
+        hash::{Hash, Hasher},
+    };
+
+    use nalgebra::ComplexField;
+
+    use super::{Dec, Number};
+
+    #[test]
+    fn floor_and_ceil() {
+        assert_eq!(Dec::from(1.5).floor(), Dec::from(1));
+        assert_eq!(Dec::from(-1.5).floor(), Dec::from(-2));
+        assert_eq!(Dec::from(1.5).ceil(), Dec::from(2));
+        assert_eq!(Dec::from(-1.5).ceil(), Dec::from(-1));
+    }
+
+    fn hash_of(n: Number<f64>) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        n.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn float_number_is_totally_ordered() {
+        let nan = Number::<f64>::from(f64::NAN);
+        assert_eq!(nan, nan);
+        assert_eq!(nan.cmp(&Number::from(f64::INFINITY)), Ordering::Greater);
+        assert_eq!(Number::<f64>::from(-0.0), Number::from(0.0));
+        assert_eq!(hash_of(Number::from(-0.0)), hash_of(Number::from(0.0)));
+        assert!(Number::<f64>::from(-1.0) < Number::from(1.0));
+    }
+
+    #[test]
+    fn float_number_divides_by_zero() {
+        let one = Number::<f64>::from(1.0);
+        let zero = Number::<f64>::from(0.0);
+        assert_eq!(one / zero, Number::from(f64::INFINITY));
     }
 }
@@ -0,0 +1,246 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::{AddAssign, DivAssign, MulAssign, Neg, RemAssign, SubAssign},
+};
+
+use num_traits::{FromPrimitive, Num, Signed, ToPrimitive};
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::dec;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// What [`super::Number`] is made of. Only operations, which differ between scalars, are
+/// here, everything else is implemented once for `Number`.
+pub trait Scalar:
+    Copy
+    + Default
+    + fmt::Display
+    + Num
+    + Signed
+    + FromPrimitive
+    + ToPrimitive
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + RemAssign
+    + Serialize
+    + DeserializeOwned
+    + Send
+    + Sync
+    + 'static
+{
+    const EPS: Self;
+    /// Smallest difference, which the scalar tells apart from zero around one
+    const EPSILON: Self;
+    const MIN: Self;
+    const MAX: Self;
+    const PI: Self;
+    const TWO_PI: Self;
+
+    /// Total order: every value is equal to itself, so it can be used for `Eq` and `Ord`
+    fn total_cmp(&self, other: &Self) -> Ordering;
+    /// Hash, which agrees with [`Scalar::total_cmp`]
+    fn total_hash<H: Hasher>(&self, state: &mut H);
+
+    fn from_decimal(value: Decimal) -> Self;
+    fn to_decimal(self) -> Decimal;
+    fn from_f64_retain(value: f64) -> Option<Self>;
+    fn from_f32_retain(value: f32) -> Option<Self>;
+
+    fn sqrt(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn exp(self) -> Self;
+    fn powu(self, exp: u64) -> Self;
+    fn powi(self, exp: i64) -> Self;
+    fn floor(self) -> Self;
+    fn ceil(self) -> Self;
+    fn round(self) -> Self;
+    fn round_dp(self, dp: u32) -> Self;
+    fn is_sign_positive(self) -> bool;
+    fn is_sign_negative(self) -> bool;
+}
+
+impl Scalar for Decimal {
+    const EPS: Self = dec!(1e-8);
+    const EPSILON: Self = Decimal::from_parts(1, 0, 0, false, 28);
+    const MIN: Self = Decimal::MIN;
+    const MAX: Self = Decimal::MAX;
+    const PI: Self = Decimal::PI;
+    const TWO_PI: Self = Decimal::TWO_PI;
+
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        self.cmp(other)
+    }
+
+    fn total_hash<H: Hasher>(&self, state: &mut H) {
+        self.hash(state)
+    }
+
+    fn from_decimal(value: Decimal) -> Self {
+        value
+    }
+
+    fn to_decimal(self) -> Decimal {
+        self
+    }
+
+    fn from_f64_retain(value: f64) -> Option<Self> {
+        Decimal::from_f64_retain(value)
+    }
+
+    fn from_f32_retain(value: f32) -> Option<Self> {
+        Decimal::from_f32_retain(value)
+    }
+
+    fn sqrt(self) -> Self {
+        MathematicalOps::sqrt(&self).expect("Cannot take square root of negative number")
+    }
+
+    fn sin(self) -> Self {
+        MathematicalOps::sin(&self)
+    }
+
+    fn cos(self) -> Self {
+        MathematicalOps::cos(&self)
+    }
+
+    fn exp(self) -> Self {
+        MathematicalOps::exp(&self)
+    }
+
+    fn powu(self, exp: u64) -> Self {
+        MathematicalOps::powu(&self, exp)
+    }
+
+    fn powi(self, exp: i64) -> Self {
+        MathematicalOps::powi(&self, exp)
+    }
+
+    fn floor(self) -> Self {
+        Decimal::floor(&self)
+    }
+
+    fn ceil(self) -> Self {
+        Decimal::ceil(&self)
+    }
+
+    fn round(self) -> Self {
+        Decimal::round(&self)
+    }
+
+    fn round_dp(self, dp: u32) -> Self {
+        Decimal::round_dp(&self, dp)
+    }
+
+    fn is_sign_positive(self) -> bool {
+        Decimal::is_sign_positive(&self)
+    }
+
+    fn is_sign_negative(self) -> bool {
+        Decimal::is_sign_negative(&self)
+    }
+}
+
+/// Zeroes of both signs are the same and all NaNs are the same, so `-0.0 == 0.0`, like
+/// for floats, and NaN equals itself, which floats don't do.
+fn normalized(value: f64) -> f64 {
+    if value.is_nan() {
+        f64::NAN
+    } else {
+        value + 0.0
+    }
+}
+
+impl Scalar for f64 {
+    const EPS: Self = 1e-8;
+    // Decimal keeps 28 digits after point, f64 - about 16 significant ones, so 1e-28
+    // would be far below rounding error of any f64 computation
+    const EPSILON: Self = f64::EPSILON;
+    const MIN: Self = f64::MIN;
+    const MAX: Self = f64::MAX;
+    const PI: Self = std::f64::consts::PI;
+    const TWO_PI: Self = std::f64::consts::TAU;
+
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        f64::total_cmp(&normalized(*self), &normalized(*other))
+    }
+
+    fn total_hash<H: Hasher>(&self, state: &mut H) {
+        normalized(*self).to_bits().hash(state)
+    }
+
+    fn from_decimal(value: Decimal) -> Self {
+        value.to_f64().unwrap_or_default()
+    }
+
+    fn to_decimal(self) -> Decimal {
+        Decimal::from_f64(self).unwrap_or_default()
+    }
+
+    fn from_f64_retain(value: f64) -> Option<Self> {
+        Some(value)
+    }
+
+    fn from_f32_retain(value: f32) -> Option<Self> {
+        Some(value.into())
+    }
+
+    fn sqrt(self) -> Self {
+        f64::sqrt(self)
+    }
+
+    fn sin(self) -> Self {
+        f64::sin(self)
+    }
+
+    fn cos(self) -> Self {
+        f64::cos(self)
+    }
+
+    fn exp(self) -> Self {
+        f64::exp(self)
+    }
+
+    fn powu(self, exp: u64) -> Self {
+        f64::powi(self, exp as i32)
+    }
+
+    fn powi(self, exp: i64) -> Self {
+        f64::powi(self, exp as i32)
+    }
+
+    fn floor(self) -> Self {
+        f64::floor(self)
+    }
+
+    fn ceil(self) -> Self {
+        f64::ceil(self)
+    }
+
+    fn round(self) -> Self {
+        f64::round(self)
+    }
+
+    /// f64 has about 15 significant digits, so rounding, which doesn't fit in them,
+    /// leaves number as is.
+    fn round_dp(self, dp: u32) -> Self {
+        let m = 10f64.powi(dp as i32);
+        let scaled = self * m;
+        if scaled.abs() >= 1e15 {
+            return self;
+        }
+        scaled.round() / m
+    }
+
+    fn is_sign_positive(self) -> bool {
+        f64::is_sign_positive(self)
+    }
+
+    fn is_sign_negative(self) -> bool {
+        f64::is_sign_negative(self)
+    }
+}
@@ -0,0 +1,275 @@
+use std::collections::{HashMap, HashSet};
+
+use itertools::Itertools;
+use nalgebra::Vector3;
+use num_traits::{One, Signed};
+use rust_decimal_macros::dec;
+
+use crate::{
+    decimal::{Dec, NORMAL_DOT_ROUNDING},
+    planar::polygon::Polygon,
+};
+
+/// Points, which are closer to face than this, are treated as lying on it
+fn on_face() -> Dec {
+    dec!(1e-6).into()
+}
+
+struct Face {
+    pts: [usize; 3],
+    normal: Vector3<Dec>,
+    dist: Dec,
+    outside: Vec<usize>,
+    alive: bool,
+}
+
+impl Face {
+    fn new(pts: [usize; 3], points: &[Vector3<Dec>]) -> Self {
+        let [a, b, c] = pts.map(|ix| points[ix]);
+        let normal = (b - a).cross(&(c - a)).normalize();
+        Self {
+            pts,
+            dist: normal.dot(&a),
+            normal,
+            outside: Vec::new(),
+            alive: true,
+        }
+    }
+
+    fn distance_to(&self, pt: &Vector3<Dec>) -> Dec {
+        self.normal.dot(pt) - self.dist
+    }
+
+    fn edges(&self) -> [(usize, usize); 3] {
+        let [a, b, c] = self.pts;
+        [(a, b), (b, c), (c, a)]
+    }
+}
+
+/// Convex hull of point cloud (quickhull). Coplanar faces are merged into one polygon,
+/// all polygons look outside of hull.
+/// Returns nothing, if all points lie in one plane.
+pub fn convex_hull_3d(points: &[Vector3<Dec>]) -> Vec<Polygon> {
+    let Some(simplex) = initial_simplex(points) else {
+        return Vec::new();
+    };
+
+    let center = simplex.iter().map(|ix| points[*ix]).sum::<Vector3<Dec>>() / Dec::from(4);
+    let [a, b, c, d] = simplex;
+    let mut faces = [[a, b, c], [a, b, d], [a, c, d], [b, c, d]]
+        .into_iter()
+        .map(|pts| {
+            let face = Face::new(pts, points);
+            if face.distance_to(&center).is_positive() {
+                Face::new([pts[0], pts[2], pts[1]], points)
+            } else {
+                face
+            }
+        })
+        .collect_vec();
+
+    let candidates = (0..points.len())
+        .filter(|ix| !simplex.contains(ix))
+        .collect_vec();
+    assign_outside(&mut faces, 0, candidates, points);
+
+    while let Some(face_ix) = faces.iter().position(|f| f.alive && !f.outside.is_empty()) {
+        let eye = *faces[face_ix]
+            .outside
+            .iter()
+            .max_by_key(|ix| faces[face_ix].distance_to(&points[**ix]))
+            .expect("outside is not empty");
+
+        let visible = faces
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.alive && f.distance_to(&points[eye]) > on_face())
+            .map(|(ix, _)| ix)
+            .collect_vec();
+
+        let visible_edges = visible
+            .iter()
+            .flat_map(|ix| faces[*ix].edges())
+            .collect::<HashSet<_>>();
+        let horizon = visible_edges
+            .iter()
+            .filter(|(a, b)| !visible_edges.contains(&(*b, *a)))
+            .copied()
+            .collect_vec();
+
+        let mut orphans = Vec::new();
+        for ix in visible {
+            faces[ix].alive = false;
+            orphans.append(&mut faces[ix].outside);
+        }
+        orphans.retain(|ix| *ix != eye);
+
+        let first_new = faces.len();
+        faces.extend(
+            horizon
+                .into_iter()
+                .map(|(a, b)| Face::new([a, b, eye], points)),
+        );
+        assign_outside(&mut faces, first_new, orphans, points);
+    }
+
+    merge_coplanar(faces.into_iter().filter(|f| f.alive).collect(), points)
+}
+
+fn initial_simplex(points: &[Vector3<Dec>]) -> Option<[usize; 4]> {
+    let (a, _) = points.iter().enumerate().min_by_key(|(_, p)| p.x)?;
+    let (b, ab) = points
+        .iter()
+        .enumerate()
+        .map(|(ix, p)| (ix, (p - points[a]).magnitude_squared()))
+        .max_by_key(|(_, d)| *d)?;
+    if ab <= on_face() {
+        return None;
+    }
+
+    let dir = points[b] - points[a];
+    let (c, abc) = points
+        .iter()
+        .enumerate()
+        .map(|(ix, p)| (ix, (p - points[a]).cross(&dir).magnitude_squared()))
+        .max_by_key(|(_, d)| *d)?;
+    if abc <= on_face() {
+        return None;
+    }
+
+    let normal = dir.cross(&(points[c] - points[a])).normalize();
+    let (d, abcd) = points
+        .iter()
+        .enumerate()
+        .map(|(ix, p)| (ix, normal.dot(&(p - points[a])).abs()))
+        .max_by_key(|(_, d)| *d)?;
+    if abcd <= on_face() {
+        return None;
+    }
+
+    Some([a, b, c, d])
+}
+
+/// Gives each point to first face (starting from `from_face`), which can see it.
+/// Points, which are not seen by any face are inside hull.
+fn assign_outside(faces: &mut [Face], from_face: usize, pts: Vec<usize>, points: &[Vector3<Dec>]) {
+    for pt in pts {
+        if let Some(face) = faces[from_face..]
+            .iter_mut()
+            .find(|f| f.alive && f.distance_to(&points[pt]) > on_face())
+        {
+            face.outside.push(pt);
+        }
+    }
+}
+
+fn merge_coplanar(faces: Vec<Face>, points: &[Vector3<Dec>]) -> Vec<Polygon> {
+    let mut edge_face = HashMap::new();
+    for (ix, f) in faces.iter().enumerate() {
+        for e in f.edges() {
+            edge_face.insert(e, ix);
+        }
+    }
+
+    let mut group = (0..faces.len()).collect_vec();
+    for (ix, f) in faces.iter().enumerate() {
+        for (a, b) in f.edges() {
+            let Some(&other) = edge_face.get(&(b, a)) else {
+                continue;
+            };
+            let dot = f.normal.dot(&faces[other].normal);
+            if dot.round_dp(NORMAL_DOT_ROUNDING) == Dec::one() {
+                let (ga, gb) = (find_group(&mut group, ix), find_group(&mut group, other));
+                group[ga] = gb;
+            }
+        }
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for ix in 0..faces.len() {
+        let g = find_group(&mut group, ix);
+        groups.entry(g).or_default().push(ix);
+    }
+
+    groups
+        .into_values()
+        .sorted()
+        .filter_map(|group| {
+            let normal = faces[group[0]].normal;
+            let pts = group
+                .iter()
+                .flat_map(|ix| faces[*ix].pts)
+                .unique()
+                .map(|ix| points[ix])
+                .collect_vec();
+            let center = pts.iter().sum::<Vector3<Dec>>() / Dec::from(pts.len());
+            let u = (pts[0] - center).normalize();
+            let v = normal.cross(&u);
+            let sorted = pts
+                .into_iter()
+                .sorted_by_key(|p| {
+                    let d = p - center;
+                    d.dot(&v).atan2(&d.dot(&u))
+                })
+                .collect_vec();
+
+            let len = sorted.len();
+            let vertices = (0..len)
+                .filter(|ix| {
+                    let prev = sorted[(ix + len - 1) % len];
+                    let next = sorted[(ix + 1) % len];
+                    (sorted[*ix] - prev)
+                        .cross(&(next - sorted[*ix]))
+                        .magnitude_squared()
+                        > on_face()
+                })
+                .map(|ix| sorted[ix])
+                .collect_vec();
+
+            Polygon::new(vertices).ok()
+        })
+        .collect()
+}
+
+fn find_group(group: &mut [usize], ix: usize) -> usize {
+    let mut root = ix;
+    while group[root] != root {
+        root = group[root];
+    }
+    group[ix] = root;
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector3;
+    use rust_decimal_macros::dec;
+
+    use crate::decimal::Dec;
+
+    use super::convex_hull_3d;
+
+    #[test]
+    fn hull_of_cube_with_inner_points() {
+        let mut points = Vec::new();
+        for x in [-1, 1] {
+            for y in [-1, 1] {
+                for z in [-1, 1] {
+                    points.push(Vector3::new(x, y, z).map(Dec::from));
+                }
+            }
+        }
+        points.push(Vector3::new(dec!(0.5), dec!(-0.2), dec!(0.1)).map(Dec::from));
+        points.push(Vector3::new(dec!(0), dec!(0), dec!(1)).map(Dec::from));
+        points.push(Vector3::new(dec!(-0.3), dec!(0.7), dec!(-0.9)).map(Dec::from));
+
+        let hull = convex_hull_3d(&points);
+
+        assert_eq!(hull.len(), 6);
+        for polygon in hull {
+            assert_eq!(polygon.vertices.len(), 4);
+            let center = polygon.vertices.iter().sum::<Vector3<Dec>>() / Dec::from(4);
+            assert_eq!(polygon.get_normal().dot(&center), Dec::from(1));
+        }
+    }
+}
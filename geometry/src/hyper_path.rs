@@ -1,4 +1,6 @@
 pub mod direction_perpendicular;
+pub mod even_split;
+pub mod fillet;
 pub mod hyper_line;
 #[allow(clippy::module_inception)]
 pub mod hyper_path;
@@ -6,5 +8,7 @@ pub mod hyper_point;
 pub mod hyper_surface;
 pub mod length;
 pub mod line;
+pub mod offset;
 pub mod shifted_hyper_path;
+pub mod spline;
 pub mod split_hyper_line;
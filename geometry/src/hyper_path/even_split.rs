@@ -0,0 +1,154 @@
+use std::{
+    fmt,
+    ops::{Mul, Sub},
+};
+
+use crate::parametric_iterator::ParametricIterator;
+
+use super::{
+    hyper_line::HyperLine, hyper_point::Tensor, length::Length, line::GetT,
+    split_hyper_line::SplitHyperLine,
+};
+
+/// Amount of chords, which measure length along line
+const CHORDS: usize = 64;
+
+impl<T, S> HyperLine<T>
+where
+    T: Tensor<Scalar = S> + Mul<S, Output = T> + Sub<T, Output = T> + Length<Scalar = S>,
+    S: From<u16> + Copy + nalgebra::RealField,
+    Self: SplitHyperLine<S> + Clone + fmt::Debug,
+{
+    /// Parameters of chord ends and lengths of chords
+    fn chords(&self) -> Vec<(S, S, S)> {
+        ParametricIterator::<S>::new(CHORDS)
+            .map(|(t, tt)| (t, tt, (self.get_t(tt) - self.get_t(t)).length()))
+            .collect()
+    }
+
+    /// Splits line into `parts` of the same length. Unlike `split_by_weights`, which splits
+    /// by parameter, points do not bunch up, where curve bends. Length is measured by
+    /// chords, so parts of curves are equal approximately.
+    pub fn split_even(&self, parts: usize) -> Vec<Self> {
+        if parts < 2 {
+            return vec![self.clone()];
+        }
+        let chords = self.chords();
+        let total = chords.iter().fold(S::zero(), |sum, (_, _, c)| sum + *c);
+        let step = total / S::from(parts as u16);
+
+        let mut params = Vec::new();
+        let (mut ix, mut passed) = (0, S::zero());
+        for part in 1..parts {
+            let length = step * S::from(part as u16);
+            while ix < chords.len() - 1 && passed + chords[ix].2 < length {
+                passed += chords[ix].2;
+                ix += 1;
+            }
+            let (t, tt, chord) = chords[ix];
+            let along = if chord.is_zero() {
+                S::zero()
+            } else {
+                ((length - passed) / chord).min(S::one())
+            };
+            params.push(t + (tt - t) * along);
+        }
+        self.split_by(params)
+    }
+
+    /// Splits line into the smallest amount of equal parts, which are not longer, than
+    /// `max_length`
+    pub fn split_by_max_length(&self, max_length: S) -> Vec<Self> {
+        if max_length <= S::zero() {
+            return vec![self.clone()];
+        }
+        let total = self
+            .chords()
+            .into_iter()
+            .fold(S::zero(), |sum, (_, _, c)| sum + c);
+        let mut parts = 1;
+        while total / S::from(parts as u16) > max_length {
+            parts += 1;
+        }
+        self.split_even(parts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{ComplexField, Vector3};
+    use num_traits::{One, Zero};
+
+    use crate::{
+        decimal::Dec,
+        hyper_path::{hyper_line::HyperLine, line::GetT},
+    };
+
+    /// Chords between ends of parts: parts of the same circle are equal, when chords are
+    fn chords(parts: &[HyperLine<Vector3<Dec>>]) -> Vec<Dec> {
+        parts
+            .iter()
+            .map(|p| (p.get_t(Dec::one()) - p.get_t(Dec::zero())).magnitude())
+            .collect()
+    }
+
+    fn quarter_arc() -> HyperLine<Vector3<Dec>> {
+        HyperLine::new_arc(
+            Vector3::new(Dec::from(10), Dec::zero(), Dec::zero()),
+            Vector3::zeros(),
+            Vector3::new(Dec::zero(), Dec::from(10), Dec::zero()),
+            Dec::from(std::f64::consts::FRAC_PI_2),
+        )
+    }
+
+    #[test]
+    fn line_split_into_equal_parts() {
+        let line = HyperLine::new_2(
+            Vector3::zeros(),
+            Vector3::new(Dec::from(6), Dec::from(8), Dec::zero()),
+        );
+
+        let parts = line.split_even(4);
+
+        assert_eq!(parts.len(), 4);
+        for chord in chords(&parts) {
+            assert!((chord - Dec::from(2.5)).abs() < Dec::from(1e-6));
+        }
+    }
+
+    #[test]
+    fn arc_split_into_equal_arc_lengths() {
+        let arc = quarter_arc();
+
+        let parts = arc.split_even(3);
+
+        // Arc of 30 degrees has chord of one radius times 2 sin(15)
+        let expected = Dec::from(20.0 * 15f64.to_radians().sin());
+        assert_eq!(parts.len(), 3);
+        for chord in chords(&parts) {
+            assert!((chord - expected).abs() < Dec::from(1e-3), "{chord}");
+        }
+        assert!(
+            (parts[0].get_t(Dec::zero()) - arc.get_t(Dec::zero())).magnitude() < Dec::from(1e-6)
+        );
+        assert!((parts[2].get_t(Dec::one()) - arc.get_t(Dec::one())).magnitude() < Dec::from(1e-6));
+    }
+
+    #[test]
+    fn split_by_max_length_takes_fewest_parts() {
+        let line = HyperLine::new_2(
+            Vector3::zeros(),
+            Vector3::new(Dec::from(10), Dec::zero(), Dec::zero()),
+        );
+        assert_eq!(line.split_by_max_length(Dec::from(3)).len(), 4);
+        assert_eq!(line.split_by_max_length(Dec::from(10)).len(), 1);
+
+        // Quarter of circle of radius 10 is 15.7 long
+        let parts = quarter_arc().split_by_max_length(Dec::from(4));
+        assert_eq!(parts.len(), 4);
+        let chords = chords(&parts);
+        for chord in &chords {
+            assert!((*chord - chords[0]).abs() < Dec::from(1e-3), "{chords:?}");
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use itertools::Itertools;
+use nalgebra::{ComplexField, Vector3};
+use num_traits::{One, Zero};
+
+use crate::decimal::{Dec, STABILITY_ROUNDING};
+
+use super::{
+    hyper_line::HyperLine, hyper_path::Root, hyper_point::SuperPoint, length::Length, line::GetT,
+    split_hyper_line::SplitHyperLine,
+};
+
+type Line = HyperLine<SuperPoint<Dec>>;
+
+fn start_dir(line: &Line) -> Vector3<Dec> {
+    let delta = Dec::one() / Dec::from(65535);
+    (line.get_t(delta).point - line.get_t(Dec::zero()).point).normalize()
+}
+
+fn end_dir(line: &Line) -> Vector3<Dec> {
+    let delta = Dec::one() / Dec::from(65535);
+    (line.get_t(Dec::one()).point - line.get_t(Dec::one() - delta).point).normalize()
+}
+
+/// Joint between `prev` and `next`: distance to cut from both lines and angle of turn
+fn joint(prev: &Line, next: &Line, radius: Dec) -> Option<(Dec, Dec)> {
+    // Joints, which turn by about a degree or less, are smooth enough
+    let smooth = Dec::from(9998) / Dec::from(10000);
+    let cos = end_dir(prev).dot(&start_dir(next));
+    if cos > smooth || cos < -smooth {
+        return None;
+    }
+    let angle = cos.acos();
+    let half = angle / Dec::from(2);
+    let room = prev.length().min(next.length()) / Dec::from(3);
+    Some(((radius * half.sin() / half.cos()).min(room), angle))
+}
+
+/// Line from the end of `prev` to the start of `next`, which touches both of them
+fn fillet(prev: &Line, next: &Line, cut: Dec, angle: Dec) -> Line {
+    let (a, b) = (*prev.0.last().expect("ok"), *next.0.first().expect("ok"));
+    let (dir_a, dir_b) = (end_dir(prev), start_dir(next));
+    let half = angle / Dec::from(2);
+    let radius = cut * half.cos() / half.sin();
+    if prev.0.len() == 2 && next.0.len() == 2 {
+        let to_center = (dir_b - dir_a * dir_a.dot(&dir_b)).normalize();
+        let center = SuperPoint {
+            side_dir: a.side_dir,
+            point: a.point + to_center * radius,
+        };
+        return HyperLine::new_arc(a, center, b, angle);
+    }
+    let quarter = angle / Dec::from(4);
+    let handle = radius * Dec::from(4) / Dec::from(3) * quarter.sin() / quarter.cos();
+    HyperLine::new_4(
+        a,
+        SuperPoint {
+            side_dir: a.side_dir,
+            point: a.point + dir_a * handle,
+        },
+        SuperPoint {
+            side_dir: b.side_dir,
+            point: b.point - dir_b * handle,
+        },
+        b,
+    )
+}
+
+impl Root<SuperPoint<Dec>> {
+    /// Replaces sharp joints of consecutive lines with fillets of `radius`, which touch
+    /// both lines. Joint of two straight lines becomes circular arc, other joints - cubic
+    /// curves. When path ends, where it starts, its last joint is rounded too. At corners
+    /// between short lines radius is reduced, so no more than a third of line is cut
+    /// from each of its ends.
+    pub fn fillet_corners(self, radius: impl Into<Dec>) -> Self {
+        let radius = radius.into();
+        if self.items.is_empty() || radius <= Dec::zero() {
+            return self;
+        }
+        let lines = self.items.into_iter().collect_vec();
+        let count = lines.len();
+        let start = lines[0].0.first().expect("ok").point;
+        let end = lines[count - 1].0.last().expect("ok").point;
+        let closed = (start - end)
+            .magnitude_squared()
+            .round_dp(STABILITY_ROUNDING)
+            .is_zero();
+        let joints = (0..count)
+            .map(|ix| {
+                let next = (ix + 1) % count;
+                (closed || next != 0)
+                    .then(|| joint(&lines[ix], &lines[next], radius))
+                    .flatten()
+            })
+            .collect_vec();
+
+        let trimmed = lines
+            .iter()
+            .enumerate()
+            .map(|(ix, line)| {
+                let mut line = line.clone();
+                if let Some((cut, _)) = joints[ix] {
+                    line = line.split_hyper_line(Dec::one() - cut / line.length()).0;
+                }
+                if let Some((cut, _)) = joints[(ix + count - 1) % count] {
+                    line = line.split_hyper_line(cut / line.length()).1;
+                }
+                line
+            })
+            .collect_vec();
+
+        let mut result = Self::new();
+        for (ix, line) in trimmed.iter().enumerate() {
+            result.items.push_back(line.clone());
+            if let Some((cut, angle)) = joints[ix] {
+                let next = &trimmed[(ix + 1) % count];
+                result.items.push_back(fillet(line, next, cut, angle));
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use nalgebra::{ComplexField, Vector3};
+    use num_traits::Zero;
+
+    use crate::{
+        decimal::Dec,
+        hyper_path::{
+            hyper_line::HyperLine,
+            hyper_path::{HyperPath, Root},
+            hyper_point::SuperPoint,
+            length::Length,
+            line::GetT,
+        },
+    };
+
+    use super::{end_dir, start_dir};
+
+    fn point(x: i32, y: i32) -> SuperPoint<Dec> {
+        SuperPoint {
+            side_dir: Vector3::z(),
+            point: Vector3::new(Dec::from(x), Dec::from(y), Dec::zero()),
+        }
+    }
+
+    fn path(points: &[(i32, i32)]) -> Root<SuperPoint<Dec>> {
+        points
+            .iter()
+            .tuple_windows()
+            .fold(Root::new(), |root, ((ax, ay), (bx, by))| {
+                root.push_back(HyperLine::new_2(point(*ax, *ay), point(*bx, *by)))
+            })
+    }
+
+    /// Each line starts, where previous ends, and goes the same way
+    fn assert_smooth(path: &Root<SuperPoint<Dec>>, closed: bool) {
+        let lines = path.items.iter().collect_vec();
+        let pairs = lines.iter().circular_tuple_windows().take(if closed {
+            lines.len()
+        } else {
+            lines.len() - 1
+        });
+        for (a, b) in pairs {
+            let gap = a.0.last().unwrap().point - b.0.first().unwrap().point;
+            assert!(gap.magnitude() < Dec::from(1e-6), "gap {gap:?}");
+            assert!(end_dir(a).dot(&start_dir(b)) > Dec::from(0.9999));
+        }
+    }
+
+    #[test]
+    fn square_gets_round_corners() {
+        let square = path(&[(0, 0), (20, 0), (20, 20), (0, 20), (0, 0)]);
+
+        let rounded = square.fillet_corners(2);
+
+        assert_eq!(rounded.items.len(), 8);
+        assert_smooth(&rounded, true);
+        for line in rounded.items.iter().step_by(2) {
+            assert!((f64::from(line.length()) - 16.0).abs() < 1e-6);
+        }
+        let middle = rounded.items[1].get_t(Dec::from(0.5)).point;
+        let center = point(18, 2).point;
+        assert!(((middle - center).magnitude() - Dec::from(2)).abs() < Dec::from(1e-6));
+    }
+
+    #[test]
+    fn open_path_keeps_its_ends() {
+        let corner = path(&[(0, 0), (20, 0), (20, 20)]);
+
+        let rounded = corner.fillet_corners(2);
+
+        assert_eq!(rounded.items.len(), 3);
+        assert_smooth(&rounded, false);
+        assert_eq!(rounded.items[0].0[0].point, point(0, 0).point);
+        assert_eq!(
+            rounded.items[2].0.last().unwrap().point,
+            point(20, 20).point
+        );
+    }
+
+    #[test]
+    fn straight_joint_is_kept() {
+        let line = path(&[(0, 0), (10, 0), (20, 0)]);
+
+        assert_eq!(line.fillet_corners(2).items.len(), 2);
+    }
+
+    #[test]
+    fn radius_is_reduced_at_short_lines() {
+        let corner = path(&[(0, 0), (3, 0), (3, 3)]);
+
+        let rounded = corner.fillet_corners(10);
+
+        assert_eq!(rounded.items.len(), 3);
+        assert!((f64::from(rounded.items[0].length()) - 2.0).abs() < 1e-6);
+        assert!((f64::from(rounded.items[2].length()) - 2.0).abs() < 1e-6);
+    }
+}
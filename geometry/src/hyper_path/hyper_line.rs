@@ -4,8 +4,11 @@ use std::{
     ops::{Add, Div, Mul, Sub},
 };
 
-use nalgebra::Vector3;
+use anyhow::anyhow;
+use itertools::Itertools;
+use nalgebra::{ComplexField, Vector3};
 use num_traits::{One, Pow, Zero};
+use serde::{Deserialize, Serialize};
 
 use crate::parametric_iterator::ParametricIterator;
 
@@ -17,28 +20,45 @@ use super::{
     split_hyper_line::SplitHyperLine,
 };
 
-#[derive(Clone, PartialEq)]
-pub struct HyperLine<T>(pub(super) Vec<T>);
+/// Two control points make straight line, four - cubic bezier. When line has weights, it
+/// is rational bezier with a weight for each control point: circular arc is three points
+/// with weights `[1, cos(sweep / 2), 1]`.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "T: Serialize, T::Scalar: Serialize",
+    deserialize = "T: Deserialize<'de>, T::Scalar: Deserialize<'de>"
+))]
+pub struct HyperLine<T: Tensor>(
+    pub(super) Vec<T>,
+    #[serde(default)] pub(super) Option<Vec<T::Scalar>>,
+);
 
-impl<T: fmt::Debug> fmt::Debug for HyperLine<T> {
+impl<T: Tensor + fmt::Debug> fmt::Debug for HyperLine<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for i in 0..self.0.len() {
             write!(f, "\nHL {i}: {:?} ", self.0[i])?;
         }
+        if let Some(weights) = &self.1 {
+            write!(f, "\nHL weights {weights:?}")?;
+        }
         Ok(())
     }
 }
 
 impl<T> HyperLine<T>
 where
-    T: Clone,
+    T: Tensor,
 {
     pub fn new_2(a: T, b: T) -> Self {
-        Self([a, b].to_vec())
+        Self([a, b].to_vec(), None)
     }
 
     pub fn new_4(a: T, b: T, c: T, d: T) -> Self {
-        Self([a, b, c, d].to_vec())
+        Self([a, b, c, d].to_vec(), None)
+    }
+
+    pub fn is_rational(&self) -> bool {
+        self.1.is_some()
     }
 
     pub fn map<F>(mut self, map: F) -> Self
@@ -50,6 +70,52 @@ where
     }
 }
 
+impl<T> HyperLine<T>
+where
+    T: Tensor + Add<T, Output = T> + Sub<T, Output = T> + Mul<T::Scalar, Output = T>,
+    T::Scalar: ComplexField,
+{
+    /// Circular arc around `center` from `start` to `end`, which are at the same distance
+    /// from it, `sweep` is angle between them in radians. Sweep must be less than half of
+    /// turn, so full circle is made of three arcs at least.
+    pub fn new_arc(start: T, center: T, end: T, sweep: T::Scalar) -> Self {
+        let one = T::Scalar::one();
+        let two = one + one;
+        let w = (sweep / two).cos();
+        let control = center + ((start - center) + (end - center)) * (one / (two * w * w));
+        Self([start, control, end].to_vec(), Some([one, w, one].to_vec()))
+    }
+}
+
+impl<T> HyperLine<T>
+where
+    T: Tensor + Mul<T::Scalar, Output = T> + Sub<T, Output = T>,
+{
+    /// Blends control points of two lines of same order
+    pub fn lerp(&self, other: &Self, t: T::Scalar) -> anyhow::Result<Self> {
+        if self.0.len() != other.0.len() {
+            return Err(anyhow!(
+                "Cannot blend lines with different amount of points: {} <> {}",
+                self.0.len(),
+                other.0.len()
+            ));
+        }
+        let weights = match (&self.1, &other.1) {
+            (None, None) => None,
+            (Some(a), Some(b)) => Some(a.iter().zip(b).map(|(a, b)| *a + (*b - *a) * t).collect()),
+            _ => return Err(anyhow!("Cannot blend rational line with bezier line")),
+        };
+        Ok(Self(
+            self.0
+                .iter()
+                .zip(&other.0)
+                .map(|(a, b)| *a + (*b - *a) * t)
+                .collect(),
+            weights,
+        ))
+    }
+}
+
 pub trait ShiftInPlane {
     type Scalar;
     fn shift_in_plane(self, normal: Vector3<Self::Scalar>, amount: Self::Scalar) -> Self;
@@ -84,6 +150,18 @@ where
         let ext_b = dir_b.cross(&normal);
         let ext_e = dir_e.cross(&normal);
 
+        if let (Some(_), [start, control, end]) = (&self.1, self.0.as_mut_slice()) {
+            // Middle point goes along bisector to the crossing of shifted tangents, so
+            // shifted arc is concentric with this one and weights stay the same
+            let ext_b = (control.point() - start.point()).normalize().cross(&normal);
+            let ext_e = (end.point() - control.point()).normalize().cross(&normal);
+            let miter =
+                (ext_b + ext_e) * (T::Scalar::one() / (T::Scalar::one() + ext_b.dot(&ext_e)));
+            start.set_point(start.point() + ext_b * amount);
+            control.set_point(control.point() + miter * amount);
+            end.set_point(end.point() + ext_e * amount);
+            return self;
+        }
         if self.0.len() % 2 == 0 {
             let center = self.0.len() / 2;
             for (ix, p) in self.0.iter_mut().enumerate() {
@@ -137,16 +215,19 @@ where
 
 impl<S, T> SplitHyperLine<S> for HyperLine<T>
 where
+    T: Tensor<Scalar = S>,
     T: Sub<T, Output = T>,
     T: Add<T, Output = T>,
     T: Mul<S, Output = T>,
-    T: Copy,
     S: Copy,
 {
     fn split_hyper_line(&self, t: S) -> (Self, Self)
     where
         Self: Sized,
     {
+        if let Some(weights) = &self.1 {
+            return split_rational(&self.0, weights, t);
+        }
         match self.0.len() {
             2 => {
                 let v = self.0[1] - self.0[0];
@@ -174,18 +255,58 @@ where
     }
 }
 
-impl<S> HyperLine<SuperPoint<S>> {
+impl<S> HyperLine<SuperPoint<S>>
+where
+    SuperPoint<S>: Tensor<Scalar = S>,
+    Vector3<S>: Tensor<Scalar = S>,
+{
     pub fn to_points(self) -> HyperLine<Vector3<S>> {
-        HyperLine(self.0.into_iter().map(|sp| sp.point).collect())
+        HyperLine(self.0.into_iter().map(|sp| sp.point).collect(), self.1)
     }
 }
 
-impl<T> IsLinear for HyperLine<T> {
+impl<T: Tensor> IsLinear for HyperLine<T> {
     fn is_linear(&self) -> bool {
         self.0.len() == 2
     }
 }
 
+/// De Casteljau algorithm for rational line: points are multiplied by their weights, so
+/// they are split as usual, and divided by new weights after it
+fn split_rational<T>(
+    points: &[T],
+    weights: &[T::Scalar],
+    t: T::Scalar,
+) -> (HyperLine<T>, HyperLine<T>)
+where
+    T: Tensor + Sub<T, Output = T> + Add<T, Output = T> + Mul<T::Scalar, Output = T>,
+{
+    let mut level = points
+        .iter()
+        .zip(weights)
+        .map(|(p, w)| (*p * *w, *w))
+        .collect::<Vec<_>>();
+    let (mut first, mut second) = (Vec::new(), Vec::new());
+    while let (Some(head), Some(tail)) = (level.first(), level.last()) {
+        first.push(*head);
+        second.push(*tail);
+        level = level
+            .iter()
+            .tuple_windows()
+            .map(|((p, w), (pp, ww))| (lerp(p, pp, t), *w + (*ww - *w) * t))
+            .collect();
+    }
+    second.reverse();
+    let line = |items: Vec<(T, T::Scalar)>| {
+        let (points, weights) = items
+            .into_iter()
+            .map(|(p, w)| (p * (T::Scalar::one() / w), w))
+            .unzip();
+        HyperLine(points, Some(weights))
+    };
+    (line(first), line(second))
+}
+
 fn lerp<T, S>(one: &T, other: &T, t: S) -> T
 where
     T: Sub<T, Output = T> + Add<T, Output = T> + Mul<S, Output = T> + Copy,
@@ -3,7 +3,9 @@ use std::{
     ops::{Mul, Sub},
 };
 
+use anyhow::anyhow;
 use num_traits::{One, Zero};
+use serde::{Deserialize, Serialize};
 
 use super::{hyper_line::HyperLine, hyper_point::Tensor, length::Length};
 
@@ -11,12 +13,16 @@ pub trait IsLinear {
     fn is_linear(&self) -> bool;
 }
 
-#[derive(Clone)]
-pub struct Root<Tensor> {
-    items: VecDeque<HyperLine<Tensor>>,
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "T: Serialize, T::Scalar: Serialize",
+    deserialize = "T: Deserialize<'de>, T::Scalar: Deserialize<'de>"
+))]
+pub struct Root<T: Tensor> {
+    pub(super) items: VecDeque<HyperLine<T>>,
 }
 
-impl<T> Default for Root<T> {
+impl<T: Tensor> Default for Root<T> {
     fn default() -> Self {
         Self {
             items: Default::default(),
@@ -24,7 +30,7 @@ impl<T> Default for Root<T> {
     }
 }
 
-impl<Tensor> Root<Tensor> {
+impl<T: Tensor> Root<T> {
     pub fn new() -> Self {
         Self {
             items: VecDeque::new(),
@@ -32,8 +38,31 @@ impl<Tensor> Root<Tensor> {
     }
 }
 
+impl<T> Root<T>
+where
+    T: Tensor + Mul<<T as Tensor>::Scalar, Output = T> + Sub<T, Output = T>,
+{
+    /// Blends two paths of same structure point by point
+    pub fn lerp(&self, other: &Self, t: <T as Tensor>::Scalar) -> anyhow::Result<Self> {
+        if self.items.len() != other.items.len() {
+            return Err(anyhow!(
+                "Cannot blend paths with different amount of lines: {} <> {}",
+                self.items.len(),
+                other.items.len()
+            ));
+        }
+        let items = self
+            .items
+            .iter()
+            .zip(&other.items)
+            .map(|(a, b)| a.lerp(b, t))
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self { items })
+    }
+}
+
 #[allow(clippy::len_without_is_empty)]
-pub trait HyperPath<T>: Sized + Length {
+pub trait HyperPath<T: Tensor>: Sized + Length {
     fn push_back(self, h: HyperLine<T>) -> Self;
 
     fn connect_ends(&mut self);
@@ -42,7 +71,7 @@ pub trait HyperPath<T>: Sized + Length {
 
     fn extend(self, h: impl IntoIterator<Item = HyperLine<T>>) -> Self;
 
-    fn map<F, R>(self, map: F) -> Root<R>
+    fn map<F, R: Tensor>(self, map: F) -> Root<R>
     where
         F: Fn(HyperLine<T>) -> HyperLine<R>;
 
@@ -84,7 +113,7 @@ where
         self
     }
 
-    fn map<F, R>(self, map: F) -> Root<R>
+    fn map<F, R: Tensor>(self, map: F) -> Root<R>
     where
         F: Fn(HyperLine<T>) -> HyperLine<R>,
     {
@@ -6,6 +6,7 @@ use std::{
 
 use nalgebra::{Dim, Matrix, Storage, Vector3};
 use num_traits::{One, Pow, Zero};
+use serde::{Deserialize, Serialize};
 
 use super::length::Length;
 
@@ -77,7 +78,11 @@ pub struct HyperPointT<T> {
     pub point: Vector3<T>,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "Vector3<T>: Serialize",
+    deserialize = "Vector3<T>: Deserialize<'de>"
+))]
 pub struct SuperPoint<T> {
     pub side_dir: Vector3<T>,
     pub point: Vector3<T>,
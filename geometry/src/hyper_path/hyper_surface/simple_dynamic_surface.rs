@@ -21,9 +21,9 @@ use num_traits::Pow;
 
 use super::primitive_dynamic_surface::PrimitiveSurface;
 
-pub struct SimpleSurface<S, T>(pub HyperLine<T>, pub HyperLine<T>, PhantomData<S>);
+pub struct SimpleSurface<S, T: Tensor>(pub HyperLine<T>, pub HyperLine<T>, PhantomData<S>);
 
-impl<S, T> SimpleSurface<S, T> {
+impl<S, T: Tensor> SimpleSurface<S, T> {
     pub fn new(l: HyperLine<T>, s: HyperLine<T>) -> Self {
         Self(l, s, PhantomData)
     }
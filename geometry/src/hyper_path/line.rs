@@ -2,7 +2,7 @@ use std::fmt;
 use std::ops::{Mul, Sub};
 
 use itertools::Itertools;
-use num_traits::{One, Pow};
+use num_traits::{One, Pow, Zero};
 
 use super::hyper_line::HyperLine;
 use super::hyper_point::Tensor;
@@ -25,6 +25,14 @@ where
 where {
         let o = self.0.len();
         let ws = (0..o).map(|i| bernstein::<_>(i, o, t));
+        if let Some(weights) = &self.1 {
+            let ws = ws.zip(weights).map(|(b, w)| b * *w).collect_vec();
+            let total = ws.iter().fold(T::Scalar::zero(), |a, w| a + *w);
+            return ws
+                .into_iter()
+                .zip(&self.0)
+                .fold(T::zero(), |a, (w, t)| a + *t * (w / total));
+        }
         let v: Vec<T> = ws.zip(&self.0).map(|(w, t)| *t * w).collect_vec();
         v.into_iter().fold(T::zero(), |a, t| a + t)
     }
@@ -54,9 +62,15 @@ const fn fact(i: usize) -> usize {
 #[cfg(test)]
 mod tests {
     use itertools::Itertools;
+    use nalgebra::{ComplexField, Vector3};
     use num_traits::{One, Zero};
 
-    use crate::decimal::Dec;
+    use crate::{
+        decimal::Dec,
+        hyper_path::{hyper_line::HyperLine, split_hyper_line::SplitHyperLine},
+    };
+
+    use super::GetT;
 
     #[test]
     fn bernstein() {
@@ -68,4 +82,57 @@ mod tests {
         let ws = (0..4).map(|i| super::bernstein::<_>(i, 4, t)).collect_vec();
         assert_eq!(ws, vec![Dec::zero(), Dec::zero(), Dec::zero(), Dec::one(),]);
     }
+
+    #[test]
+    fn arc_stays_on_circle() {
+        let arc = HyperLine::new_arc(
+            Vector3::new(Dec::from(2), Dec::zero(), Dec::zero()),
+            Vector3::zeros(),
+            Vector3::new(Dec::zero(), Dec::from(2), Dec::zero()),
+            Dec::from(std::f64::consts::FRAC_PI_2),
+        );
+        let on_circle = |p: Vector3<Dec>| (p.magnitude() - Dec::from(2)).abs() < Dec::from(1e-6);
+        for i in 0..=10 {
+            assert!(on_circle(arc.get_t(Dec::from(i) / Dec::from(10))));
+        }
+        let middle = arc.get_t(Dec::one() / Dec::from(2));
+        assert!((middle.x - middle.y).abs() < Dec::from(1e-6));
+
+        let t = Dec::from(3) / Dec::from(10);
+        let (first, second) = arc.split_hyper_line(t);
+        let split = arc.get_t(t);
+        assert!((first.get_t(Dec::one()) - split).magnitude() < Dec::from(1e-6));
+        assert!((second.get_t(Dec::zero()) - split).magnitude() < Dec::from(1e-6));
+        for i in 0..=10 {
+            let t = Dec::from(i) / Dec::from(10);
+            assert!(on_circle(first.get_t(t)));
+            assert!(on_circle(second.get_t(t)));
+        }
+    }
+
+    #[test]
+    fn split_halves_of_arc_stay_on_its_circle() {
+        let center = Vector3::new(Dec::from(3), Dec::one(), Dec::zero());
+        let at = |angle: f64| {
+            center
+                + Vector3::new(Dec::from(angle.cos()), Dec::from(angle.sin()), Dec::zero())
+                    * Dec::from(5)
+        };
+        let sweep = 2.0 * std::f64::consts::FRAC_PI_3;
+        let arc = HyperLine::new_arc(at(0.0), center, at(sweep), Dec::from(sweep));
+        let on_circle =
+            |p: Vector3<Dec>| ((p - center).magnitude() - Dec::from(5)).abs() < Dec::from(1e-6);
+
+        let half = Dec::one() / Dec::from(2);
+        let (first, second) = arc.split_hyper_line(half);
+        // Halves of halves have scaled weights, they must be exact too
+        let (first_quarter, second_quarter) = first.split_hyper_line(half);
+        for line in [&first, &second, &first_quarter, &second_quarter] {
+            for i in 0..=10 {
+                assert!(on_circle(line.get_t(Dec::from(i) / Dec::from(10))));
+            }
+        }
+        assert!((first.get_t(Dec::one()) - at(sweep / 2.0)).magnitude() < Dec::from(1e-6));
+        assert!((second_quarter.get_t(Dec::one()) - at(sweep / 2.0)).magnitude() < Dec::from(1e-6));
+    }
 }
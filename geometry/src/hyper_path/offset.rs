@@ -0,0 +1,220 @@
+use std::ops::{Mul, Sub};
+
+use nalgebra::{ComplexField, Vector3};
+use num_traits::{One, Zero};
+
+use super::{
+    hyper_line::{HyperLine, ShiftInPlane},
+    hyper_path::Root,
+    hyper_point::{Point, Tensor},
+    line::GetT,
+};
+
+/// Miter, which goes further, than this many offset distances, is replaced by midpoint
+const MITER_LIMIT: u16 = 4;
+
+/// Center and radius of line, when it is circular arc
+fn arc_circle<T>(line: &HyperLine<T>) -> Option<(Vector3<T::Scalar>, T::Scalar)>
+where
+    T: Tensor + Point<Vector = Vector3<<T as Tensor>::Scalar>>,
+    <T as Tensor>::Scalar: From<u16> + nalgebra::RealField,
+{
+    let (Some(weights), [a, control, b]) = (&line.1, line.0.as_slice()) else {
+        return None;
+    };
+    let one = T::Scalar::one();
+    // Weights of split arc are scaled, in standard form end weights are ones
+    let w2 = weights[1] * weights[1] / (weights[0] * weights[2]);
+    if w2 >= one {
+        return None;
+    }
+    let middle = (a.point() + b.point()) / T::Scalar::from(2);
+    let center = (middle - control.point() * w2) / (one - w2);
+    Some((center, (a.point() - center).norm()))
+}
+
+fn onto_circle<S>(point: Vector3<S>, (center, radius): (Vector3<S>, S)) -> Vector3<S>
+where
+    S: nalgebra::RealField + Copy,
+{
+    center + (point - center).normalize() * radius
+}
+
+/// Crossing of two circles in the plane with `normal`, which is the closest to `near`
+fn circles_crossing<S>(
+    (c1, r1): (Vector3<S>, S),
+    (c2, r2): (Vector3<S>, S),
+    normal: Vector3<S>,
+    near: Vector3<S>,
+) -> Option<Vector3<S>>
+where
+    S: From<u16> + nalgebra::RealField + Copy,
+{
+    let d = (c2 - c1).norm();
+    if d.is_zero() {
+        return None;
+    }
+    let along = (r1 * r1 - r2 * r2 + d * d) / (S::from(2) * d);
+    let h2 = r1 * r1 - along * along;
+    if h2.is_negative() {
+        return None;
+    }
+    let dir = (c2 - c1) / d;
+    let base = c1 + dir * along;
+    let side = normal.cross(&dir) * h2.sqrt();
+    let (p1, p2) = (base + side, base - side);
+    Some(if (p1 - near).norm() <= (p2 - near).norm() {
+        p1
+    } else {
+        p2
+    })
+}
+
+/// Moves end point of line with index `end` to `to`. Bezier handle next to it is moved
+/// along, so direction of line at the end stays the same. Arc is built again around its
+/// center, so it keeps radius and its end goes to the point of circle nearest to `to`.
+fn move_end<T>(line: &mut HyperLine<T>, end: usize, to: Vector3<T::Scalar>)
+where
+    T: Tensor + Point<Vector = Vector3<<T as Tensor>::Scalar>>,
+    <T as Tensor>::Scalar: From<u16> + nalgebra::RealField,
+{
+    if let Some(circle) = arc_circle(line) {
+        let (center, radius) = circle;
+        let one = T::Scalar::one();
+        line.0[end].set_point(onto_circle(to, circle));
+        let (a, b) = (line.0[0].point() - center, line.0[2].point() - center);
+        let cos = a.dot(&b) / (radius * radius);
+        let w = ((one + cos) / T::Scalar::from(2)).sqrt();
+        line.0[1].set_point(center + (a + b) / (T::Scalar::from(2) * w * w));
+        line.1 = Some(vec![one, w, one]);
+        return;
+    }
+    let delta = to - line.0[end].point();
+    if line.0.len() == 4 {
+        let handle = if end == 0 { 1 } else { 2 };
+        let h = line.0[handle].point();
+        line.0[handle].set_point(h + delta);
+    }
+    line.0[end].set_point(to);
+}
+
+impl<T> Root<T>
+where
+    T: Tensor
+        + Mul<<T as Tensor>::Scalar, Output = T>
+        + Sub<T, Output = T>
+        + Point<Vector = Vector3<<T as Tensor>::Scalar>>,
+    <T as Tensor>::Scalar: From<u16> + nalgebra::RealField,
+{
+    /// Closed path at `distance` from this one in the plane with `normal`. Positive distance
+    /// moves lines to the right of their direction, when looking against `normal`, so inside
+    /// of clockwise outline. Shifted lines are joined in crossing of their tangents, so
+    /// straight lines keep sharp corners, too long miters are cut to the middle of the gap.
+    /// Arcs keep their centers, so they are joined on their circles.
+    pub fn offset(self, normal: Vector3<T::Scalar>, distance: T::Scalar) -> Self {
+        let one = T::Scalar::one();
+        let delta = one / T::Scalar::from(65535);
+        let half = one / T::Scalar::from(2);
+        let mut items = self
+            .items
+            .into_iter()
+            .map(|l| l.shift_in_plane(normal, distance))
+            .collect::<Vec<_>>();
+
+        let count = items.len();
+        for cur in 0..count {
+            let next = (cur + 1) % count;
+            let end = items[cur].get_t(one).point();
+            let end_dir = (end - items[cur].get_t(one - delta).point()).normalize();
+            let start = items[next].get_t(T::Scalar::zero()).point();
+            let start_dir = (items[next].get_t(delta).point() - start).normalize();
+
+            let gap = start - end;
+            let middle = end + gap * half;
+            let turn = end_dir.cross(&start_dir).dot(&normal);
+            let corner = if turn.abs() > delta {
+                let miter = end + end_dir * (gap.cross(&start_dir).dot(&normal) / turn);
+                if (miter - middle).norm() <= distance.abs() * T::Scalar::from(MITER_LIMIT) {
+                    miter
+                } else {
+                    middle
+                }
+            } else {
+                middle
+            };
+
+            let corner = match (arc_circle(&items[cur]), arc_circle(&items[next])) {
+                (None, None) => corner,
+                (Some(circle), None) | (None, Some(circle)) => onto_circle(corner, circle),
+                (Some(a), Some(b)) => circles_crossing(a, b, normal, corner).unwrap_or(corner),
+            };
+
+            let last = items[cur].0.len() - 1;
+            move_end(&mut items[cur], last, corner);
+            move_end(&mut items[next], 0, corner);
+        }
+        Self {
+            items: items.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{ComplexField, Vector3};
+    use num_traits::{One, Zero};
+
+    use crate::{
+        decimal::Dec,
+        hyper_path::{
+            hyper_line::HyperLine,
+            hyper_path::{HyperPath, Root},
+            hyper_point::SuperPoint,
+            line::GetT,
+        },
+    };
+
+    fn at(x: i32, y: i32) -> SuperPoint<Dec> {
+        SuperPoint {
+            side_dir: Vector3::zeros(),
+            point: Vector3::new(Dec::from(x), Dec::from(y), Dec::zero()),
+        }
+    }
+
+    #[test]
+    fn offset_square_keeps_sharp_corners() {
+        let corners = [at(0, 0), at(0, 10), at(10, 10), at(10, 0)];
+        let square = (0..4)
+            .map(|ix| HyperLine::new_2(corners[ix], corners[(ix + 1) % 4]))
+            .fold(Root::new(), |hp, l| hp.push_back(l));
+
+        let inner = square.offset(Vector3::z(), Dec::from(2));
+        let expected = [at(2, 2), at(2, 8), at(8, 8), at(8, 2)];
+        for (line, corner) in inner.items.iter().zip(expected) {
+            assert!((line.0[0].point - corner.point).magnitude() < Dec::from(1e-6));
+        }
+    }
+
+    #[test]
+    fn offset_circle_keeps_center() {
+        let corners = [at(10, 0), at(0, 10), at(-10, 0), at(0, -10)];
+        let quarter = Dec::from(std::f64::consts::FRAC_PI_2);
+        let circle = (0..4)
+            .map(|ix| HyperLine::new_arc(corners[ix], at(0, 0), corners[(ix + 1) % 4], quarter))
+            .fold(Root::new(), |hp, l| hp.push_back(l));
+
+        let shifted = circle.offset(Vector3::z(), Dec::from(2));
+        let radius = shifted.items[0].0[0].point.magnitude();
+        assert!(
+            (radius - Dec::from(8)).abs() < Dec::from(1e-6)
+                || (radius - Dec::from(12)).abs() < Dec::from(1e-6)
+        );
+        for line in &shifted.items {
+            for i in 0..=8 {
+                let p = line.get_t(Dec::from(i) / Dec::from(8)).point;
+                assert!((p.magnitude() - radius).abs() < Dec::from(1e-6));
+            }
+            assert!(line.get_t(Dec::one()).point.z.is_zero());
+        }
+    }
+}
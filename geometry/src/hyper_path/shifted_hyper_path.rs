@@ -14,7 +14,7 @@ use super::{
     length::Length,
 };
 
-pub struct ShiftedSideHyperPath<S, T, Hp: HyperPath<T>> {
+pub struct ShiftedSideHyperPath<S, T: Tensor, Hp: HyperPath<T>> {
     root: Hp,
     shift: S,
     normal: Vector3<S>,
@@ -77,7 +77,7 @@ where
         todo!()
     }
 
-    fn map<F, R>(self, _map: F) -> super::hyper_path::Root<R>
+    fn map<F, R: Tensor>(self, _map: F) -> super::hyper_path::Root<R>
     where
         F: Fn(HyperLine<T>) -> HyperLine<R>,
     {
@@ -87,6 +87,7 @@ where
 
 impl<S, T, Hp> Length for ShiftedSideHyperPath<S, T, Hp>
 where
+    T: Tensor,
     Hp: HyperPath<T>,
     Hp: Length<Scalar = S>,
 {
@@ -97,7 +98,7 @@ where
     }
 }
 
-impl<S, T, Hp: HyperPath<T>> ShiftedSideHyperPath<S, T, Hp>
+impl<S, T: Tensor, Hp: HyperPath<T>> ShiftedSideHyperPath<S, T, Hp>
 where
     T: SideDir,
 {
@@ -0,0 +1,48 @@
+use std::ops::{Add, Mul, Sub};
+
+use anyhow::anyhow;
+use itertools::Itertools;
+use num_traits::One;
+
+use super::{hyper_line::HyperLine, hyper_path::Root, hyper_point::Tensor};
+
+impl<T> Root<T>
+where
+    T: Tensor + Add<T, Output = T> + Sub<T, Output = T> + Mul<<T as Tensor>::Scalar, Output = T>,
+    <T as Tensor>::Scalar: From<u16>,
+{
+    /// Smooth closed path, which goes through `points` in their order, made of cubic
+    /// lines. Each point has its tension: zero gives Catmull-Rom curve, one makes sharp
+    /// corner in the point, values between make path tighter around the point.
+    pub fn closed_spline(
+        points: impl IntoIterator<Item = (T, <T as Tensor>::Scalar)>,
+    ) -> anyhow::Result<Self> {
+        let points = points.into_iter().collect_vec();
+        if points.len() < 3 {
+            return Err(anyhow!(
+                "Closed spline needs 3 points at least, got {}",
+                points.len()
+            ));
+        }
+        let six = <T as Tensor>::Scalar::from(6);
+        // Tangent of cardinal spline is `(1 - tension) * (next - prev) / 2`, bezier handle
+        // is third of it
+        let handle = |ix: usize| {
+            let count = points.len();
+            let (prev, next) = (
+                points[(ix + count - 1) % count].0,
+                points[(ix + 1) % count].0,
+            );
+            let (_, tension) = points[ix];
+            (next - prev) * ((<T as Tensor>::Scalar::one() - tension) / six)
+        };
+        let items = (0..points.len())
+            .map(|ix| {
+                let next = (ix + 1) % points.len();
+                let (a, b) = (points[ix].0, points[next].0);
+                HyperLine::new_4(a, a + handle(ix), b - handle(next), b)
+            })
+            .collect();
+        Ok(Self { items })
+    }
+}
@@ -1,6 +1,7 @@
 use nalgebra::Vector3;
 use num_traits::{Bounded, Pow, Zero};
 use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
 
 use crate::{decimal::Dec, primitives_relation::relation::Relation};
 
@@ -9,7 +10,7 @@ use super::{
     sphere::Sphere,
 };
 
-#[derive(Debug, Clone, Default, Copy, PartialEq)]
+#[derive(Debug, Clone, Default, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Aabb {
     pub(crate) min: Vector3<Dec>,
     pub(crate) max: Vector3<Dec>,
@@ -172,7 +173,51 @@ impl<T: Clone> Relation<Node<T>> for Aabb {
 impl Relation<Aabb> for Aabb {
     type Relate = BoundRelation;
 
-    fn relate(&self, _to: &Aabb) -> Self::Relate {
-        todo!("implement aabb <> aabb");
+    fn relate(&self, to: &Aabb) -> Self::Relate {
+        if self.min.x <= to.max.x
+            && self.max.x >= to.min.x
+            && self.min.y <= to.max.y
+            && self.max.y >= to.min.y
+            && self.min.z <= to.max.z
+            && self.max.z >= to.min.z
+        {
+            BoundRelation::Intersects
+        } else {
+            BoundRelation::Unrelated
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector3;
+
+    use crate::{
+        decimal::Dec, indexes::octree::BoundRelation, primitives_relation::relation::Relation,
+    };
+
+    use super::Aabb;
+
+    fn cube(from: i32, to: i32) -> Aabb {
+        Aabb::from_points(&[
+            Vector3::repeat(Dec::from(from)),
+            Vector3::repeat(Dec::from(to)),
+        ])
+    }
+
+    #[test]
+    fn aabb_relates_to_aabb() {
+        assert!(matches!(
+            cube(0, 2).relate(&cube(1, 3)),
+            BoundRelation::Intersects
+        ));
+        assert!(matches!(
+            cube(0, 2).relate(&cube(2, 3)),
+            BoundRelation::Intersects
+        ));
+        assert!(matches!(
+            cube(0, 1).relate(&cube(2, 3)),
+            BoundRelation::Unrelated
+        ));
     }
 }
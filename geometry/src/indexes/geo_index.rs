@@ -1,8 +1,22 @@
+pub mod alignment_pins;
+pub mod boolean_report;
+pub mod clearance;
+pub mod coplanar_merge;
+pub mod decimation;
+pub mod error;
 pub mod face;
+pub mod face_origin;
+pub mod fillet;
 pub mod geo_object;
+pub mod hinge;
 pub mod index;
+pub mod living_hinge;
 pub mod mesh;
 pub mod poly;
 pub mod poly_rtree;
 pub mod rib;
 pub mod seg;
+pub mod slice;
+pub mod three_mf;
+pub mod topology;
+pub mod validation;
@@ -0,0 +1,113 @@
+use rust_decimal_macros::dec;
+
+use crate::{decimal::Dec, geometry::GeometryDyn, origin::Origin, shapes::Cylinder};
+
+use super::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId};
+
+/// Alignment pins (dowels) between two mating parts. Each position is an origin on the mating
+/// surface, with z axis pointing from `mesh_a` into `mesh_b`. Pin is centered at the origin, so
+/// half of its length is fused into `mesh_a`, and other half sticks into the hole of `mesh_b`.
+/// Holes are wider and deeper than pins by `clearance`, so parts can be printed separately
+/// and still fit together.
+#[derive(Clone, Debug)]
+pub struct AlignmentPins {
+    mesh_a: MeshId,
+    mesh_b: MeshId,
+    positions: Vec<Origin>,
+    radius: Dec,
+    length: Dec,
+    clearance: Dec,
+    steps: usize,
+}
+
+impl AlignmentPins {
+    pub fn between(mesh_a: MeshId, mesh_b: MeshId, positions: Vec<Origin>) -> Self {
+        Self {
+            mesh_a,
+            mesh_b,
+            positions,
+            radius: dec!(1.5).into(),
+            length: dec!(4).into(),
+            clearance: dec!(0.15).into(),
+            steps: 16,
+        }
+    }
+
+    pub fn radius(mut self, radius: impl Into<Dec>) -> Self {
+        self.radius = radius.into();
+        self
+    }
+
+    /// Full length of pin. Both parts should be thicker than half of it.
+    pub fn length(mut self, length: impl Into<Dec>) -> Self {
+        self.length = length.into();
+        self
+    }
+
+    /// Gap between pin and hole walls, and between pin end and hole bottom
+    pub fn clearance(mut self, clearance: impl Into<Dec>) -> Self {
+        self.clearance = clearance.into();
+        self
+    }
+
+    /// Amount of sides of pin and hole
+    pub fn steps(mut self, steps: usize) -> Self {
+        self.steps = steps.max(3);
+        self
+    }
+
+    fn pin(&self, position: &Origin) -> Cylinder {
+        Cylinder::centered(position.clone(), self.length, self.radius).steps(self.steps)
+    }
+
+    fn hole(&self, position: &Origin) -> Cylinder {
+        Cylinder::centered(
+            position.clone(),
+            self.length + self.clearance * 2,
+            self.radius + self.clearance,
+        )
+        .steps(self.steps)
+    }
+}
+
+impl GeoIndex {
+    /// Cuts holes in `mesh_b` and adds pins to `mesh_a`. Both meshes must be in this index,
+    /// parts from different indexes can be handled with `add_alignment_pins` and
+    /// `cut_alignment_holes`.
+    pub fn alignment_pins(&mut self, pins: &AlignmentPins) -> anyhow::Result<()> {
+        self.cut_alignment_holes(pins.mesh_b, pins)?;
+        self.add_alignment_pins(pins.mesh_a, pins)
+    }
+
+    /// Fuses pins into `mesh_id`
+    pub fn add_alignment_pins(
+        &mut self,
+        mesh_id: MeshId,
+        pins: &AlignmentPins,
+    ) -> anyhow::Result<()> {
+        for position in &pins.positions {
+            let pin_mesh = self.new_mesh();
+            pins.pin(position)
+                .polygonize(pin_mesh.make_mut_ref(self), 0)?;
+            self.unite_mesh(mesh_id, pin_mesh)?;
+        }
+
+        Ok(())
+    }
+
+    /// Cuts holes for pins in `mesh_id`
+    pub fn cut_alignment_holes(
+        &mut self,
+        mesh_id: MeshId,
+        pins: &AlignmentPins,
+    ) -> anyhow::Result<()> {
+        for position in &pins.positions {
+            let hole_mesh = self.new_mesh();
+            pins.hole(position)
+                .polygonize(hole_mesh.make_mut_ref(self), 0)?;
+            self.subtract_mesh(mesh_id, hole_mesh)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,248 @@
+use std::{fs, path::PathBuf};
+
+use itertools::Itertools;
+use nalgebra::{Vector2, Vector3};
+use num_traits::Zero;
+use stl_io::{Triangle, Vector};
+
+use crate::{decimal::Dec, log::warning, planar::triangulation};
+
+use super::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId, poly::UnrefPoly};
+
+/// Everything, which is needed to reproduce a boolean operation, collected before it
+/// runs. Polygons of inputs are kept as points, because operation removes and flips them.
+pub(super) struct BooleanInputs {
+    op: &'static str,
+    meshes: [MeshId; 2],
+    polygons: [Vec<Vec<Vector3<Dec>>>; 2],
+    open_segments: usize,
+    journal: Vec<String>,
+}
+
+impl BooleanInputs {
+    /// Records step of the operation with polygons, it selected
+    pub(super) fn step(&mut self, name: &str, polygons: &[UnrefPoly]) {
+        self.journal.push(format!(
+            "{name}: {} polygons [{}]",
+            polygons.len(),
+            polygons
+                .iter()
+                .map(|p| format!("{}:{:?}", p.mesh_id.0, p.poly_id))
+                .join(", ")
+        ));
+    }
+}
+
+impl GeoIndex {
+    /// Inputs of boolean operation `op` on meshes `a` and `b`, if failure reports are
+    /// enabled
+    pub(super) fn boolean_inputs(
+        &self,
+        op: &'static str,
+        a: MeshId,
+        b: MeshId,
+    ) -> Option<BooleanInputs> {
+        if !self.boolean_failure_reports {
+            return None;
+        }
+        let polygons = [a, b].map(|mesh_id| {
+            self.get_mesh_polygons(mesh_id)
+                .into_iter()
+                .map(|p| p.make_ref(self).segments().map(|s| s.from()).collect_vec())
+                .collect_vec()
+        });
+        Some(BooleanInputs {
+            op,
+            meshes: [a, b],
+            polygons,
+            open_segments: self.open_segments(a) + self.open_segments(b),
+            journal: Vec::new(),
+        })
+    }
+
+    /// Writes failure bundle into debug path, if `result` of operation has more open
+    /// edges, than its inputs had together
+    pub(super) fn report_boolean(&mut self, inputs: Option<BooleanInputs>, result: MeshId) {
+        let Some(inputs) = inputs else {
+            return;
+        };
+        let open_segments = self.open_segments(result);
+        if open_segments <= inputs.open_segments {
+            return;
+        }
+        self.boolean_counter += 1;
+        let dir = self
+            .debug_path
+            .join(format!("boolean-{:03}-{}", self.boolean_counter, inputs.op));
+        match self.write_boolean_bundle(&dir, &inputs, result, open_segments) {
+            Ok(()) => warning!(
+                "Boolean {} left {open_segments} open edges, see {dir:?}",
+                inputs.op
+            ),
+            Err(e) => warning!("Cannot write boolean failure bundle to {dir:?}: {e}"),
+        }
+    }
+
+    fn open_segments(&self, mesh_id: MeshId) -> usize {
+        if !self.meshes.contains_key(&mesh_id) {
+            return 0;
+        }
+        self.get_mesh(mesh_id)
+            .boundary_loops()
+            .iter()
+            .map(|l| l.len())
+            .sum()
+    }
+
+    fn write_boolean_bundle(
+        &self,
+        dir: &PathBuf,
+        inputs: &BooleanInputs,
+        result: MeshId,
+        open_segments: usize,
+    ) -> anyhow::Result<()> {
+        fs::create_dir_all(dir)?;
+        for (name, polygons) in ["a.stl", "b.stl"].into_iter().zip(&inputs.polygons) {
+            let mut file = fs::File::create(dir.join(name))?;
+            stl_io::write_stl(&mut file, stl_triangles(polygons).iter())?;
+        }
+
+        let [a, b] = inputs.meshes;
+        fs::write(
+            dir.join("settings.txt"),
+            [
+                format!("operation: {}", inputs.op),
+                format!("meshes: a = {}, b = {}, result = {}", a.0, b.0, result.0),
+                format!("points_precision: {}", self.points_precision),
+                format!(
+                    "input_polygon_min_rib_length: {}",
+                    self.input_polygon_min_rib_length
+                ),
+                format!("open edges before: {}", inputs.open_segments),
+                format!("open edges after: {open_segments}"),
+            ]
+            .join("\n"),
+        )?;
+        fs::write(dir.join("journal.txt"), inputs.journal.join("\n"))?;
+        fs::write(dir.join("open-edges.svg"), self.open_edges_svg(result))?;
+        Ok(())
+    }
+
+    /// Open edges of mesh in red and polygons, which they belong to, in gray. Mesh is seen
+    /// along the axis, where open edges are thinnest.
+    fn open_edges_svg(&self, mesh_id: MeshId) -> String {
+        let mesh = self.get_mesh(mesh_id);
+        let loops = mesh.boundary_loops();
+        let open = loops
+            .iter()
+            .flatten()
+            .map(|s| (s.rib_id(), s.from(), s.to()))
+            .collect_vec();
+        let polygons = mesh
+            .all_polygons()
+            .into_iter()
+            .map(|p| p.make_ref(self).segments().collect_vec())
+            .filter(|segs| {
+                segs.iter()
+                    .any(|s| open.iter().any(|(rib_id, _, _)| *rib_id == s.rib_id()))
+            })
+            .map(|segs| segs.iter().map(|s| s.from()).collect_vec())
+            .collect_vec();
+
+        let points = open.iter().flat_map(|(_, a, b)| [*a, *b]).collect_vec();
+        let view_axis = (0..3)
+            .min_by_key(|axis| {
+                let (min, max) = points
+                    .iter()
+                    .map(|p| p[*axis])
+                    .minmax()
+                    .into_option()
+                    .unwrap_or_default();
+                max - min
+            })
+            .unwrap_or(2);
+        let (u, v) = match view_axis {
+            0 => (1, 2),
+            1 => (0, 2),
+            _ => (0, 1),
+        };
+        let project = |p: &Vector3<Dec>| Vector2::new(f64::from(p[u]), -f64::from(p[v]));
+
+        let all = polygons.iter().flatten().map(project).collect_vec();
+        let (left, right) = all
+            .iter()
+            .map(|p| p.x)
+            .minmax()
+            .into_option()
+            .unwrap_or_default();
+        let (top, bottom) = all
+            .iter()
+            .map(|p| p.y)
+            .minmax()
+            .into_option()
+            .unwrap_or_default();
+        let margin = 1.0;
+        let mut items = vec![format!(
+            "<svg viewBox=\"{} {} {} {}\" xmlns=\"http://www.w3.org/2000/svg\" width=\"800\">",
+            left - margin,
+            top - margin,
+            right - left + margin * 2.0,
+            bottom - top + margin * 2.0
+        )];
+        for polygon in &polygons {
+            let path = polygon
+                .iter()
+                .map(|p| {
+                    let p = project(p);
+                    format!("{:.4},{:.4}", p.x, p.y)
+                })
+                .join(" ");
+            items.push(format!(
+                "<polygon points=\"{path}\" fill=\"#ddd\" fill-opacity=\"0.5\" stroke=\"gray\" stroke-width=\"0.05\"/>"
+            ));
+        }
+        for (rib_id, from, to) in &open {
+            let (from, to) = (project(from), project(to));
+            items.push(format!(
+                "<line x1=\"{:.4}\" y1=\"{:.4}\" x2=\"{:.4}\" y2=\"{:.4}\" stroke=\"red\" stroke-width=\"0.1\"><title>{rib_id:?}</title></line>",
+                from.x, from.y, to.x, to.y
+            ));
+        }
+        items.push("</svg>".to_string());
+        items.join("\n")
+    }
+}
+
+/// Triangles of polygons, given as points. Polygons, which cannot be triangulated, are
+/// skipped.
+fn stl_triangles(polygons: &[Vec<Vector3<Dec>>]) -> Vec<Triangle> {
+    let to_stl = |v: Vector3<Dec>| Vector::new([v.x.into(), v.y.into(), v.z.into()]);
+    let mut result = Vec::new();
+    for points in polygons {
+        // Newell's method, works for concave polygons too
+        let normal = points
+            .iter()
+            .circular_tuple_windows()
+            .fold(Vector3::<Dec>::zero(), |n, (a, b)| n + a.cross(b));
+        if normal.is_zero() || points.len() < 3 {
+            continue;
+        }
+        let normal = normal.normalize();
+        let x = (points[1] - points[0]).normalize();
+        let y = normal.cross(&x);
+        let flat = points
+            .iter()
+            .map(|p| Vector2::new((p - points[0]).dot(&x), (p - points[0]).dot(&y)))
+            .collect_vec();
+        let Ok(triangles) = triangulation::triangulate(&flat) else {
+            continue;
+        };
+        for triangle in triangles {
+            result.push(Triangle {
+                normal: to_stl(normal),
+                vertices: triangle.map(|ix| to_stl(points[ix])),
+            });
+        }
+    }
+    result
+}
@@ -0,0 +1,385 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use nalgebra::Vector3;
+use rstar::{
+    primitives::{GeomWithData, Rectangle},
+    RTree, AABB,
+};
+
+use crate::decimal::Dec;
+
+use super::{
+    error::GeoIndexError, geo_object::GeoObject, index::GeoIndex, mesh::MeshId, poly::UnrefPoly,
+};
+
+type V = Vector3<f64>;
+
+const EPS: f64 = 1e-12;
+
+/// Closest points of two polygons
+#[derive(Clone, Debug)]
+pub struct Contact {
+    pub a: UnrefPoly,
+    pub b: UnrefPoly,
+    pub on_a: Vector3<Dec>,
+    pub on_b: Vector3<Dec>,
+    /// Zero for polygons, which cross each other
+    pub distance: Dec,
+}
+
+/// Result of `GeoIndex::check_clearance`. Each pair of polygons is reported once, with
+/// its closest points. Contacts are sorted by distance.
+#[derive(Clone, Debug, Default)]
+pub struct ClearanceReport {
+    /// Polygons of one mesh cross polygons of another
+    pub penetrations: Vec<Contact>,
+    /// Polygons, which are closer, than minimal gap, but do not cross
+    pub too_close: Vec<Contact>,
+    /// Whole mesh `a` is inside of `b` without touching it
+    pub a_inside_b: bool,
+    pub b_inside_a: bool,
+}
+
+impl ClearanceReport {
+    pub fn is_clear(&self) -> bool {
+        self.penetrations.is_empty()
+            && self.too_close.is_empty()
+            && !self.a_inside_b
+            && !self.b_inside_a
+    }
+}
+
+/// Closest point of triangle to `p`, Ericson, "Real-Time Collision Detection", 5.1.5
+pub(super) fn closest_on_triangle(p: V, [a, b, c]: [V; 3]) -> V {
+    let (ab, ac, ap) = (b - a, c - a, p - a);
+    let (d1, d2) = (ab.dot(&ap), ac.dot(&ap));
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+    let bp = p - b;
+    let (d3, d4) = (ab.dot(&bp), ac.dot(&bp));
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3));
+    }
+    let cp = p - c;
+    let (d5, d6) = (ab.dot(&cp), ac.dot(&cp));
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6));
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+    let denom = 1.0 / (va + vb + vc);
+    a + ab * (vb * denom) + ac * (vc * denom)
+}
+
+/// Closest points of two segments, Ericson, 5.1.9
+fn closest_on_segments((p1, q1): (V, V), (p2, q2): (V, V)) -> (V, V) {
+    let (d1, d2, r) = (q1 - p1, q2 - p2, p1 - p2);
+    let (a, e, f) = (d1.dot(&d1), d2.dot(&d2), d2.dot(&r));
+    if a <= EPS && e <= EPS {
+        return (p1, p2);
+    }
+    let (s, t) = if a <= EPS {
+        (0.0, (f / e).clamp(0.0, 1.0))
+    } else {
+        let c = d1.dot(&r);
+        if e <= EPS {
+            ((-c / a).clamp(0.0, 1.0), 0.0)
+        } else {
+            let b = d1.dot(&d2);
+            let denom = a * e - b * b;
+            let s = if denom.abs() > EPS {
+                ((b * f - c * e) / denom).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let t = (b * s + f) / e;
+            if t < 0.0 {
+                ((-c / a).clamp(0.0, 1.0), 0.0)
+            } else if t > 1.0 {
+                (((b - c) / a).clamp(0.0, 1.0), 1.0)
+            } else {
+                (s, t)
+            }
+        }
+    };
+    (p1 + d1 * s, p2 + d2 * t)
+}
+
+/// Point, where segment crosses triangle. Segments in the plane of triangle do not cross it.
+fn segment_crossing((p, q): (V, V), [a, b, c]: [V; 3]) -> Option<V> {
+    let dir = q - p;
+    let (e1, e2) = (b - a, c - a);
+    let h = dir.cross(&e2);
+    let det = e1.dot(&h);
+    if det.abs() < EPS {
+        return None;
+    }
+    let s = p - a;
+    let u = s.dot(&h) / det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let qv = s.cross(&e1);
+    let v = dir.dot(&qv) / det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = e2.dot(&qv) / det;
+    (0.0..=1.0).contains(&t).then(|| p + dir * t)
+}
+
+fn edges([a, b, c]: [V; 3]) -> [(V, V); 3] {
+    [(a, b), (b, c), (c, a)]
+}
+
+/// Closest points of triangles and whether they cross
+fn triangles_contact(ta: [V; 3], tb: [V; 3]) -> (V, V, bool) {
+    for e in edges(ta) {
+        if let Some(x) = segment_crossing(e, tb) {
+            return (x, x, true);
+        }
+    }
+    for e in edges(tb) {
+        if let Some(x) = segment_crossing(e, ta) {
+            return (x, x, true);
+        }
+    }
+    let vertices_a = ta.map(|p| (p, closest_on_triangle(p, tb)));
+    let vertices_b = tb.map(|p| (closest_on_triangle(p, ta), p));
+    let (on_a, on_b) = vertices_a
+        .into_iter()
+        .chain(vertices_b)
+        .chain(
+            edges(ta)
+                .into_iter()
+                .cartesian_product(edges(tb))
+                .map(|(ea, eb)| closest_on_segments(ea, eb)),
+        )
+        .min_by(|x, y| (x.0 - x.1).norm().total_cmp(&(y.0 - y.1).norm()))
+        .expect("triangles have vertices");
+    (on_a, on_b, false)
+}
+
+impl GeoIndex {
+    pub(super) fn float_triangles(
+        &self,
+        mesh_id: MeshId,
+    ) -> anyhow::Result<Vec<(UnrefPoly, [V; 3])>> {
+        if !self.meshes.contains_key(&mesh_id) {
+            return Err(GeoIndexError::MeshNotFound(mesh_id).into());
+        }
+        let mut triangles = Vec::new();
+        for poly in self.get_mesh_polygons(mesh_id) {
+            for t in poly.make_ref(self).triangles()? {
+                let t = t.map(|pt| {
+                    let p = self.vertices.get_point(pt);
+                    V::new(p.x.into(), p.y.into(), p.z.into())
+                });
+                triangles.push((poly, t));
+            }
+        }
+        Ok(triangles)
+    }
+
+    /// Finds places, where `mesh_a` and `mesh_b` cross each other or are closer, than
+    /// `min_gap`. Meshes are expected to be closed, so when one of them is inside of the
+    /// other without touching, it is reported too.
+    pub fn check_clearance(
+        &self,
+        mesh_a: MeshId,
+        mesh_b: MeshId,
+        min_gap: impl Into<Dec>,
+    ) -> anyhow::Result<ClearanceReport> {
+        let gap: f64 = min_gap.into().into();
+        let triangles_a = self.float_triangles(mesh_a)?;
+        let triangles_b = self.float_triangles(mesh_b)?;
+        let envelope = |t: &[V; 3], grow: f64| {
+            let min = t.iter().fold(t[0], |m, p| m.inf(p)).add_scalar(-grow);
+            let max = t.iter().fold(t[0], |m, p| m.sup(p)).add_scalar(grow);
+            AABB::from_corners([min.x, min.y, min.z], [max.x, max.y, max.z])
+        };
+        let tree = RTree::bulk_load(
+            triangles_b
+                .iter()
+                .enumerate()
+                .map(|(ix, (_, t))| {
+                    let e = envelope(t, 0.0);
+                    GeomWithData::new(Rectangle::from_corners(e.lower(), e.upper()), ix)
+                })
+                .collect(),
+        );
+
+        let mut contacts: HashMap<(UnrefPoly, UnrefPoly), (V, V, bool)> = HashMap::new();
+        for (poly_a, ta) in &triangles_a {
+            for item in tree.locate_in_envelope_intersecting(&envelope(ta, gap)) {
+                let (poly_b, tb) = &triangles_b[item.data];
+                let contact = triangles_contact(*ta, *tb);
+                let distance = (contact.0 - contact.1).norm();
+                if !contact.2 && distance >= gap {
+                    continue;
+                }
+                contacts
+                    .entry((*poly_a, *poly_b))
+                    .and_modify(|c| {
+                        if (contact.2 && !c.2)
+                            || (c.2 == contact.2 && distance < (c.0 - c.1).norm())
+                        {
+                            *c = contact;
+                        }
+                    })
+                    .or_insert(contact);
+            }
+        }
+
+        let to_dec = |v: V| Vector3::new(Dec::from(v.x), Dec::from(v.y), Dec::from(v.z));
+        let mut report = ClearanceReport::default();
+        for ((a, b), (on_a, on_b, crossing)) in contacts.into_iter().sorted_by(|x, y| {
+            (x.1 .0 - x.1 .1)
+                .norm()
+                .total_cmp(&(y.1 .0 - y.1 .1).norm())
+        }) {
+            let contact = Contact {
+                a,
+                b,
+                on_a: to_dec(on_a),
+                on_b: to_dec(on_b),
+                distance: Dec::from((on_a - on_b).norm()),
+            };
+            if crossing {
+                report.penetrations.push(contact);
+            } else {
+                report.too_close.push(contact);
+            }
+        }
+
+        if report.penetrations.is_empty() {
+            report.a_inside_b = Self::is_inside(&triangles_a, &triangles_b);
+            report.b_inside_a = Self::is_inside(&triangles_b, &triangles_a);
+        }
+        Ok(report)
+    }
+
+    /// Ray from the first vertex of `inner` crosses `outer` odd number of times
+    fn is_inside(inner: &[(UnrefPoly, [V; 3])], outer: &[(UnrefPoly, [V; 3])]) -> bool {
+        let Some((_, [p, ..])) = inner.first() else {
+            return false;
+        };
+        let far = outer
+            .iter()
+            .flat_map(|(_, t)| t.iter())
+            .map(|v| (v - p).norm())
+            .fold(0.0, f64::max)
+            * 2.0
+            + 1.0;
+        // Direction is skewed, so the ray does not go along ribs of axis-aligned meshes
+        let ray = (*p, p + V::new(0.5773, 0.5774, 0.5775).normalize() * far);
+        outer
+            .iter()
+            .filter(|(_, t)| segment_crossing(ray, *t).is_some())
+            .count()
+            % 2
+            == 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector3;
+    use num_traits::Zero;
+    use rust_decimal_macros::dec;
+
+    use crate::{
+        decimal::Dec,
+        geometry::GeometryDyn,
+        indexes::{
+            aabb::Aabb,
+            geo_index::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId},
+        },
+        origin::Origin,
+        shapes::Rect,
+    };
+
+    fn index() -> GeoIndex {
+        GeoIndex::new(Aabb::from_points(&[
+            Vector3::new(Dec::from(-50), Dec::from(-50), Dec::from(-50)),
+            Vector3::new(Dec::from(50), Dec::from(50), Dec::from(50)),
+        ]))
+        .points_precision(dec!(0.001))
+    }
+
+    fn cube(index: &mut GeoIndex, offset_x: Dec, size: Dec) -> MeshId {
+        let mesh = index.new_mesh();
+        Rect::centered(Origin::new().offset_x(offset_x), size, size, size)
+            .polygonize(mesh.make_mut_ref(index), 0)
+            .unwrap();
+        mesh
+    }
+
+    #[test]
+    fn far_boxes_are_clear() {
+        let mut index = index();
+        let a = cube(&mut index, Dec::from(-10), Dec::from(10));
+        let b = cube(&mut index, Dec::from(10), Dec::from(10));
+
+        let report = index.check_clearance(a, b, 1).unwrap();
+
+        assert!(report.is_clear(), "{report:?}");
+    }
+
+    #[test]
+    fn close_boxes() {
+        let mut index = index();
+        let a = cube(&mut index, Dec::from(-6), Dec::from(10));
+        let b = cube(&mut index, Dec::from(6), Dec::from(10));
+
+        let report = index.check_clearance(a, b, 3).unwrap();
+
+        assert!(report.penetrations.is_empty(), "{report:?}");
+        let closest = report.too_close.first().expect("boxes are too close");
+        assert_eq!(closest.distance.round_dp(6), Dec::from(2));
+        assert_eq!(closest.on_a.x.round_dp(6), Dec::from(-1));
+        assert_eq!(closest.on_b.x.round_dp(6), Dec::from(1));
+        assert!(index.check_clearance(a, b, 1).unwrap().is_clear());
+    }
+
+    #[test]
+    fn crossing_boxes() {
+        let mut index = index();
+        let a = cube(&mut index, Dec::from(-3), Dec::from(10));
+        let b = cube(&mut index, Dec::from(3), Dec::from(10));
+
+        let report = index.check_clearance(a, b, 1).unwrap();
+
+        assert!(!report.penetrations.is_empty(), "{report:?}");
+        assert!(report
+            .penetrations
+            .iter()
+            .all(|c| c.distance.round_dp(6).is_zero()));
+        assert!(!report.a_inside_b && !report.b_inside_a);
+    }
+
+    #[test]
+    fn box_inside_box() {
+        let mut index = index();
+        let a = cube(&mut index, Dec::from(dec!(0.5)), Dec::from(4));
+        let b = cube(&mut index, Dec::zero(), Dec::from(20));
+
+        let report = index.check_clearance(a, b, 1).unwrap();
+
+        assert!(report.penetrations.is_empty(), "{report:?}");
+        assert!(report.a_inside_b);
+        assert!(!report.b_inside_a);
+    }
+}
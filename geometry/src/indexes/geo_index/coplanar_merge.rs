@@ -0,0 +1,215 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use itertools::Itertools;
+
+use crate::indexes::{aabb::Aabb, vertex_index::PtId};
+
+use super::{
+    face::{Face, FaceId},
+    geo_object::GeoObject,
+    index::GeoIndex,
+    mesh::MeshId,
+    poly::{Poly, PolyId, UnrefPoly},
+    rib::RibId,
+    seg::Seg,
+};
+
+impl GeoIndex {
+    /// Replaces each group of adjacent polygons of `mesh_id`, which lie in the same plane
+    /// and look the same way, with one polygon and removes ribs between them. Faces, shared
+    /// with other meshes, are kept as is, so are groups, which would make a polygon with
+    /// a hole. Returns number of removed polygons.
+    pub fn merge_coplanar_polygons(&mut self, mesh_id: MeshId) -> anyhow::Result<usize> {
+        let mut face_users: BTreeMap<FaceId, usize> = BTreeMap::new();
+        for mesh in self.meshes.values() {
+            for poly in mesh.polies.values() {
+                *face_users.entry(poly.face_id).or_default() += 1;
+            }
+        }
+
+        let mut rib_polygons: BTreeMap<RibId, Vec<PolyId>> = BTreeMap::new();
+        let mut planes = BTreeMap::new();
+        for poly in self.get_mesh_polygons(mesh_id) {
+            let poly_ref = poly.make_ref(self);
+            for seg in poly_ref.segments() {
+                rib_polygons
+                    .entry(seg.rib_id)
+                    .or_default()
+                    .push(poly.poly_id);
+            }
+            if face_users.get(&poly_ref.face_id()) == Some(&1) {
+                planes.insert(poly.poly_id, poly_ref.plane());
+            }
+        }
+
+        let mut neighbours: BTreeMap<PolyId, Vec<PolyId>> = BTreeMap::new();
+        for polygons in rib_polygons.values() {
+            if let [a, b] = polygons.as_slice() {
+                if a != b
+                    && planes
+                        .get(a)
+                        .is_some_and(|plane| planes.get(b) == Some(plane))
+                {
+                    neighbours.entry(*a).or_default().push(*b);
+                    neighbours.entry(*b).or_default().push(*a);
+                }
+            }
+        }
+
+        let mut visited = BTreeSet::new();
+        let mut removed = 0;
+        for start in neighbours.keys().copied().collect_vec() {
+            if !visited.insert(start) {
+                continue;
+            }
+            let mut group = vec![start];
+            let mut ix = 0;
+            while ix < group.len() {
+                for n in &neighbours[&group[ix]] {
+                    if visited.insert(*n) {
+                        group.push(*n);
+                    }
+                }
+                ix += 1;
+            }
+            removed += self.merge_polygons(mesh_id, &group)?;
+        }
+        Ok(removed)
+    }
+
+    fn merge_polygons(&mut self, mesh_id: MeshId, group: &[PolyId]) -> anyhow::Result<usize> {
+        let polygons = group
+            .iter()
+            .map(|&poly_id| UnrefPoly { mesh_id, poly_id })
+            .collect_vec();
+        let segs = polygons
+            .iter()
+            .flat_map(|p| p.make_ref(self).segments().map(|s| s.seg()).collect_vec())
+            .collect_vec();
+        let rib_uses = segs.iter().map(|s| s.rib_id).counts();
+
+        let mut outgoing: BTreeMap<PtId, Vec<Seg>> = BTreeMap::new();
+        for seg in segs.iter().filter(|s| rib_uses[&s.rib_id] == 1) {
+            outgoing.entry(seg.from(&self.ribs)).or_default().push(*seg);
+        }
+        // Two loops, touching in one point, or a loop around hole
+        if outgoing.values().any(|segs| segs.len() > 1) {
+            return Ok(0);
+        }
+        let Some(start) = outgoing.keys().next().copied() else {
+            return Ok(0);
+        };
+        let mut border = Vec::new();
+        let mut pt = start;
+        while let Some(seg) = outgoing.get(&pt).map(|segs| segs[0]) {
+            if border.len() == outgoing.len() {
+                break;
+            }
+            border.push(seg);
+            pt = seg.to(&self.ribs);
+            if pt == start {
+                break;
+            }
+        }
+        if pt != start || border.len() != outgoing.len() {
+            return Ok(0);
+        }
+
+        let plane = polygons[0].make_ref(self).plane();
+        let vertices = border
+            .iter()
+            .map(|s| self.vertices.get_point(s.from(&self.ribs)))
+            .collect_vec();
+        let (face_id, _) =
+            self.insert_face(Face::create(border, plane, Aabb::from_points(&vertices)));
+
+        for poly in &polygons {
+            self.remove_polygon(poly.poly_id, mesh_id);
+        }
+        if let Some(mesh) = self.meshes.get_mut(&mesh_id) {
+            mesh.add(Poly::fow(face_id));
+        }
+        for (rib_id, uses) in rib_uses {
+            if uses > 1 && self.rib_to_face.get(&rib_id).is_none_or(|f| f.is_empty()) {
+                self.remove_rib(rib_id)?;
+            }
+        }
+
+        Ok(group.len() - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector3;
+    use rust_decimal_macros::dec;
+
+    use crate::{
+        decimal::Dec,
+        indexes::{
+            aabb::Aabb,
+            geo_index::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId},
+        },
+        origin::Origin,
+        shapes::{Cylinder, Rect},
+    };
+
+    fn index() -> GeoIndex {
+        GeoIndex::new(Aabb::from_points(&[
+            Vector3::new(Dec::from(-50), Dec::from(-50), Dec::from(-50)),
+            Vector3::new(Dec::from(50), Dec::from(50), Dec::from(50)),
+        ]))
+        .points_precision(dec!(0.001))
+    }
+
+    /// Mesh of polygons, each split into triangle fan
+    fn triangulated(index: &mut GeoIndex, polygons: Vec<Vec<Vector3<Dec>>>) -> MeshId {
+        let mesh = index.new_mesh();
+        let mut mesh_ref = mesh.make_mut_ref(index);
+        for polygon in polygons {
+            for (b, c) in polygon[1..].iter().zip(&polygon[2..]) {
+                mesh_ref.add_polygon(&[polygon[0], *b, *c]).unwrap();
+            }
+        }
+        mesh
+    }
+
+    #[test]
+    fn triangulated_cube() {
+        let mut index = index();
+        let cube = Rect::centered(Origin::new(), 10.into(), 10.into(), 10.into());
+        let mesh = triangulated(&mut index, cube.render());
+
+        assert_eq!(index.merge_coplanar_polygons(mesh).unwrap(), 6);
+
+        assert_eq!(mesh.make_ref(&index).into_polygons().len(), 6);
+        let report = mesh.make_ref(&index).validate();
+        assert!(report.is_valid(), "{report}");
+    }
+
+    #[test]
+    fn triangulated_cylinder_caps() {
+        let mut index = index();
+        let cylinder = Cylinder::centered(Origin::new(), 10, 5).steps(16);
+        let mesh = triangulated(&mut index, cylinder.render());
+
+        assert_eq!(index.merge_coplanar_polygons(mesh).unwrap(), 16 + 2 * 13);
+
+        assert_eq!(mesh.make_ref(&index).into_polygons().len(), 16 + 2);
+        let report = mesh.make_ref(&index).validate();
+        assert!(report.is_valid(), "{report}");
+    }
+
+    #[test]
+    fn merged_cube_stays_as_is() {
+        let mut index = index();
+        let mesh = index.new_mesh();
+        let mut mesh_ref = mesh.make_mut_ref(&mut index);
+        for side in Rect::centered(Origin::new(), 10.into(), 10.into(), 10.into()).render() {
+            mesh_ref.add_polygon(&side).unwrap();
+        }
+
+        assert_eq!(index.merge_coplanar_polygons(mesh).unwrap(), 0);
+        assert_eq!(mesh.make_ref(&index).into_polygons().len(), 6);
+    }
+}
@@ -0,0 +1,412 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap},
+};
+
+use itertools::Itertools;
+use nalgebra::{Matrix4, Vector3, Vector4};
+
+use crate::{decimal::Dec, indexes::vertex_index::PtId};
+
+use super::{face::FaceId, geo_object::GeoObject, mesh::MeshRefMut};
+
+/// When `MeshRefMut::decimate` stops collapsing edges
+#[derive(Clone, Copy, Debug)]
+pub enum Decimation {
+    /// Mesh has no more triangles, than this
+    Triangles(usize),
+    /// Any point of new surface is not further, than this distance from surface before
+    /// decimation. Distance is estimated with quadric error metric.
+    MaxError(Dec),
+}
+
+struct Collapse {
+    cost: f64,
+    keep: usize,
+    remove: usize,
+    target: Vector3<f64>,
+    stamps: (usize, usize),
+}
+
+impl PartialEq for Collapse {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost.total_cmp(&other.cost).is_eq()
+    }
+}
+
+impl Eq for Collapse {}
+
+impl PartialOrd for Collapse {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Collapse {
+    /// Cheapest collapse is the greatest, so it is on top of the heap
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+/// Triangle soup of one mesh with per-vertex quadrics
+struct Decimator {
+    points: Vec<Vector3<f64>>,
+    original: Vec<Vector3<Dec>>,
+    moved: Vec<bool>,
+    locked: Vec<bool>,
+    removed: Vec<bool>,
+    stamps: Vec<usize>,
+    quadrics: Vec<Matrix4<f64>>,
+    triangles: Vec<[usize; 3]>,
+    alive: Vec<bool>,
+    vertex_triangles: Vec<Vec<usize>>,
+}
+
+impl Decimator {
+    fn normal(&self, [a, b, c]: [usize; 3]) -> Vector3<f64> {
+        (self.points[b] - self.points[a]).cross(&(self.points[c] - self.points[a]))
+    }
+
+    fn error(q: &Matrix4<f64>, p: Vector3<f64>) -> f64 {
+        let p = Vector4::new(p.x, p.y, p.z, 1.0);
+        (p.transpose() * q * p)[0].max(0.0)
+    }
+
+    fn neighbours(&self, v: usize) -> BTreeSet<usize> {
+        self.vertex_triangles[v]
+            .iter()
+            .filter(|t| self.alive[**t])
+            .flat_map(|t| self.triangles[*t])
+            .filter(|n| *n != v)
+            .collect()
+    }
+
+    fn candidate(&self, a: usize, b: usize) -> Option<Collapse> {
+        let (keep, remove) = match (self.locked[a], self.locked[b]) {
+            (true, true) => return None,
+            (false, true) => (b, a),
+            _ => (a, b),
+        };
+        let q = self.quadrics[keep] + self.quadrics[remove];
+        let targets = if self.locked[keep] {
+            vec![self.points[keep]]
+        } else {
+            vec![
+                self.points[keep],
+                self.points[remove],
+                (self.points[keep] + self.points[remove]) / 2.0,
+            ]
+        };
+        let (cost, target) = targets
+            .into_iter()
+            .map(|p| (Self::error(&q, p), p))
+            .min_by(|x, y| x.0.total_cmp(&y.0))?;
+        Some(Collapse {
+            cost,
+            keep,
+            remove,
+            target,
+            stamps: (self.stamps[keep], self.stamps[remove]),
+        })
+    }
+
+    /// Collapse keeps surface manifold and does not turn any triangle over
+    fn can_collapse(&self, c: &Collapse) -> bool {
+        let common = self
+            .neighbours(c.keep)
+            .intersection(&self.neighbours(c.remove))
+            .count();
+        if common != 2 {
+            return false;
+        }
+        [c.keep, c.remove].into_iter().all(|v| {
+            self.vertex_triangles[v]
+                .iter()
+                .filter(|t| self.alive[**t])
+                .map(|t| self.triangles[*t])
+                .filter(|t| !(t.contains(&c.keep) && t.contains(&c.remove)))
+                .all(|t| {
+                    let before = self.normal(t);
+                    let [a, b, cc] = t.map(|p| if p == v { c.target } else { self.points[p] });
+                    let after = (b - a).cross(&(cc - a));
+                    after.norm() > f64::EPSILON && before.dot(&after) > 0.0
+                })
+        })
+    }
+
+    /// Returns amount of triangles, which are gone
+    fn collapse(&mut self, c: &Collapse) -> usize {
+        let Collapse {
+            keep,
+            remove,
+            target,
+            ..
+        } = *c;
+        if !self.locked[keep] {
+            self.points[keep] = target;
+            self.moved[keep] = true;
+        }
+        self.quadrics[keep] = self.quadrics[keep] + self.quadrics[remove];
+        self.removed[remove] = true;
+        self.stamps[keep] += 1;
+        let mut gone = 0;
+        for t in std::mem::take(&mut self.vertex_triangles[remove]) {
+            if !self.alive[t] {
+                continue;
+            }
+            if self.triangles[t].contains(&keep) {
+                self.alive[t] = false;
+                gone += 1;
+            } else {
+                for p in &mut self.triangles[t] {
+                    if *p == remove {
+                        *p = keep;
+                    }
+                }
+                self.vertex_triangles[keep].push(t);
+            }
+        }
+        gone
+    }
+}
+
+impl<'a> MeshRefMut<'a> {
+    /// Reduces amount of triangles by collapsing edges with the smallest quadric error.
+    /// Vertices on ribs, which are shared with other meshes, and on open borders of this
+    /// mesh are not moved, so mesh stays connected to its neighbours. Polygons are
+    /// replaced by triangles. Returns amount of triangles after decimation.
+    pub fn decimate(&mut self, target: Decimation) -> anyhow::Result<usize> {
+        let index = &*self.geo_index;
+        let polygons = self.mesh_id.make_ref(index).into_polygons();
+        let own_faces = polygons
+            .iter()
+            .map(|p| p.make_ref(index).face_id())
+            .collect::<BTreeSet<FaceId>>();
+
+        let mut ids: HashMap<PtId, usize> = HashMap::new();
+        let mut original = Vec::new();
+        let mut triangles = Vec::new();
+        let mut locked_pts = BTreeSet::new();
+        for poly in &polygons {
+            let poly = poly.make_ref(index);
+            for seg in poly.segments() {
+                let shared = index
+                    .rib_to_face
+                    .get(&seg.rib_id)
+                    .into_iter()
+                    .flatten()
+                    .any(|f| !own_faces.contains(f));
+                if shared {
+                    locked_pts.extend([seg.from_pt(), seg.to_pt()]);
+                }
+            }
+            for triangle in poly.triangles()? {
+                triangles.push(triangle.map(|pt| {
+                    *ids.entry(pt).or_insert_with(|| {
+                        original.push(index.vertices.get_point(pt));
+                        original.len() - 1
+                    })
+                }));
+            }
+        }
+
+        let count = original.len();
+        let mut locked = vec![false; count];
+        for pt in locked_pts {
+            if let Some(ix) = ids.get(&pt) {
+                locked[*ix] = true;
+            }
+        }
+        let mut edges: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+        for [a, b, c] in &triangles {
+            for (x, y) in [(*a, *b), (*b, *c), (*c, *a)] {
+                *edges.entry((x.min(y), x.max(y))).or_default() += 1;
+            }
+        }
+        for ((a, b), uses) in &edges {
+            if *uses != 2 {
+                locked[*a] = true;
+                locked[*b] = true;
+            }
+        }
+
+        let points = original
+            .iter()
+            .map(|p| Vector3::new(p.x.into(), p.y.into(), p.z.into()))
+            .collect_vec();
+        let mut vertex_triangles = vec![Vec::new(); count];
+        let mut quadrics = vec![Matrix4::zeros(); count];
+        for (t, [a, b, c]) in triangles.iter().enumerate() {
+            let n: Vector3<f64> = (points[*b] - points[*a]).cross(&(points[*c] - points[*a]));
+            let Some(n) = n.try_normalize(f64::EPSILON) else {
+                continue;
+            };
+            let plane = Vector4::new(n.x, n.y, n.z, -n.dot(&points[*a]));
+            let k = plane * plane.transpose();
+            for v in [a, b, c] {
+                quadrics[*v] += k;
+                vertex_triangles[*v].push(t);
+            }
+        }
+
+        let mut decimator = Decimator {
+            points,
+            original,
+            moved: vec![false; count],
+            locked,
+            removed: vec![false; count],
+            stamps: vec![0; count],
+            quadrics,
+            alive: vec![true; triangles.len()],
+            triangles,
+            vertex_triangles,
+        };
+
+        let (max_triangles, max_cost) = match target {
+            Decimation::Triangles(t) => (t, f64::INFINITY),
+            Decimation::MaxError(e) => {
+                let e: f64 = e.into();
+                (0, e * e)
+            }
+        };
+        let mut heap = edges
+            .keys()
+            .filter_map(|(a, b)| decimator.candidate(*a, *b))
+            .collect::<BinaryHeap<_>>();
+        let mut alive = decimator.triangles.len();
+        while alive > max_triangles {
+            let Some(c) = heap.pop() else {
+                break;
+            };
+            if c.cost > max_cost {
+                break;
+            }
+            if decimator.removed[c.keep]
+                || decimator.removed[c.remove]
+                || (decimator.stamps[c.keep], decimator.stamps[c.remove]) != c.stamps
+                || !decimator.can_collapse(&c)
+            {
+                continue;
+            }
+            alive -= decimator.collapse(&c);
+            for n in decimator.neighbours(c.keep) {
+                if let Some(next) = decimator.candidate(c.keep, n) {
+                    heap.push(next);
+                }
+            }
+        }
+
+        let point = |v: usize| {
+            if decimator.moved[v] {
+                let p = decimator.points[v];
+                Vector3::new(Dec::from(p.x), Dec::from(p.y), Dec::from(p.z))
+            } else {
+                decimator.original[v]
+            }
+        };
+        let result = decimator
+            .triangles
+            .iter()
+            .zip(&decimator.alive)
+            .filter(|(_, alive)| **alive)
+            .map(|(t, _)| t.map(&point))
+            .collect_vec();
+
+        for p in polygons {
+            p.make_mut_ref(self.geo_index).remove();
+        }
+        for triangle in &result {
+            self.add_polygon(triangle)?;
+        }
+        Ok(result.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector3;
+    use rust_decimal_macros::dec;
+
+    use crate::{
+        decimal::Dec,
+        geometry::GeometryDyn,
+        indexes::{
+            aabb::Aabb,
+            geo_index::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId},
+        },
+        origin::Origin,
+        shapes::{Cylinder, Rect},
+    };
+
+    use super::Decimation;
+
+    fn index() -> GeoIndex {
+        GeoIndex::new(Aabb::from_points(&[
+            Vector3::new(Dec::from(-50), Dec::from(-50), Dec::from(-50)),
+            Vector3::new(Dec::from(50), Dec::from(50), Dec::from(50)),
+        ]))
+        .points_precision(dec!(0.001))
+    }
+
+    fn add(index: &mut GeoIndex, shape: impl GeometryDyn) -> MeshId {
+        let mesh = index.new_mesh();
+        shape.polygonize(mesh.make_mut_ref(index), 0).unwrap();
+        mesh
+    }
+
+    #[test]
+    fn cube_keeps_its_corners() {
+        let mut index = index();
+        let mesh = add(
+            &mut index,
+            Rect::centered(Origin::new(), 10.into(), 10.into(), 10.into()),
+        );
+
+        let triangles = mesh
+            .make_mut_ref(&mut index)
+            .decimate(Decimation::MaxError(dec!(0.01).into()))
+            .unwrap();
+
+        assert_eq!(triangles, 12);
+        let report = mesh.make_ref(&index).validate();
+        assert!(report.is_valid(), "{report}");
+    }
+
+    #[test]
+    fn cylinder_is_reduced_to_target() {
+        let mut index = index();
+        let mesh = add(
+            &mut index,
+            Cylinder::centered(Origin::new(), 10, 5).steps(32),
+        );
+        let before = mesh.make_ref(&index).stl_triangles().unwrap().len();
+
+        let triangles = mesh
+            .make_mut_ref(&mut index)
+            .decimate(Decimation::Triangles(before / 2))
+            .unwrap();
+
+        assert!(triangles <= before / 2, "{triangles} of {before}");
+        assert_eq!(mesh.make_ref(&index).into_polygons().len(), triangles);
+        let report = mesh.make_ref(&index).validate();
+        assert!(report.is_valid(), "{report}");
+    }
+
+    #[test]
+    fn error_limit_keeps_cylinder_round() {
+        let mut index = index();
+        let mesh = add(
+            &mut index,
+            Cylinder::centered(Origin::new(), 10, 5).steps(32),
+        );
+        let before = mesh.make_ref(&index).stl_triangles().unwrap().len();
+
+        let triangles = mesh
+            .make_mut_ref(&mut index)
+            .decimate(Decimation::MaxError(dec!(0.0001).into()))
+            .unwrap();
+
+        assert_eq!(triangles, before);
+    }
+}
@@ -0,0 +1,31 @@
+use nalgebra::Vector3;
+use thiserror::Error;
+
+use crate::decimal::Dec;
+
+use super::{face::FaceId, mesh::MeshId, poly::UnrefPoly, rib::RibId};
+
+/// Failures of splitting and boolean operations in `GeoIndex`. Usually they mean, that
+/// input polygons are degenerate or too close to each other for index precision.
+#[derive(Debug, Error)]
+pub enum GeoIndexError {
+    #[error("Less than 3 segments per polygon is not possible, splitting {face:?}")]
+    TooFewSegments { face: FaceId },
+    #[error("Cannot find bridge points for face: {face:?}")]
+    NoBridgePoints { face: FaceId },
+    #[error("Rib {rib:?} is not in face {face:?}")]
+    RibNotInFace { rib: RibId, face: FaceId },
+    #[error("Segment too short: {from:?} -> {to:?}")]
+    SegmentTooShort {
+        from: Vector3<Dec>,
+        to: Vector3<Dec>,
+    },
+    #[error("Rib {0:?} cannot be removed, it is still used by faces")]
+    RibInUse(RibId),
+    #[error("Common ribs of {face:?} and {tool:?} are lost after splitting")]
+    LostRibs { face: FaceId, tool: FaceId },
+    #[error("Mesh {0:?} not found")]
+    MeshNotFound(MeshId),
+    #[error("Polygon {0:?} not found")]
+    PolyNotFound(UnrefPoly),
+}
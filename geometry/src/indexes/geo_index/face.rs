@@ -1,14 +1,15 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, sync::OnceLock};
 
 use itertools::Itertools;
 use nalgebra::Vector3;
 use num_traits::{Bounded, Zero};
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     decimal::Dec,
     indexes::{aabb::Aabb, vertex_index::PtId},
-    planar::plane::Plane,
+    planar::{plane::Plane, triangulation},
     polygon_basis::PolygonBasis,
 };
 
@@ -19,15 +20,21 @@ use super::{
     seg::{Seg, SegRef, SegmentDir},
 };
 
-#[derive(PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord, Debug, Serialize, Deserialize)]
 pub struct FaceId(pub usize);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Face {
     segments: Vec<Seg>,
     aabb: Aabb,
     pub(crate) ribs: Vec<RibId>,
     plane: Plane,
+    /// Lazily calculated 2d basis, see [`Face::invalidate_cache`]
+    #[serde(skip)]
+    basis: OnceLock<PolygonBasis>,
+    /// Lazily calculated triangulation, shared between exporters, see [`Face::invalidate_cache`]
+    #[serde(skip)]
+    triangles: OnceLock<Vec<[PtId; 3]>>,
 }
 
 impl From<usize> for FaceId {
@@ -77,20 +84,14 @@ impl Face {
 
     pub(crate) fn create(segments: Vec<Seg>, plane: Plane, aabb: Aabb) -> Self {
         let ribs = segments.iter().map(|s| s.rib_id).sorted().collect_vec();
-        if ribs.contains(&RibId(645))
-            && ribs.contains(&RibId(657))
-            //&& ribs.contains(&RibId(653))
-            && ribs.contains(&RibId(654))
-            && ribs.contains(&RibId(659))
-        {
-            println!("CREATE ~~~~",);
-        }
 
         Self {
             segments,
             plane,
             aabb,
             ribs,
+            basis: OnceLock::new(),
+            triangles: OnceLock::new(),
         }
     }
 
@@ -117,7 +118,15 @@ impl Face {
             .map(|s| s.rib_id)
             .sorted()
             .collect_vec();
-        self.ribs = ribs
+        self.ribs = ribs;
+        self.invalidate_cache();
+    }
+
+    /// Drops cached basis and triangulation. Must be called on every change of segments,
+    /// plane or positions of face points, otherwise stale values are returned
+    pub(crate) fn invalidate_cache(&mut self) {
+        self.basis.take();
+        self.triangles.take();
     }
 
     pub(crate) fn update_rib_index(
@@ -140,13 +149,12 @@ impl Face {
         }
     }
 
-    pub(crate) fn segments<'a>(
-        &'a self,
-        dir: SegmentDir,
-    ) -> Box<dyn Iterator<Item = &'a Seg> + 'a> {
+    /// Segments of face, as polygon with `dir` goes around it: reversed polygon goes
+    /// backwards with each segment flipped
+    pub(crate) fn segments<'a>(&'a self, dir: SegmentDir) -> Box<dyn Iterator<Item = Seg> + 'a> {
         match dir {
-            SegmentDir::Fow => Box::new(self.segments.iter()),
-            SegmentDir::Rev => Box::new(self.segments.iter().rev()),
+            SegmentDir::Fow => Box::new(self.segments.iter().copied()),
+            SegmentDir::Rev => Box::new(self.segments.iter().rev().map(Seg::flip)),
         }
     }
 }
@@ -251,6 +259,50 @@ impl<'a> FaceRef<'a> {
         items.join("\n")
     }
     pub(crate) fn calculate_2d_basis(&self) -> PolygonBasis {
+        match self.index.faces.get(&self.face_id) {
+            Some(face) => face.basis.get_or_init(|| self.build_2d_basis()).clone(),
+            None => self.build_2d_basis(),
+        }
+    }
+
+    /// Triangles of face in its own winding
+    pub(crate) fn triangles(&self) -> anyhow::Result<Vec<[PtId; 3]>> {
+        let face = self.index.faces.get(&self.face_id);
+        if let Some(triangles) = face.and_then(|f| f.triangles.get()) {
+            return Ok(triangles.clone());
+        }
+
+        let triangles = self.build_triangles()?;
+        if let Some(face) = face {
+            face.triangles.set(triangles.clone()).ok();
+        }
+        Ok(triangles)
+    }
+
+    fn build_triangles(&self) -> anyhow::Result<Vec<[PtId; 3]>> {
+        let pts = self
+            .segments(SegmentDir::Fow)
+            .map(|s| s.from_pt())
+            .collect_vec();
+        let basis = self.calculate_2d_basis();
+        let points_2d = pts
+            .iter()
+            .map(|pt| basis.project_on_plane_z(&self.index.vertices.get_point(*pt)))
+            .collect_vec();
+
+        if triangulation::is_convex(&points_2d) {
+            Ok((1..pts.len() - 1)
+                .map(|i| [pts[0], pts[i], pts[i + 1]])
+                .collect())
+        } else {
+            Ok(triangulation::triangulate(&points_2d)?
+                .into_iter()
+                .map(|t| t.map(|ix| pts[ix]))
+                .collect())
+        }
+    }
+
+    fn build_2d_basis(&self) -> PolygonBasis {
         let plane = self.plane();
         let vertices = self
             .index
@@ -286,7 +338,7 @@ impl<'a> FaceRef<'a> {
             .get(&self.face_id)
             .into_iter()
             .flat_map(move |face| face.segments(dir))
-            .map(|seg| self.index.load_segref(seg))
+            .map(|seg| self.index.load_segref(&seg))
     }
 
     pub(crate) fn plane(&self) -> &Plane {
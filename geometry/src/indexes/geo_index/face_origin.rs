@@ -0,0 +1,141 @@
+use nalgebra::Vector3;
+use num_traits::Zero;
+
+use crate::{decimal::Dec, origin::Origin};
+
+use super::{
+    clearance::closest_on_triangle, error::GeoIndexError, geo_object::GeoObject, index::GeoIndex,
+    mesh::MeshId, poly::UnrefPoly,
+};
+
+impl GeoIndex {
+    /// Origin in the centroid of polygon: `z` axis is normal of polygon, looking out of its
+    /// mesh, `x` goes along the longest segment of polygon.
+    pub fn face_origin(&self, poly: UnrefPoly) -> anyhow::Result<Origin> {
+        let found = self
+            .meshes
+            .get(&poly.mesh_id)
+            .is_some_and(|mesh| mesh.polies.contains_key(&poly.poly_id));
+        if !found {
+            return Err(GeoIndexError::PolyNotFound(poly).into());
+        }
+        let poly_ref = poly.make_ref(self);
+
+        let (mut weighted, mut area) = (Vector3::zero(), Dec::zero());
+        for triangle in poly_ref.triangles()? {
+            let [a, b, c] = triangle.map(|pt| self.vertices.get_point(pt));
+            let doubled = (b - a).cross(&(c - a)).magnitude();
+            weighted += (a + b + c) * doubled;
+            area += doubled * Dec::from(3);
+        }
+        let center = if area.is_zero() {
+            let points = poly_ref.segments().map(|s| s.from()).collect::<Vec<_>>();
+            points.iter().fold(Vector3::zero(), |s, p| s + p) / Dec::from(points.len())
+        } else {
+            weighted / area
+        };
+
+        let normal = poly_ref.normal();
+        let x = poly_ref
+            .segments()
+            .map(|s| s.to() - s.from())
+            .max_by(|a, b| a.magnitude_squared().cmp(&b.magnitude_squared()))
+            .expect("polygon has segments")
+            .normalize();
+        Ok(Origin::look_at(center, center + normal, normal.cross(&x)))
+    }
+
+    /// Polygon of `mesh_id`, which is the closest to `point`, and the closest point on it
+    pub fn nearest_polygon(
+        &self,
+        mesh_id: MeshId,
+        point: Vector3<Dec>,
+    ) -> anyhow::Result<Option<(UnrefPoly, Vector3<Dec>)>> {
+        let p: Vector3<f64> = Vector3::new(point.x.into(), point.y.into(), point.z.into());
+        let nearest = self
+            .float_triangles(mesh_id)?
+            .into_iter()
+            .map(|(poly, t)| (poly, closest_on_triangle(p, t)))
+            .min_by(|(_, a), (_, b)| (a - p).norm().total_cmp(&(b - p).norm()));
+        Ok(nearest.map(|(poly, c)| (poly, Vector3::new(c.x.into(), c.y.into(), c.z.into()))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector3;
+    use rust_decimal_macros::dec;
+
+    use crate::{
+        decimal::Dec,
+        geometry::GeometryDyn,
+        indexes::{
+            aabb::Aabb,
+            geo_index::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId},
+        },
+        origin::Origin,
+        shapes::Rect,
+    };
+
+    fn rounded(v: Vector3<Dec>) -> Vector3<Dec> {
+        v.map(|c| c.round_dp(6))
+    }
+
+    fn cube(index: &mut GeoIndex) -> MeshId {
+        let mesh = index.new_mesh();
+        Rect::centered(Origin::new(), 10.into(), 10.into(), 10.into())
+            .polygonize(mesh.make_mut_ref(index), 0)
+            .unwrap();
+        mesh
+    }
+
+    fn index() -> GeoIndex {
+        GeoIndex::new(Aabb::from_points(&[
+            Vector3::new(Dec::from(-50), Dec::from(-50), Dec::from(-50)),
+            Vector3::new(Dec::from(50), Dec::from(50), Dec::from(50)),
+        ]))
+        .points_precision(dec!(0.001))
+    }
+
+    #[test]
+    fn origins_of_cube_sides_look_out() {
+        let mut index = index();
+        let mesh = cube(&mut index);
+
+        for poly in mesh.make_ref(&index).into_polygons() {
+            let origin = index.face_origin(poly).unwrap();
+            let z = rounded(origin.z());
+
+            assert_eq!(rounded(origin.center), z * Dec::from(5));
+            assert_eq!(z.abs().sum(), Dec::from(1));
+            // `x` goes along a side
+            assert_eq!(rounded(origin.x()).abs().sum(), Dec::from(1));
+            assert_eq!(origin.x().dot(&z).round_dp(6), Dec::from(0));
+        }
+    }
+
+    #[test]
+    fn removed_polygon_has_no_origin() {
+        let mut index = index();
+        let mesh = cube(&mut index);
+        let poly = mesh.make_ref(&index).into_polygons()[0];
+        poly.make_mut_ref(&mut index).remove();
+
+        assert!(index.face_origin(poly).is_err());
+    }
+
+    #[test]
+    fn nearest_polygon_of_cube() {
+        let mut index = index();
+        let mesh = cube(&mut index);
+        let point = Vector3::new(Dec::from(20), Dec::from(1), Dec::from(2));
+
+        let (poly, closest) = index.nearest_polygon(mesh, point).unwrap().unwrap();
+
+        assert_eq!(
+            rounded(closest),
+            Vector3::new(Dec::from(5), Dec::from(1), Dec::from(2))
+        );
+        assert_eq!(rounded(index.face_origin(poly).unwrap().z()), Vector3::x());
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::{BTreeMap, HashSet};
+
+use itertools::Itertools;
+use nalgebra::{ComplexField, Vector3};
+use num_traits::{One, Signed};
+
+use crate::decimal::{Dec, NORMAL_DOT_ROUNDING};
+
+use super::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId, poly::UnrefPoly, rib::RibId};
+
+/// Which ribs of mesh should be rounded
+#[derive(Clone, Debug)]
+pub enum RibSelector {
+    /// Exact set of ribs
+    Ribs(Vec<RibId>),
+    /// All ribs, where angle between normals of adjacent polygons is bigger than this (in radians)
+    SharperThan(Dec),
+}
+
+/// Replaces convex sharp ribs of mesh with rounded blend.
+/// Concave ribs are left untouched.
+#[derive(Clone, Debug)]
+pub struct Fillet {
+    selector: RibSelector,
+    radius: Dec,
+    segments: usize,
+}
+
+impl Fillet {
+    pub fn ribs(ribs: impl IntoIterator<Item = RibId>) -> Self {
+        Self::new(RibSelector::Ribs(ribs.into_iter().collect()))
+    }
+
+    pub fn sharper_than(angle: impl Into<Dec>) -> Self {
+        Self::new(RibSelector::SharperThan(angle.into()))
+    }
+
+    fn new(selector: RibSelector) -> Self {
+        Self {
+            selector,
+            radius: Dec::one(),
+            segments: 6,
+        }
+    }
+
+    pub fn radius(mut self, radius: impl Into<Dec>) -> Self {
+        self.radius = radius.into();
+        self
+    }
+
+    /// Amount of flat segments in each blend
+    pub fn segments(mut self, segments: usize) -> Self {
+        self.segments = segments.max(1);
+        self
+    }
+}
+
+impl GeoIndex {
+    /// Rounds selected ribs of the mesh. For each rib, a cutter is created, which occupies space between
+    /// two adjacent polygons and the blending arc, then it is subtracted from the mesh.
+    pub fn fillet(&mut self, mesh_id: MeshId, fillet: &Fillet) -> anyhow::Result<()> {
+        let mut rib_polygons: BTreeMap<RibId, Vec<UnrefPoly>> = BTreeMap::new();
+        for poly in self.get_mesh_polygons(mesh_id) {
+            for seg in poly.make_ref(self).segments() {
+                rib_polygons.entry(seg.rib_id).or_default().push(poly);
+            }
+        }
+
+        let selected = match &fillet.selector {
+            RibSelector::Ribs(ribs) => ribs.iter().copied().collect::<HashSet<_>>(),
+            RibSelector::SharperThan(_) => rib_polygons.keys().copied().collect(),
+        };
+
+        let cutters = rib_polygons
+            .into_iter()
+            .filter(|(rib_id, _)| selected.contains(rib_id))
+            .filter_map(|(rib_id, polygons)| match polygons.as_slice() {
+                [one, two] => self.fillet_cutter(rib_id, *one, *two, fillet),
+                _ => None,
+            })
+            .collect_vec();
+
+        for cutter in cutters {
+            let cutter_mesh = self.new_mesh();
+            for polygon in cutter {
+                self.add_polygon_to_mesh(&polygon, cutter_mesh)?;
+            }
+            self.subtract_mesh(mesh_id, cutter_mesh)?;
+        }
+
+        Ok(())
+    }
+
+    fn fillet_cutter(
+        &self,
+        rib_id: RibId,
+        one: UnrefPoly,
+        two: UnrefPoly,
+        fillet: &Fillet,
+    ) -> Option<Vec<Vec<Vector3<Dec>>>> {
+        let n1 = one.make_ref(self).normal();
+        let n2 = two.make_ref(self).normal();
+        let d1 = self.detect_poly_dir(rib_id, one);
+        let d2 = self.detect_poly_dir(rib_id, two);
+
+        let normals_dot = n1.dot(&n2).round_dp(NORMAL_DOT_ROUNDING);
+        if normals_dot >= Dec::one() || !d1.dot(&n2).is_negative() {
+            // Flat or concave rib
+            return None;
+        }
+        let angle = normals_dot.acos();
+        if let RibSelector::SharperThan(threshold) = fillet.selector {
+            if angle <= threshold {
+                return None;
+            }
+        }
+
+        let rib = rib_id.make_ref(self);
+        let two_dec = Dec::from(2);
+        let inner_angle = Dec::pi() - angle;
+        let tangent_distance =
+            fillet.radius * (inner_angle / two_dec).cos() / (inner_angle / two_dec).sin();
+        let bisector = (d1 + d2).normalize();
+        let edge = rib.from();
+        let center = edge + bisector * (fillet.radius / (inner_angle / two_dec).sin());
+        let from_center_1 = edge + d1 * tangent_distance - center;
+        let from_center_2 = edge + d2 * tangent_distance - center;
+
+        // Move cutter a bit outside of mesh, so it does not share planes with it
+        let shift = fillet.radius / Dec::from(10);
+        let mut section = (0..=fillet.segments)
+            .map(|s| {
+                let t = Dec::from(s) / Dec::from(fillet.segments);
+                center
+                    + (from_center_1 * ((Dec::one() - t) * angle).sin()
+                        + from_center_2 * (t * angle).sin())
+                        / angle.sin()
+            })
+            .collect_vec();
+        section.push(edge + d2 * tangent_distance + n2 * shift);
+        section.push(edge + (n1 + n2) * shift);
+        section.push(edge + d1 * tangent_distance + n1 * shift);
+
+        let axis = rib.dir().normalize();
+        // Cutter goes past ends of the rib, but not further, than rib is long, so it
+        // does not reach other ribs of short edges
+        let extension = shift.min(rib.dir().magnitude());
+        // Corner near the rib is always convex, use it to detect winding of section
+        let l = section.len();
+        let section_normal =
+            (section[l - 2] - section[l - 3]).cross(&(section[l - 1] - section[l - 2]));
+        if section_normal.dot(&axis).is_negative() {
+            section.reverse();
+        }
+        let start = section.iter().map(|p| p - axis * extension).collect_vec();
+        let end = section
+            .iter()
+            .map(|p| p + rib.dir() + axis * extension)
+            .collect_vec();
+
+        let mut polygons = (0..section.len())
+            .map(|i| {
+                let next = (i + 1) % section.len();
+                vec![start[i], start[next], end[next], end[i]]
+            })
+            .collect_vec();
+        polygons.push(end);
+        polygons.push(start.into_iter().rev().collect());
+
+        Some(polygons)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{ComplexField, Vector3};
+    use rust_decimal_macros::dec;
+
+    use crate::{
+        decimal::Dec,
+        geometry::GeometryDyn,
+        indexes::{
+            aabb::Aabb,
+            geo_index::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId},
+        },
+        origin::Origin,
+        shapes::Rect,
+    };
+
+    use super::Fillet;
+
+    fn cube(index: &mut GeoIndex) -> MeshId {
+        let mesh = index.new_mesh();
+        Rect::centered(Origin::new(), 10.into(), 10.into(), 10.into())
+            .polygonize(mesh.make_mut_ref(index), 0)
+            .unwrap();
+        mesh
+    }
+
+    fn index() -> GeoIndex {
+        GeoIndex::new(Aabb::from_points(&[
+            Vector3::new(Dec::from(-50), Dec::from(-50), Dec::from(-50)),
+            Vector3::new(Dec::from(50), Dec::from(50), Dec::from(50)),
+        ]))
+        .points_precision(dec!(0.001))
+    }
+
+    #[test]
+    fn right_angles_are_not_sharper_than_obtuse() {
+        let mut index = index();
+        let mesh = cube(&mut index);
+
+        index
+            .fillet(mesh, &Fillet::sharper_than(Dec::from(dec!(1.6))))
+            .unwrap();
+
+        assert_eq!(mesh.make_ref(&index).into_polygons().len(), 6);
+        let report = mesh.make_ref(&index).validate();
+        assert!(report.is_valid(), "{report}");
+    }
+
+    #[test]
+    fn fillet_all_ribs_of_cube() {
+        let mut index = index();
+        let mesh = cube(&mut index);
+
+        index
+            .fillet(mesh, &Fillet::sharper_than(Dec::from(dec!(1.5))).radius(2))
+            .unwrap();
+
+        let report = mesh.make_ref(&index).validate();
+        assert!(report.is_valid(), "{report}");
+        // No point is left on ribs of cube, where two sides met
+        for poly in mesh.make_ref(&index).into_polygons() {
+            for point in poly.make_ref(&index).segments().map(|s| s.from()) {
+                let on_sides = point.iter().filter(|c| c.abs().round_dp(6) == Dec::from(5));
+                assert!(on_sides.count() <= 1, "{point}");
+            }
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use rust_decimal_macros::dec;
+
+use crate::{decimal::Dec, geometry::GeometryDyn, origin::Origin, shapes::Cylinder};
+
+use super::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId};
+
+/// Which of two hinged parts is built
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HingeHalf {
+    /// Owns knuckles with even numbers, starting from the first one
+    First,
+    /// Owns knuckles with odd numbers
+    Second,
+}
+
+/// Hinge between two parts. Axis starts at origin and goes along its z axis for `length`.
+/// Knuckles are spread along the axis and belong to parts one by one. Each part gets
+/// its own knuckles, a gap around knuckles of other part and a hole for the pin.
+/// Knuckles should overlap the part, they are attached to.
+#[derive(Clone, Debug)]
+pub struct Hinge {
+    mesh_a: MeshId,
+    mesh_b: MeshId,
+    axis: Origin,
+    length: Dec,
+    knuckles: usize,
+    knuckle_radius: Dec,
+    pin_diameter: Dec,
+    clearance: Dec,
+    steps: usize,
+}
+
+impl Hinge {
+    pub fn between(mesh_a: MeshId, mesh_b: MeshId, axis: Origin, length: impl Into<Dec>) -> Self {
+        Self {
+            mesh_a,
+            mesh_b,
+            axis,
+            length: length.into(),
+            knuckles: 3,
+            knuckle_radius: dec!(3).into(),
+            pin_diameter: dec!(2).into(),
+            clearance: dec!(0.3).into(),
+            steps: 24,
+        }
+    }
+
+    /// Amount of knuckles on both parts together
+    pub fn knuckles(mut self, knuckles: usize) -> Self {
+        self.knuckles = knuckles.max(2);
+        self
+    }
+
+    pub fn knuckle_radius(mut self, knuckle_radius: impl Into<Dec>) -> Self {
+        self.knuckle_radius = knuckle_radius.into();
+        self
+    }
+
+    /// Diameter of pin. Hole is wider by clearance.
+    pub fn pin_diameter(mut self, pin_diameter: impl Into<Dec>) -> Self {
+        self.pin_diameter = pin_diameter.into();
+        self
+    }
+
+    /// Gap between knuckles of different parts, and between pin and its hole
+    pub fn clearance(mut self, clearance: impl Into<Dec>) -> Self {
+        self.clearance = clearance.into();
+        self
+    }
+
+    /// Amount of sides of knuckles and pin hole
+    pub fn steps(mut self, steps: usize) -> Self {
+        self.steps = steps.max(3);
+        self
+    }
+
+    fn knuckle_length(&self) -> Dec {
+        (self.length - self.clearance * Dec::from(self.knuckles - 1)) / Dec::from(self.knuckles)
+    }
+
+    fn knuckle_start(&self, ix: usize) -> Dec {
+        (self.knuckle_length() + self.clearance) * Dec::from(ix)
+    }
+
+    fn owner(ix: usize) -> HingeHalf {
+        match ix % 2 {
+            0 => HingeHalf::First,
+            _ => HingeHalf::Second,
+        }
+    }
+
+    fn knuckle(&self, ix: usize) -> Cylinder {
+        Cylinder::with_bottom_at(
+            self.axis.clone().offset_z(self.knuckle_start(ix)),
+            self.knuckle_length(),
+            self.knuckle_radius,
+        )
+        .steps(self.steps)
+    }
+
+    /// Space around knuckle of other part
+    fn knuckle_gap(&self, ix: usize) -> Cylinder {
+        Cylinder::with_bottom_at(
+            self.axis
+                .clone()
+                .offset_z(self.knuckle_start(ix) - self.clearance),
+            self.knuckle_length() + self.clearance * 2,
+            self.knuckle_radius + self.clearance,
+        )
+        .steps(self.steps)
+    }
+
+    fn pin_hole(&self) -> Cylinder {
+        Cylinder::with_bottom_at(
+            self.axis.clone().offset_z(-self.clearance),
+            self.length + self.clearance * 2,
+            self.pin_diameter / 2 + self.clearance,
+        )
+        .steps(self.steps)
+    }
+}
+
+impl GeoIndex {
+    /// Builds both halves of hinge, when both meshes are in this index
+    pub fn hinge(&mut self, hinge: &Hinge) -> anyhow::Result<()> {
+        self.hinge_half(hinge.mesh_a, hinge, HingeHalf::First)?;
+        self.hinge_half(hinge.mesh_b, hinge, HingeHalf::Second)
+    }
+
+    /// Builds one half of hinge on `mesh_id`. Useful when parts are built in different indexes.
+    pub fn hinge_half(
+        &mut self,
+        mesh_id: MeshId,
+        hinge: &Hinge,
+        half: HingeHalf,
+    ) -> anyhow::Result<()> {
+        for ix in (0..hinge.knuckles).filter(|ix| Hinge::owner(*ix) != half) {
+            let gap_mesh = self.new_mesh();
+            hinge
+                .knuckle_gap(ix)
+                .polygonize(gap_mesh.make_mut_ref(self), 0)?;
+            self.subtract_mesh(mesh_id, gap_mesh)?;
+        }
+
+        for ix in (0..hinge.knuckles).filter(|ix| Hinge::owner(*ix) == half) {
+            let knuckle_mesh = self.new_mesh();
+            hinge
+                .knuckle(ix)
+                .polygonize(knuckle_mesh.make_mut_ref(self), 0)?;
+            self.unite_mesh(mesh_id, knuckle_mesh)?;
+        }
+
+        let hole_mesh = self.new_mesh();
+        hinge
+            .pin_hole()
+            .polygonize(hole_mesh.make_mut_ref(self), 0)?;
+        self.subtract_mesh(mesh_id, hole_mesh)?;
+
+        Ok(())
+    }
+}
@@ -3,23 +3,27 @@ use std::ops::Div;
 use std::path::PathBuf;
 use std::time::SystemTime;
 use std::{
-    collections::{HashSet, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
     fmt::Debug,
     hash::Hash,
 };
 
 use anyhow::anyhow;
 use itertools::{Either, Itertools};
-use nalgebra::{ComplexField, Vector3};
+use nalgebra::{ComplexField, Matrix3, Vector3};
 use num_traits::{One, Signed, Zero};
+use rayon::prelude::*;
 use rstar::RTree;
 use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use stl_io::Triangle;
 
 use crate::linear::line::Line;
+use crate::log::{debug, span, trace};
 use crate::planar::plane::Plane;
 use crate::polygon_basis::PolygonBasis;
 use crate::{
-    decimal::Dec,
+    decimal::{Dec, NORMAL_DOT_ROUNDING},
     indexes::{
         aabb::Aabb,
         geo_index::{poly::PolyRef, seg::SegRef},
@@ -28,11 +32,12 @@ use crate::{
     primitives_relation::{planar::PlanarRelation, relation::Relation},
 };
 
+use super::error::GeoIndexError;
 use super::face::{Face, FaceId, FaceRef, FaceToFaceRelation};
 use super::geo_object::GeoObject;
 use super::mesh::Mesh;
 use super::poly::{Poly, PolyId, PolyRefMut, UnrefPoly};
-use super::poly_rtree::FaceRtreeRecord;
+use super::poly_rtree::{FaceRtreeRecord, RibRtreeRecord};
 use super::rib::RibRef;
 use super::seg::SegmentRef;
 use super::{
@@ -41,10 +46,15 @@ use super::{
     seg::{Seg, SegmentDir},
 };
 
-#[derive(Debug)]
+/// Point, where rib of one face crosses other face: either new one or already indexed
+type IntersectionPoint = (Either<Vector3<Dec>, PtId>, RibId);
+
+/// Saved with serde, expensive build can be continued from checkpoint
+#[derive(Debug, Serialize, Deserialize)]
 pub struct GeoIndex {
     pub(crate) vertices: VertexIndex,
     pub(super) face_index: RTree<FaceRtreeRecord>,
+    pub(super) rib_index: RTree<RibRtreeRecord>,
     pub(super) ribs: BTreeMap<RibId, Rib>,
     pub(super) faces: BTreeMap<FaceId, Face>,
     pub(super) meshes: BTreeMap<MeshId, Mesh>,
@@ -57,13 +67,15 @@ pub struct GeoIndex {
     pub(super) split_ribs: BTreeMap<RibId, Vec<RibId>>,
     //face_to_mesh: BTreeMap<FaceId, Vec<MeshId>>,
     face_split_debug: BTreeMap<FaceId, Option<FaceId>>,
-    input_polygon_min_rib_length: Dec,
-    points_precision: Dec,
+    pub(super) input_polygon_min_rib_length: Dec,
+    pub(super) points_precision: Dec,
     rib_counter: usize,
     face_counter: usize,
     mesh_counter: usize,
     current_color: usize,
-    debug_path: PathBuf,
+    pub(super) debug_path: PathBuf,
+    pub(super) boolean_failure_reports: bool,
+    pub(super) boolean_counter: usize,
 }
 
 impl GeoIndex {
@@ -74,6 +86,7 @@ impl GeoIndex {
             meshes: BTreeMap::default(),
             vertices,
             face_index: Default::default(),
+            rib_index: Default::default(),
             ribs: Default::default(),
             faces: Default::default(),
             pt_to_ribs: Default::default(),
@@ -92,6 +105,8 @@ impl GeoIndex {
 
             current_color: 0,
             debug_path: "/tmp/".into(),
+            boolean_failure_reports: true,
+            boolean_counter: 0,
             //default_mesh,
         }
     }
@@ -107,6 +122,14 @@ impl GeoIndex {
         self
     }
 
+    /// Booleans, which leave more open edges in result, than inputs had, write failure
+    /// bundle into debug path: inputs as stl, open edges as svg, tolerances and steps of
+    /// the operation. Enabled by default.
+    pub fn boolean_failure_reports(mut self, enabled: bool) -> Self {
+        self.boolean_failure_reports = enabled;
+        self
+    }
+
     pub fn input_polygon_min_rib_length(
         mut self,
         input_polygon_min_rib_length: impl Into<Dec>,
@@ -143,13 +166,34 @@ impl GeoIndex {
     }
 
     fn collect_seg_chains(&self, mut ribs: Vec<RibId>) -> Vec<Vec<Seg>> {
-        let mut result = Vec::new();
-        if ribs.is_empty() {
-            return Vec::new();
+        // Endpoint -> ribs map, so finding next rib of chain does not scan all ribs
+        let mut pt_ribs: HashMap<PtId, Vec<RibId>> = HashMap::new();
+        for rib_id in ribs.iter().rev() {
+            let rib = &self.ribs[rib_id];
+            pt_ribs.entry(rib.0).or_default().push(*rib_id);
+            pt_ribs.entry(rib.1).or_default().push(*rib_id);
+        }
+        let mut used = HashSet::new();
+
+        fn take_rib(
+            pt_ribs: &mut HashMap<PtId, Vec<RibId>>,
+            used: &mut HashSet<RibId>,
+            pt: PtId,
+        ) -> Option<RibId> {
+            let ribs = pt_ribs.get_mut(&pt)?;
+            while let Some(rib_id) = ribs.pop() {
+                if used.insert(rib_id) {
+                    return Some(rib_id);
+                }
+            }
+            None
         }
 
-        loop {
-            let rib_id = ribs.pop().expect("non-empty ribs");
+        let mut result = Vec::new();
+        while let Some(rib_id) = ribs.pop() {
+            if !used.insert(rib_id) {
+                continue;
+            }
 
             let mut chain = VecDeque::new();
             chain.push_back(SegRef {
@@ -158,15 +202,10 @@ impl GeoIndex {
                 index: self,
             });
 
-            'inner: loop {
-                if let Some(from_ix) = ribs.iter().position(|rib_id| {
-                    let rib = &self.ribs[rib_id];
-                    let to = chain.back().unwrap().to_pt();
-
-                    to == rib.0 || to == rib.1
-                }) {
-                    let to = chain.back().unwrap().to_pt();
-                    let new_rib = ribs.swap_remove(from_ix);
+            loop {
+                let to = chain.back().unwrap().to_pt();
+                let from = chain.front().unwrap().from_pt();
+                if let Some(new_rib) = take_rib(&mut pt_ribs, &mut used, to) {
                     let r = self.ribs[&new_rib];
                     chain.push_back(SegRef {
                         rib_id: new_rib,
@@ -177,14 +216,7 @@ impl GeoIndex {
                         },
                         index: self,
                     });
-                } else if let Some(to_ix) = ribs.iter().position(|rib_id| {
-                    let rib = &self.ribs[rib_id];
-                    let from = chain.front().unwrap().from_pt();
-
-                    from == rib.0 || from == rib.1
-                }) {
-                    let from = chain.front().unwrap().from_pt();
-                    let new_rib = ribs.swap_remove(to_ix);
+                } else if let Some(new_rib) = take_rib(&mut pt_ribs, &mut used, from) {
                     let r = self.ribs[&new_rib];
                     chain.push_front(SegRef {
                         rib_id: new_rib,
@@ -197,13 +229,9 @@ impl GeoIndex {
                     });
                 } else {
                     result.push(chain.into_iter().map(|c| c.seg()).collect_vec());
-                    break 'inner;
+                    break;
                 }
             }
-
-            if ribs.is_empty() {
-                break;
-            }
         }
         result
     }
@@ -252,7 +280,12 @@ impl GeoIndex {
         })
     }
 
-    pub fn split_face_by_chain(&mut self, chain: Vec<Seg>, face_id: FaceId) -> [FaceId; 2] {
+    pub fn split_face_by_chain(
+        &mut self,
+        chain: Vec<Seg>,
+        face_id: FaceId,
+    ) -> Result<[FaceId; 2], GeoIndexError> {
+        let _span = span!("split_face_by_chain", ?face_id, chain = chain.len());
         let face_ref = self.load_face_ref(face_id);
         let chain = chain.into_iter().map(|s| s.to_ref(self)).collect_vec();
         let chain_last = chain.last().unwrap().to_pt();
@@ -273,7 +306,7 @@ impl GeoIndex {
         let backs = [backs, &reversed_chain.into_iter().collect_vec()].concat();
 
         if fronts.len() < 3 || backs.len() < 3 {
-            panic!("Less than 3 segments per polygon is not possible {face_id:?}");
+            return Err(GeoIndexError::TooFewSegments { face: face_id });
         }
 
         let front_aabb = self.calculate_aabb_from_segments(fronts.clone().into_iter());
@@ -299,22 +332,20 @@ impl GeoIndex {
             self.debug_svg_face("P-", face_id, &basis, &[]);
         }
         self.remove_face(face_id);
-        [face_one, face_two]
-            .into_iter()
-            .for_each(|(child_face_id, _is_created)| {
-                if let Some(basis) = self.has_debug_req(face_id) {
-                    self.debug_svg_face(&format!("from-{face_id:?}-"), child_face_id, &basis, &[]);
-                }
-                for r in &ribs_to_index {
-                    Self::save_index(&mut self.rib_to_face, *r, child_face_id);
-                }
-                self.unify_faces_ribs(child_face_id);
-                self.create_common_ribs_for_adjacent_faces(child_face_id);
+        for (child_face_id, _is_created) in [face_one, face_two] {
+            if let Some(basis) = self.has_debug_req(face_id) {
+                self.debug_svg_face(&format!("from-{face_id:?}-"), child_face_id, &basis, &[]);
+            }
+            for r in &ribs_to_index {
+                Self::save_index(&mut self.rib_to_face, *r, child_face_id);
+            }
+            self.unify_faces_ribs(child_face_id)?;
+            self.create_common_ribs_for_adjacent_faces(child_face_id);
 
-                Self::save_index(&mut self.face_splits, face_id, child_face_id);
-            });
+            Self::save_index(&mut self.face_splits, face_id, child_face_id);
+        }
 
-        new_ids.try_into().expect("ok")
+        Ok(new_ids.try_into().expect("ok"))
     }
 
     pub fn find_first_bridge_point(
@@ -360,9 +391,12 @@ impl GeoIndex {
                         test_dir,
                     );
                     let is_bridge = self.is_bridge(&segs, (chain_pts[ix], *p));
-                    if face_id.0 == 2 {
-                        println!("is_bridge {is_bridge}, is between: {is_vec_dir_between_two_other_dirs}");
-                    }
+                    trace!(
+                        ?face_id,
+                        is_bridge,
+                        is_vec_dir_between_two_other_dirs,
+                        "bridge candidate"
+                    );
                     is_vec_dir_between_two_other_dirs && is_bridge
                 })
                 .max_by_key(|p| {
@@ -461,7 +495,8 @@ impl GeoIndex {
         &mut self,
         face_id: FaceId,
         mut chain: Vec<Seg>,
-    ) -> Vec<FaceId> {
+    ) -> Result<Vec<FaceId>, GeoIndexError> {
+        let _span = span!("split_face_by_closed_chain", ?face_id, chain = chain.len());
         let face_ref = self.load_face_ref(face_id);
         let pb = face_ref.calculate_2d_basis();
         let area = |s: SegRef| {
@@ -556,38 +591,34 @@ impl GeoIndex {
                 }
 
                 self.replace_faces_in_meshes(face_id, &faces);
-                [face_one, face_two, face_three]
-                    .into_iter()
-                    .map(|(child_face_id, _is_created)| {
-                        if let Some(basis_face) = self
-                            .face_split_debug
-                            .get(&face_id)
-                            .or(self.face_split_debug.get(&child_face_id))
-                        {
-                            let basis = if let Some(face) = basis_face {
-                                face.make_ref(self).calculate_2d_basis()
-                            } else {
-                                child_face_id.make_ref(self).calculate_2d_basis()
-                            };
-                            self.debug_svg_face(
-                                &format!("from-{face_id:?}-"),
-                                child_face_id,
-                                &basis,
-                                &[],
-                            );
-                        }
-                        Self::save_index(&mut self.face_splits, face_id, child_face_id);
-                        self.unify_faces_ribs(child_face_id);
-                        self.create_common_ribs_for_adjacent_faces(child_face_id);
-                        child_face_id
-                    })
-                    .collect_vec();
+                for child_face_id in faces.iter().copied() {
+                    if let Some(basis_face) = self
+                        .face_split_debug
+                        .get(&face_id)
+                        .or(self.face_split_debug.get(&child_face_id))
+                    {
+                        let basis = if let Some(face) = basis_face {
+                            face.make_ref(self).calculate_2d_basis()
+                        } else {
+                            child_face_id.make_ref(self).calculate_2d_basis()
+                        };
+                        self.debug_svg_face(
+                            &format!("from-{face_id:?}-"),
+                            child_face_id,
+                            &basis,
+                            &[],
+                        );
+                    }
+                    Self::save_index(&mut self.face_splits, face_id, child_face_id);
+                    self.unify_faces_ribs(child_face_id)?;
+                    self.create_common_ribs_for_adjacent_faces(child_face_id);
+                }
                 self.remove_face(face_id);
-                return faces;
+                return Ok(faces);
             }
         }
 
-        panic!("Cannot find bridge points for face: {face_id:?}");
+        Err(GeoIndexError::NoBridgePoints { face: face_id })
     }
 
     fn create_common_ribs_for_adjacent_faces(&mut self, tool_face_id: FaceId) {
@@ -727,7 +758,7 @@ impl GeoIndex {
         }
     }
 
-    pub fn split_faces_by_orphan_ribs(&mut self) {
+    pub fn split_faces_by_orphan_ribs(&mut self) -> Result<(), GeoIndexError> {
         while let Some((face_id, cutting_chain, leftoffs)) = self
             .partially_split_faces
             .iter()
@@ -743,11 +774,7 @@ impl GeoIndex {
                         }
                     })
                     .collect_vec();
-                if face_id.0 == 2482 {
-                    for r in &ribs {
-                        println!("Split ribs for face 2482{}", r.make_ref(self))
-                    }
-                }
+                trace!(?face_id, ?ribs, "orphan ribs");
 
                 let mut chains = self.collect_seg_chains(ribs);
 
@@ -779,9 +806,9 @@ impl GeoIndex {
             })
         {
             let new_polies = if self.is_chain_circular(&cutting_chain) {
-                self.split_face_by_closed_chain(face_id, cutting_chain)
+                self.split_face_by_closed_chain(face_id, cutting_chain)?
             } else {
-                self.split_face_by_chain(cutting_chain, face_id).to_vec()
+                self.split_face_by_chain(cutting_chain, face_id)?.to_vec()
             };
 
             self.partially_split_faces.remove(&face_id);
@@ -792,13 +819,14 @@ impl GeoIndex {
                 }
             }
         }
+        Ok(())
     }
 
     pub(crate) fn split_floating_rib_using_indexed_pts(
         &mut self,
         pts: &[PtId],
         rib_id: RibId,
-    ) -> Vec<RibId> {
+    ) -> Result<Vec<RibId>, GeoIndexError> {
         let mut vs_peekable = pts
             .iter()
             .chain([
@@ -817,22 +845,19 @@ impl GeoIndex {
         let mut replacement = Vec::new();
         while let Some(v) = vs_peekable.next() {
             if let Some(vn) = vs_peekable.peek() {
-                replacement.push(self.save_segment_unchecked((v, *vn)));
+                replacement.push(self.save_segment_unchecked((v, *vn))?);
             }
         }
         let new_ids = replacement.iter().map(|s| s.rib_id).collect();
 
-        new_ids
+        Ok(new_ids)
     }
     pub(crate) fn split_rib_in_face_using_indexed_pts(
         &mut self,
         pts: &[PtId],
         rib_id: RibId,
         face_id: FaceId,
-    ) -> Vec<RibId> {
-        if face_id.0 == 2495 && rib_id == 3847 {
-            println!("DO something with {face_id:?} and {rib_id:?}");
-        }
+    ) -> Result<Vec<RibId>, GeoIndexError> {
         let fr = self.load_face_ref(face_id);
         if let Some(ix) = fr
             .segments(SegmentDir::Fow)
@@ -858,16 +883,12 @@ impl GeoIndex {
             let mut replacement = Vec::new();
             while let Some(v) = vs_peekable.next() {
                 if let Some(vn) = vs_peekable.peek() {
-                    replacement.push(self.save_segment_unchecked((v, *vn)));
+                    replacement.push(self.save_segment_unchecked((v, *vn))?);
                 }
             }
             let new_ids = replacement.iter().map(|s| s.rib_id).collect();
 
-            if face_id.0 == 2495 {
-                println!(
-                    "DO something with {face_id:?} and {rib_id:?} replace with [{replacement:?}]"
-                );
-            }
+            trace!(?face_id, ?rib_id, ?replacement, "split rib in face");
 
             for r in &replacement {
                 Self::save_index(&mut self.rib_to_face, r.rib_id, face_id);
@@ -878,9 +899,12 @@ impl GeoIndex {
                 face.replace_segments(ix, replacement);
             }
 
-            new_ids
+            Ok(new_ids)
         } else {
-            panic!("NO RIB");
+            Err(GeoIndexError::RibNotInFace {
+                rib: rib_id,
+                face: face_id,
+            })
         }
     }
 
@@ -937,14 +961,17 @@ impl GeoIndex {
         Ok(plane)
     }
 
-    fn save_polygon_new(&mut self, vertices: &[Vector3<Dec>]) -> anyhow::Result<Poly> {
+    fn save_polygon_new(
+        &mut self,
+        vertices: &[Vector3<Dec>],
+        points_precision: Dec,
+    ) -> anyhow::Result<Poly> {
         let mut segs = Vec::new();
         let aabb = Aabb::from_points(vertices);
-        for i in 0..vertices.len() {
-            let next = (i + 1) % vertices.len();
-            let from = vertices[i];
-            let to = vertices[next];
-            segs.push(self.save_segment((from, to))?);
+        let pts = self.vertices.insert_many(vertices, points_precision);
+        for i in 0..pts.len() {
+            let next = (i + 1) % pts.len();
+            segs.push(self.save_segment_pts(pts[i], pts[next])?);
         }
 
         let plane = self.calculate_plane_for_segs(&segs)?;
@@ -990,7 +1017,8 @@ impl GeoIndex {
             .map(|s| Vector3::new(s.x.into(), s.y.into(), s.z.into()))
             .collect_vec();
 
-        let poly_mesh = self.save_polygon_new(&vertices)?;
+        let points_precision = self.mesh_points_precision(mesh_id);
+        let poly_mesh = self.save_polygon_new(&vertices, points_precision)?;
 
         let poly_id = if let Some(m) = self.meshes.get_mut(&mesh_id) {
             m.add(poly_mesh)
@@ -1001,14 +1029,14 @@ impl GeoIndex {
         let poly = UnrefPoly { mesh_id, poly_id };
 
         let _t = SystemTime::now();
-        self.unify_faces_ribs(poly_mesh.face_id);
+        self.unify_faces_ribs(poly_mesh.face_id)?;
 
         let _t = SystemTime::now();
         self.find_older_and_replace_face_in_poly(poly);
         //println!("  find-replace: {}ms", _t.elapsed().unwrap().as_millis());
 
         let _t = SystemTime::now();
-        self.create_common_ribs_between_faces(poly, mesh_id);
+        self.create_common_ribs_between_faces(poly, mesh_id)?;
         //println!( "  common-ribs-between: {}ms", _t.elapsed().unwrap().as_millis());
 
         let _t = SystemTime::now();
@@ -1016,7 +1044,7 @@ impl GeoIndex {
         //println!( "  common-ribs-adjacent: {}ms", _t.elapsed().unwrap().as_millis());
 
         let _t = SystemTime::now();
-        self.split_faces_by_orphan_ribs();
+        self.split_faces_by_orphan_ribs()?;
         //println!("  split: {}ms", _t.elapsed().unwrap().as_millis());
 
         //println!( "Add polygon to mesh time: {}ms", ts.elapsed().unwrap().as_millis());
@@ -1024,30 +1052,25 @@ impl GeoIndex {
     }
 
     /* TODO: REMOVE
-    fn save_polygon(&mut self, polygon: &Polygon, mesh_id: Option<MeshId>) {
+    fn save_polygon(
+        &mut self,
+        polygon: &Polygon,
+        mesh_id: Option<MeshId>,
+    ) -> Result<(), GeoIndexError> {
         let aabb = Aabb::from_points(&polygon.vertices);
         let mesh_id = mesh_id.unwrap_or(self.default_mesh);
-        if polygon
+        if let Some(short) = polygon
             .get_segments()
             .into_iter()
-            .any(|seg| seg.dir().magnitude() < self.input_polygon_min_rib_length)
+            .find(|seg| seg.dir().magnitude() < self.input_polygon_min_rib_length)
         {
             for s in polygon.get_segments() {
-                let f = s.from;
-                let t = s.to;
-                let m = s.dir().magnitude();
-                println!(
-                    "Segment: {} {} {} --> {} {} {} [{}]",
-                    f.x.round_dp(10),
-                    f.y.round_dp(10),
-                    f.z.round_dp(10),
-                    t.x.round_dp(10),
-                    t.y.round_dp(10),
-                    t.z.round_dp(10),
-                    m
-                );
+                debug!(from = ?s.from, to = ?s.to, length = %s.dir().magnitude(), "segment");
             }
-            panic!("Input rib for new polygon is too short");
+            return Err(GeoIndexError::SegmentTooShort {
+                from: short.from,
+                to: short.to,
+            });
         }
 
         let segments = polygon
@@ -1063,9 +1086,7 @@ impl GeoIndex {
 
         let face_id = self.insert_face(face);
         let poly_id = self.insert_poly(poly).0;
-        if poly_id == 31 {
-            println!("saved new poly in {mesh_id:?}: {poly_id:?}");
-        }
+        debug!(?mesh_id, ?poly_id, "saved new poly");
 
         if let Some(m) = self.meshes.get_mut(&mesh_id) {
             m.0.push(poly_id);
@@ -1074,6 +1095,7 @@ impl GeoIndex {
         self.unify_ribs(poly_id);
         self.create_common_ribs(poly_id, mesh_id);
         self.split_polygons_by_orphan_ribs();
+        Ok(())
     }
 
     pub fn save_segment_splittin_ribs(&mut self, segment: Segment) -> Vec<Seg> {
@@ -1185,6 +1207,14 @@ impl GeoIndex {
         if from == to {
             return Err(anyhow!("Segment too short: {from_v:?} -> {to_v:?}"));
         }
+        self.save_segment_pts(from, to)
+    }
+
+    fn save_segment_pts(&mut self, from: PtId, to: PtId) -> anyhow::Result<Seg> {
+        if from == to {
+            let v = self.vertices.get_point(from);
+            return Err(anyhow!("Segment too short: {v:?} -> {v:?}"));
+        }
         let (rib, dir) = Rib::build(from, to);
 
         let (rib_id, _) = self.insert_rib(rib);
@@ -1194,12 +1224,18 @@ impl GeoIndex {
         Ok(Seg { rib_id, dir })
     }
 
-    pub fn save_segment_unchecked(&mut self, (from_v, to_v): (Vector3<Dec>, Vector3<Dec>)) -> Seg {
+    pub fn save_segment_unchecked(
+        &mut self,
+        (from_v, to_v): (Vector3<Dec>, Vector3<Dec>),
+    ) -> Result<Seg, GeoIndexError> {
         let from = self.insert_point(from_v);
         let to = self.insert_point(to_v);
 
         if from == to {
-            panic!("Segment too short: {from_v:?} -> {to_v:?}");
+            return Err(GeoIndexError::SegmentTooShort {
+                from: from_v,
+                to: to_v,
+            });
         }
         let (rib, dir) = Rib::build(from, to);
 
@@ -1207,7 +1243,7 @@ impl GeoIndex {
         Self::save_index(&mut self.pt_to_ribs, from, rib_id);
         Self::save_index(&mut self.pt_to_ribs, to, rib_id);
 
-        Seg { rib_id, dir }
+        Ok(Seg { rib_id, dir })
     }
 
     /*
@@ -1226,17 +1262,31 @@ impl GeoIndex {
         }
     }
 
+    fn rib_aabb(&self, rib: Rib) -> Aabb {
+        Aabb::from_points(&[
+            self.vertices.get_point(rib.0),
+            self.vertices.get_point(rib.1),
+        ])
+    }
+
     pub(super) fn insert_rib(&mut self, rib: Rib) -> (RibId, bool) {
-        if let Some(rib_id) = self.ribs.iter().find(|(_, &s)| s == rib).map(|(id, _)| id) {
-            (*rib_id, false)
+        let aabb = self.rib_aabb(rib);
+        let existing = self
+            .rib_index
+            .locate_in_envelope(&aabb.into())
+            .map(|r| r.0)
+            .find(|id| self.ribs[id] == rib);
+        if let Some(rib_id) = existing {
+            (rib_id, false)
         } else {
             let rib_id = self.get_next_rib_id();
             self.ribs.insert(rib_id, rib);
+            self.rib_index.insert(RibRtreeRecord(rib_id, aabb));
             (rib_id, true)
         }
     }
 
-    fn insert_face(&mut self, face: Face) -> (FaceId, bool) {
+    pub(super) fn insert_face(&mut self, face: Face) -> (FaceId, bool) {
         if let Some(face_id) = self
             .faces
             .iter()
@@ -1291,7 +1341,8 @@ impl GeoIndex {
 
     /// Remove polygon from all available related structures
     pub fn remove_face(&mut self, face_id: FaceId) {
-        if let Some(face) = self.faces.remove(&face_id) {
+        if let Some(mut face) = self.faces.remove(&face_id) {
+            face.invalidate_cache();
             face.delete_me_from_rib_index(face_id, &mut self.rib_to_face);
 
             self.face_index
@@ -1307,7 +1358,8 @@ impl GeoIndex {
                 let left_meshes = self.get_face_meshes(poly.face_id);
 
                 if left_meshes.is_empty() {
-                    if let Some(face) = self.faces.remove(&poly.face_id) {
+                    if let Some(mut face) = self.faces.remove(&poly.face_id) {
+                        face.invalidate_cache();
                         face.delete_me_from_rib_index(poly.face_id, &mut self.rib_to_face);
 
                         self.face_index
@@ -1374,6 +1426,36 @@ impl GeoIndex {
             .collect()
     }
 
+    /// Mirrors all meshes across yz plane, so right-hand part becomes left-hand one.
+    /// All polygons are removed before mirrored ones are added, so meshes don't cut each
+    /// other on the way.
+    pub fn mirror_x(&mut self) -> anyhow::Result<()> {
+        let polygons = self
+            .meshes
+            .keys()
+            .flat_map(|&mesh_id| self.load_mesh_ref(mesh_id).all_polygons())
+            .map(|p| {
+                let mut points = p
+                    .make_ref(self)
+                    .segments()
+                    .map(|s| s.from())
+                    .map(|pt| Vector3::new(-pt.x, pt.y, pt.z))
+                    .collect_vec();
+                points.reverse();
+                (p, points)
+            })
+            .collect_vec();
+
+        for (p, _) in &polygons {
+            p.make_mut_ref(self).remove();
+        }
+        for (p, points) in polygons {
+            p.mesh_id.make_mut_ref(self).add_polygon(&points)?;
+        }
+
+        Ok(())
+    }
+
     pub(super) fn get_face_points(&self, face_id: FaceId) -> HashSet<PtId> {
         self.load_face_ref(face_id)
             .segments(SegmentDir::Fow)
@@ -1439,23 +1521,26 @@ impl GeoIndex {
         }
     }
 
-    fn remove_rib(&mut self, rib_id: RibId) {
+    pub(super) fn remove_rib(&mut self, rib_id: RibId) -> Result<(), GeoIndexError> {
         if self.rib_to_face.get(&rib_id).is_some_and(|v| !v.is_empty()) {
-            panic!("rib index to poly is not empty");
+            return Err(GeoIndexError::RibInUse(rib_id));
         }
         if let Some(rib) = self.ribs.remove(&rib_id) {
+            let aabb = self.rib_aabb(rib);
+            self.rib_index.remove(&RibRtreeRecord(rib_id, aabb));
             Self::remove_item_from_index(&mut self.pt_to_ribs, &rib.0, &rib_id);
             Self::remove_item_from_index(&mut self.pt_to_ribs, &rib.1, &rib_id);
         }
 
         self.rib_to_face.remove(&rib_id);
+        Ok(())
     }
 
     fn collect_intersection_points_between_two_faces(
         &self,
         src_id: FaceId,
         tool_id: FaceId,
-    ) -> Vec<(Either<Vector3<Dec>, PtId>, RibId)> {
+    ) -> Result<Vec<IntersectionPoint>, GeoIndexError> {
         let tool = self.load_face_ref(tool_id);
         let plane = tool.plane();
         let mut vertices = Vec::new();
@@ -1464,7 +1549,10 @@ impl GeoIndex {
 
         for seg in self.load_face_ref(src_id).segments(SegmentDir::Fow) {
             if seg.to_pt() == seg.from_pt() {
-                panic!("Seg to equals to seg_from");
+                return Err(GeoIndexError::SegmentTooShort {
+                    from: seg.from(),
+                    to: seg.to(),
+                });
             }
             if let Some(t) = plane.get_intersection_param2(seg.from(), seg.to()) {
                 let maybe_zero = (Dec::zero() - t).abs() < vertex_pulling_sq;
@@ -1475,7 +1563,7 @@ impl GeoIndex {
             }
         }
 
-        vertices
+        Ok(vertices
             .clone()
             .into_iter()
             .map(|(v, rib)| {
@@ -1489,10 +1577,14 @@ impl GeoIndex {
                     (Either::Left(v), rib)
                 }
             })
-            .collect()
+            .collect())
     }
 
-    fn create_common_ribs_between_faces(&mut self, tool: UnrefPoly, mesh_id: MeshId) {
+    fn create_common_ribs_between_faces(
+        &mut self,
+        tool: UnrefPoly,
+        mesh_id: MeshId,
+    ) -> Result<(), GeoIndexError> {
         let tool_face_id = tool.make_ref(self).face_id();
         let tool_aabb = *self.load_face_ref(tool_face_id).aabb();
 
@@ -1510,30 +1602,50 @@ impl GeoIndex {
             .collect_vec();
 
         if faces.is_empty() {
-            return;
+            return Ok(());
         }
 
         let tool_plane = self.load_face_ref(tool_face_id).plane().to_owned();
-        for src_id in faces.iter() {
-            let src_plane = self.load_face_ref(*src_id).plane().to_owned();
-            let common_line = match tool_plane.relate(&src_plane) {
-                PlanarRelation::Intersect(line) => line,
-                _ => {
-                    continue;
+
+        // Faces, crossed by tool plane, and points of crossing are searched in parallel.
+        // Cuts below change ribs of faces, so points of faces, which ribs were changed
+        // by previous cut, are collected again.
+        let index = &*self;
+        let candidates = faces
+            .par_iter()
+            .map(|src_id| {
+                let src_plane = index.faces[src_id].plane();
+                let PlanarRelation::Intersect(common_line) = tool_plane.relate(src_plane) else {
+                    return Ok(None);
+                };
+                let vertices_src =
+                    index.collect_intersection_points_between_two_faces(*src_id, tool_face_id)?;
+                if vertices_src.is_empty() {
+                    return Ok(None);
                 }
+                let src_ribs = index.faces[src_id].ribs.clone();
+                Ok(Some((src_id, common_line, src_ribs, vertices_src)))
+            })
+            .collect::<Result<Vec<_>, GeoIndexError>>()?;
+
+        for (src_id, common_line, src_ribs, vertices_src) in candidates.into_iter().flatten() {
+            let points_precision = self
+                .mesh_points_precision(mesh_id)
+                .min(self.face_points_precision(*src_id));
+            let vertices_src = if self.faces[src_id].ribs == src_ribs {
+                vertices_src
+            } else {
+                self.collect_intersection_points_between_two_faces(*src_id, tool_face_id)?
             };
-
-            let vertices_src =
-                self.collect_intersection_points_between_two_faces(*src_id, tool_face_id);
             let vertices_tool =
-                self.collect_intersection_points_between_two_faces(tool_face_id, *src_id);
+                self.collect_intersection_points_between_two_faces(tool_face_id, *src_id)?;
 
             let mut cut_ribs_index = BTreeMap::new();
             let pts_src = vertices_src
                 .into_iter()
                 .map(|(v, rib_id)| match v {
                     Either::Left(v) => {
-                        let pt = self.vertices.get_or_insert_point(v, self.points_precision);
+                        let pt = self.vertices.get_or_insert_point(v, points_precision);
                         (pt, rib_id)
                     }
                     Either::Right(pt) => (pt, rib_id),
@@ -1548,7 +1660,7 @@ impl GeoIndex {
                 .into_iter()
                 .map(|(v, rib_id)| match v {
                     Either::Left(v) => {
-                        let pt = self.vertices.get_or_insert_point(v, self.points_precision);
+                        let pt = self.vertices.get_or_insert_point(v, points_precision);
                         (pt, rib_id)
                     }
                     Either::Right(pt) => (pt, rib_id),
@@ -1671,16 +1783,14 @@ impl GeoIndex {
                                     .flatten()
                                     .collect_vec();
                                 let new_splitted_ribs = if rib_faces.is_empty() {
-                                    self.split_floating_rib_using_indexed_pts(&pts, rib_id)
+                                    self.split_floating_rib_using_indexed_pts(&pts, rib_id)?
                                 } else {
-                                    let new_ribs = rib_faces
-                                        .into_iter()
-                                        .flat_map(|face_id| {
-                                            self.split_rib_in_face_using_indexed_pts(
-                                                &pts, rib_id, face_id,
-                                            )
-                                        })
-                                        .collect::<HashSet<_>>();
+                                    let mut new_ribs = HashSet::new();
+                                    for face_id in rib_faces {
+                                        new_ribs.extend(self.split_rib_in_face_using_indexed_pts(
+                                            &pts, rib_id, face_id,
+                                        )?);
+                                    }
                                     new_ribs.into_iter().collect_vec()
                                 };
                                 new_splitted_ribs.iter().for_each(|new_rib_id| {
@@ -1692,7 +1802,7 @@ impl GeoIndex {
                                     splitted_new_ribs.extend(new_splitted_ribs);
                                 }
 
-                                self.remove_rib(rib_id);
+                                self.remove_rib(rib_id)?;
                             }
                         }
                     }
@@ -1715,7 +1825,7 @@ impl GeoIndex {
                                         &[pt],
                                         poly_rib_id,
                                         poly_id,
-                                    );
+                                    )?;
                                 }
                             }
                         }
@@ -1726,7 +1836,10 @@ impl GeoIndex {
             }
 
             if !new_ribs.is_empty() && splitted_new_ribs.is_empty() {
-                panic!("splitted_new_ribs : {splitted_new_ribs:?} / {new_ribs:?}");
+                return Err(GeoIndexError::LostRibs {
+                    face: *src_id,
+                    tool: tool_face_id,
+                });
             }
 
             for new_rib_id in splitted_new_ribs.clone() {
@@ -1736,9 +1849,6 @@ impl GeoIndex {
                     .is_some_and(|faces| faces.contains(&tool_face_id))
                     && self.rib_inside_face(new_rib_id, tool_face_id)
                 {
-                    if new_rib_id == 3846 {
-                        println!("Push 3846 for {tool_face_id:?}");
-                    }
                     Self::save_index(&mut self.partially_split_faces, tool_face_id, new_rib_id);
                 }
 
@@ -1748,13 +1858,11 @@ impl GeoIndex {
                     .is_some_and(|ps| ps.contains(src_id))
                     && self.rib_inside_face(new_rib_id, *src_id)
                 {
-                    if new_rib_id == 3846 {
-                        println!("Push 3846 for {src_id:?}");
-                    }
                     Self::save_index(&mut self.partially_split_faces, *src_id, new_rib_id);
                 }
             }
         }
+        Ok(())
     }
 
     fn find_intersecting_ribs_on_same_line_in_face(
@@ -1776,23 +1884,32 @@ impl GeoIndex {
             origin: rib1.from(),
             dir: rib1.dir().normalize(),
         };
-        if face_id.0 == 2496 && rib_id == 3847 {
-            println!("LOOK FOR splits of 3847");
-        }
 
-        self.load_face_ref(face_id)
-            .segments(SegmentDir::Fow)
-            .filter(|seg| {
-                line.distance_to_pt_squared(seg.from()).abs() < vertex_pulling_sq
-                    && line.distance_to_pt_squared(seg.to()).abs() < vertex_pulling_sq
-                    && line.dir.dot(&seg.dir().normalize()).abs()
-                        > Dec::from(dec!(0.9999984769132877))
-                // Less than 1 degree
+        // Only ribs, which overlap with this one, can split it or be split by it
+        let mut aabb = self.rib_aabb(self.ribs[&rib_id]);
+        aabb.min -= Vector3::repeat(vertex_pulling);
+        aabb.max += Vector3::repeat(vertex_pulling);
+
+        self.rib_index
+            .locate_in_envelope_intersecting(&aabb.into())
+            .map(|r| r.0)
+            .filter(|r| {
+                self.rib_to_face
+                    .get(r)
+                    .is_some_and(|faces| faces.contains(&face_id))
             })
-            .map(|s| RibRef {
-                rib_id: s.rib_id,
+            .sorted()
+            .map(|rib_id| RibRef {
+                rib_id,
                 index: self,
             })
+            .filter(|rib2| {
+                line.distance_to_pt_squared(rib2.from()).abs() < vertex_pulling_sq
+                    && line.distance_to_pt_squared(rib2.to()).abs() < vertex_pulling_sq
+                    && line.dir.dot(&rib2.dir().normalize()).abs()
+                        > Dec::from(dec!(0.9999984769132877))
+                // Less than 1 degree
+            })
             .map(|rib2| {
                 (
                     (vec![rib1.from_pt(), rib1.to_pt(), rib2.from_pt(), rib2.to_pt()])
@@ -1841,7 +1958,7 @@ impl GeoIndex {
 
     /// make common lines have common ribs
     /// This function processes only those ribs, which are on one line
-    fn unify_faces_ribs(&mut self, tool_face_id: FaceId) {
+    fn unify_faces_ribs(&mut self, tool_face_id: FaceId) -> Result<(), GeoIndexError> {
         let tool_aabb = *self.load_face_ref(tool_face_id).aabb();
         let faces = self
             .face_index
@@ -1864,11 +1981,8 @@ impl GeoIndex {
                 let mut splitted = Vec::new();
                 for (rib_id, pts) in splits {
                     for face_id in self.rib_to_face.remove(&rib_id).into_iter().flatten() {
-                        if rib_id == 3847 && face_id.0 == 2495 {
-                            println!("split rib 3847 because {tool_face_id:?}")
-                        }
                         let new_ribs =
-                            self.split_rib_in_face_using_indexed_pts(&pts, rib_id, face_id);
+                            self.split_rib_in_face_using_indexed_pts(&pts, rib_id, face_id)?;
 
                         new_ribs
                             .iter()
@@ -1877,10 +1991,11 @@ impl GeoIndex {
                         splitted.extend(new_ribs);
                     }
 
-                    self.remove_rib(rib_id);
+                    self.remove_rib(rib_id)?;
                 }
             }
         }
+        Ok(())
     }
 
     pub fn move_all_polygons(&mut self, from_mesh: MeshId, to_mesh: MeshId) {
@@ -1897,6 +2012,250 @@ impl GeoIndex {
         }
     }
 
+    /// Moves polygons of `from_mesh`, which match `predicate`, into `to_mesh`. It is
+    /// for manual fixes, when boolean operation put some polygons into wrong mesh.
+    /// Returns new ids of moved polygons.
+    pub fn reassign_polygons(
+        &mut self,
+        from_mesh: MeshId,
+        predicate: impl Fn(&PolyRef) -> bool,
+        to_mesh: MeshId,
+    ) -> Vec<UnrefPoly> {
+        if from_mesh == to_mesh || !self.meshes.contains_key(&to_mesh) {
+            return Vec::new();
+        }
+        let selected = self
+            .get_mesh_polygons(from_mesh)
+            .into_iter()
+            .filter(|p| predicate(&p.make_ref(self)))
+            .map(|p| p.poly_id)
+            .collect_vec();
+
+        let mut moved = Vec::new();
+        for poly_id in selected {
+            let Some(poly) = self
+                .meshes
+                .get_mut(&from_mesh)
+                .and_then(|m| m.polies.remove(&poly_id))
+            else {
+                continue;
+            };
+            if let Some(mesh) = self.meshes.get_mut(&to_mesh) {
+                let poly_id = mesh.add(poly);
+                moved.push(UnrefPoly {
+                    mesh_id: to_mesh,
+                    poly_id,
+                });
+            }
+        }
+        moved
+    }
+
+    /// Removes volume of `tool` mesh from `from_mesh`, then moves remaining polygons of `tool` into `from_mesh`.
+    pub fn subtract_mesh(&mut self, from_mesh: MeshId, tool: MeshId) -> Result<(), GeoIndexError> {
+        self.check_meshes(&[from_mesh, tool])?;
+        let _span = span!("subtract_mesh", ?from_mesh, ?tool);
+        let mut report = self.boolean_inputs("subtract", from_mesh, tool);
+        let to_remove = [
+            self.select_polygons(tool, from_mesh, PolygonFilter::Front),
+            self.select_polygons(from_mesh, tool, PolygonFilter::Back),
+        ]
+        .concat();
+        let to_flip = self.select_polygons(tool, from_mesh, PolygonFilter::Back);
+        if let Some(report) = &mut report {
+            report.step("remove", &to_remove);
+            report.step("flip", &to_flip);
+        }
+
+        for p in to_remove {
+            p.make_mut_ref(self).remove();
+        }
+        for p in to_flip {
+            p.make_mut_ref(self).flip();
+        }
+        self.move_all_polygons(tool, from_mesh);
+        self.meshes.remove(&tool);
+        self.report_boolean(report, from_mesh);
+        Ok(())
+    }
+
+    /// Adds volume of `tool` mesh to `to_mesh`, then moves remaining polygons of `tool` into `to_mesh`.
+    pub fn unite_mesh(&mut self, to_mesh: MeshId, tool: MeshId) -> Result<(), GeoIndexError> {
+        self.check_meshes(&[to_mesh, tool])?;
+        let _span = span!("unite_mesh", ?to_mesh, ?tool);
+        let mut report = self.boolean_inputs("unite", to_mesh, tool);
+        let to_remove = [
+            self.select_polygons(tool, to_mesh, PolygonFilter::Back),
+            self.select_polygons(to_mesh, tool, PolygonFilter::Back),
+        ]
+        .concat();
+        if let Some(report) = &mut report {
+            report.step("remove", &to_remove);
+        }
+
+        for p in to_remove {
+            p.make_mut_ref(self).remove();
+        }
+        self.move_all_polygons(tool, to_mesh);
+        self.meshes.remove(&tool);
+        self.report_boolean(report, to_mesh);
+        Ok(())
+    }
+
+    fn check_meshes(&self, meshes: &[MeshId]) -> Result<(), GeoIndexError> {
+        match meshes.iter().find(|m| !self.meshes.contains_key(m)) {
+            Some(mesh_id) => Err(GeoIndexError::MeshNotFound(*mesh_id)),
+            None => Ok(()),
+        }
+    }
+
+    /// Creates new mesh, which surface is shifted from surface of `mesh_id` by `distance` along
+    /// polygon normals. Positive distance makes mesh bigger, negative - smaller.
+    /// Each vertex is moved so it stays on `distance` from all adjacent polygon planes where
+    /// possible. Self-intersections, which may appear with big negative distances, are not resolved.
+    pub fn offset_mesh(
+        &mut self,
+        mesh_id: MeshId,
+        distance: impl Into<Dec>,
+    ) -> anyhow::Result<MeshId> {
+        let distance = distance.into();
+        let polygons = self.mesh_polygon_points(mesh_id);
+        let shifted = self.shifted_vertices(&polygons, distance);
+
+        let offset_mesh = self.new_mesh();
+        for (_, pts) in polygons {
+            let vertices = pts.iter().map(|pt| shifted[pt]).collect_vec();
+            self.add_polygon_to_mesh(&vertices, offset_mesh)?;
+        }
+
+        Ok(offset_mesh)
+    }
+
+    /// Makes solid from open surface. Copy of `mesh_id`, shifted by `thickness` against
+    /// polygon normals, is created, and border loops of both are connected with walls.
+    /// With negative `thickness` solid grows in direction of normals instead.
+    /// Polygons of `mesh_id` are left as is, result is put into new mesh.
+    pub fn thicken_surface(
+        &mut self,
+        mesh_id: MeshId,
+        thickness: impl Into<Dec>,
+    ) -> anyhow::Result<MeshId> {
+        let thickness = thickness.into();
+        let polygons = self.mesh_polygon_points(mesh_id);
+        let shifted = self.shifted_vertices(&polygons, -thickness);
+        let original = shifted
+            .keys()
+            .map(|pt| (*pt, self.vertices.get_point(*pt)))
+            .collect::<BTreeMap<_, _>>();
+        let (top, bottom) = if thickness.is_negative() {
+            (&shifted, &original)
+        } else {
+            (&original, &shifted)
+        };
+        let borders = mesh_id
+            .make_ref(self)
+            .boundary_loops()
+            .into_iter()
+            .flatten()
+            .map(|s| (s.from_pt(), s.to_pt()))
+            .collect_vec();
+
+        let solid = self.new_mesh();
+        for (_, pts) in polygons {
+            let upper = pts.iter().map(|pt| top[pt]).collect_vec();
+            let lower = pts.iter().rev().map(|pt| bottom[pt]).collect_vec();
+            self.add_polygon_to_mesh(&upper, solid)?;
+            self.add_polygon_to_mesh(&lower, solid)?;
+        }
+
+        for (from, to) in borders {
+            // Shifted border is not always parallel to original one, so wall is made of triangles
+            self.add_polygon_to_mesh(&[top[&from], bottom[&from], bottom[&to]], solid)?;
+            self.add_polygon_to_mesh(&[top[&from], bottom[&to], top[&to]], solid)?;
+        }
+
+        Ok(solid)
+    }
+
+    fn mesh_polygon_points(&self, mesh_id: MeshId) -> Vec<(Vector3<Dec>, Vec<PtId>)> {
+        self.get_mesh_polygons(mesh_id)
+            .into_iter()
+            .map(|p| {
+                let poly = p.make_ref(self);
+                (
+                    poly.normal(),
+                    poly.segments().map(|s| s.from_pt()).collect_vec(),
+                )
+            })
+            .collect()
+    }
+
+    fn shifted_vertices(
+        &self,
+        polygons: &[(Vector3<Dec>, Vec<PtId>)],
+        distance: Dec,
+    ) -> BTreeMap<PtId, Vector3<Dec>> {
+        let mut pt_normals: BTreeMap<PtId, Vec<Vector3<Dec>>> = BTreeMap::new();
+        for (normal, pts) in polygons {
+            for pt in pts {
+                let normals = pt_normals.entry(*pt).or_default();
+                if !normals.iter().any(|n| {
+                    (n.dot(normal) - Dec::one())
+                        .round_dp(NORMAL_DOT_ROUNDING)
+                        .is_zero()
+                }) {
+                    normals.push(*normal);
+                }
+            }
+        }
+
+        pt_normals
+            .into_iter()
+            .map(|(pt, normals)| {
+                let v = self.vertices.get_point(pt) + Self::offset_direction(&normals) * distance;
+                (pt, v)
+            })
+            .collect()
+    }
+
+    /// Finds vector `x`, for which `n.dot(x) == 1` for every normal `n`.
+    /// When it is not possible - finds closest solution. Opposite normals of thin wall
+    /// have no solution, normal of the first face is taken for them.
+    fn offset_direction(normals: &[Vector3<Dec>]) -> Vector3<Dec> {
+        match normals {
+            [] => Vector3::zeros(),
+            [n] => *n,
+            [n1, n2] => {
+                let denominator = Dec::one() + n1.dot(n2);
+                if denominator.round_dp(NORMAL_DOT_ROUNDING).is_zero() {
+                    *n1
+                } else {
+                    (n1 + n2) / denominator
+                }
+            }
+            _ => {
+                let a = normals
+                    .iter()
+                    .fold(Matrix3::zeros(), |m: Matrix3<Dec>, n| m + n * n.transpose());
+                let b = normals
+                    .iter()
+                    .fold(Vector3::zeros(), |v: Vector3<Dec>, n| v + n);
+
+                if b.magnitude_squared().round_dp(NORMAL_DOT_ROUNDING).is_zero() {
+                    normals[0]
+                } else if a.determinant().round_dp(NORMAL_DOT_ROUNDING).is_zero() {
+                    let avg = b.normalize();
+                    let dots = normals.iter().map(|n| n.dot(&avg)).sum::<Dec>();
+                    avg * Dec::from(normals.len()) / dots
+                } else {
+                    a.try_inverse()
+                        .map(|inv| inv * b)
+                        .unwrap_or_else(|| b / Dec::from(normals.len()))
+                }
+            }
+        }
+    }
+
     pub fn is_vec_dir_between_two_other_dirs(
         &self,
         plane_normal: Vector3<Dec>,
@@ -1994,7 +2353,6 @@ impl GeoIndex {
             line_straight,
             segs.iter(),
             vertex_pulling * vertex_pulling,
-            false,
         );
         if intersections % 2 == 1 {
             in_poly_dir
@@ -2025,10 +2383,7 @@ impl GeoIndex {
                 }
             }
         }
-        println!(
-            "  .. collect facemeshindex {}ms",
-            _t.elapsed().unwrap().as_millis()
-        );
+        trace!(elapsed = ?_t.elapsed(), "collect face mesh index");
         let _t = SystemTime::now();
 
         let ribs_with_faces = self
@@ -2044,10 +2399,7 @@ impl GeoIndex {
                 meshes.contains(&of_mesh) && meshes.contains(&by_mesh)
             })
             .collect_vec();
-        println!(
-            "  .. collect ribs with faces {}ms",
-            _t.elapsed().unwrap().as_millis()
-        );
+        trace!(elapsed = ?_t.elapsed(), "collect ribs with faces");
 
         let mut planes: Vec<Plane> = Vec::new();
         let mut poly_plane = BTreeMap::new();
@@ -2080,7 +2432,7 @@ impl GeoIndex {
                 }
             }
         }
-        println!("  .. fill shared {}ms", _t.elapsed().unwrap().as_millis());
+        trace!(elapsed = ?_t.elapsed(), "fill shared");
 
         if matches!(filter, PolygonFilter::Shared) {
             // early return for shareds
@@ -2150,16 +2502,10 @@ impl GeoIndex {
                 }
             }
         }
-        println!(
-            "  .. detect edge polies {}ms",
-            _t.elapsed().unwrap().as_millis()
-        );
+        trace!(elapsed = ?_t.elapsed(), "detect edge polygons");
         let _t = SystemTime::now();
         let visited = self.spread_visited_around_2(&ribs, of_mesh, visited);
-        println!(
-            "  .. spread outer polies {}ms",
-            _t.elapsed().unwrap().as_millis()
-        );
+        trace!(elapsed = ?_t.elapsed(), "spread outer polygons");
         let result = visited
             .into_iter()
             .filter(|(_, r)| *r == filter)
@@ -2168,7 +2514,7 @@ impl GeoIndex {
                 poly_id,
             })
             .collect_vec();
-        println!("select: {}ms", _ts.elapsed().unwrap().as_millis());
+        debug!(?of_mesh, ?by_mesh, ?filter, elapsed = ?_ts.elapsed(), "select polygons");
         result
     }
 
@@ -2230,27 +2576,7 @@ impl GeoIndex {
         chain: Vec<Seg>,
     ) -> Option<(Vec<Seg>, Vec<Seg>)> {
         let face_ref = self.load_face_ref(face_id);
-        if face_id.0 == 2494 {
-            println!("~~~~~~~~~~~~~~~~~~~~~~~~");
-            for ch in &chain {
-                let sr = ch.make_ref(self);
-                println!(
-                    "possible chain: {:?}: {:?} -> {:?}",
-                    sr.rib_id,
-                    sr.from_pt(),
-                    sr.to_pt()
-                )
-            }
-            println!("--");
-            for sr in face_id.make_ref(self).segments(SegmentDir::Fow) {
-                println!(
-                    "poly: {:?}: {:?} -> {:?}",
-                    sr.rib_id,
-                    sr.from_pt(),
-                    sr.to_pt()
-                )
-            }
-        }
+        trace!(?face_id, ?chain, "possible splitting chain");
 
         let chain_without_face_ribs = chain
             .into_iter()
@@ -2352,7 +2678,7 @@ impl GeoIndex {
         let color = COLORS[self.current_color % COLORS.len()];
 
         let filename = self.debug_path.join(format!("{pre}face-{face_id:?}.svg"));
-        println!("~~~DEBUG {filename:?}  {face_id:?}");
+        debug!(?filename, ?face_id, "write face svg");
         std::fs::write(
             filename,
             face_id
@@ -2383,6 +2709,16 @@ impl GeoIndex {
         format!("points={points};\n polyhedron(points, [{hedras}]);")
     }
 
+    /// Triangles of all meshes. Each face is triangulated only once, so calling
+    /// this after other exports is cheap.
+    pub fn stl_triangles(&self) -> anyhow::Result<Vec<Triangle>> {
+        let mut triangles = Vec::new();
+        for mesh in self.meshes() {
+            triangles.extend(mesh.stl_triangles()?);
+        }
+        Ok(triangles)
+    }
+
     fn is_chain_inside_face(&self, chain: &[Seg], face_id: FaceId) -> bool {
         chain
             .iter()
@@ -2394,13 +2730,9 @@ impl GeoIndex {
         line: Line,
         seg_refs: impl Iterator<Item = &'a SegRef<'a>> + Clone,
         vertex_pulling_sq: Dec,
-        _do_debug: bool,
     ) -> usize {
         //let vertex_pulling = Dec::from(dec!(0.001)); // one micrometer
         //let vertex_pulling_sq = vertex_pulling * vertex_pulling;
-        if _do_debug {
-            println!("Line: {line:?}");
-        }
 
         let mut hits_points_new = seg_refs
             .clone()
@@ -2410,14 +2742,6 @@ impl GeoIndex {
                     let dot = (seg_ref.from() - line.origin).dot(&line.dir);
                     // Filter for positive line direction
                     if dot.is_positive() {
-                        if _do_debug {
-                            println!(
-                                " push pt: {} because {} is positive distance_to: {}",
-                                seg_ref.from_pt(),
-                                dot,
-                                distance_to
-                            );
-                        }
                         return Some(seg_ref.from_pt());
                     }
                 }
@@ -2428,14 +2752,6 @@ impl GeoIndex {
 
         // Collect also points, that hitting segments somewhere in half
         for seg_ref in seg_refs.clone() {
-            if _do_debug {
-                println!(
-                    " {:?} -> {:?}: {:?}",
-                    seg_ref.from_pt(),
-                    seg_ref.to_pt(),
-                    seg_ref.rib_id
-                );
-            }
             let some_ab = line.get_intersection_params_seg_ref(seg_ref);
             if let Some((a, b)) = some_ab {
                 let diff = (b - Dec::one()).abs().min(b.abs());
@@ -2454,14 +2770,6 @@ impl GeoIndex {
                         })
                         .any(|v| (v - pt).magnitude_squared() < vertex_pulling_sq)
                     {
-                        if _do_debug {
-                            let ab = line.get_intersection_params_seg_ref(seg_ref);
-                            println!(
-                                " push vertex: {} {} {}  ({}, {}) [[{ab:?}]]",
-                                pt.x, pt.y, pt.z, a, b
-                            );
-                            println!(" seg dir: {:?}", seg_ref.dir().normalize());
-                        }
                         hits_points_new.push(Either::Right(pt));
                     }
                 }
@@ -2503,7 +2811,14 @@ impl GeoIndex {
                         let base = self.vertices.get_point(*hp);
                         let f = from.to() - base;
                         let t = to.from() - base;
-                        let norm = f.normalize().cross(&line.dir).normalize();
+                        let side = f.cross(&line.dir);
+                        if side.magnitude_squared().is_zero()
+                            || t.cross(&line.dir).magnitude_squared().is_zero()
+                        {
+                            // One of segments goes along the line, it is not a crossing
+                            return false;
+                        }
+                        let norm = side.normalize();
                         let perpendicular_in_plane = norm.cross(&line.dir).normalize();
                         let fd = perpendicular_in_plane.dot(&f);
                         let td = perpendicular_in_plane.dot(&t);
@@ -2542,15 +2857,13 @@ impl GeoIndex {
             })
             .collect_vec();
 
-        if _do_debug {
-            println!(
-                "ribs:{} points:{:?} hits_points_new: {} [{:?}]",
-                crossed_on_ribs.len(),
-                crossed_points,
-                hits_points_new.iter().filter_map(|hp| hp.right()).count(),
-                hits_points_new
-            )
-        }
+        trace!(
+            ?line,
+            crossed_ribs = crossed_on_ribs.len(),
+            ?crossed_points,
+            ?hits_points_new,
+            "line crosses segments"
+        );
         crossed_on_ribs.len()
             + crossed_points.len()
             + hits_points_new.iter().filter_map(|hp| hp.right()).count()
@@ -2561,7 +2874,6 @@ impl GeoIndex {
         line: Line,
         face_id: FaceId,
         vertex_pulling_sq: Dec,
-        _do_debug: bool,
     ) -> usize {
         let all_face_segments = self
             .load_face_ref(face_id)
@@ -2571,7 +2883,6 @@ impl GeoIndex {
             line.clone(),
             all_face_segments.iter(),
             vertex_pulling_sq,
-            _do_debug,
         )
     }
 
@@ -2600,30 +2911,19 @@ impl GeoIndex {
             .magnitude()
             .div(2)
             .min(Dec::one() / Dec::from(1000));
-        if face_id.0 == 2482 && rib_id == 3846 {
-            println!("select vp: {vertex_pulling}");
-        }
 
-        let total_intersects = self.collect_line_face_intersections(
-            line,
-            face_id,
-            vertex_pulling * vertex_pulling,
-            face_id.0 == 2482 && rib_id == 3846,
-        );
-        if face_id.0 == 2482 && rib_id == 3846 {
-            println!("Counted intersecions for 3846 and 2482 {total_intersects}");
-        }
+        let total_intersects =
+            self.collect_line_face_intersections(line, face_id, vertex_pulling * vertex_pulling);
+        trace!(?rib_id, ?face_id, %vertex_pulling, total_intersects, "rib inside face");
 
         /*
         let is_i = if face_pts.contains(&rib_id.make_ref(self).from_pt())
             || face_pts.contains(&rib_id.make_ref(self).to_pt())
         {
-            println!(
-                "face border contains some point of rib => need to check, if other point is inside {total_intersects}"
-            );
+            trace!(total_intersects, "face border contains some point of rib");
             (total_intersects.saturating_sub(1)) % 2 != 0
         } else {
-            println!("All rib points somewhere in space");
+            trace!(total_intersects, "all rib points somewhere in space");
 
             total_intersects % 2 != 0
         };
@@ -2662,6 +2962,36 @@ impl GeoIndex {
         self.meshes.insert(mesh_id, Mesh::default());
         mesh_id
     }
+
+    /// Creates mesh, which welds its points with own precision instead of index one.
+    /// Useful for small details, which are placed on big meshes.
+    pub fn new_mesh_with_precision(&mut self, points_precision: impl Into<Dec>) -> MeshId {
+        let mesh_id = self.new_mesh();
+        if let Some(mesh) = self.meshes.get_mut(&mesh_id) {
+            mesh.points_precision = Some(points_precision.into());
+        }
+        mesh_id
+    }
+
+    pub(super) fn mesh_points_precision(&self, mesh_id: MeshId) -> Dec {
+        self.meshes
+            .get(&mesh_id)
+            .and_then(|m| m.points_precision)
+            .unwrap_or(self.points_precision)
+    }
+
+    /// Finest precision among meshes, which have polygons on this face
+    fn face_points_precision(&self, face_id: FaceId) -> Dec {
+        if self.meshes.values().all(|m| m.points_precision.is_none()) {
+            return self.points_precision;
+        }
+        self.meshes
+            .values()
+            .filter(|m| m.polies.values().any(|p| p.face_id == face_id))
+            .map(|m| m.points_precision.unwrap_or(self.points_precision))
+            .min()
+            .unwrap_or(self.points_precision)
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -2678,3 +3008,96 @@ pub enum PolygonRelation {
     ToolPolygonBackOfSrc,
     ToolPolygonFrontOfSrc,
 }
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use nalgebra::{ComplexField, Vector3};
+    use rust_decimal_macros::dec;
+
+    use crate::{
+        decimal::Dec,
+        geometry::GeometryDyn,
+        indexes::{
+            aabb::Aabb,
+            geo_index::{geo_object::GeoObject, mesh::MeshId},
+        },
+        origin::Origin,
+        shapes::{Cylinder, Rect},
+    };
+
+    use super::GeoIndex;
+
+    fn index() -> GeoIndex {
+        GeoIndex::new(Aabb::from_points(&[
+            Vector3::new(Dec::from(-50), Dec::from(-50), Dec::from(-50)),
+            Vector3::new(Dec::from(50), Dec::from(50), Dec::from(50)),
+        ]))
+        .points_precision(dec!(0.001))
+    }
+
+    /// Distance from `point` inside of convex mesh to its surface
+    fn depth(index: &GeoIndex, mesh: MeshId, point: Vector3<Dec>) -> Dec {
+        mesh.make_ref(index)
+            .into_polygons()
+            .into_iter()
+            .map(|p| {
+                let poly = p.make_ref(index);
+                let on_plane = poly.segments().next().unwrap().from();
+                poly.normal().dot(&(on_plane - point))
+            })
+            .min()
+            .unwrap()
+    }
+
+    /// Hollows `mesh` with walls `thickness` thick and checks, that walls are that thick
+    fn check_shell(index: &mut GeoIndex, mesh: MeshId, thickness: Dec) {
+        let inner = index.offset_mesh(mesh, -thickness).unwrap();
+        let report = inner.make_ref(index).validate();
+        assert!(report.is_valid(), "{report}");
+        let inner_points = inner
+            .make_ref(index)
+            .into_polygons()
+            .into_iter()
+            .flat_map(|p| p.make_ref(index).segments().map(|s| s.from()).collect_vec())
+            .collect_vec();
+        for point in inner_points {
+            let wall = depth(index, mesh, point);
+            assert!((wall - thickness).abs() < Dec::from(dec!(0.001)), "{wall}");
+        }
+
+        let outer_polygons = mesh.make_ref(index).into_polygons().len();
+        let inner_polygons = inner.make_ref(index).into_polygons().len();
+        index.subtract_mesh(mesh, inner).unwrap();
+
+        let report = mesh.make_ref(index).validate();
+        assert!(report.is_valid(), "{report}");
+        assert_eq!(
+            mesh.make_ref(index).into_polygons().len(),
+            outer_polygons + inner_polygons
+        );
+    }
+
+    #[test]
+    fn offset_cube_into_shell() {
+        let mut index = index();
+        let mesh = index.new_mesh();
+        Rect::centered(Origin::new(), 10.into(), 10.into(), 10.into())
+            .polygonize(mesh.make_mut_ref(&mut index), 0)
+            .unwrap();
+
+        check_shell(&mut index, mesh, Dec::from(2));
+    }
+
+    #[test]
+    fn offset_cylinder_into_shell() {
+        let mut index = index();
+        let mesh = index.new_mesh();
+        Cylinder::centered(Origin::new(), 10, 5)
+            .steps(16)
+            .polygonize(mesh.make_mut_ref(&mut index), 0)
+            .unwrap();
+
+        check_shell(&mut index, mesh, Dec::from(1));
+    }
+}
@@ -0,0 +1,142 @@
+use nalgebra::ComplexField;
+use rust_decimal_macros::dec;
+
+use crate::{
+    decimal::Dec,
+    geometry::GeometryDyn,
+    origin::Origin,
+    shapes::{Align, Rect},
+};
+
+use super::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId};
+
+/// Pattern of slots, which makes a strip of part flexible. Origin is in the middle of
+/// the strip on part surface, strip goes along x axis, z axis looks out of the part.
+/// Slots go across the strip (along y) in rows, slots of neighbour rows are shifted by
+/// half of period, so material between them bends like a spring.
+#[derive(Clone, Debug)]
+pub struct LivingHinge {
+    origin: Origin,
+    length: Dec,
+    width: Dec,
+    depth: Dec,
+    cut_length: Dec,
+    spacing: Dec,
+    bridge_width: Dec,
+    cut_width: Dec,
+}
+
+impl LivingHinge {
+    pub fn strip(origin: Origin, length: impl Into<Dec>, width: impl Into<Dec>) -> Self {
+        Self {
+            origin,
+            length: length.into(),
+            width: width.into(),
+            depth: Dec::from(2),
+            cut_length: Dec::from(10),
+            spacing: Dec::from(2),
+            bridge_width: Dec::from(2),
+            cut_width: dec!(0.5).into(),
+        }
+    }
+
+    /// How deep slots go into the part. Should be not less than part thickness
+    /// to get through cuts.
+    pub fn depth(mut self, depth: impl Into<Dec>) -> Self {
+        self.depth = depth.into();
+        self
+    }
+
+    /// Length of each slot across the strip
+    pub fn cut_length(mut self, cut_length: impl Into<Dec>) -> Self {
+        self.cut_length = cut_length.into();
+        self
+    }
+
+    /// Distance between rows of slots along the strip
+    pub fn spacing(mut self, spacing: impl Into<Dec>) -> Self {
+        self.spacing = spacing.into();
+        self
+    }
+
+    /// Material left between two slots in one row
+    pub fn bridge_width(mut self, bridge_width: impl Into<Dec>) -> Self {
+        self.bridge_width = bridge_width.into();
+        self
+    }
+
+    /// Width of each slot along the strip
+    pub fn cut_width(mut self, cut_width: impl Into<Dec>) -> Self {
+        self.cut_width = cut_width.into();
+        self
+    }
+
+    fn rows(&self) -> Vec<Dec> {
+        let rows = i128::from((self.length / self.spacing).floor()) as usize + 1;
+        let start = -self.spacing * Dec::from(rows - 1) / 2;
+        (0..rows)
+            .map(|i| start + self.spacing * Dec::from(i))
+            .collect()
+    }
+
+    /// Slot intervals across the strip, clipped by strip edges
+    fn row_slots(&self, shifted: bool) -> Vec<(Dec, Dec)> {
+        let period = self.cut_length + self.bridge_width;
+        let half_width = self.width / 2;
+        let first_center = if shifted { period / 2 } else { Dec::from(0) };
+        let periods = i128::from((half_width / period).ceil()) + 1;
+
+        (-periods..=periods)
+            .map(|k| first_center + period * Dec::from(k))
+            .map(|c| {
+                (
+                    (c - self.cut_length / 2).max(-half_width),
+                    (c + self.cut_length / 2).min(half_width),
+                )
+            })
+            .filter(|(from, to)| *to - *from >= self.bridge_width)
+            .collect()
+    }
+
+    fn slots(&self) -> Vec<Rect> {
+        let sw = Dec::from(dec!(0.1));
+        self.rows()
+            .into_iter()
+            .enumerate()
+            .flat_map(|(i, x)| {
+                self.row_slots(i % 2 == 1)
+                    .into_iter()
+                    .map(move |(from, to)| (x, from, to))
+            })
+            .map(|(x, from, to)| {
+                Rect::build()
+                    .origin(
+                        self.origin
+                            .clone()
+                            .offset_x(x)
+                            .offset_y((from + to) / 2)
+                            .offset_z(sw),
+                    )
+                    .width(self.cut_width)
+                    .height(to - from)
+                    .depth(self.depth + sw)
+                    .align_z(Align::Pos)
+                    .build()
+            })
+            .collect()
+    }
+}
+
+impl GeoIndex {
+    /// Cuts living hinge slots in the mesh. All slots are collected in one tool mesh,
+    /// so boolean is done once.
+    pub fn living_hinge(&mut self, mesh_id: MeshId, hinge: &LivingHinge) -> anyhow::Result<()> {
+        let cutter = self.new_mesh();
+        for slot in hinge.slots() {
+            slot.polygonize(cutter.make_mut_ref(self), 0)?;
+        }
+        self.subtract_mesh(mesh_id, cutter)?;
+
+        Ok(())
+    }
+}
@@ -1,36 +1,52 @@
-use std::{collections::HashMap, ops::Deref};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    ops::Deref,
+};
 
+use anyhow::anyhow;
 use itertools::Itertools;
 use nalgebra::Vector3;
-
-use crate::decimal::Dec;
+use num_traits::{Signed, Zero};
+use serde::{Deserialize, Serialize};
+use stl_io::{Triangle, Vector};
+
+use crate::{
+    decimal::Dec,
+    indexes::vertex_index::PtId,
+    log::trace,
+    origin::Origin,
+    planar::{plane::Plane, polygon::Polygon, triangulation},
+    polygon_basis::PolygonBasis,
+};
 
 use super::{
     face::FaceId,
     geo_object::{GeoObject, UnRef},
-    index::GeoIndex,
+    index::{GeoIndex, PolygonFilter},
     poly::{Poly, PolyId, UnrefPoly},
+    rib::RibId,
+    seg::{Seg, SegRef},
 };
 
-#[derive(Debug, PartialEq, Clone, Default)]
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
 pub struct Mesh {
     poly_counter: usize,
     pub(super) polies: HashMap<PolyId, Poly>,
+    /// Overrides `points_precision` of index for this mesh
+    pub(super) points_precision: Option<Dec>,
 }
 
 impl Mesh {
     pub(crate) fn add(&mut self, poly: Poly) -> PolyId {
         let poly_id = PolyId(self.poly_counter);
-        if poly.face_id.0 == 2493 || poly.face_id.0 == 2494 {
-            println!("  ...{poly_id:?} ");
-        }
+        trace!(?poly_id, face_id = ?poly.face_id, "add polygon");
         self.poly_counter += 1;
         self.polies.insert(poly_id, poly);
         poly_id
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Serialize, Deserialize)]
 pub struct MeshId(pub usize);
 
 impl PartialEq<usize> for MeshId {
@@ -83,7 +99,7 @@ impl<'a> MeshRef<'a> {
         self.geo_index.select_polygons(
             self.mesh_id,
             mesh_ref.mesh_id,
-            super::index::PolygonFilter::Back,
+            PolygonFilter::Back,
         )
     }
 
@@ -91,7 +107,7 @@ impl<'a> MeshRef<'a> {
         self.geo_index.select_polygons(
             self.mesh_id,
             mesh_ref.mesh_id,
-            super::index::PolygonFilter::Front,
+            PolygonFilter::Front,
         )
     }
 
@@ -99,10 +115,73 @@ impl<'a> MeshRef<'a> {
         self.geo_index.select_polygons(
             self.mesh_id,
             mesh_ref.mesh_id,
-            super::index::PolygonFilter::Shared,
+            PolygonFilter::Shared,
         )
     }
 
+    /// Triangles of all polygons, ready to be written to stl
+    pub fn stl_triangles(&self) -> anyhow::Result<Vec<Triangle>> {
+        let to_stl = |v: Vector3<Dec>| Vector::new([v.x.into(), v.y.into(), v.z.into()]);
+        let mut result = Vec::new();
+        for poly in self.all_polygons() {
+            let poly = poly.make_ref(self.geo_index);
+            let normal = to_stl(poly.normal());
+            for triangle in poly.triangles()? {
+                result.push(Triangle {
+                    normal,
+                    vertices: triangle.map(|pt| to_stl(self.geo_index.vertices.get_point(pt))),
+                });
+            }
+        }
+        Ok(result)
+    }
+
+    /// Returns loops of ribs, which belong to only one polygon of this mesh.
+    /// Segments in each loop are ordered and directed as in the polygon they belong to.
+    /// For closed mesh result is empty. If border cannot be closed (non-manifold
+    /// vertex, for example) - open chain is returned as is.
+    pub fn boundary_loops(&self) -> Vec<Vec<SegRef<'a>>> {
+        let index = self.geo_index;
+        let mut rib_segs: BTreeMap<RibId, Vec<Seg>> = BTreeMap::new();
+        for &poly_id in self.mesh().polies.keys() {
+            let poly = UnrefPoly {
+                poly_id,
+                mesh_id: self.mesh_id,
+            };
+            for seg in poly.make_ref(index).segments() {
+                rib_segs.entry(seg.rib_id).or_default().push(seg.seg());
+            }
+        }
+
+        let mut outgoing: BTreeMap<PtId, Vec<Seg>> = BTreeMap::new();
+        for seg in rib_segs
+            .into_values()
+            .filter(|segs| segs.len() == 1)
+            .flatten()
+        {
+            outgoing.entry(seg.from(&index.ribs)).or_default().push(seg);
+        }
+
+        let mut loops = Vec::new();
+        while let Some(mut pt) = outgoing.keys().next().copied() {
+            let start = pt;
+            let mut chain = Vec::new();
+            while let Some(seg) = outgoing.get_mut(&pt).and_then(|segs| segs.pop()) {
+                if outgoing.get(&pt).is_some_and(|segs| segs.is_empty()) {
+                    outgoing.remove(&pt);
+                }
+                pt = seg.to(&index.ribs);
+                chain.push(seg.to_ref(index));
+                if pt == start {
+                    break;
+                }
+            }
+            loops.push(chain);
+        }
+
+        loops
+    }
+
     fn mesh(&self) -> &Mesh {
         &self.geo_index.meshes[&self.mesh_id]
     }
@@ -136,22 +215,200 @@ impl<'a> MeshRefMut<'a> {
         self.geo_index.add_polygon_to_mesh(p, self.mesh_id)
     }
 
+    /// Adds planar polygon with holes as triangles of constrained triangulation. Plane and
+    /// outer side are taken from `outer` winding, holes may go either way.
+    pub fn add_polygon_with_holes(
+        &mut self,
+        outer: &[Vector3<Dec>],
+        holes: &[Vec<Vector3<Dec>>],
+    ) -> anyhow::Result<()> {
+        if holes.is_empty() {
+            return self.add_polygon(outer);
+        }
+        let basis = Polygon::calculate_basis_2d(outer)?;
+        let project =
+            |c: &[Vector3<Dec>]| c.iter().map(|p| basis.project_on_plane_z(p)).collect_vec();
+        let points = outer.iter().chain(holes.iter().flatten()).collect_vec();
+        let triangles = triangulation::triangulate_with_holes(
+            &project(outer),
+            &holes.iter().map(|h| project(h)).collect_vec(),
+        )?;
+        for t in triangles {
+            self.add_polygon(&t.map(|ix| *points[ix]))?;
+        }
+        Ok(())
+    }
+
     pub fn back_of(&self, mesh_ref: MeshRef<'_>) -> Vec<UnrefPoly> {
         self.geo_index.select_polygons(
             self.mesh_id,
             mesh_ref.mesh_id,
-            super::index::PolygonFilter::Back,
+            PolygonFilter::Back,
         )
     }
 
+    /// Moves mesh into `origin`: each point is treated as local coordinates of origin.
+    pub fn transform(&mut self, origin: &Origin) -> anyhow::Result<()> {
+        self.map_points(|p| origin.center + origin.rotation * p, false)
+    }
+
+    /// Scales mesh relative to zero point. Negative factor mirrors mesh through zero point.
+    pub fn scale(&mut self, factor: impl Into<Dec>) -> anyhow::Result<()> {
+        let factor = factor.into();
+        if factor.is_zero() {
+            return Err(anyhow!("Cannot scale mesh to zero size"));
+        }
+        self.map_points(|p| p * factor, factor.is_negative())
+    }
+
+    /// Mirrors mesh across yz plane. Use `GeoIndex::mirror_x` to mirror all meshes at once.
+    pub fn mirror_x(&mut self) -> anyhow::Result<()> {
+        self.map_points(|p| Vector3::new(-p.x, p.y, p.z), true)
+    }
+
+    /// Polygons are taken out of the index and added back with moved points, so ribs,
+    /// faces and intersections with other meshes are rebuilt as for new polygons.
+    fn map_points(
+        &mut self,
+        f: impl Fn(Vector3<Dec>) -> Vector3<Dec>,
+        flip: bool,
+    ) -> anyhow::Result<()> {
+        let polygons = self
+            .all_polygons()
+            .into_iter()
+            .map(|p| {
+                let mut points = p
+                    .make_ref(self.geo_index)
+                    .segments()
+                    .map(|s| f(s.from()))
+                    .collect_vec();
+                if flip {
+                    points.reverse();
+                }
+                (p, points)
+            })
+            .collect_vec();
+
+        for (p, _) in &polygons {
+            p.make_mut_ref(self.geo_index).remove();
+        }
+        for (_, points) in polygons {
+            self.add_polygon(&points)?;
+        }
+
+        Ok(())
+    }
+
     pub fn front_of(&self, mesh_ref: MeshRefMut<'_>) -> Vec<UnrefPoly> {
         self.geo_index.select_polygons(
             self.mesh_id,
             mesh_ref.mesh_id,
-            super::index::PolygonFilter::Front,
+            PolygonFilter::Front,
         )
     }
 
+    /// Symmetric difference of this mesh and `other`: parts of both meshes, that are not
+    /// inside each other. Polygons, which are inside other mesh are flipped, shared polygons
+    /// are dropped. Result is stored in new mesh, source meshes are left untouched.
+    pub fn boolean_xor(&mut self, other: MeshId) -> MeshId {
+        let mut result = Vec::new();
+        for (of_mesh, by_mesh) in [(self.mesh_id, other), (other, self.mesh_id)] {
+            let index = &*self.geo_index;
+            let backs = index
+                .select_polygons(of_mesh, by_mesh, PolygonFilter::Back)
+                .into_iter()
+                .collect::<HashSet<_>>();
+            let shared = index
+                .select_polygons(of_mesh, by_mesh, PolygonFilter::Shared)
+                .into_iter()
+                .collect::<HashSet<_>>();
+
+            for poly in index.get_mesh_polygons(of_mesh) {
+                if shared.contains(&poly) {
+                    continue;
+                }
+                let mut p = index.meshes[&of_mesh].polies[&poly.poly_id];
+                if backs.contains(&poly) {
+                    p.flip();
+                }
+                result.push(p);
+            }
+        }
+
+        let mesh_id = self.geo_index.new_mesh();
+        if let Some(mesh) = self.geo_index.meshes.get_mut(&mesh_id) {
+            for p in result {
+                mesh.add(p);
+            }
+        }
+        mesh_id
+    }
+
+    /// Fills every planar boundary loop of this mesh with new polygon.
+    /// Non-convex loops are triangulated. Non-planar loops are skipped.
+    /// Returns amount of capped loops. Loop is planar, when its points are not further
+    /// from plane, than points precision of this mesh.
+    pub fn cap_boundaries(&mut self) -> anyhow::Result<usize> {
+        let tolerance = self.geo_index.mesh_points_precision(self.mesh_id);
+        let loops = self
+            .mesh_id
+            .make_ref(self.geo_index)
+            .boundary_loops()
+            .into_iter()
+            .map(|segs| segs.into_iter().map(|s| s.from()).rev().collect_vec())
+            .collect_vec();
+
+        let mut capped = 0;
+        for points in loops {
+            if points.len() < 3 {
+                continue;
+            }
+            let normal = points.iter().zip(points.iter().cycle().skip(1)).fold(
+                Vector3::zero(),
+                |n: Vector3<Dec>, (a, b)| {
+                    n + Vector3::new(
+                        (a.y - b.y) * (a.z + b.z),
+                        (a.z - b.z) * (a.x + b.x),
+                        (a.x - b.x) * (a.y + b.y),
+                    )
+                },
+            );
+            if normal.magnitude_squared().is_zero() {
+                continue;
+            }
+            let normal = normal.normalize();
+            let plane = Plane::new_from_normal_and_point(normal, points[0]);
+            if !points
+                .iter()
+                .all(|p| (plane.normal().dot(p) - plane.d()).abs() <= tolerance)
+            {
+                continue;
+            }
+
+            let x = (points[1] - points[0]).normalize();
+            let basis = PolygonBasis {
+                center: points[0],
+                x,
+                y: normal.cross(&x),
+            };
+            let points_2d = points
+                .iter()
+                .map(|p| basis.project_on_plane_z(p))
+                .collect_vec();
+
+            if triangulation::is_convex(&points_2d) {
+                self.add_polygon(&points)?;
+            } else {
+                for t in triangulation::triangulate(&points_2d)? {
+                    self.add_polygon(&t.map(|ix| points[ix]))?;
+                }
+            }
+            capped += 1;
+        }
+
+        Ok(capped)
+    }
+
     fn mesh_obj(&self) -> &Mesh {
         &self.geo_index.meshes[&self.mesh_id]
     }
@@ -204,3 +461,152 @@ impl<'a> UnRef<'a> for MeshRefMut<'a> {
         self.mesh_id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use nalgebra::Vector3;
+    use rust_decimal_macros::dec;
+
+    use crate::{
+        decimal::Dec,
+        geometry::GeometryDyn,
+        indexes::{
+            aabb::Aabb,
+            geo_index::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId},
+        },
+        origin::Origin,
+        shapes::Rect,
+    };
+
+    fn index() -> GeoIndex {
+        GeoIndex::new(Aabb::from_points(&[
+            Vector3::new(Dec::from(-50), Dec::from(-50), Dec::from(-50)),
+            Vector3::new(Dec::from(50), Dec::from(50), Dec::from(50)),
+        ]))
+        .points_precision(dec!(0.001))
+    }
+
+    fn cube(index: &mut GeoIndex, origin: Origin) -> MeshId {
+        let mesh = index.new_mesh();
+        Rect::centered(origin, 10.into(), 10.into(), 10.into())
+            .polygonize(mesh.make_mut_ref(index), 0)
+            .unwrap();
+        mesh
+    }
+
+    fn offset(x: i32, y: i32, z: i32) -> Origin {
+        Origin::new().offset(Vector3::new(Dec::from(x), Dec::from(y), Dec::from(z)))
+    }
+
+    /// Smallest and biggest coordinates of mesh points
+    fn bounds(index: &GeoIndex, mesh: MeshId) -> (Vector3<Dec>, Vector3<Dec>) {
+        let points = mesh
+            .make_ref(index)
+            .into_polygons()
+            .into_iter()
+            .flat_map(|p| p.make_ref(index).segments().map(|s| s.from()).collect_vec())
+            .map(|p| p.map(|c| c.round_dp(6)))
+            .collect_vec();
+        points.iter().fold((points[0], points[0]), |(min, max), p| {
+            (min.zip_map(p, Dec::min), max.zip_map(p, Dec::max))
+        })
+    }
+
+    #[test]
+    fn xor_of_overlapping_cubes() {
+        let mut index = index();
+        let a = cube(&mut index, Origin::new());
+        let b = cube(&mut index, offset(5, 5, 5));
+
+        let xor = a.make_mut_ref(&mut index).boolean_xor(b);
+
+        // Each cube is split into 9 polygons by the other, all of them are kept
+        assert_eq!(xor.make_ref(&index).into_polygons().len(), 18);
+        assert_eq!(a.make_ref(&index).into_polygons().len(), 9);
+        // Surfaces of cubes cross along six ribs, four polygons meet at each of them.
+        // Elsewhere result is closed and its polygons agree on direction.
+        let report = xor.make_ref(&index).validate();
+        assert!(report.open_ribs.is_empty(), "{report}");
+        assert!(report.flipped.is_empty(), "{report}");
+        assert_eq!(report.non_manifold_ribs.len(), 6, "{report}");
+        assert!(report
+            .non_manifold_ribs
+            .iter()
+            .all(|rib| rib.polygons.len() == 4));
+        assert!(!report.inside_out);
+    }
+
+    #[test]
+    fn cap_removed_side_of_cube() {
+        let mut index = index();
+        let mesh = cube(&mut index, Origin::new());
+        let side = mesh.make_ref(&index).into_polygons()[0];
+        side.make_mut_ref(&mut index).remove();
+
+        let capped = mesh.make_mut_ref(&mut index).cap_boundaries().unwrap();
+
+        assert_eq!(capped, 1);
+        let report = mesh.make_ref(&index).validate();
+        assert!(report.is_valid(), "{report}");
+        assert_eq!(mesh.make_ref(&index).into_polygons().len(), 6);
+    }
+
+    #[test]
+    fn non_planar_boundary_is_not_capped() {
+        let mut index = index();
+        let mesh = index.new_mesh();
+        let point = |x: i32, y: i32, z: i32| Vector3::new(Dec::from(x), Dec::from(y), Dec::from(z));
+        let mut mesh_ref = mesh.make_mut_ref(&mut index);
+        mesh_ref
+            .add_polygon(&[point(0, 0, 0), point(10, 0, 0), point(10, 10, 5)])
+            .unwrap();
+        mesh_ref
+            .add_polygon(&[point(0, 0, 0), point(10, 10, 5), point(0, 10, 0)])
+            .unwrap();
+
+        let capped = mesh.make_mut_ref(&mut index).cap_boundaries().unwrap();
+
+        assert_eq!(capped, 0);
+        assert_eq!(mesh.make_ref(&index).into_polygons().len(), 2);
+        assert_eq!(mesh.make_ref(&index).validate().open_ribs.len(), 4);
+    }
+
+    #[test]
+    fn mirrored_cube_is_valid() {
+        let mut index = index();
+        let mesh = cube(&mut index, offset(20, 1, 2));
+
+        mesh.make_mut_ref(&mut index).mirror_x().unwrap();
+
+        let report = mesh.make_ref(&index).validate();
+        assert!(report.is_valid(), "{report}");
+        assert!(!report.inside_out);
+        assert_eq!(
+            bounds(&index, mesh),
+            (
+                Vector3::new(Dec::from(-25), Dec::from(-4), Dec::from(-3)),
+                Vector3::new(Dec::from(-15), Dec::from(6), Dec::from(7))
+            )
+        );
+    }
+
+    #[test]
+    fn cube_scaled_by_negative_factor_is_valid() {
+        let mut index = index();
+        let mesh = cube(&mut index, offset(10, 0, 0));
+
+        mesh.make_mut_ref(&mut index).scale(-2).unwrap();
+
+        let report = mesh.make_ref(&index).validate();
+        assert!(report.is_valid(), "{report}");
+        assert!(!report.inside_out);
+        assert_eq!(
+            bounds(&index, mesh),
+            (
+                Vector3::new(Dec::from(-30), Dec::from(-10), Dec::from(-10)),
+                Vector3::new(Dec::from(-10), Dec::from(10), Dec::from(10))
+            )
+        );
+    }
+}
@@ -4,8 +4,15 @@ use itertools::Itertools;
 use nalgebra::{Vector2, Vector3};
 use num_traits::{Bounded, Zero};
 use rand::Rng;
-
-use crate::{decimal::Dec, indexes::aabb::Aabb, planar::plane::Plane, polygon_basis::PolygonBasis};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    decimal::Dec,
+    indexes::{aabb::Aabb, vertex_index::PtId},
+    log::trace,
+    planar::plane::Plane,
+    polygon_basis::PolygonBasis,
+};
 
 use super::{
     face::{Face, FaceId},
@@ -178,7 +185,7 @@ impl<'a> PolyRef<'a> {
 
         while !t.done() {
             t.step().tap_err(|e| {
-                println!("basis {basis:?}");
+                debug!(?basis, "cannot triangulate polygon");
                 let mut parents = self
                     .index
                     .polygon_splits
@@ -193,9 +200,9 @@ impl<'a> PolyRef<'a> {
                     chain.push(parent);
                 }
                 chain.reverse();
-                println!(
-                    "chain: {}",
-                    chain.into_iter().map(|p| format!("{p:?}")).join(" > ")
+                debug!(
+                    chain = %chain.into_iter().map(|p| format!("{p:?}")).join(" > "),
+                    "splits of polygon"
                 );
 
                 panic!("{e}");
@@ -299,9 +306,20 @@ impl<'a> PolyRef<'a> {
         self.mesh_id
     }
 
-    pub(crate) fn normal(&self) -> Vector3<Dec> {
+    /// Normal of polygon, looking out of its mesh
+    pub fn normal(&self) -> Vector3<Dec> {
         self.plane().normal()
     }
+
+    /// Triangles of polygon, looking same way as polygon does.
+    /// Triangulation is cached in face, so repeated exports do not run it again.
+    pub fn triangles(&self) -> anyhow::Result<Vec<[PtId; 3]>> {
+        let triangles = self.index.load_face_ref(self.face_id()).triangles()?;
+        Ok(match self.dir() {
+            SegmentDir::Fow => triangles,
+            SegmentDir::Rev => triangles.into_iter().map(|[a, b, c]| [a, c, b]).collect(),
+        })
+    }
 }
 
 #[derive(Clone)]
@@ -373,9 +391,7 @@ impl<'a> PolyRefMut<'a> {
     }
     pub(crate) fn replace(&mut self, replacement: Vec<Poly>) {
         let poly_ix = self.poly_id;
-        if self.mesh_id == 6 && (poly_ix == 35) {
-            println!("replace poly 35 with {replacement:?}");
-        }
+        trace!(mesh_id = ?self.mesh_id, poly_id = ?poly_ix, ?replacement, "replace polygon");
         if let Some(mesh) = self.index.meshes.get_mut(&self.mesh_id) {
             mesh.polies.remove(&poly_ix);
             for p in replacement {
@@ -415,7 +431,7 @@ pub struct Poly {
 }
 
 */
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub struct Poly {
     pub(crate) face_id: FaceId,
     pub(crate) dir: SegmentDir,
@@ -436,7 +452,7 @@ impl Poly {
         }
     }
 
-    fn flip(&mut self) {
+    pub(super) fn flip(&mut self) {
         self.dir = self.dir.flip();
     }
 }
@@ -538,7 +554,7 @@ impl PartialEq for Poly {
 }
 */
 
-#[derive(PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct PolyId(pub usize);
 
 impl PartialEq<usize> for PolyId {
@@ -1,9 +1,10 @@
 use nalgebra::Vector3;
 use rstar::{Point, RTreeObject, AABB};
+use serde::{Deserialize, Serialize};
 
 use crate::{decimal::Dec, indexes::aabb::Aabb};
 
-use super::face::FaceId;
+use super::{face::FaceId, rib::RibId};
 
 // use super::poly::PolyId;
 
@@ -47,8 +48,19 @@ impl RTreeObject for FaceRtreeRecord {
     }
 }
 
-#[derive(Clone, PartialEq, Debug)]
+impl RTreeObject for RibRtreeRecord {
+    type Envelope = AABB<RtreePt>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.1.into()
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct RtreePt([Dec; 3]);
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct FaceRtreeRecord(pub(super) FaceId, pub(super) Aabb);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct RibRtreeRecord(pub(super) RibId, pub(super) Aabb);
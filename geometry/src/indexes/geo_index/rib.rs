@@ -2,6 +2,7 @@ use std::{fmt, ops::Deref};
 
 use nalgebra::Vector3;
 use num_traits::One;
+use serde::{Deserialize, Serialize};
 
 use crate::{decimal::Dec, indexes::vertex_index::PtId};
 
@@ -11,10 +12,10 @@ use super::{
     seg::SegmentDir,
 };
 
-#[derive(PartialEq, Eq, Hash, Clone, Copy, Ord, PartialOrd)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Ord, PartialOrd, Serialize, Deserialize)]
 pub struct RibId(pub(super) usize);
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub struct Rib(pub(super) PtId, pub(super) PtId);
 
 impl Rib {
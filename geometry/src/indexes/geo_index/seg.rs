@@ -6,6 +6,7 @@ use std::{
 use nalgebra::{ComplexField, Matrix2, Vector2, Vector3};
 use num_traits::Zero;
 use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{decimal::Dec, indexes::vertex_index::PtId};
@@ -19,7 +20,7 @@ use super::{
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct SegId(Uuid);
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum SegmentDir {
     Fow,
     Rev,
@@ -34,7 +35,7 @@ impl SegmentDir {
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub struct Seg {
     pub(super) rib_id: RibId,
     pub(super) dir: SegmentDir,
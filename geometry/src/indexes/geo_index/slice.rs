@@ -0,0 +1,300 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use nalgebra::{Vector2, Vector3};
+use num_traits::{Signed, Zero};
+
+use crate::{
+    decimal::{Dec, STABILITY_ROUNDING},
+    indexes::vertex_index::PtId,
+    planar::{
+        plane::Plane,
+        region::{Contour, Region},
+    },
+    polygon_basis::PolygonBasis,
+};
+
+use super::{error::GeoIndexError, geo_object::GeoObject, index::GeoIndex, mesh::MeshId};
+
+/// Cross-section of mesh by plane. Basis lies in the plane, its `x` goes along projection
+/// of world `x` axis, so section by horizontal plane keeps world `x` and `y`.
+#[derive(Clone, Debug)]
+pub struct Section {
+    pub basis: PolygonBasis,
+    /// Loops in coordinates of `basis`
+    pub outlines: Vec<Contour>,
+}
+
+fn num(d: Dec) -> Dec {
+    d.round_dp(4)
+}
+
+impl Section {
+    /// Outlines as region for 2D booleans and offsets
+    pub fn region(&self) -> Region {
+        Region::new(self.outlines.clone())
+    }
+
+    fn bounds(&self) -> Option<(Vector2<Dec>, Vector2<Dec>)> {
+        let mut points = self.outlines.iter().flat_map(|o| o.points.iter());
+        let first = *points.next()?;
+        Some(points.fold((first, first), |(min, max), p| {
+            (
+                Vector2::new(min.x.min(p.x), min.y.min(p.y)),
+                Vector2::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        }))
+    }
+
+    /// Outlines as one path in millimeters, holes are cut with even-odd fill rule
+    pub fn svg(&self) -> String {
+        let margin = Dec::from(1);
+        let (min, max) = self
+            .bounds()
+            .unwrap_or((Vector2::zeros(), Vector2::zeros()));
+        let (width, height) = (max.x - min.x + margin * 2, max.y - min.y + margin * 2);
+        // Svg `y` goes down
+        let path = self
+            .outlines
+            .iter()
+            .map(|o| {
+                let points = o
+                    .points
+                    .iter()
+                    .map(|p| format!("{} {}", num(p.x), num(-p.y)))
+                    .join(" L ");
+                format!("M {points} Z")
+            })
+            .join(" ");
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}mm\" height=\"{h}mm\" viewBox=\"{x} {y} {w} {h}\">\n\
+             <path fill=\"#ddd\" fill-rule=\"evenodd\" stroke=\"black\" stroke-width=\"0.1\" d=\"{path}\"/>\n\
+             </svg>\n",
+            w = num(width),
+            h = num(height),
+            x = num(min.x - margin),
+            y = num(-max.y - margin),
+        )
+    }
+
+    /// Outlines as closed polylines of ASCII DXF R12 in millimeters
+    pub fn dxf(&self) -> String {
+        let mut dxf = String::from(
+            "0\nSECTION\n2\nHEADER\n9\n$INSUNITS\n70\n4\n0\nENDSEC\n0\nSECTION\n2\nENTITIES\n",
+        );
+        for outline in &self.outlines {
+            dxf += "0\nPOLYLINE\n8\n0\n66\n1\n70\n1\n10\n0\n20\n0\n30\n0\n";
+            for p in &outline.points {
+                dxf += &format!("0\nVERTEX\n8\n0\n10\n{}\n20\n{}\n", num(p.x), num(p.y));
+            }
+            dxf += "0\nSEQEND\n8\n0\n";
+        }
+        dxf + "0\nENDSEC\n0\nEOF\n"
+    }
+}
+
+/// Removes repeated points and points in the middle of straight runs
+fn simplify(mut points: Vec<Vector2<Dec>>) -> Vec<Vector2<Dec>> {
+    loop {
+        let count = points.len();
+        if count < 3 {
+            return points;
+        }
+        let redundant = (0..count).find(|ix| {
+            let a = points[(ix + count - 1) % count];
+            let b = points[*ix];
+            let c = points[(ix + 1) % count];
+            let (ab, bc) = (b - a, c - b);
+            let cross = (ab.x * bc.y - ab.y * bc.x).round_dp(STABILITY_ROUNDING);
+            ab.magnitude_squared()
+                .round_dp(STABILITY_ROUNDING)
+                .is_zero()
+                || (cross.is_zero() && !ab.dot(&bc).is_negative())
+        });
+        match redundant {
+            Some(ix) => {
+                points.remove(ix);
+            }
+            None => return points,
+        }
+    }
+}
+
+impl GeoIndex {
+    /// Cuts `mesh_id` by `plane` and returns closed loops of the cut. Outer loops go
+    /// counter-clockwise, when looking against plane normal, holes go clockwise. Chains,
+    /// which do not close, because mesh has holes, are dropped.
+    pub fn slice(&self, mesh_id: MeshId, plane: &Plane) -> anyhow::Result<Section> {
+        if !self.meshes.contains_key(&mesh_id) {
+            return Err(GeoIndexError::MeshNotFound(mesh_id).into());
+        }
+        let normal = plane.normal();
+        // On tie `max_by` takes the last axis, so world `x` wins for horizontal planes
+        let x = [Vector3::y(), Vector3::x()]
+            .into_iter()
+            .map(|axis: Vector3<Dec>| axis - normal * axis.dot(&normal))
+            .max_by(|a, b| a.magnitude_squared().cmp(&b.magnitude_squared()))
+            .expect("two axes")
+            .normalize();
+        let basis = PolygonBasis {
+            center: plane.point_on_plane(),
+            x,
+            y: normal.cross(&x),
+        };
+
+        // Points on the plane count as above it, so every triangle, which crosses the
+        // plane, has one lone vertex and gives exactly one segment.
+        let mut distances: HashMap<PtId, Dec> = HashMap::new();
+        let mut distance = |pt: PtId| {
+            *distances
+                .entry(pt)
+                .or_insert_with(|| normal.dot(&self.vertices.get_point(pt)) - plane.d())
+        };
+        let mut points: HashMap<(PtId, PtId), Vector3<Dec>> = HashMap::new();
+        let mut next: HashMap<(PtId, PtId), (PtId, PtId)> = HashMap::new();
+        for poly in self.get_mesh_polygons(mesh_id) {
+            for triangle in poly.make_ref(self).triangles()? {
+                let above = triangle.map(|pt| !distance(pt).is_negative());
+                if above.iter().all(|a| *a == above[0]) {
+                    continue;
+                }
+                let Some(lone) = (0..3).find(|ix| above[(ix + 1) % 3] == above[(ix + 2) % 3])
+                else {
+                    continue;
+                };
+                let key = |a: PtId, b: PtId| (a.min(b), a.max(b));
+                let lone_pt = triangle[lone];
+                let out = key(lone_pt, triangle[(lone + 1) % 3]);
+                let inc = key(triangle[(lone + 2) % 3], lone_pt);
+                for (from, to) in [out, inc] {
+                    points.entry((from, to)).or_insert_with(|| {
+                        let (df, dt) = (distance(from), distance(to));
+                        let (pf, pt) = (self.vertices.get_point(from), self.vertices.get_point(to));
+                        pf + (pt - pf) * (df / (df - dt))
+                    });
+                }
+                if above[lone] {
+                    next.insert(out, inc);
+                } else {
+                    next.insert(inc, out);
+                }
+            }
+        }
+
+        let mut outlines = Vec::new();
+        while let Some(&start) = next.keys().next() {
+            let mut chain = vec![start];
+            let mut current = start;
+            let closed = loop {
+                match next.remove(&current) {
+                    Some(n) if n == start => break true,
+                    Some(n) => {
+                        chain.push(n);
+                        current = n;
+                    }
+                    None => break false,
+                }
+            };
+            if !closed {
+                continue;
+            }
+            let outline = simplify(
+                chain
+                    .iter()
+                    .map(|k| basis.project_on_plane_z(&points[k]))
+                    .collect(),
+            );
+            if outline.len() >= 3 {
+                outlines.push(Contour::new(outline));
+            }
+        }
+
+        Ok(Section { basis, outlines })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector3;
+    use num_traits::Zero;
+    use rust_decimal_macros::dec;
+
+    use crate::{
+        decimal::Dec,
+        geometry::GeometryDyn,
+        indexes::{
+            aabb::Aabb,
+            geo_index::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId},
+        },
+        origin::Origin,
+        planar::plane::Plane,
+        shapes::Rect,
+    };
+
+    /// Mesh of two boxes 10x10x10, which centers are offset along `x` and `z`
+    fn two_boxes(index: &mut GeoIndex) -> MeshId {
+        let mesh = index.new_mesh();
+        for (x, z) in [(-10, 0), (10, 3)] {
+            Rect::centered(
+                Origin::new().offset_x(x).offset_z(z),
+                10.into(),
+                10.into(),
+                10.into(),
+            )
+            .polygonize(mesh.make_mut_ref(index), 0)
+            .unwrap();
+        }
+        mesh
+    }
+
+    fn index() -> GeoIndex {
+        GeoIndex::new(Aabb::from_points(&[
+            Vector3::new(Dec::from(-50), Dec::from(-50), Dec::from(-50)),
+            Vector3::new(Dec::from(50), Dec::from(50), Dec::from(50)),
+        ]))
+        .points_precision(dec!(0.001))
+    }
+
+    fn horizontal(z: Dec) -> Plane {
+        Plane::new_from_normal_and_point(Vector3::z(), Vector3::new(Dec::zero(), Dec::zero(), z))
+    }
+
+    #[test]
+    fn both_boxes_are_cut() {
+        let mut index = index();
+        let mesh = two_boxes(&mut index);
+
+        let section = index.slice(mesh, &horizontal(Dec::zero())).unwrap();
+
+        assert_eq!(section.outlines.len(), 2);
+        for outline in &section.outlines {
+            assert_eq!(outline.points.len(), 4);
+            assert!(!outline.is_hole());
+            assert_eq!(outline.signed_area().round_dp(6), Dec::from(100));
+        }
+        assert_eq!(section.region().area().round_dp(6), Dec::from(200));
+        assert_eq!(section.dxf().matches("POLYLINE").count(), 2);
+        assert_eq!(section.svg().matches('M').count(), 2);
+    }
+
+    #[test]
+    fn only_higher_box_is_cut() {
+        let mut index = index();
+        let mesh = two_boxes(&mut index);
+
+        let section = index.slice(mesh, &horizontal(Dec::from(6))).unwrap();
+
+        assert_eq!(section.outlines.len(), 1);
+        assert!(section.outlines[0].points.iter().all(|p| p.x > Dec::zero()));
+    }
+
+    #[test]
+    fn plane_above_boxes() {
+        let mut index = index();
+        let mesh = two_boxes(&mut index);
+
+        let section = index.slice(mesh, &horizontal(Dec::from(20))).unwrap();
+
+        assert!(section.outlines.is_empty());
+    }
+}
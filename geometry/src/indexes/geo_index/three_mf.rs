@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use nalgebra::Vector3;
+
+use crate::decimal::Dec;
+
+use super::index::GeoIndex;
+
+/// Preferred place of layer seam on the outer surface of the model. Seam is a vertical
+/// stripe of `width`, centered at `point`, on surfaces, which look to `outward` side and
+/// are not deeper than `depth` from the point.
+#[derive(Clone, Debug)]
+pub struct Seam {
+    point: Vector3<Dec>,
+    outward: Vector3<Dec>,
+    width: Dec,
+    depth: Dec,
+}
+
+impl Seam {
+    pub fn new(point: Vector3<Dec>, outward: Vector3<Dec>) -> Self {
+        Self {
+            point,
+            outward: Vector3::new(outward.x, outward.y, Dec::from(0)).normalize(),
+            width: Dec::from(3),
+            depth: Dec::from(10),
+        }
+    }
+
+    pub fn width(mut self, width: impl Into<Dec>) -> Self {
+        self.width = width.into();
+        self
+    }
+
+    /// Walls, which lean inside, need more depth
+    pub fn depth(mut self, depth: impl Into<Dec>) -> Self {
+        self.depth = depth.into();
+        self
+    }
+
+    /// Same seam on the model, mirrored by `GeoIndex::mirror_x`
+    pub fn mirror_x(mut self) -> Self {
+        self.point.x = -self.point.x;
+        self.outward.x = -self.outward.x;
+        self
+    }
+
+    /// Triangles are painted whole, so big triangles make seam area wider
+    fn contains(&self, triangle: &[Vector3<f32>; 3], normal: Vector3<f32>) -> bool {
+        let outward: Vector3<f32> = self.outward.map(f32::from);
+        let along = Vector3::z().cross(&outward);
+        let point = self.point.map(f32::from);
+        let half_width = f32::from(self.width) / 2.0;
+        let (from, to) = triangle
+            .iter()
+            .map(|v| (v - point).dot(&along))
+            .fold((f32::MAX, f32::MIN), |(a, b), d| (a.min(d), b.max(d)));
+        let deepest = triangle
+            .iter()
+            .map(|v| (v - point).dot(&outward))
+            .fold(f32::MAX, f32::min);
+        // Only walls, which look outside: not top, bottom, inner or opposite walls
+        normal.dot(&outward) > 0.5
+            && deepest >= -f32::from(self.depth)
+            && from <= half_width
+            && to >= -half_width
+    }
+}
+
+impl GeoIndex {
+    /// All meshes as one object in 3MF file. Triangles of `seam` are painted as seam
+    /// enforcers in formats of PrusaSlicer (`slic3rpe:custom_seam`) and of
+    /// Bambu Studio and OrcaSlicer (`paint_seam`).
+    pub fn three_mf(&self, seam: Option<&Seam>) -> anyhow::Result<Vec<u8>> {
+        let mut vertices: Vec<Vector3<f32>> = Vec::new();
+        let mut vertex_ids = HashMap::new();
+        let mut triangles = Vec::new();
+        for triangle in self.stl_triangles()? {
+            let points = triangle.vertices.map(|v| Vector3::new(v[0], v[1], v[2]));
+            let ids = points.map(|p| {
+                *vertex_ids.entry(p.map(f32::to_bits)).or_insert_with(|| {
+                    vertices.push(p);
+                    vertices.len() - 1
+                })
+            });
+            let normal = Vector3::new(triangle.normal[0], triangle.normal[1], triangle.normal[2]);
+            let painted = seam.is_some_and(|s| s.contains(&points, normal));
+            triangles.push((ids, painted));
+        }
+
+        let vertices = vertices
+            .iter()
+            .map(|v| format!("<vertex x=\"{}\" y=\"{}\" z=\"{}\"/>", v.x, v.y, v.z))
+            .join("\n");
+        let triangles = triangles
+            .iter()
+            .map(|([v1, v2, v3], painted)| {
+                // Whole triangle in enforcer state, without subdivision
+                let paint = if *painted {
+                    " slic3rpe:custom_seam=\"4\" paint_seam=\"4\""
+                } else {
+                    ""
+                };
+                format!("<triangle v1=\"{v1}\" v2=\"{v2}\" v3=\"{v3}\"{paint}/>")
+            })
+            .join("\n");
+        let model = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<model unit="millimeter" xml:lang="en-US" xmlns="http://schemas.microsoft.com/3dmanufacturing/core/2015/02" xmlns:slic3rpe="http://schemas.slic3r.org/3mf/2017/06">
+<resources>
+<object id="1" type="model">
+<mesh>
+<vertices>
+{vertices}
+</vertices>
+<triangles>
+{triangles}
+</triangles>
+</mesh>
+</object>
+</resources>
+<build>
+<item objectid="1"/>
+</build>
+</model>
+"#
+        );
+
+        Ok(stored_zip(&[
+            ("[Content_Types].xml", CONTENT_TYPES.as_bytes()),
+            ("_rels/.rels", RELS.as_bytes()),
+            ("3D/3dmodel.model", model.as_bytes()),
+        ]))
+    }
+}
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+<Default Extension="model" ContentType="application/vnd.ms-package.3dmanufacturing-3dmodel+xml"/>
+</Types>
+"#;
+
+const RELS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Target="/3D/3dmodel.model" Id="rel0" Type="http://schemas.microsoft.com/3dmanufacturing/2013/01/3dmodel"/>
+</Relationships>
+"#;
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB88320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Zip archive without compression: 3MF is a zip, and models are small enough
+fn stored_zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+    // 1980-01-01 00:00
+    const DATE: u16 = 0x21;
+    let mut archive = Vec::new();
+    let mut directory = Vec::new();
+    for (name, data) in files {
+        let offset = archive.len() as u32;
+        let crc = crc32(data);
+        let size = data.len() as u32;
+        let name_length = name.len() as u16;
+
+        archive.extend(0x04034b50u32.to_le_bytes());
+        archive.extend(20u16.to_le_bytes());
+        archive.extend([0u16, 0, 0, DATE].iter().flat_map(|v| v.to_le_bytes()));
+        archive.extend([crc, size, size].iter().flat_map(|v| v.to_le_bytes()));
+        archive.extend([name_length, 0].iter().flat_map(|v| v.to_le_bytes()));
+        archive.extend(name.as_bytes());
+        archive.extend(*data);
+
+        directory.extend(0x02014b50u32.to_le_bytes());
+        directory.extend(
+            [20u16, 20, 0, 0, 0, DATE]
+                .iter()
+                .flat_map(|v| v.to_le_bytes()),
+        );
+        directory.extend([crc, size, size].iter().flat_map(|v| v.to_le_bytes()));
+        directory.extend(
+            [name_length, 0, 0, 0, 0]
+                .iter()
+                .flat_map(|v| v.to_le_bytes()),
+        );
+        directory.extend([0u32, offset].iter().flat_map(|v| v.to_le_bytes()));
+        directory.extend(name.as_bytes());
+    }
+
+    let directory_offset = archive.len() as u32;
+    let count = files.len() as u16;
+    archive.extend(&directory);
+    archive.extend(0x06054b50u32.to_le_bytes());
+    archive.extend([0u16, 0, count, count].iter().flat_map(|v| v.to_le_bytes()));
+    archive.extend(
+        [directory.len() as u32, directory_offset]
+            .iter()
+            .flat_map(|v| v.to_le_bytes()),
+    );
+    archive.extend(0u16.to_le_bytes());
+    archive
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
+
+use crate::indexes::vertex_index::PtId;
+
+use super::{geo_object::GeoObject, index::GeoIndex};
+
+/// Read-only snapshot of meshes with their connectivity, for external tools: FEM meshers,
+/// scripts. Unlike STL, vertices are shared, so neighbour polygons and meshes can be
+/// found by vertex indices. Order is stable for the same index content.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Topology {
+    /// Only vertices, used by polygons, in millimeters
+    pub vertices: Vec<[f64; 3]>,
+    pub meshes: Vec<TopologyMesh>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TopologyMesh {
+    /// Same number as `MeshId` of the mesh in index
+    pub id: usize,
+    pub polygons: Vec<TopologyPolygon>,
+}
+
+/// Polygon without holes. Vertices go counterclockwise, when polygon is seen from
+/// outside of the mesh.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TopologyPolygon {
+    pub vertices: Vec<usize>,
+    /// Polygons, which lie in the same plane, share a face
+    pub face: usize,
+    /// Triangulation of the polygon with the same orientation
+    pub triangles: Vec<[usize; 3]>,
+}
+
+impl GeoIndex {
+    pub fn export_topology(&self) -> anyhow::Result<Topology> {
+        let mut vertices = Vec::new();
+        let mut vertex_ids: HashMap<PtId, usize> = HashMap::new();
+        let mut vertex = |pt: PtId| {
+            *vertex_ids.entry(pt).or_insert_with(|| {
+                let v = self.vertices.get_point(pt);
+                vertices.push([v.x.into(), v.y.into(), v.z.into()]);
+                vertices.len() - 1
+            })
+        };
+
+        let mut meshes = Vec::new();
+        for mesh in self.meshes().into_iter().sorted_by_key(|m| m.mesh_id) {
+            let mut polygons = Vec::new();
+            for poly in mesh.all_polygons().into_iter().sorted_by_key(|p| p.poly_id) {
+                let poly = poly.make_ref(self);
+                let loop_vertices = poly.segments().map(|s| vertex(s.from_pt())).collect();
+                let triangles = poly
+                    .triangles()?
+                    .into_iter()
+                    .map(|t| t.map(&mut vertex))
+                    .collect();
+                polygons.push(TopologyPolygon {
+                    vertices: loop_vertices,
+                    face: poly.face_id().0,
+                    triangles,
+                });
+            }
+            meshes.push(TopologyMesh {
+                id: mesh.mesh_id.0,
+                polygons,
+            });
+        }
+
+        Ok(Topology { vertices, meshes })
+    }
+}
+
+impl Topology {
+    /// Normal of polygon by Newell's method, looks out of the mesh
+    pub fn normal(&self, polygon: &TopologyPolygon) -> Vector3<f64> {
+        polygon
+            .vertices
+            .iter()
+            .circular_tuple_windows()
+            .map(|(a, b)| {
+                let (a, b) = (self.vertices[*a], self.vertices[*b]);
+                Vector3::new(
+                    (a[1] - b[1]) * (a[2] + b[2]),
+                    (a[2] - b[2]) * (a[0] + b[0]),
+                    (a[0] - b[0]) * (a[1] + b[1]),
+                )
+            })
+            .fold(Vector3::zeros(), |a, n| a + n)
+            .normalize()
+    }
+
+    pub fn center(&self, polygon: &TopologyPolygon) -> Vector3<f64> {
+        polygon
+            .vertices
+            .iter()
+            .map(|v| Vector3::from(self.vertices[*v]))
+            .fold(Vector3::zeros(), |a, v| a + v)
+            / polygon.vertices.len() as f64
+    }
+
+    /// Surface mesh in Gmsh ASCII format 2.2 for FEM tools. Triangles of each polygon go
+    /// to physical group, named by `group`; faces of index become elementary surfaces.
+    pub fn gmsh(&self, group: impl Fn(&TopologyMesh, &TopologyPolygon) -> String) -> String {
+        let mut groups: Vec<String> = Vec::new();
+        let mut elements = Vec::new();
+        for mesh in &self.meshes {
+            for polygon in &mesh.polygons {
+                let name = group(mesh, polygon);
+                let physical = match groups.iter().position(|g| *g == name) {
+                    Some(ix) => ix + 1,
+                    None => {
+                        groups.push(name);
+                        groups.len()
+                    }
+                };
+                for [a, b, c] in &polygon.triangles {
+                    elements.push(format!(
+                        "{} 2 2 {physical} {} {} {} {}",
+                        elements.len() + 1,
+                        polygon.face + 1,
+                        a + 1,
+                        b + 1,
+                        c + 1
+                    ));
+                }
+            }
+        }
+        let names = groups
+            .iter()
+            .enumerate()
+            .map(|(ix, name)| format!("2 {} \"{name}\"", ix + 1))
+            .join("\n");
+        let nodes = self
+            .vertices
+            .iter()
+            .enumerate()
+            .map(|(ix, [x, y, z])| format!("{} {x} {y} {z}", ix + 1))
+            .join("\n");
+        format!(
+            "$MeshFormat\n2.2 0 8\n$EndMeshFormat\n\
+             $PhysicalNames\n{}\n{names}\n$EndPhysicalNames\n\
+             $Nodes\n{}\n{nodes}\n$EndNodes\n\
+             $Elements\n{}\n{}\n$EndElements\n",
+            groups.len(),
+            self.vertices.len(),
+            elements.len(),
+            elements.join("\n")
+        )
+    }
+}
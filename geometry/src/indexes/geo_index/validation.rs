@@ -0,0 +1,241 @@
+use std::{collections::BTreeMap, fmt};
+
+use itertools::Itertools;
+use nalgebra::Vector3;
+use num_traits::Zero;
+
+use crate::decimal::Dec;
+
+use super::{
+    geo_object::GeoObject,
+    mesh::MeshRef,
+    poly::{PolyId, UnrefPoly},
+    rib::RibId,
+    seg::SegmentDir,
+};
+
+/// Rib with its ends and polygons of the mesh, which use it
+#[derive(Clone, Debug)]
+pub struct RibDefect {
+    pub rib_id: RibId,
+    pub from: Vector3<Dec>,
+    pub to: Vector3<Dec>,
+    pub polygons: Vec<PolyId>,
+}
+
+#[derive(Clone, Debug)]
+pub struct PolygonDefect {
+    pub poly_id: PolyId,
+    pub vertices: Vec<Vector3<Dec>>,
+}
+
+/// Defects, which make slicers reject exported mesh. Closed manifold mesh has every rib
+/// in exactly two polygons, which go along it in opposite directions.
+#[derive(Clone, Debug, Default)]
+pub struct MeshReport {
+    /// Ribs with only one polygon: holes in the surface
+    pub open_ribs: Vec<RibDefect>,
+    /// Ribs with more than two polygons
+    pub non_manifold_ribs: Vec<RibDefect>,
+    /// Ribs, where both polygons go the same direction, so one of them is flipped
+    pub flipped: Vec<RibDefect>,
+    pub zero_area: Vec<PolygonDefect>,
+    /// Closed mesh has negative volume: all normals look inside
+    pub inside_out: bool,
+}
+
+impl MeshReport {
+    pub fn is_valid(&self) -> bool {
+        self.open_ribs.is_empty()
+            && self.non_manifold_ribs.is_empty()
+            && self.flipped.is_empty()
+            && self.zero_area.is_empty()
+            && !self.inside_out
+    }
+}
+
+fn point(v: &Vector3<Dec>) -> String {
+    format!(
+        "[{}, {}, {}]",
+        v.x.round_dp(4),
+        v.y.round_dp(4),
+        v.z.round_dp(4)
+    )
+}
+
+impl fmt::Display for MeshReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_valid() {
+            return writeln!(f, "Mesh is closed and manifold");
+        }
+        for (title, ribs) in [
+            ("Open rib", &self.open_ribs),
+            ("Non-manifold rib", &self.non_manifold_ribs),
+            ("Flipped polygons at rib", &self.flipped),
+        ] {
+            for rib in ribs {
+                writeln!(
+                    f,
+                    "{title} {:?}: {} -> {}, polygons {:?}",
+                    rib.rib_id,
+                    point(&rib.from),
+                    point(&rib.to),
+                    rib.polygons
+                )?;
+            }
+        }
+        for poly in &self.zero_area {
+            writeln!(
+                f,
+                "Zero area polygon {:?}: {}",
+                poly.poly_id,
+                poly.vertices.iter().map(point).join(", ")
+            )?;
+        }
+        if self.inside_out {
+            writeln!(f, "Mesh is inside out")?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> MeshRef<'a> {
+    /// Checks, that mesh is watertight and manifold. Polygons, which area is less than
+    /// square of points precision of the mesh, are reported as zero area.
+    pub fn validate(&self) -> MeshReport {
+        let index = self.geo_index;
+        let precision = index.mesh_points_precision(self.mesh_id);
+        let mut report = MeshReport::default();
+
+        let mut rib_polygons: BTreeMap<RibId, Vec<(PolyId, SegmentDir)>> = BTreeMap::new();
+        let mut volume = Dec::zero();
+        for poly in self.all_polygons() {
+            let UnrefPoly { poly_id, .. } = poly;
+            let poly = poly.make_ref(index);
+            let mut vertices = Vec::new();
+            for seg in poly.segments() {
+                rib_polygons
+                    .entry(seg.rib_id)
+                    .or_default()
+                    .push((poly_id, seg.dir));
+                vertices.push(seg.from());
+            }
+
+            let newell = vertices
+                .iter()
+                .circular_tuple_windows()
+                .fold(Vector3::zeros(), |n, (a, b)| n + a.cross(b));
+            if newell.magnitude() / Dec::from(2) < precision * precision {
+                report.zero_area.push(PolygonDefect { poly_id, vertices });
+            } else if let Some((first, rest)) = vertices.split_first() {
+                volume += rest
+                    .iter()
+                    .tuple_windows()
+                    .map(|(b, c)| first.dot(&b.cross(c)))
+                    .sum::<Dec>();
+            }
+        }
+
+        for (rib_id, polygons) in rib_polygons {
+            let rib = rib_id.make_ref(index);
+            let defect = RibDefect {
+                rib_id,
+                from: rib.from(),
+                to: rib.to(),
+                polygons: polygons.iter().map(|(p, _)| *p).collect(),
+            };
+            match polygons.as_slice() {
+                [_] => report.open_ribs.push(defect),
+                [(_, a), (_, b)] if a == b => report.flipped.push(defect),
+                [_, _] => {}
+                _ => report.non_manifold_ribs.push(defect),
+            }
+        }
+
+        report.inside_out = report.open_ribs.is_empty() && volume < Dec::zero();
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector3;
+    use rust_decimal_macros::dec;
+
+    use crate::{
+        decimal::Dec,
+        geometry::GeometryDyn,
+        indexes::{
+            aabb::Aabb,
+            geo_index::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId},
+        },
+        origin::Origin,
+        shapes::{Cylinder, Rect},
+    };
+
+    fn index() -> GeoIndex {
+        GeoIndex::new(Aabb::from_points(&[
+            Vector3::new(Dec::from(-50), Dec::from(-50), Dec::from(-50)),
+            Vector3::new(Dec::from(50), Dec::from(50), Dec::from(50)),
+        ]))
+        .points_precision(dec!(0.001))
+    }
+
+    fn cube(index: &mut GeoIndex) -> MeshId {
+        let mesh = index.new_mesh();
+        Rect::centered(Origin::new(), 10.into(), 10.into(), 10.into())
+            .polygonize(mesh.make_mut_ref(index), 0)
+            .unwrap();
+        mesh
+    }
+
+    #[test]
+    fn cube_is_valid() {
+        let mut index = index();
+        let mesh = cube(&mut index);
+        let report = mesh.make_ref(&index).validate();
+        assert!(report.is_valid(), "{report}");
+    }
+
+    #[test]
+    fn cylinder_is_valid() {
+        let mut index = index();
+        let mesh = index.new_mesh();
+        Cylinder::centered(Origin::new(), 10, 5)
+            .steps(16)
+            .polygonize(mesh.make_mut_ref(&mut index), 0)
+            .unwrap();
+        let report = mesh.make_ref(&index).validate();
+        assert!(report.is_valid(), "{report}");
+    }
+
+    #[test]
+    fn cube_without_side_has_open_ribs() {
+        let mut index = index();
+        let mesh = cube(&mut index);
+        let side = mesh.make_ref(&index).into_polygons()[0];
+        side.make_mut_ref(&mut index).remove();
+
+        let report = mesh.make_ref(&index).validate();
+        assert_eq!(report.open_ribs.len(), 4, "{report}");
+        assert!(report.non_manifold_ribs.is_empty());
+        assert!(report.flipped.is_empty());
+        assert!(!report.inside_out);
+    }
+
+    #[test]
+    fn reversed_cube_is_inside_out() {
+        let mut index = index();
+        let mesh = index.new_mesh();
+        let mut mesh_ref = mesh.make_mut_ref(&mut index);
+        for mut side in Rect::centered(Origin::new(), 10.into(), 10.into(), 10.into()).render() {
+            side.reverse();
+            mesh_ref.add_polygon(&side).unwrap();
+        }
+
+        let report = mesh.make_ref(&index).validate();
+        assert!(report.open_ribs.is_empty(), "{report}");
+        assert!(report.flipped.is_empty(), "{report}");
+        assert!(report.inside_out);
+    }
+}
@@ -2,6 +2,7 @@ use crate::indexes::aabb::Aabb;
 use itertools::Itertools;
 use nalgebra::Vector3;
 use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
 
 use crate::{decimal::Dec, primitives_relation::relation::Relation};
 
@@ -32,20 +33,20 @@ impl<T: Clone> Relation<Node<T>> for Sphere {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Node<T: Clone> {
     pub data: T,
     pub point: Vector3<Dec>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub enum OctreeContent<T: Clone> {
     Empty,
     Quadrants([Box<Octree<T>>; 8]),
     Container(Vec<Node<T>>),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Octree<T: Clone> {
     aabb: Aabb,
     contents: OctreeContent<T>,
@@ -1,7 +1,10 @@
 use core::fmt;
+use std::collections::HashMap;
 
 use itertools::Itertools;
-use nalgebra::Vector3;
+use nalgebra::{ComplexField, Vector3};
+use num_traits::Zero;
+use serde::{Deserialize, Serialize};
 
 use crate::decimal::Dec;
 
@@ -11,13 +14,13 @@ use super::{
     sphere::Sphere,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct VertexIndex {
     octree: Octree<usize>,
     points: Vec<Vector3<Dec>>,
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Serialize, Deserialize)]
 pub struct PtId(usize);
 
 impl PartialEq<usize> for PtId {
@@ -55,6 +58,64 @@ impl VertexIndex {
         }
     }
 
+    /// Same as calling `get_or_insert_point` for each vertex, but instead of searching octree
+    /// for every vertex, points are binned into grid with cell of `separation_distance`
+    /// and only neighbour cells are checked.
+    pub fn insert_many(
+        &mut self,
+        vertices: &[Vector3<Dec>],
+        separation_distance: Dec,
+    ) -> Vec<PtId> {
+        if vertices.is_empty() || separation_distance <= Dec::zero() {
+            return vertices
+                .iter()
+                .map(|v| self.get_or_insert_point(*v, separation_distance))
+                .collect();
+        }
+
+        let cell = |v: &Vector3<Dec>| {
+            v.map(|c| i128::from((c / separation_distance).floor()))
+                .into()
+        };
+        let mut grid: HashMap<[i128; 3], Vec<usize>> = HashMap::new();
+
+        let mut bound = Aabb::from_points(vertices);
+        bound.min = bound.min.add_scalar(-separation_distance * 2);
+        bound.max = bound.max.add_scalar(separation_distance * 2);
+        for node in self.octree.query_within_aabb(bound) {
+            grid.entry(cell(&node.point)).or_default().push(node.data);
+        }
+
+        let max_distance_squared = separation_distance * separation_distance;
+        let mut result = Vec::with_capacity(vertices.len());
+        for vertex in vertices {
+            let [x, y, z]: [i128; 3] = cell(vertex);
+            let closest = (x - 1..=x + 1)
+                .cartesian_product(y - 1..=y + 1)
+                .cartesian_product(z - 1..=z + 1)
+                .filter_map(|((x, y), z)| grid.get(&[x, y, z]))
+                .flatten()
+                .map(|ix| (*ix, (self.points[*ix] - vertex).magnitude_squared()))
+                .filter(|(_, d)| *d < max_distance_squared)
+                .min_by_key(|(ix, d)| (*d, *ix));
+
+            if let Some((ix, _)) = closest {
+                result.push(PtId(ix));
+            } else {
+                self.points.push(*vertex);
+                let id = self.points.len() - 1;
+                self.octree.insert(Node {
+                    data: id,
+                    point: *vertex,
+                });
+                grid.entry([x, y, z]).or_default().push(id);
+                result.push(PtId(id));
+            }
+        }
+
+        result
+    }
+
     pub fn get_point(&self, ix: PtId) -> Vector3<Dec> {
         self.points[ix.0]
     }
@@ -94,3 +155,86 @@ impl From<PtId> for usize {
         value.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use nalgebra::Vector3;
+    use rust_decimal_macros::dec;
+
+    use crate::{decimal::Dec, indexes::aabb::Aabb};
+
+    use super::{PtId, VertexIndex};
+
+    fn vertex_index() -> VertexIndex {
+        VertexIndex::new(Aabb::from_points(&[
+            Vector3::new(Dec::from(-50), Dec::from(-50), Dec::from(-50)),
+            Vector3::new(Dec::from(50), Dec::from(50), Dec::from(50)),
+        ]))
+    }
+
+    fn point(x: Dec, y: Dec, z: Dec) -> Vector3<Dec> {
+        Vector3::new(x, y, z)
+    }
+
+    /// Points of a grid with step 1, each of them goes with its copy and a point, which
+    /// is 0.001 away from it
+    fn points() -> Vec<Vector3<Dec>> {
+        let mut points = Vec::new();
+        for i in 0..27 {
+            let p = point(Dec::from(i % 3), Dec::from(i / 3 % 3), Dec::from(i / 9));
+            points.push(p);
+            points.push(p + Vector3::new(Dec::from(dec!(0.001)), Dec::from(0), Dec::from(0)));
+            points.push(p);
+        }
+        points
+    }
+
+    fn one_by_one(index: &mut VertexIndex, points: &[Vector3<Dec>], separation: Dec) -> Vec<PtId> {
+        points
+            .iter()
+            .map(|p| index.get_or_insert_point(*p, separation))
+            .collect()
+    }
+
+    #[test]
+    fn insert_many_matches_one_by_one() {
+        let separation = Dec::from(dec!(0.01));
+        let points = points();
+
+        let many = vertex_index().insert_many(&points, separation);
+        let single = one_by_one(&mut vertex_index(), &points, separation);
+
+        assert_eq!(many, single);
+        // Copies and close points are merged
+        assert_eq!(many.iter().max(), Some(&PtId(26)));
+        assert!(many
+            .chunks(3)
+            .all(|ids| ids[0] == ids[1] && ids[1] == ids[2]));
+    }
+
+    #[test]
+    fn insert_many_finds_points_inserted_before() {
+        let separation = Dec::from(dec!(0.01));
+        let points = points();
+        let mut index = vertex_index();
+        let before = one_by_one(&mut index, &points[..30], separation);
+
+        let many = index.insert_many(&points, separation);
+
+        assert_eq!(&many[..30], before.as_slice());
+        assert_eq!(many, one_by_one(&mut vertex_index(), &points, separation));
+    }
+
+    #[test]
+    fn insert_many_with_zero_separation() {
+        let points = points();
+
+        let many = vertex_index().insert_many(&points, Dec::from(0));
+        let single = one_by_one(&mut vertex_index(), &points, Dec::from(0));
+
+        assert_eq!(many, single);
+        // Nothing is closer, than zero, so even copies are new points
+        assert_eq!(many.iter().unique().count(), points.len());
+    }
+}
@@ -2,9 +2,11 @@ pub mod basis;
 pub mod bezier;
 pub mod decimal;
 pub mod geometry;
+pub mod hull;
 pub mod hyper_path;
 pub mod indexes;
 pub mod linear;
+pub mod log;
 pub mod origin;
 pub mod parametric_iterator;
 pub mod path;
@@ -16,3 +18,4 @@ pub mod reversable;
 pub mod shapes;
 pub mod stiching;
 pub mod surface;
+pub mod text;
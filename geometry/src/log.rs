@@ -0,0 +1,65 @@
+//! Diagnostics of mesh operations. With `tracing` feature spans and events go to
+//! `tracing`, without it they are compiled out, so builds are quiet and not slower.
+
+/// Guard of span, when `tracing` feature is disabled
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct NoSpan;
+
+/// Enters debug span, which lasts until returned guard is dropped
+macro_rules! span {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        let guard = tracing::debug_span!($($arg)*).entered();
+        #[cfg(not(feature = "tracing"))]
+        let guard = $crate::log::NoSpan;
+        guard
+    }};
+}
+
+macro_rules! debug {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    }};
+}
+
+macro_rules! trace {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        tracing::trace!($($arg)*);
+    }};
+}
+
+/// Warnings are printed to stderr also without `tracing` feature, so only format string
+/// with arguments is accepted, not fields
+macro_rules! warning {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        tracing::warn!($($arg)*);
+        #[cfg(not(feature = "tracing"))]
+        eprintln!($($arg)*);
+    }};
+}
+
+pub(crate) use debug;
+pub(crate) use span;
+pub(crate) use trace;
+pub(crate) use warning;
+
+/// Prints events to stderr. `filter` is in `RUST_LOG` format, like `geometry=debug` or
+/// `geometry::indexes=trace`.
+#[cfg(feature = "tracing")]
+pub fn init(filter: &str) -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::try_new(filter)?)
+        .with_writer(std::io::stderr)
+        .try_init()
+        .map_err(|e| anyhow::anyhow!("{e}"))
+}
+
+#[cfg(not(feature = "tracing"))]
+pub fn init(_filter: &str) -> anyhow::Result<()> {
+    Err(anyhow::anyhow!(
+        "Logging is not available: geometry is built without `tracing` feature"
+    ))
+}
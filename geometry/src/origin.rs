@@ -1,9 +1,16 @@
-use nalgebra::{ClosedAdd, Matrix4, SimdRealField, UnitQuaternion, Vector3};
+use nalgebra::{
+    ClosedAdd, Isometry3, Matrix4, Rotation3, SimdRealField, Translation3, UnitQuaternion, Vector3,
+};
 use num_traits::Zero;
+use serde::{Deserialize, Serialize};
 
 use super::decimal::Dec;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "Vector3<F>: Serialize, UnitQuaternion<F>: Serialize",
+    deserialize = "Vector3<F>: Deserialize<'de>, UnitQuaternion<F>: Deserialize<'de>"
+))]
 pub struct BaseOrigin<F> {
     pub center: Vector3<F>,
     pub rotation: UnitQuaternion<F>,
@@ -35,6 +42,30 @@ where
         }
     }
 
+    /// Origin in `eye`, which `z` axis looks at `target` and `y` axis is as close to `up`,
+    /// as possible. `up` must not be parallel to the line of sight.
+    pub fn look_at(eye: Vector3<F>, target: Vector3<F>, up: Vector3<F>) -> Self {
+        Self {
+            center: eye,
+            rotation: UnitQuaternion::face_towards(&(target - eye), &up),
+        }
+    }
+
+    /// Origin from homogeneous matrix, which turns local coordinates into global. Upper
+    /// left corner of matrix must be rotation without scale.
+    pub fn from_matrix(m: &Matrix4<F>) -> Self {
+        let rotation = Rotation3::from_matrix_unchecked(m.fixed_view::<3, 3>(0, 0).into_owned());
+        Self {
+            center: m.fixed_view::<3, 1>(0, 3).into_owned(),
+            rotation: UnitQuaternion::from_rotation_matrix(&rotation),
+        }
+    }
+
+    /// Transformation from local coordinates into global
+    pub fn isometry(&self) -> Isometry3<F> {
+        Isometry3::from_parts(Translation3::from(self.center), self.rotation)
+    }
+
     pub fn project(&self, v: Vector3<F>) -> Vector3<F> {
         let v = v - self.center;
         self.center + self.x() * v.dot(&self.x()) + self.y() * v.dot(&self.y())
@@ -59,11 +90,18 @@ where
         self
     }
 
+    /// Moves center by `axis` in global coordinates
     pub fn offset(mut self, axis: Vector3<F>) -> Self {
         self.center = axis + self.center;
         self
     }
 
+    /// Moves center by `axis` in coordinates of this origin
+    pub fn offset_local(mut self, axis: Vector3<F>) -> Self {
+        self.center = self.rotation * axis + self.center;
+        self
+    }
+
     pub fn rotate(mut self, quat: UnitQuaternion<F>) -> Self {
         self.rotation *= quat;
         self
@@ -75,6 +113,19 @@ where
         self
     }
 
+    /// Turns by `roll` around local `x`, then by `pitch` around `y` and by `yaw` around `z`
+    /// axis, as they were before the turn. Angles are in radians.
+    pub fn rotate_euler(
+        mut self,
+        roll: impl Into<F>,
+        pitch: impl Into<F>,
+        yaw: impl Into<F>,
+    ) -> Self {
+        let quat = UnitQuaternion::from_euler_angles(roll.into(), pitch.into(), yaw.into());
+        self.rotation *= quat;
+        self
+    }
+
     pub fn left(&self) -> Vector3<F> {
         -self.x()
     }
@@ -108,6 +159,43 @@ where
         self.center = origin.rotation * (self.center) + origin.center;
         self.rotation = origin.rotation * self.rotation;
     }
+
+    /// Inverse transformation: turns global coordinates into local coordinates of this origin
+    pub fn inverse(&self) -> Self {
+        let rotation = self.rotation.inverse();
+        Self {
+            center: rotation * (-self.center),
+            rotation,
+        }
+    }
+}
+
+impl<F> From<Isometry3<F>> for BaseOrigin<F>
+where
+    F: nalgebra::Scalar,
+{
+    fn from(isometry: Isometry3<F>) -> Self {
+        Self {
+            center: isometry.translation.vector,
+            rotation: isometry.rotation,
+        }
+    }
+}
+
+impl Origin {
+    /// Blends two origins: center is moved by straight line, rotation - along shortest arc.
+    pub fn lerp(&self, other: &Origin, t: impl Into<Dec>) -> Self {
+        let t = t.into();
+        let other_rotation = if self.rotation.coords.dot(&other.rotation.coords) < Dec::zero() {
+            UnitQuaternion::new_unchecked(-other.rotation.into_inner())
+        } else {
+            other.rotation
+        };
+        Self {
+            center: self.center.lerp(&other.center, t),
+            rotation: self.rotation.nlerp(&other_rotation, t),
+        }
+    }
 }
 
 /*
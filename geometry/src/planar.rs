@@ -1,3 +1,5 @@
 pub mod face;
 pub mod plane;
 pub mod polygon;
+pub mod region;
+pub mod triangulation;
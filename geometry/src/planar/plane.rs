@@ -2,10 +2,11 @@ use std::fmt;
 
 use nalgebra::{ComplexField, Vector3};
 use num_traits::{One, Signed, Zero};
+use serde::{Deserialize, Serialize};
 
 use crate::decimal::{Dec, STABILITY_ROUNDING};
 
-#[derive(Clone, Eq, PartialOrd)]
+#[derive(Clone, Eq, PartialOrd, Serialize, Deserialize)]
 pub struct Plane {
     normal: Vector3<Dec>,
     d: Dec,
@@ -0,0 +1,417 @@
+use std::collections::{HashMap, HashSet};
+
+use itertools::Itertools;
+use nalgebra::Vector2;
+use num_traits::Signed;
+
+use crate::decimal::Dec;
+
+type P = Vector2<f64>;
+type Key = (i64, i64);
+
+/// Points closer than this are the same point
+const SNAP: f64 = 1e-7;
+/// Distance from edge, where inside and outside are probed
+const PROBE: f64 = 1e-5;
+/// Full circle of rounded corners is made of this many segments
+const ARC_SEGMENTS: usize = 32;
+
+/// Closed loop on the plane. Counter-clockwise loops bound area, clockwise ones are holes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Contour {
+    pub points: Vec<Vector2<Dec>>,
+}
+
+impl Contour {
+    pub fn new(points: Vec<Vector2<Dec>>) -> Self {
+        Self { points }
+    }
+
+    /// Positive for counter-clockwise loops, negative for holes
+    pub fn signed_area(&self) -> Dec {
+        self.points
+            .iter()
+            .circular_tuple_windows()
+            .map(|(a, b)| a.x * b.y - b.x * a.y)
+            .sum::<Dec>()
+            / Dec::from(2)
+    }
+
+    pub fn is_hole(&self) -> bool {
+        self.signed_area().is_negative()
+    }
+
+    pub fn reversed(mut self) -> Self {
+        self.points.reverse();
+        self
+    }
+
+    fn float(&self) -> Vec<P> {
+        self.points
+            .iter()
+            .map(|p| P::new(p.x.into(), p.y.into()))
+            .collect()
+    }
+}
+
+/// Area on the plane, bounded by contours. Point is inside, when contours go around it
+/// non-zero number of times, so outer contours go counter-clockwise and holes clockwise.
+/// Booleans work in `f64` and return contours without self-intersections.
+#[derive(Clone, Debug, Default)]
+pub struct Region {
+    pub contours: Vec<Contour>,
+}
+
+fn cross(a: P, b: P) -> f64 {
+    a.x * b.y - a.y * b.x
+}
+
+fn key(p: P) -> Key {
+    ((p.x / SNAP).round() as i64, (p.y / SNAP).round() as i64)
+}
+
+fn winding(contours: &[Vec<P>], p: P) -> i32 {
+    let mut winding = 0;
+    for (a, b) in contours
+        .iter()
+        .flat_map(|c| c.iter().circular_tuple_windows())
+    {
+        let side = cross(b - a, p - a);
+        if a.y <= p.y {
+            if b.y > p.y && side > 0.0 {
+                winding += 1;
+            }
+        } else if b.y <= p.y && side < 0.0 {
+            winding -= 1;
+        }
+    }
+    winding
+}
+
+/// Parameter of `point` on segment, when it lies inside of segment
+fn param_on((from, to): (P, P), point: P) -> Option<f64> {
+    let dir = to - from;
+    let t = (point - from).dot(&dir) / dir.norm_squared();
+    (t > 0.0 && t < 1.0 && (from + dir * t - point).norm() < SNAP).then_some(t)
+}
+
+fn overlap((a, b): (P, P), (c, d): (P, P)) -> bool {
+    a.x.min(b.x) <= c.x.max(d.x) + SNAP
+        && c.x.min(d.x) <= a.x.max(b.x) + SNAP
+        && a.y.min(b.y) <= c.y.max(d.y) + SNAP
+        && c.y.min(d.y) <= a.y.max(b.y) + SNAP
+}
+
+/// Removes points in the middle of straight runs
+fn simplify(mut points: Vec<P>) -> Vec<P> {
+    loop {
+        let count = points.len();
+        if count < 3 {
+            return points;
+        }
+        let redundant = (0..count).find(|ix| {
+            let a = points[(ix + count - 1) % count];
+            let b = points[*ix];
+            let c = points[(ix + 1) % count];
+            cross(b - a, c - b).abs() < SNAP * (c - a).norm() && (b - a).dot(&(c - b)) >= 0.0
+        });
+        match redundant {
+            Some(ix) => {
+                points.remove(ix);
+            }
+            None => return points,
+        }
+    }
+}
+
+fn circle(center: P, radius: f64) -> Vec<P> {
+    (0..ARC_SEGMENTS)
+        .map(|ix| {
+            let angle = std::f64::consts::TAU * ix as f64 / ARC_SEGMENTS as f64;
+            center + P::new(angle.cos(), angle.sin()) * radius
+        })
+        .collect()
+}
+
+impl Region {
+    /// Contours are taken as is, they may overlap and intersect each other
+    pub fn new(contours: Vec<Contour>) -> Self {
+        Self { contours }
+    }
+
+    /// Inside of polygon with any orientation
+    pub fn polygon(points: Vec<Vector2<Dec>>) -> Self {
+        let contour = Contour::new(points);
+        let contour = if contour.is_hole() {
+            contour.reversed()
+        } else {
+            contour
+        };
+        Self::new(vec![contour])
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contours.is_empty()
+    }
+
+    /// Area of region, which contours do not overlap
+    pub fn area(&self) -> Dec {
+        self.contours.iter().map(|c| c.signed_area()).sum()
+    }
+
+    pub fn contains(&self, point: &Vector2<Dec>) -> bool {
+        winding(&self.float(), P::new(point.x.into(), point.y.into())) != 0
+    }
+
+    /// Same area with overlapping and self-intersecting contours resolved
+    pub fn normalized(&self) -> Region {
+        Self::combine(&[self.float()], |inside| inside[0])
+    }
+
+    pub fn union(&self, other: &Region) -> Region {
+        Self::combine(&[self.float(), other.float()], |inside| {
+            inside[0] || inside[1]
+        })
+    }
+
+    pub fn difference(&self, other: &Region) -> Region {
+        Self::combine(&[self.float(), other.float()], |inside| {
+            inside[0] && !inside[1]
+        })
+    }
+
+    pub fn intersection(&self, other: &Region) -> Region {
+        Self::combine(&[self.float(), other.float()], |inside| {
+            inside[0] && inside[1]
+        })
+    }
+
+    /// Grows region by `distance` with rounded corners, negative distance shrinks it
+    pub fn offset(&self, distance: impl Into<Dec>) -> Region {
+        let distance: f64 = distance.into().into();
+        if distance == 0.0 {
+            return self.normalized();
+        }
+        let contours = self.float();
+        let radius = distance.abs();
+        let mut inputs = vec![contours.clone()];
+        for (a, b) in contours
+            .iter()
+            .flat_map(|c| c.iter().circular_tuple_windows())
+        {
+            let Some(dir) = (b - a).try_normalize(SNAP) else {
+                continue;
+            };
+            let side = P::new(-dir.y, dir.x) * radius;
+            inputs.push(vec![vec![a - side, b - side, b + side, a + side]]);
+            inputs.push(vec![circle(*a, radius)]);
+        }
+        if distance > 0.0 {
+            Self::combine(&inputs, |inside| inside.iter().any(|i| *i))
+        } else {
+            Self::combine(&inputs, |inside| {
+                inside[0] && !inside[1..].iter().any(|i| *i)
+            })
+        }
+    }
+
+    fn float(&self) -> Vec<Vec<P>> {
+        self.contours.iter().map(|c| c.float()).collect()
+    }
+
+    /// Splits edges of all inputs in crossings and keeps those, which have area, selected
+    /// by `inside`, on one side only. Kept edges are oriented to have this area on the
+    /// left and are chained into contours.
+    fn combine(inputs: &[Vec<Vec<P>>], inside: impl Fn(&[bool]) -> bool) -> Region {
+        let edges = inputs
+            .iter()
+            .flatten()
+            .flat_map(|c| c.iter().copied().circular_tuple_windows())
+            .filter(|(a, b): &(P, P)| (b - a).norm() > SNAP)
+            .collect_vec();
+
+        let mut params = vec![vec![0.0, 1.0]; edges.len()];
+        for i in 0..edges.len() {
+            for j in i + 1..edges.len() {
+                let ((a, b), (c, d)) = (edges[i], edges[j]);
+                if !overlap(edges[i], edges[j]) {
+                    continue;
+                }
+                for (ix, edge, points) in [(i, edges[i], [c, d]), (j, edges[j], [a, b])] {
+                    params[ix].extend(points.into_iter().filter_map(|p| param_on(edge, p)));
+                }
+                let (r, s) = (b - a, d - c);
+                let denom = cross(r, s);
+                if denom.abs() > SNAP * r.norm() * s.norm() {
+                    let t = cross(c - a, s) / denom;
+                    let u = cross(c - a, r) / denom;
+                    if t > 0.0 && t < 1.0 && u > 0.0 && u < 1.0 {
+                        params[i].push(t);
+                        params[j].push(u);
+                    }
+                }
+            }
+        }
+
+        let mut positions: HashMap<Key, P> = HashMap::new();
+        let mut kept: HashSet<(Key, Key)> = HashSet::new();
+        for ((a, b), mut ts) in edges.iter().zip(params) {
+            ts.sort_by(f64::total_cmp);
+            let points = ts
+                .into_iter()
+                .map(|t| {
+                    let p = a + (b - a) * t;
+                    (key(p), *positions.entry(key(p)).or_insert(p))
+                })
+                .dedup_by(|x, y| x.0 == y.0)
+                .collect_vec();
+            for ((from_key, from), (to_key, to)) in points.into_iter().tuple_windows() {
+                let dir = (to - from).normalize();
+                let mid = (from + to) / 2.0;
+                let left = mid + P::new(-dir.y, dir.x) * PROBE;
+                let right = mid - P::new(-dir.y, dir.x) * PROBE;
+                let is_inside =
+                    |p: P| inside(&inputs.iter().map(|c| winding(c, p) != 0).collect_vec());
+                let edge = match (is_inside(left), is_inside(right)) {
+                    (true, false) => (from_key, to_key),
+                    (false, true) => (to_key, from_key),
+                    _ => continue,
+                };
+                if !kept.remove(&(edge.1, edge.0)) {
+                    kept.insert(edge);
+                }
+            }
+        }
+
+        let mut outgoing: HashMap<Key, Vec<Key>> = HashMap::new();
+        for (from, to) in &kept {
+            outgoing.entry(*from).or_default().push(*to);
+        }
+        let mut contours = Vec::new();
+        while let Some((&start, _)) = outgoing.iter().find(|(_, next)| !next.is_empty()) {
+            let mut chain = vec![start];
+            let (mut prev, mut current) = (start, outgoing.get_mut(&start).unwrap().remove(0));
+            while current != start {
+                chain.push(current);
+                let back = positions[&prev] - positions[&current];
+                // Sharpest right turn keeps contours, touching in one point, separate
+                let Some((ix, _)) = outgoing.get(&current).and_then(|next| {
+                    next.iter().enumerate().min_by(|(_, x), (_, y)| {
+                        let angle = |k: &Key| {
+                            let out = positions[k] - positions[&current];
+                            let a = cross(back, out).atan2(back.dot(&out));
+                            if a <= 0.0 {
+                                a + std::f64::consts::TAU
+                            } else {
+                                a
+                            }
+                        };
+                        angle(x).total_cmp(&angle(y))
+                    })
+                }) else {
+                    break;
+                };
+                prev = current;
+                current = outgoing.get_mut(&current).unwrap().remove(ix);
+            }
+            if current != start {
+                continue;
+            }
+            let points = simplify(chain.iter().map(|k| positions[k]).collect());
+            if points.len() >= 3 {
+                contours.push(Contour::new(
+                    points
+                        .into_iter()
+                        .map(|p| Vector2::new(Dec::from(p.x), Dec::from(p.y)))
+                        .collect(),
+                ));
+            }
+        }
+
+        Region::new(contours)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector2;
+
+    use crate::decimal::Dec;
+
+    use super::Region;
+
+    fn square(x: i32, y: i32, size: i32) -> Region {
+        Region::polygon(
+            [(x, y), (x + size, y), (x + size, y + size), (x, y + size)]
+                .into_iter()
+                .map(|(x, y)| Vector2::new(Dec::from(x), Dec::from(y)))
+                .collect(),
+        )
+    }
+
+    fn area(region: &Region) -> Dec {
+        region.area().round_dp(6)
+    }
+
+    #[test]
+    fn booleans_of_overlapping_squares() {
+        let a = square(0, 0, 10);
+        let b = square(5, 5, 10);
+
+        assert_eq!(area(&a.union(&b)), Dec::from(175));
+        assert_eq!(area(&a.intersection(&b)), Dec::from(25));
+        assert_eq!(area(&a.difference(&b)), Dec::from(75));
+        assert_eq!(a.union(&b).contours.len(), 1);
+        assert_eq!(a.intersection(&b).contours[0].points.len(), 4);
+    }
+
+    #[test]
+    fn difference_makes_hole() {
+        let outer = square(0, 0, 10);
+        let inner = square(3, 3, 4);
+
+        let frame = outer.difference(&inner);
+
+        assert_eq!(area(&frame), Dec::from(84));
+        assert_eq!(frame.contours.iter().filter(|c| c.is_hole()).count(), 1);
+        assert!(!frame.contains(&Vector2::new(Dec::from(5), Dec::from(5))));
+        assert!(frame.contains(&Vector2::new(Dec::from(1), Dec::from(5))));
+    }
+
+    #[test]
+    fn separate_squares() {
+        let a = square(0, 0, 10);
+        let b = square(20, 0, 10);
+
+        assert_eq!(area(&a.union(&b)), Dec::from(200));
+        assert!(a.intersection(&b).is_empty());
+    }
+
+    #[test]
+    fn offset_rounds_corners() {
+        let a = square(0, 0, 10);
+
+        let shrunk = a.offset(-1);
+        assert_eq!(area(&shrunk), Dec::from(64));
+        assert_eq!(shrunk.contours[0].points.len(), 4);
+
+        // Corners together make polygon of 32 segments inscribed in unit circle
+        let grown = a.offset(1);
+        let circle = 16.0 * (std::f64::consts::TAU / 32.0).sin();
+        let expected = 100.0 + 4.0 * 10.0 + circle;
+        assert!((f64::from(grown.area()) - expected).abs() < 1e-6);
+        assert_eq!(grown.contours.len(), 1);
+    }
+
+    #[test]
+    fn normalized_self_overlapping_contours() {
+        let twice = Region::new(
+            [square(0, 0, 10), square(0, 0, 10)]
+                .map(|s| s.contours[0].clone())
+                .to_vec(),
+        );
+
+        assert_eq!(area(&twice), Dec::from(200));
+        assert_eq!(area(&twice.normalized()), Dec::from(100));
+    }
+}
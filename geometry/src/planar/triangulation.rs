@@ -0,0 +1,299 @@
+use anyhow::anyhow;
+use nalgebra::Vector2;
+use num_traits::{Signed, Zero};
+
+use crate::decimal::Dec;
+
+fn cross(a: Vector2<Dec>, b: Vector2<Dec>) -> Dec {
+    a.x * b.y - a.y * b.x
+}
+
+/// Doubled signed area of polygon. Positive for counter-clockwise polygons.
+pub fn signed_area(points: &[Vector2<Dec>]) -> Dec {
+    (0..points.len())
+        .map(|current| {
+            let next = (current + 1) % points.len();
+            cross(points[current], points[next])
+        })
+        .sum()
+}
+
+/// Checks, that polygon has no reflex vertices.
+pub fn is_convex(points: &[Vector2<Dec>]) -> bool {
+    let area = signed_area(points);
+    let len = points.len();
+    (0..len).all(|i| {
+        let a = points[(i + len - 1) % len];
+        let b = points[i];
+        let c = points[(i + 1) % len];
+        !(cross(b - a, c - b) * area).is_negative()
+    })
+}
+
+fn is_inside_triangle(pt: Vector2<Dec>, a: Vector2<Dec>, b: Vector2<Dec>, c: Vector2<Dec>) -> bool {
+    !cross(b - a, pt - a).is_negative()
+        && !cross(c - b, pt - b).is_negative()
+        && !cross(a - c, pt - c).is_negative()
+}
+
+/// Ear-clipping triangulation of simple polygon.
+/// Polygons, made by `merge_holes` are also accepted.
+/// Returns triangles as indices of `points`, with the same winding as the input polygon.
+pub fn triangulate(points: &[Vector2<Dec>]) -> anyhow::Result<Vec<[usize; 3]>> {
+    if points.len() < 3 {
+        return Err(anyhow!(
+            "Cannot triangulate polygon with less than 3 points"
+        ));
+    }
+    let area = signed_area(points);
+    if area.is_zero() {
+        return Err(anyhow!("Cannot triangulate polygon with zero area"));
+    }
+
+    let mut ixs = (0..points.len()).collect::<Vec<_>>();
+    if area.is_negative() {
+        ixs.reverse();
+    }
+
+    let mut result = Vec::new();
+    while ixs.len() > 3 {
+        let len = ixs.len();
+        let corner = |i: usize| (ixs[(i + len - 1) % len], ixs[i], ixs[(i + 1) % len]);
+
+        if let Some(degenerate) = (0..len).find(|&i| {
+            let (a, b, c) = corner(i);
+            cross(points[b] - points[a], points[c] - points[b]).is_zero()
+        }) {
+            ixs.remove(degenerate);
+            continue;
+        }
+
+        let ear = (0..len)
+            .find(|&i| {
+                let (a, b, c) = corner(i);
+                let [pa, pb, pc] = [points[a], points[b], points[c]];
+                cross(pb - pa, pc - pb).is_positive()
+                    && ixs
+                        .iter()
+                        .filter(|&&j| ![pa, pb, pc].contains(&points[j]))
+                        .all(|&j| !is_inside_triangle(points[j], pa, pb, pc))
+            })
+            .ok_or_else(|| anyhow!("Cannot find ear in polygon, is it self-intersecting?"))?;
+
+        let (a, b, c) = corner(ear);
+        result.push([a, b, c]);
+        ixs.remove(ear);
+    }
+    result.push([ixs[0], ixs[1], ixs[2]]);
+
+    if area.is_negative() {
+        for t in result.iter_mut() {
+            t.swap(1, 2);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Constrained Delaunay triangulation of polygon with holes. Points are indexed as if
+/// outer contour and holes were joined in one list in this order. Triangles are
+/// counter-clockwise, whatever winding contours have. Unlike ear clipping of `merge_holes`
+/// result, it does not make thin triangles along bridges.
+pub fn triangulate_with_holes(
+    outer: &[Vector2<Dec>],
+    holes: &[Vec<Vector2<Dec>>],
+) -> anyhow::Result<Vec<[usize; 3]>> {
+    let mut points = Vec::new();
+    let mut contours = Vec::new();
+    for contour in [outer]
+        .into_iter()
+        .chain(holes.iter().map(|h| h.as_slice()))
+    {
+        if contour.len() < 3 {
+            return Err(anyhow!(
+                "Cannot triangulate contour with less than 3 points"
+            ));
+        }
+        let start = points.len();
+        points.extend(contour.iter().map(|p| (f64::from(p.x), f64::from(p.y))));
+        contours.push((start..points.len()).chain([start]).collect::<Vec<_>>());
+    }
+    let all = outer
+        .iter()
+        .chain(holes.iter().flatten())
+        .collect::<Vec<_>>();
+
+    Ok(cdt::triangulate_contours(&points, &contours)
+        .map_err(|e| anyhow!("Cannot triangulate polygon with holes: {e}"))?
+        .into_iter()
+        .map(|(a, b, c)| {
+            if cross(all[b] - all[a], all[c] - all[b]).is_negative() {
+                [a, c, b]
+            } else {
+                [a, b, c]
+            }
+        })
+        .collect())
+}
+
+/// Joins holes into outer contour with zero-width bridges, so polygon with holes can be
+/// triangulated as one contour. Outer contour is made counter-clockwise, holes - clockwise.
+/// Holes must lie strictly inside outer contour and must not intersect each other.
+pub fn merge_holes(outer: &[Vector2<Dec>], holes: &[Vec<Vector2<Dec>>]) -> Vec<Vector2<Dec>> {
+    let mut result = outer.to_vec();
+    if signed_area(&result).is_negative() {
+        result.reverse();
+    }
+
+    let mut holes = holes
+        .iter()
+        .filter(|h| h.len() > 2)
+        .map(|h| {
+            let mut h = h.clone();
+            if signed_area(&h).is_positive() {
+                h.reverse();
+            }
+            h
+        })
+        .collect::<Vec<_>>();
+    holes.sort_by_key(|h| std::cmp::Reverse(h.iter().map(|p| p.x).max()));
+
+    for hole in holes {
+        let Some(m) = (0..hole.len()).max_by_key(|&i| hole[i].x) else {
+            continue;
+        };
+        let Some(v) = bridge_vertex(&result, hole[m]) else {
+            continue;
+        };
+        let mut bridged = result[..=v].to_vec();
+        bridged.extend((0..=hole.len()).map(|i| hole[(m + i) % hole.len()]));
+        bridged.extend_from_slice(&result[v..]);
+        result = bridged;
+    }
+
+    result
+}
+
+/// Finds vertex of counter-clockwise `contour`, which is visible from point `m` inside it.
+fn bridge_vertex(contour: &[Vector2<Dec>], m: Vector2<Dec>) -> Option<usize> {
+    let len = contour.len();
+    let mut closest: Option<(Dec, usize)> = None;
+    for i in 0..len {
+        let (a, b) = (contour[i], contour[(i + 1) % len]);
+        if (a.y - m.y) * (b.y - m.y) > Dec::zero() || a.y == b.y {
+            continue;
+        }
+        let x = a.x + (m.y - a.y) * (b.x - a.x) / (b.y - a.y);
+        if x < m.x || closest.is_some_and(|(cx, _)| cx <= x) {
+            continue;
+        }
+        let candidate = if a.y == m.y {
+            i
+        } else if b.y == m.y {
+            (i + 1) % len
+        } else if a.x > b.x {
+            i
+        } else {
+            (i + 1) % len
+        };
+        closest = Some((x, candidate));
+    }
+    let (x, mut v) = closest?;
+
+    let hit = Vector2::new(x, m.y);
+    if contour[v] == hit {
+        return Some(v);
+    }
+    let (t0, t1) = if contour[v].y < m.y {
+        (contour[v], hit)
+    } else {
+        (hit, contour[v])
+    };
+    let mut best_slope = None;
+    for i in 0..len {
+        let p = contour[i];
+        let prev = contour[(i + len - 1) % len];
+        let next = contour[(i + 1) % len];
+        let is_reflex = cross(p - prev, next - p) < Dec::zero();
+        if i == v || !is_reflex || p.x <= m.x || !is_inside_triangle(p, m, t0, t1) {
+            continue;
+        }
+        let slope = (p.y - m.y).abs() / (p.x - m.x);
+        if best_slope.is_none_or(|s| slope < s) {
+            best_slope = Some(slope);
+            v = i;
+        }
+    }
+
+    Some(v)
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector2;
+
+    use crate::decimal::Dec;
+
+    use super::{is_convex, merge_holes, signed_area, triangulate, triangulate_with_holes};
+
+    #[test]
+    fn triangulate_l_shape() {
+        let pts = [(0, 0), (2, 0), (2, 1), (1, 1), (1, 2), (0, 2)]
+            .map(|(x, y)| Vector2::new(Dec::from(x), Dec::from(y)));
+        assert!(!is_convex(&pts));
+
+        let triangles = triangulate(&pts).unwrap();
+        assert_eq!(triangles.len(), 4);
+
+        let total: Dec = triangles
+            .iter()
+            .map(|t| signed_area(&t.map(|ix| pts[ix])))
+            .sum();
+        assert_eq!(total, signed_area(&pts));
+    }
+
+    #[test]
+    fn triangulate_square_with_hole() {
+        let outer =
+            [(0, 0), (4, 0), (4, 4), (0, 4)].map(|(x, y)| Vector2::new(Dec::from(x), Dec::from(y)));
+        let hole = [(1, 1), (3, 1), (3, 3), (1, 3)]
+            .map(|(x, y)| Vector2::new(Dec::from(x), Dec::from(y)))
+            .to_vec();
+
+        let pts = merge_holes(&outer, &[hole]);
+        assert_eq!(pts.len(), 10);
+
+        let triangles = triangulate(&pts).unwrap();
+        let total: Dec = triangles
+            .iter()
+            .map(|t| signed_area(&t.map(|ix| pts[ix])))
+            .sum();
+        assert_eq!(total, Dec::from(24));
+    }
+
+    #[test]
+    fn triangulate_rectangle_with_two_holes() {
+        let outer =
+            [(0, 0), (6, 0), (6, 4), (0, 4)].map(|(x, y)| Vector2::new(Dec::from(x), Dec::from(y)));
+        let holes = [
+            [(1, 1), (2, 1), (2, 3), (1, 3)],
+            [(3, 1), (5, 1), (5, 3), (3, 3)],
+        ]
+        .map(|h| {
+            h.map(|(x, y)| Vector2::new(Dec::from(x), Dec::from(y)))
+                .to_vec()
+        });
+        let pts = outer
+            .iter()
+            .chain(holes.iter().flatten())
+            .copied()
+            .collect::<Vec<_>>();
+
+        let triangles = triangulate_with_holes(&outer, &holes).unwrap();
+        let total: Dec = triangles
+            .iter()
+            .map(|t| signed_area(&t.map(|ix| pts[ix])))
+            .sum();
+        assert_eq!(total, Dec::from(36));
+    }
+}
@@ -1,8 +1,25 @@
+mod cone;
 mod cylinder;
+mod ellipsoid;
+mod extrusion;
 mod plane;
 mod rect;
+mod revolution;
+mod tessellation;
+mod thread;
+mod torus;
 
+pub use cone::Cone;
 pub use cylinder::Cylinder;
+pub use ellipsoid::Ellipsoid;
+pub use ellipsoid::Sphere;
+pub use extrusion::extrude;
+pub use extrusion::Extrusion;
 pub use plane::Plane;
 pub use rect::Align;
 pub use rect::Rect;
+pub use revolution::revolve;
+pub use revolution::Revolution;
+pub use tessellation::steps_for_chord_error;
+pub use thread::HelicalThread;
+pub use torus::Torus;
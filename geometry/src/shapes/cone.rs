@@ -0,0 +1,154 @@
+use nalgebra::{ComplexField, Vector3};
+use num_traits::Zero;
+
+use crate::{
+    decimal::Dec, geometry::GeometryDyn, indexes::geo_index::mesh::MeshRefMut, origin::Origin,
+};
+
+use super::steps_for_chord_error;
+
+/// Cone or frustum along z axis of origin. Any of radii can be zero, then the
+/// shape ends with apex there.
+#[derive(Clone)]
+pub struct Cone {
+    top_basis: Origin,
+    steps: usize,
+    max_chord_error: Option<Dec>,
+    top_cap: bool,
+    bottom_cap: bool,
+    bottom_radius: Dec,
+    top_radius: Dec,
+    height: Dec,
+}
+
+impl Cone {
+    pub fn centered(
+        origin: Origin,
+        height: impl Into<Dec>,
+        bottom_radius: impl Into<Dec>,
+        top_radius: impl Into<Dec>,
+    ) -> Self {
+        let height = height.into();
+        let top_basis = origin.offset_z(height / 2);
+        Self::new(top_basis, height, bottom_radius.into(), top_radius.into())
+    }
+
+    pub fn with_top_at(
+        origin: Origin,
+        height: impl Into<Dec>,
+        bottom_radius: impl Into<Dec>,
+        top_radius: impl Into<Dec>,
+    ) -> Self {
+        Self::new(
+            origin,
+            height.into(),
+            bottom_radius.into(),
+            top_radius.into(),
+        )
+    }
+
+    pub fn with_bottom_at(
+        origin: Origin,
+        height: impl Into<Dec>,
+        bottom_radius: impl Into<Dec>,
+        top_radius: impl Into<Dec>,
+    ) -> Self {
+        let height = height.into();
+        let top_basis = origin.offset_z(height);
+        Self::new(top_basis, height, bottom_radius.into(), top_radius.into())
+    }
+
+    fn new(top_basis: Origin, height: Dec, bottom_radius: Dec, top_radius: Dec) -> Self {
+        Self {
+            top_basis,
+            steps: 10,
+            max_chord_error: None,
+            top_cap: true,
+            bottom_cap: true,
+            bottom_radius,
+            top_radius,
+            height,
+        }
+    }
+
+    pub fn top_cap(mut self, top_cap: bool) -> Self {
+        self.top_cap = top_cap;
+        self
+    }
+
+    pub fn bottom_cap(mut self, bottom_cap: bool) -> Self {
+        self.bottom_cap = bottom_cap;
+        self
+    }
+
+    pub fn steps(mut self, steps: usize) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    /// Calculate amount of steps from biggest radius, so that wall does not deviate from
+    /// ideal cone more than `max_chord_error`. Overrides `steps`.
+    pub fn max_chord_error(mut self, max_chord_error: impl Into<Dec>) -> Self {
+        self.max_chord_error = Some(max_chord_error.into());
+        self
+    }
+
+    fn steps_count(&self) -> usize {
+        let radius = self.bottom_radius.max(self.top_radius);
+        self.max_chord_error
+            .map(|e| steps_for_chord_error(radius, Dec::two_pi(), e).max(3))
+            .unwrap_or(self.steps)
+    }
+
+    pub fn render(&self) -> Vec<Vec<Vector3<Dec>>> {
+        let up = self.top_basis.z();
+        let top_center = self.top_basis.center;
+        let bottom_center = top_center - up * self.height;
+
+        let steps = self.steps_count();
+        let circle = |center: Vector3<Dec>, radius: Dec| {
+            (0..steps)
+                .map(|s| {
+                    let angle = Dec::from(s) / Dec::from(steps) * Dec::two_pi();
+                    center
+                        + self.top_basis.x() * angle.cos() * radius
+                        + self.top_basis.y() * angle.sin() * radius
+                })
+                .collect::<Vec<_>>()
+        };
+        let top = circle(top_center, self.top_radius);
+        let mut bottom = circle(bottom_center, self.bottom_radius);
+
+        let mut polygons = Vec::new();
+        for (prev, next) in (0..steps).map(|s| (s, (s + 1) % steps)) {
+            if self.top_radius.is_zero() {
+                polygons.push(vec![bottom[prev], bottom[next], top_center]);
+            } else if self.bottom_radius.is_zero() {
+                polygons.push(vec![bottom_center, top[next], top[prev]]);
+            } else {
+                polygons.push(vec![bottom[prev], bottom[next], top[next], top[prev]]);
+            }
+        }
+
+        if self.top_cap && !self.top_radius.is_zero() {
+            polygons.push(top);
+        }
+
+        if self.bottom_cap && !self.bottom_radius.is_zero() {
+            bottom.reverse();
+            polygons.push(bottom);
+        }
+
+        polygons
+    }
+}
+
+impl GeometryDyn for Cone {
+    fn polygonize(&self, mut mesh: MeshRefMut, _complexity: usize) -> anyhow::Result<()> {
+        for p in self.render() {
+            mesh.add_polygon(&p)?;
+        }
+
+        Ok(())
+    }
+}
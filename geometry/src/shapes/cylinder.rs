@@ -6,10 +6,13 @@ use crate::{
     decimal::Dec, geometry::GeometryDyn, indexes::geo_index::mesh::MeshRefMut, origin::Origin,
 };
 
+use super::steps_for_chord_error;
+
 #[derive(Clone)]
 pub struct Cylinder {
     top_basis: Origin,
     steps: usize,
+    max_chord_error: Option<Dec>,
     top_cap: bool,
     bottom_cap: bool,
     radius: Dec,
@@ -25,6 +28,7 @@ impl Cylinder {
         Self {
             top_basis,
             steps: 10,
+            max_chord_error: None,
             top_cap: true,
             bottom_cap: true,
             radius,
@@ -52,6 +56,19 @@ impl Cylinder {
         self
     }
 
+    /// Calculate amount of steps from radius, so that wall does not deviate from
+    /// ideal cylinder more than `max_chord_error`. Overrides `steps`.
+    pub fn max_chord_error(mut self, max_chord_error: impl Into<Dec>) -> Self {
+        self.max_chord_error = Some(max_chord_error.into());
+        self
+    }
+
+    fn steps_count(&self) -> usize {
+        self.max_chord_error
+            .map(|e| steps_for_chord_error(self.radius, Dec::two_pi(), e).max(3))
+            .unwrap_or(self.steps)
+    }
+
     pub fn with_top_at(origin: Origin, height: impl Into<Dec>, radius: impl Into<Dec>) -> Self {
         let radius = radius.into();
         let height = height.into();
@@ -60,6 +77,7 @@ impl Cylinder {
         Self {
             top_basis,
             steps: 10,
+            max_chord_error: None,
             top_cap: true,
             bottom_cap: true,
             radius,
@@ -73,6 +91,7 @@ impl Cylinder {
         Self {
             top_basis,
             steps: 10,
+            max_chord_error: None,
             top_cap: true,
             bottom_cap: true,
             radius,
@@ -87,11 +106,10 @@ impl Cylinder {
         let mut bottom = Vec::new();
         let mut wall = Vec::new();
         let from = Dec::zero();
-        for (prev, next) in (0..self.steps).zip(1..=self.steps) {
-            let angle_prev =
-                Dec::from(prev) / Dec::from(self.steps) * Dec::from(Decimal::TWO_PI) - from;
-            let angle_next =
-                Dec::from(next) / Dec::from(self.steps) * Dec::from(Decimal::TWO_PI) - from;
+        let steps = self.steps_count();
+        for (prev, next) in (0..steps).zip(1..=steps) {
+            let angle_prev = Dec::from(prev) / Dec::from(steps) * Dec::from(Decimal::TWO_PI) - from;
+            let angle_next = Dec::from(next) / Dec::from(steps) * Dec::from(Decimal::TWO_PI) - from;
 
             let top_prev = self.top_basis.center
                 + self.top_basis.x() * angle_prev.cos() * self.radius
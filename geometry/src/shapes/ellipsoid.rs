@@ -0,0 +1,149 @@
+use nalgebra::{ComplexField, Vector3};
+
+use crate::{
+    decimal::Dec, geometry::GeometryDyn, indexes::geo_index::mesh::MeshRefMut, origin::Origin,
+};
+
+use super::steps_for_chord_error;
+
+/// Ellipsoid with radii along x, y and z axes of its origin.
+#[derive(Clone)]
+pub struct Ellipsoid {
+    center: Origin,
+    radii: Vector3<Dec>,
+    segments: usize,
+    rings: usize,
+    max_chord_error: Option<Dec>,
+}
+
+impl Ellipsoid {
+    pub fn centered(
+        origin: Origin,
+        radius_x: impl Into<Dec>,
+        radius_y: impl Into<Dec>,
+        radius_z: impl Into<Dec>,
+    ) -> Self {
+        Self {
+            center: origin,
+            radii: Vector3::new(radius_x.into(), radius_y.into(), radius_z.into()),
+            segments: 16,
+            rings: 8,
+            max_chord_error: None,
+        }
+    }
+
+    /// Amount of steps around z axis
+    pub fn segments(mut self, segments: usize) -> Self {
+        self.segments = segments.max(3);
+        self
+    }
+
+    /// Amount of steps from top pole to bottom one
+    pub fn rings(mut self, rings: usize) -> Self {
+        self.rings = rings.max(2);
+        self
+    }
+
+    /// Calculate segments and rings from radii, so that surface does not deviate from
+    /// ideal one more than `max_chord_error`. Overrides `segments` and `rings`.
+    pub fn max_chord_error(mut self, max_chord_error: impl Into<Dec>) -> Self {
+        self.max_chord_error = Some(max_chord_error.into());
+        self
+    }
+
+    fn steps_count(&self) -> (usize, usize) {
+        if let Some(e) = self.max_chord_error {
+            let around = self.radii.x.max(self.radii.y);
+            let along = around.max(self.radii.z);
+            (
+                steps_for_chord_error(around, Dec::two_pi(), e).max(3),
+                steps_for_chord_error(along, Dec::pi(), e).max(2),
+            )
+        } else {
+            (self.segments, self.rings)
+        }
+    }
+
+    fn point(&self, theta: Dec, phi: Dec) -> Vector3<Dec> {
+        self.center.center
+            + self.center.x() * (theta.sin() * phi.cos() * self.radii.x)
+            + self.center.y() * (theta.sin() * phi.sin() * self.radii.y)
+            + self.center.z() * (theta.cos() * self.radii.z)
+    }
+
+    pub fn render(&self) -> Vec<Vec<Vector3<Dec>>> {
+        let (segments, rings) = self.steps_count();
+        let top = self.center.center + self.center.z() * self.radii.z;
+        let bottom = self.center.center - self.center.z() * self.radii.z;
+
+        let ring_points = (1..rings)
+            .map(|r| {
+                let theta = Dec::pi() * Dec::from(r) / Dec::from(rings);
+                (0..segments)
+                    .map(|s| {
+                        let phi = Dec::two_pi() * Dec::from(s) / Dec::from(segments);
+                        self.point(theta, phi)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut polygons = Vec::new();
+        for s in 0..segments {
+            let next = (s + 1) % segments;
+            polygons.push(vec![top, ring_points[0][s], ring_points[0][next]]);
+
+            for ring in ring_points.windows(2) {
+                polygons.push(vec![ring[0][s], ring[1][s], ring[1][next], ring[0][next]]);
+            }
+
+            let last = &ring_points[ring_points.len() - 1];
+            polygons.push(vec![bottom, last[next], last[s]]);
+        }
+
+        polygons
+    }
+}
+
+impl GeometryDyn for Ellipsoid {
+    fn polygonize(&self, mut mesh: MeshRefMut, _complexity: usize) -> anyhow::Result<()> {
+        for p in self.render() {
+            mesh.add_polygon(&p)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Ellipsoid with all radii equal.
+#[derive(Clone)]
+pub struct Sphere(Ellipsoid);
+
+impl Sphere {
+    pub fn centered(origin: Origin, radius: impl Into<Dec>) -> Self {
+        let radius = radius.into();
+        Self(Ellipsoid::centered(origin, radius, radius, radius))
+    }
+
+    pub fn segments(self, segments: usize) -> Self {
+        Self(self.0.segments(segments))
+    }
+
+    pub fn rings(self, rings: usize) -> Self {
+        Self(self.0.rings(rings))
+    }
+
+    pub fn max_chord_error(self, max_chord_error: impl Into<Dec>) -> Self {
+        Self(self.0.max_chord_error(max_chord_error))
+    }
+
+    pub fn render(&self) -> Vec<Vec<Vector3<Dec>>> {
+        self.0.render()
+    }
+}
+
+impl GeometryDyn for Sphere {
+    fn polygonize(&self, mesh: MeshRefMut, complexity: usize) -> anyhow::Result<()> {
+        self.0.polygonize(mesh, complexity)
+    }
+}
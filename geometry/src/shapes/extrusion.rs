@@ -0,0 +1,80 @@
+use nalgebra::{Vector2, Vector3};
+use num_traits::Signed;
+
+use crate::{
+    decimal::Dec, geometry::GeometryDyn, indexes::geo_index::mesh::MeshRefMut, origin::Origin,
+    planar::triangulation,
+};
+
+/// Prism with arbitrary (possibly concave) profile. Profile lies in xy plane of origin
+/// and is extruded along z axis.
+#[derive(Clone)]
+pub struct Extrusion {
+    origin: Origin,
+    profile: Vec<Vector2<Dec>>,
+    height: Dec,
+}
+
+pub fn extrude(profile: &[Vector2<Dec>], height: impl Into<Dec>, origin: Origin) -> Extrusion {
+    Extrusion {
+        origin,
+        profile: profile.to_vec(),
+        height: height.into(),
+    }
+}
+
+impl Extrusion {
+    fn point(&self, pt: &Vector2<Dec>, z: Dec) -> Vector3<Dec> {
+        self.origin.center + self.origin.x() * pt.x + self.origin.y() * pt.y + self.origin.z() * z
+    }
+
+    pub fn render(&self) -> anyhow::Result<Vec<Vec<Vector3<Dec>>>> {
+        let mut profile = self.profile.clone();
+        if triangulation::signed_area(&profile).is_negative() {
+            profile.reverse();
+        }
+
+        let bottom = profile
+            .iter()
+            .map(|p| self.point(p, Dec::from(0)))
+            .collect::<Vec<_>>();
+        let top = profile
+            .iter()
+            .map(|p| self.point(p, self.height))
+            .collect::<Vec<_>>();
+
+        let mut polygons = Vec::new();
+        if triangulation::is_convex(&profile) {
+            polygons.push(top.clone());
+            polygons.push(bottom.iter().rev().copied().collect());
+        } else {
+            for [a, b, c] in triangulation::triangulate(&profile)? {
+                polygons.push(vec![top[a], top[b], top[c]]);
+                polygons.push(vec![bottom[c], bottom[b], bottom[a]]);
+            }
+        }
+
+        for i in 0..profile.len() {
+            let next = (i + 1) % profile.len();
+            polygons.push(vec![bottom[i], bottom[next], top[next], top[i]]);
+        }
+
+        if self.height.is_negative() {
+            for p in &mut polygons {
+                p.reverse();
+            }
+        }
+
+        Ok(polygons)
+    }
+}
+
+impl GeometryDyn for Extrusion {
+    fn polygonize(&self, mut mesh: MeshRefMut, _complexity: usize) -> anyhow::Result<()> {
+        for p in self.render()? {
+            mesh.add_polygon(&p)?;
+        }
+
+        Ok(())
+    }
+}
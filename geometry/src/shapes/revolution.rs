@@ -0,0 +1,110 @@
+use anyhow::anyhow;
+use nalgebra::{ComplexField, Vector2, Vector3};
+use num_traits::{Signed, Zero};
+
+use crate::{
+    decimal::Dec, geometry::GeometryDyn, indexes::geo_index::mesh::MeshRefMut, origin::Origin,
+    planar::triangulation,
+};
+
+/// Solid of revolution. Profile is given in (radius, height) coordinates and is swept
+/// around z axis of origin, starting from its x axis.
+/// Profile points with zero radius lie on the axis.
+#[derive(Clone)]
+pub struct Revolution {
+    origin: Origin,
+    profile: Vec<Vector2<Dec>>,
+    angle: Dec,
+    steps: usize,
+}
+
+pub fn revolve(
+    profile: &[Vector2<Dec>],
+    axis_origin: Origin,
+    angle: impl Into<Dec>,
+    steps: usize,
+) -> Revolution {
+    Revolution {
+        origin: axis_origin,
+        profile: profile.to_vec(),
+        angle: angle.into().min(Dec::two_pi()),
+        steps: steps.max(1),
+    }
+}
+
+impl Revolution {
+    fn is_closed(&self) -> bool {
+        self.angle >= Dec::two_pi()
+    }
+
+    fn point(&self, pt: &Vector2<Dec>, angle: Dec) -> Vector3<Dec> {
+        let radial = self.origin.x() * angle.cos() + self.origin.y() * angle.sin();
+        self.origin.center + radial * pt.x + self.origin.z() * pt.y
+    }
+
+    pub fn render(&self) -> anyhow::Result<Vec<Vec<Vector3<Dec>>>> {
+        if self.profile.iter().any(|p| p.x.is_negative()) {
+            return Err(anyhow!("Profile of revolution crosses its axis"));
+        }
+        let mut profile = self.profile.clone();
+        if triangulation::signed_area(&profile).is_negative() {
+            profile.reverse();
+        }
+
+        let sections_count = if self.is_closed() {
+            self.steps
+        } else {
+            self.steps + 1
+        };
+        let sections = (0..sections_count)
+            .map(|s| {
+                let angle = self.angle * Dec::from(s) / Dec::from(self.steps);
+                profile
+                    .iter()
+                    .map(|p| self.point(p, angle))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut polygons = Vec::new();
+        for s in 0..self.steps {
+            let this = &sections[s];
+            let next = &sections[(s + 1) % sections.len()];
+            for k in 0..profile.len() {
+                let kn = (k + 1) % profile.len();
+                match (profile[k].x.is_zero(), profile[kn].x.is_zero()) {
+                    (true, true) => {}
+                    (true, false) => polygons.push(vec![this[k], next[kn], this[kn]]),
+                    (false, true) => polygons.push(vec![this[k], next[k], this[kn]]),
+                    (false, false) => polygons.push(vec![this[k], next[k], next[kn], this[kn]]),
+                }
+            }
+        }
+
+        if !self.is_closed() {
+            let start = &sections[0];
+            let end = &sections[self.steps];
+            if triangulation::is_convex(&profile) {
+                polygons.push(start.clone());
+                polygons.push(end.iter().rev().copied().collect());
+            } else {
+                for [a, b, c] in triangulation::triangulate(&profile)? {
+                    polygons.push(vec![start[a], start[b], start[c]]);
+                    polygons.push(vec![end[c], end[b], end[a]]);
+                }
+            }
+        }
+
+        Ok(polygons)
+    }
+}
+
+impl GeometryDyn for Revolution {
+    fn polygonize(&self, mut mesh: MeshRefMut, _complexity: usize) -> anyhow::Result<()> {
+        for p in self.render()? {
+            mesh.add_polygon(&p)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+use crate::decimal::Dec;
+
+/// Calculates amount of straight segments, needed to approximate arc of `radius` and `arc` angle
+/// (in radians), so that distance between any chord and the arc is not bigger than
+/// `max_chord_error`.
+pub fn steps_for_chord_error(radius: Dec, arc: Dec, max_chord_error: Dec) -> usize {
+    let radius: f64 = radius.into();
+    let arc: f64 = arc.into();
+    let max_chord_error: f64 = max_chord_error.into();
+    if radius <= 0.0 || max_chord_error <= 0.0 {
+        return 1;
+    }
+    if max_chord_error >= radius {
+        return (arc / std::f64::consts::FRAC_PI_2).ceil().max(1.0) as usize;
+    }
+    let max_angle = 2.0 * (1.0 - max_chord_error / radius).acos();
+    (arc.abs() / max_angle).ceil().max(1.0) as usize
+}
@@ -0,0 +1,135 @@
+use nalgebra::{ComplexField, Vector3};
+use num_traits::One;
+
+use crate::{
+    decimal::Dec, geometry::GeometryDyn, indexes::geo_index::mesh::MeshRefMut, origin::Origin,
+};
+
+/// Rod with helical thread of triangular profile. Thread goes from origin along its z axis,
+/// and is right handed. Crests of thread lie on `radius`, roots are `depth` closer to the axis.
+/// With several starts, each helix advances `starts * pitch` per turn.
+/// Ends of rod are flat.
+#[derive(Clone)]
+pub struct HelicalThread {
+    bottom_basis: Origin,
+    height: Dec,
+    radius: Dec,
+    pitch: Dec,
+    depth: Dec,
+    starts: usize,
+    steps: usize,
+    steps_per_pitch: usize,
+}
+
+impl HelicalThread {
+    pub fn with_bottom_at(
+        origin: Origin,
+        height: impl Into<Dec>,
+        radius: impl Into<Dec>,
+        pitch: impl Into<Dec>,
+        depth: impl Into<Dec>,
+    ) -> Self {
+        Self {
+            bottom_basis: origin,
+            height: height.into(),
+            radius: radius.into(),
+            pitch: pitch.into(),
+            depth: depth.into(),
+            starts: 1,
+            steps: 24,
+            steps_per_pitch: 6,
+        }
+    }
+
+    pub fn with_top_at(
+        origin: Origin,
+        height: impl Into<Dec>,
+        radius: impl Into<Dec>,
+        pitch: impl Into<Dec>,
+        depth: impl Into<Dec>,
+    ) -> Self {
+        let height = height.into();
+        let bottom_basis = origin.offset_z(-height);
+        Self::with_bottom_at(bottom_basis, height, radius, pitch, depth)
+    }
+
+    pub fn starts(mut self, starts: usize) -> Self {
+        self.starts = starts.max(1);
+        self
+    }
+
+    /// Amount of steps around the axis
+    pub fn steps(mut self, steps: usize) -> Self {
+        self.steps = steps.max(3);
+        self
+    }
+
+    /// Amount of rings along the axis for each pitch
+    pub fn steps_per_pitch(mut self, steps_per_pitch: usize) -> Self {
+        self.steps_per_pitch = steps_per_pitch.max(2);
+        self
+    }
+
+    fn point_radius(&self, angle: Dec, z: Dec) -> Dec {
+        let u = z / self.pitch - Dec::from(self.starts) * angle / Dec::two_pi();
+        let u = u - u.floor();
+        let crest = Dec::one() - (u * 2 - Dec::one()).abs();
+        self.radius - self.depth + self.depth * crest
+    }
+
+    pub fn render(&self) -> Vec<Vec<Vector3<Dec>>> {
+        let rings_count = (self.height / self.pitch * Dec::from(self.steps_per_pitch))
+            .ceil()
+            .max(Dec::one());
+        let rings_count = i128::from(rings_count) as usize;
+
+        let rings = (0..=rings_count)
+            .map(|k| {
+                let z = self.height * Dec::from(k) / Dec::from(rings_count);
+                (0..self.steps)
+                    .map(|j| {
+                        let angle = Dec::two_pi() * Dec::from(j) / Dec::from(self.steps);
+                        let r = self.point_radius(angle, z);
+                        self.bottom_basis.center
+                            + self.bottom_basis.x() * (angle.cos() * r)
+                            + self.bottom_basis.y() * (angle.sin() * r)
+                            + self.bottom_basis.z() * z
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut polygons = Vec::new();
+        for k in 0..rings_count {
+            let lower = &rings[k];
+            let upper = &rings[k + 1];
+            for j in 0..self.steps {
+                let jn = (j + 1) % self.steps;
+                polygons.push(vec![lower[j], lower[jn], upper[jn]]);
+                polygons.push(vec![lower[j], upper[jn], upper[j]]);
+            }
+        }
+
+        let bottom_center = self.bottom_basis.center;
+        let top_center = self.bottom_basis.center + self.bottom_basis.z() * self.height;
+        let bottom = &rings[0];
+        let top = &rings[rings_count];
+        for j in 0..self.steps {
+            let jn = (j + 1) % self.steps;
+            polygons.push(vec![top_center, top[j], top[jn]]);
+            polygons.push(vec![bottom_center, bottom[jn], bottom[j]]);
+        }
+
+        polygons
+    }
+}
+
+impl GeometryDyn for HelicalThread {
+    fn polygonize(&self, mut mesh: MeshRefMut, _complexity: usize) -> anyhow::Result<()> {
+        for p in self.render() {
+            mesh.add_polygon(&p)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use nalgebra::{ComplexField, Vector3};
+
+use crate::{
+    decimal::Dec, geometry::GeometryDyn, indexes::geo_index::mesh::MeshRefMut, origin::Origin,
+};
+
+use super::steps_for_chord_error;
+
+/// Torus around z axis of origin. Tube center lies in xy plane of origin.
+/// With sweep less than full turn, the tube starts at x axis, goes counterclockwise and
+/// ends are capped.
+#[derive(Clone)]
+pub struct Torus {
+    center: Origin,
+    major_radius: Dec,
+    minor_radius: Dec,
+    sweep: Dec,
+    major_steps: usize,
+    minor_steps: usize,
+    max_chord_error: Option<Dec>,
+}
+
+impl Torus {
+    pub fn centered(
+        origin: Origin,
+        major_radius: impl Into<Dec>,
+        minor_radius: impl Into<Dec>,
+    ) -> Self {
+        Self {
+            center: origin,
+            major_radius: major_radius.into(),
+            minor_radius: minor_radius.into(),
+            sweep: Dec::two_pi(),
+            major_steps: 32,
+            minor_steps: 8,
+            max_chord_error: None,
+        }
+    }
+
+    /// Angle (in radians) of torus part. Full turn by default
+    pub fn sweep(mut self, sweep: impl Into<Dec>) -> Self {
+        self.sweep = sweep.into().min(Dec::two_pi());
+        self
+    }
+
+    /// Amount of steps along the ring
+    pub fn major_steps(mut self, major_steps: usize) -> Self {
+        self.major_steps = major_steps.max(1);
+        self
+    }
+
+    /// Amount of steps around the tube
+    pub fn minor_steps(mut self, minor_steps: usize) -> Self {
+        self.minor_steps = minor_steps.max(3);
+        self
+    }
+
+    /// Calculate amount of steps from radii, so that surface does not deviate from
+    /// ideal one more than `max_chord_error`. Overrides `major_steps` and `minor_steps`.
+    pub fn max_chord_error(mut self, max_chord_error: impl Into<Dec>) -> Self {
+        self.max_chord_error = Some(max_chord_error.into());
+        self
+    }
+
+    fn is_closed(&self) -> bool {
+        self.sweep >= Dec::two_pi()
+    }
+
+    fn steps_count(&self) -> (usize, usize) {
+        if let Some(e) = self.max_chord_error {
+            let outer = self.major_radius + self.minor_radius;
+            (
+                steps_for_chord_error(outer, self.sweep, e).max(1),
+                steps_for_chord_error(self.minor_radius, Dec::two_pi(), e).max(3),
+            )
+        } else {
+            (self.major_steps, self.minor_steps)
+        }
+    }
+
+    pub fn render(&self) -> Vec<Vec<Vector3<Dec>>> {
+        let (major_steps, minor_steps) = self.steps_count();
+        let sections_count = if self.is_closed() {
+            major_steps
+        } else {
+            major_steps + 1
+        };
+
+        let sections = (0..sections_count)
+            .map(|i| {
+                let a = self.sweep * Dec::from(i) / Dec::from(major_steps);
+                let radial = self.center.x() * a.cos() + self.center.y() * a.sin();
+                let tube_center = self.center.center + radial * self.major_radius;
+                (0..minor_steps)
+                    .map(|j| {
+                        let b = Dec::two_pi() * Dec::from(j) / Dec::from(minor_steps);
+                        tube_center
+                            + radial * (b.cos() * self.minor_radius)
+                            + self.center.z() * (b.sin() * self.minor_radius)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut polygons = Vec::new();
+        for i in 0..major_steps {
+            let this = &sections[i];
+            let next = &sections[(i + 1) % sections.len()];
+            for j in 0..minor_steps {
+                let jn = (j + 1) % minor_steps;
+                polygons.push(vec![this[j], next[j], next[jn], this[jn]]);
+            }
+        }
+
+        if !self.is_closed() {
+            polygons.push(sections[0].clone());
+            polygons.push(sections[major_steps].iter().rev().copied().collect());
+        }
+
+        polygons
+    }
+}
+
+impl GeometryDyn for Torus {
+    fn polygonize(&self, mut mesh: MeshRefMut, _complexity: usize) -> anyhow::Result<()> {
+        for p in self.render() {
+            mesh.add_polygon(&p)?;
+        }
+
+        Ok(())
+    }
+}
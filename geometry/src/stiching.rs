@@ -1,5 +1,12 @@
 use std::fmt::Debug;
 
+use anyhow::anyhow;
+use itertools::Itertools;
+use nalgebra::Vector3;
+use num_traits::Zero;
+
+use crate::decimal::Dec;
+
 #[derive(Clone, Debug)]
 pub struct HullEdgeItem<T> {
     pub inner: T,
@@ -31,3 +38,58 @@ impl<T: Path + Clone + Debug> StichItem<T> {
     }
 }
 */
+
+/// Skin between two closed loops with any amount of points. Loops should go in the same
+/// direction, then triangles look the same way as quad `[from[i], from[i + 1], to[i + 1], to[i]]` would.
+/// Points are matched by relative length along the loop, `to` starts at point closest to `from[0]`.
+pub fn loft(from: &[Vector3<Dec>], to: &[Vector3<Dec>]) -> anyhow::Result<Vec<Vec<Vector3<Dec>>>> {
+    if from.len() < 3 || to.len() < 3 {
+        return Err(anyhow!("Loft needs loops with at least 3 points"));
+    }
+    let start = to
+        .iter()
+        .position_min_by_key(|p| (*p - from[0]).magnitude_squared())
+        .unwrap_or(0);
+    let to = to[start..]
+        .iter()
+        .chain(&to[..start])
+        .copied()
+        .collect_vec();
+
+    let params_from = loop_params(from)?;
+    let params_to = loop_params(&to)?;
+
+    let mut polygons = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < from.len() || j < to.len() {
+        let a = from[i % from.len()];
+        let b = to[j % to.len()];
+        let move_from = j == to.len() || (i < from.len() && params_from[i + 1] <= params_to[j + 1]);
+        let triangle = if move_from {
+            i += 1;
+            vec![a, from[i % from.len()], b]
+        } else {
+            j += 1;
+            vec![a, to[j % to.len()], b]
+        };
+        if triangle.iter().tuple_combinations().all(|(p, q)| p != q) {
+            polygons.push(triangle);
+        }
+    }
+
+    Ok(polygons)
+}
+
+/// Relative length along the loop for each point, including the closing one (which is 1)
+fn loop_params(points: &[Vector3<Dec>]) -> anyhow::Result<Vec<Dec>> {
+    let mut params = vec![Dec::zero()];
+    for (a, b) in points.iter().chain(points.first()).tuple_windows() {
+        let last = params[params.len() - 1];
+        params.push(last + (b - a).magnitude());
+    }
+    let total = params[params.len() - 1];
+    if total.is_zero() {
+        return Err(anyhow!("Loop for loft has zero length"));
+    }
+    Ok(params.into_iter().map(|p| p / total).collect())
+}
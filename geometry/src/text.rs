@@ -0,0 +1,322 @@
+use std::{path::Path, rc::Rc};
+
+use anyhow::anyhow;
+use nalgebra::{Vector2, Vector3};
+use num_traits::{Signed, Zero};
+use rust_decimal_macros::dec;
+
+use crate::{
+    decimal::Dec, geometry::GeometryDyn, indexes::geo_index::mesh::MeshRefMut, origin::Origin,
+    planar::triangulation,
+};
+
+/// TrueType (or OpenType) font, loaded in memory
+#[derive(Clone)]
+pub struct Font {
+    data: Rc<Vec<u8>>,
+}
+
+/// Outline of one glyph part: outer contour is counter-clockwise, holes are clockwise.
+#[derive(Clone, Debug)]
+pub struct GlyphOutline {
+    pub outer: Vec<Vector2<Dec>>,
+    pub holes: Vec<Vec<Vector2<Dec>>>,
+}
+
+impl Font {
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Self::from_bytes(std::fs::read(path)?)
+    }
+
+    pub fn from_bytes(data: Vec<u8>) -> anyhow::Result<Self> {
+        ttf_parser::Face::parse(&data, 0)?;
+        Ok(Self {
+            data: Rc::new(data),
+        })
+    }
+
+    /// Outlines of `text`, written in one line from (0, 0) along x axis.
+    /// `size` is a font size - distance between lines. Each curve of glyph is split
+    /// into `curve_steps` lines.
+    pub fn outlines(
+        &self,
+        text: &str,
+        size: Dec,
+        curve_steps: usize,
+    ) -> anyhow::Result<Vec<GlyphOutline>> {
+        let face = ttf_parser::Face::parse(&self.data, 0)?;
+        let scale = size / Dec::from(face.units_per_em() as u32);
+        let line_height = Dec::from(face.height() as i32 + face.line_gap() as i32) * scale;
+
+        let mut result = Vec::new();
+        let mut pen = Vector2::zeros();
+        for ch in text.chars() {
+            if ch == '\n' {
+                pen = Vector2::new(Dec::zero(), pen.y - line_height);
+                continue;
+            }
+            let glyph = face
+                .glyph_index(ch)
+                .ok_or_else(|| anyhow!("No glyph for `{ch}` in font"))?;
+
+            let mut builder = ContoursBuilder::new(curve_steps);
+            face.outline_glyph(glyph, &mut builder);
+            let contours = builder
+                .contours
+                .into_iter()
+                .map(|c| {
+                    c.into_iter()
+                        .map(|(x, y)| {
+                            let scaled = Vector2::new(Dec::from(x), Dec::from(y)) * scale;
+                            pen + scaled.map(|v| v.round_dp(4))
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .filter(|c| c.len() > 2 && !triangulation::signed_area(c).is_zero())
+                .collect::<Vec<_>>();
+            result.extend(nest_contours(contours));
+
+            let advance = face.glyph_hor_advance(glyph).unwrap_or(0);
+            pen.x += Dec::from(advance as u32) * scale;
+        }
+
+        Ok(result)
+    }
+}
+
+struct ContoursBuilder {
+    curve_steps: usize,
+    contours: Vec<Vec<(f32, f32)>>,
+    current: Vec<(f32, f32)>,
+}
+
+impl ContoursBuilder {
+    fn new(curve_steps: usize) -> Self {
+        Self {
+            curve_steps: curve_steps.max(1),
+            contours: Vec::new(),
+            current: Vec::new(),
+        }
+    }
+
+    fn last(&self) -> (f32, f32) {
+        self.current.last().copied().unwrap_or((0.0, 0.0))
+    }
+
+    fn curve(&mut self, point: impl Fn(f32) -> (f32, f32)) {
+        for s in 1..=self.curve_steps {
+            let t = s as f32 / self.curve_steps as f32;
+            self.current.push(point(t));
+        }
+    }
+}
+
+impl ttf_parser::OutlineBuilder for ContoursBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.close();
+        self.current.push((x, y));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.current.push((x, y));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (x0, y0) = self.last();
+        self.curve(|t| {
+            let u = 1.0 - t;
+            (
+                u * u * x0 + 2.0 * u * t * x1 + t * t * x,
+                u * u * y0 + 2.0 * u * t * y1 + t * t * y,
+            )
+        });
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x0, y0) = self.last();
+        self.curve(|t| {
+            let u = 1.0 - t;
+            (
+                u * u * u * x0 + 3.0 * u * u * t * x1 + 3.0 * u * t * t * x2 + t * t * t * x,
+                u * u * u * y0 + 3.0 * u * u * t * y1 + 3.0 * u * t * t * y2 + t * t * t * y,
+            )
+        });
+    }
+
+    fn close(&mut self) {
+        let mut contour = std::mem::take(&mut self.current);
+        contour.dedup();
+        if contour.len() > 1 && contour.first() == contour.last() {
+            contour.pop();
+        }
+        if !contour.is_empty() {
+            self.contours.push(contour);
+        }
+    }
+}
+
+fn is_inside(pt: Vector2<Dec>, contour: &[Vector2<Dec>]) -> bool {
+    let mut inside = false;
+    for i in 0..contour.len() {
+        let (a, b) = (contour[i], contour[(i + 1) % contour.len()]);
+        if (a.y > pt.y) != (b.y > pt.y) && pt.x < a.x + (pt.y - a.y) * (b.x - a.x) / (b.y - a.y) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Contours, which are inside of even amount of others are outer, rest are holes of
+/// the closest outer contour around them. Font winding rules are not trusted.
+fn nest_contours(contours: Vec<Vec<Vector2<Dec>>>) -> Vec<GlyphOutline> {
+    let parents = contours
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            (0..contours.len())
+                .filter(|j| *j != i && is_inside(c[0], &contours[*j]))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut outlines = Vec::new();
+    let mut outline_ix = vec![None; contours.len()];
+    for (i, c) in contours.iter().enumerate() {
+        if parents[i].len() % 2 == 0 {
+            let mut outer = c.clone();
+            if triangulation::signed_area(&outer).is_negative() {
+                outer.reverse();
+            }
+            outline_ix[i] = Some(outlines.len());
+            outlines.push(GlyphOutline {
+                outer,
+                holes: Vec::new(),
+            });
+        }
+    }
+    for (i, c) in contours.iter().enumerate() {
+        let Some(parent) = parents[i]
+            .iter()
+            .filter(|p| parents[**p].len() + 1 == parents[i].len())
+            .find_map(|p| outline_ix[*p])
+        else {
+            continue;
+        };
+        let mut hole = c.clone();
+        if triangulation::signed_area(&hole).is_positive() {
+            hole.reverse();
+        }
+        outlines[parent].holes.push(hole);
+    }
+
+    outlines
+}
+
+/// Solid text. Glyphs are placed on xy plane of origin, starting at it, and are extruded
+/// along z axis by depth. Negative depth extrudes down.
+#[derive(Clone)]
+pub struct Text {
+    font: Font,
+    text: String,
+    origin: Origin,
+    size: Dec,
+    depth: Dec,
+    curve_steps: usize,
+}
+
+impl Text {
+    pub fn new(font: Font, text: impl Into<String>) -> Self {
+        Self {
+            font,
+            text: text.into(),
+            origin: Origin::new(),
+            size: Dec::from(5),
+            depth: dec!(0.6).into(),
+            curve_steps: 4,
+        }
+    }
+
+    pub fn origin(mut self, origin: Origin) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Font size - distance between lines of text
+    pub fn size(mut self, size: impl Into<Dec>) -> Self {
+        self.size = size.into();
+        self
+    }
+
+    pub fn depth(mut self, depth: impl Into<Dec>) -> Self {
+        self.depth = depth.into();
+        self
+    }
+
+    /// Amount of lines for each curve of glyph outline
+    pub fn curve_steps(mut self, curve_steps: usize) -> Self {
+        self.curve_steps = curve_steps.max(1);
+        self
+    }
+
+    fn point(&self, pt: &Vector2<Dec>, z: Dec) -> Vector3<Dec> {
+        self.origin.center + self.origin.x() * pt.x + self.origin.y() * pt.y + self.origin.z() * z
+    }
+
+    pub fn render(&self) -> anyhow::Result<Vec<Vec<Vector3<Dec>>>> {
+        let mut polygons = Vec::new();
+        for outline in self
+            .font
+            .outlines(&self.text, self.size, self.curve_steps)?
+        {
+            let cap = outline
+                .outer
+                .iter()
+                .chain(outline.holes.iter().flatten())
+                .copied()
+                .collect::<Vec<_>>();
+            for [a, b, c] in triangulation::triangulate_with_holes(&outline.outer, &outline.holes)?
+            {
+                polygons.push(vec![
+                    self.point(&cap[a], self.depth),
+                    self.point(&cap[b], self.depth),
+                    self.point(&cap[c], self.depth),
+                ]);
+                polygons.push(vec![
+                    self.point(&cap[c], Dec::zero()),
+                    self.point(&cap[b], Dec::zero()),
+                    self.point(&cap[a], Dec::zero()),
+                ]);
+            }
+
+            for contour in [&outline.outer].into_iter().chain(&outline.holes) {
+                for i in 0..contour.len() {
+                    let next = (i + 1) % contour.len();
+                    polygons.push(vec![
+                        self.point(&contour[i], Dec::zero()),
+                        self.point(&contour[next], Dec::zero()),
+                        self.point(&contour[next], self.depth),
+                        self.point(&contour[i], self.depth),
+                    ]);
+                }
+            }
+        }
+
+        if self.depth.is_negative() {
+            for p in &mut polygons {
+                p.reverse();
+            }
+        }
+
+        Ok(polygons)
+    }
+}
+
+impl GeometryDyn for Text {
+    fn polygonize(&self, mut mesh: MeshRefMut, _complexity: usize) -> anyhow::Result<()> {
+        for p in self.render()? {
+            mesh.add_polygon(&p)?;
+        }
+
+        Ok(())
+    }
+}
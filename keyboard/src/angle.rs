@@ -4,7 +4,7 @@ use geometry::decimal::Dec;
 use num_traits::Zero;
 use rust_decimal::Decimal;
 
-#[derive(Default)]
+#[derive(Clone, Copy, Debug, Default)]
 pub struct Angle(Dec);
 
 impl Add for Angle {
@@ -0,0 +1,84 @@
+use anyhow::anyhow;
+use geometry::{decimal::Dec, origin::Origin};
+use itertools::Itertools;
+use num_traits::{Signed, Zero};
+use rust_decimal_macros::dec;
+
+use crate::{
+    bolt::Bolt,
+    bolt_point::BoltPoint,
+    keyboard_config::{KeyboardMesh, RightKeyboardConfig},
+};
+
+impl RightKeyboardConfig {
+    /// Places `count` bolts, which join buttons hull and bottom plate, evenly along table
+    /// outline. Axis of each bolt goes up `inset` from outer wall, bolt point is at table
+    /// level, head is in the hull and thread is in the bottom plate. Where walls come close
+    /// to each other, like in narrow corners, there is not enough material around the
+    /// bolt, so it is moved along the outline to the nearest place, where there is.
+    pub fn auto_bolts(
+        mut self,
+        count: usize,
+        bolt: Bolt,
+        inset: impl Into<Dec>,
+    ) -> anyhow::Result<Self> {
+        let inset = inset.into();
+        let template = BoltPoint::new(bolt).quality(self.quality);
+        if inset <= template.head_hole_radius() {
+            return Err(anyhow!(
+                "Bolt head hole of radius {} goes through outer wall at inset {inset}",
+                template.head_hole_radius()
+            ));
+        }
+        let samples = self.wall_samples();
+        if count == 0 || samples.len() < 3 {
+            return Ok(self);
+        }
+
+        let mut along = vec![Dec::zero()];
+        for ((a, _), (b, _)) in samples.iter().circular_tuple_windows() {
+            let last = *along.last().expect("starts with zero");
+            along.push(last + (b - a).magnitude());
+        }
+        let perimeter = along.pop().expect("has perimeter");
+
+        // Axis is far enough from every wall, not only from its own
+        let tolerance = Dec::from(dec!(0.01));
+        let axis = |ix: usize| {
+            let (point, normal) = samples[ix];
+            point - normal * inset
+        };
+        let fits = |ix: usize| {
+            let at = axis(ix);
+            samples
+                .iter()
+                .all(|(p, _)| (p - at).magnitude() >= inset - tolerance)
+        };
+
+        let spacing = perimeter / Dec::from(count);
+        let mut taken: Vec<usize> = Vec::new();
+        for k in 0..count {
+            let target = spacing * (Dec::from(k) + Dec::from(dec!(0.5)));
+            let distance = |ix: &usize| {
+                let d = (along[*ix] - target).abs();
+                d.min(perimeter - d)
+            };
+            let ix = (0..samples.len())
+                .filter(|ix| distance(ix) <= spacing / 2)
+                .filter(|ix| !taken.contains(ix))
+                .sorted_by_key(distance)
+                .find(|ix| fits(*ix))
+                .ok_or(anyhow!(
+                    "No place with enough material for bolt {} of {count}",
+                    k + 1
+                ))?;
+            taken.push(ix);
+            self.bolts.push((
+                KeyboardMesh::ButtonsHull,
+                KeyboardMesh::Bottom,
+                template.clone().origin(Origin::new().offset(axis(ix))),
+            ));
+        }
+        Ok(self)
+    }
+}
@@ -0,0 +1,201 @@
+use anyhow::anyhow;
+use geometry::{
+    decimal::Dec,
+    hyper_path::{
+        hyper_line::HyperLine,
+        hyper_path::{HyperPath, Root},
+        hyper_point::SuperPoint,
+    },
+};
+use itertools::Itertools;
+use nalgebra::{ComplexField, Vector2, Vector3};
+use num_traits::Zero;
+
+use crate::keyboard_config::RightKeyboardConfig;
+
+impl RightKeyboardConfig {
+    /// Replaces table outline with one, made from buttons. Footprints of all buttons are
+    /// projected to the table, their convex hull is moved out by `margin` and its corners
+    /// are rounded with `corner_radius`, which cannot be bigger, than `margin`. Like the
+    /// line around buttons, outline goes clockwise and starts next to the top of the right
+    /// column, so walls between them are not twisted.
+    pub fn auto_outline(
+        mut self,
+        margin: impl Into<Dec>,
+        corner_radius: impl Into<Dec>,
+    ) -> anyhow::Result<Self> {
+        let margin = margin.into();
+        let radius = corner_radius.into();
+        if radius < Dec::zero() || radius > margin {
+            return Err(anyhow!(
+                "Corner radius of outline must be from 0 to margin {margin}, got {radius}"
+            ));
+        }
+        let t = self.main_plane_thickness;
+        let footprints = self
+            .collections()
+            .flat_map(|c| c.buttons())
+            .flat_map(|b| {
+                [
+                    b.inner_left_bottom(t),
+                    b.inner_left_top(t),
+                    b.inner_right_bottom(t),
+                    b.inner_right_top(t),
+                    b.outer_left_bottom(t),
+                    b.outer_left_top(t),
+                    b.outer_right_bottom(t),
+                    b.outer_right_top(t),
+                ]
+            })
+            .map(|p| Vector2::new(p.x, p.y))
+            .collect_vec();
+        let hull = convex_hull(footprints);
+        if hull.len() < 3 {
+            return Err(anyhow!(
+                "Buttons do not cover an area to make outline around"
+            ));
+        }
+
+        let mut lines = rounded_offset(&hull, margin, radius);
+        if let Some(start) = self.main_buttons.right_line_inner(t).next() {
+            start_at(&mut lines, Vector2::new(start.point.x, start.point.y));
+        }
+
+        // Hull goes counterclockwise, walls - the other way
+        let side_dir = Vector3::z() * Dec::from(20);
+        self.table_outline = lines
+            .into_iter()
+            .rev()
+            .map(|line| {
+                let points = line
+                    .into_iter()
+                    .rev()
+                    .map(|p| SuperPoint {
+                        side_dir,
+                        point: Vector3::new(p.x, p.y, Dec::zero()),
+                    })
+                    .collect_vec();
+                match points.as_slice() {
+                    [a, b, c, d] => HyperLine::new_4(*a, *b, *c, *d),
+                    _ => HyperLine::new_2(points[0], points[points.len() - 1]),
+                }
+            })
+            .fold(Root::new(), |hp, l| hp.push_back(l));
+        Ok(self)
+    }
+}
+
+fn cross(o: Vector2<Dec>, a: Vector2<Dec>, b: Vector2<Dec>) -> Dec {
+    (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)
+}
+
+/// Counterclockwise hull without points in the middle of edges
+fn convex_hull(mut points: Vec<Vector2<Dec>>) -> Vec<Vector2<Dec>> {
+    points.sort_by_key(|p| (p.x, p.y));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+    let half = |points: &mut dyn Iterator<Item = &Vector2<Dec>>| {
+        let mut chain: Vec<Vector2<Dec>> = Vec::new();
+        for p in points {
+            while chain.len() >= 2
+                && cross(chain[chain.len() - 2], chain[chain.len() - 1], *p) <= Dec::zero()
+            {
+                chain.pop();
+            }
+            chain.push(*p);
+        }
+        chain.pop();
+        chain
+    };
+    let mut hull = half(&mut points.iter());
+    hull.extend(half(&mut points.iter().rev()));
+    hull
+}
+
+/// Closed line around counterclockwise `hull` at `margin` from it. Corners are arcs of
+/// `radius`, each made of cubic pieces not longer, than a quarter of circle, edges are
+/// straight lines.
+fn rounded_offset(hull: &[Vector2<Dec>], margin: Dec, radius: Dec) -> Vec<Vec<Vector2<Dec>>> {
+    let normal = |a: Vector2<Dec>, b: Vector2<Dec>| {
+        let d = b - a;
+        Vector2::new(d.y, -d.x) / d.magnitude()
+    };
+    let rotate = |v: Vector2<Dec>, a: Dec| {
+        Vector2::new(v.x * a.cos() - v.y * a.sin(), v.x * a.sin() + v.y * a.cos())
+    };
+    let tangent = |v: Vector2<Dec>| Vector2::new(-v.y, v.x);
+
+    // Sharp corner at `margin - radius` from hull is the center of rounded one
+    let miter = margin - radius;
+    let corners = hull
+        .iter()
+        .circular_tuple_windows()
+        .map(|(prev, at, next)| {
+            let n_in = normal(*prev, *at);
+            let n_out = normal(*at, *next);
+            let center = at + (n_in + n_out) * (miter / (Dec::from(1) + n_in.dot(&n_out)));
+            (center, n_in, n_out)
+        })
+        .collect_vec();
+
+    let mut lines = Vec::new();
+    for ((center, n_in, n_out), (next_center, _, _)) in corners.iter().circular_tuple_windows() {
+        if !radius.is_zero() {
+            let angle = n_in.dot(n_out).min(Dec::from(1)).max(Dec::from(-1)).acos();
+            let pieces = if angle > Dec::pi() / Dec::from(2) {
+                2
+            } else {
+                1
+            };
+            let step = angle / Dec::from(pieces);
+            let quarter = step / Dec::from(4);
+            let handle = radius * Dec::from(4) / Dec::from(3) * quarter.sin() / quarter.cos();
+            for piece in 0..pieces {
+                let from = rotate(*n_in, step * Dec::from(piece));
+                let to = rotate(*n_in, step * Dec::from(piece + 1));
+                lines.push(vec![
+                    center + from * radius,
+                    center + from * radius + tangent(from) * handle,
+                    center + to * radius - tangent(to) * handle,
+                    center + to * radius,
+                ]);
+            }
+        }
+        lines.push(vec![center + n_out * radius, next_center + n_out * radius]);
+    }
+    lines
+}
+
+/// Rotates closed `lines`, so they start at the point of straight edge, which is the
+/// closest to `point`
+fn start_at(lines: &mut Vec<Vec<Vector2<Dec>>>, point: Vector2<Dec>) {
+    let closest = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.len() == 2)
+        .map(|(ix, line)| {
+            let (a, b) = (line[0], line[1]);
+            let d = b - a;
+            let t = ((point - a).dot(&d) / d.magnitude_squared())
+                .max(Dec::zero())
+                .min(Dec::from(1));
+            (ix, t, (a + d * t - point).magnitude())
+        })
+        .min_by_key(|(_, _, distance)| *distance);
+    let Some((ix, t, _)) = closest else {
+        return;
+    };
+    if t.is_zero() {
+        lines.rotate_left(ix);
+    } else if t == Dec::from(1) {
+        lines.rotate_left(ix + 1);
+    } else {
+        let (a, b) = (lines[ix][0], lines[ix][1]);
+        let split = a + (b - a) * t;
+        lines[ix] = vec![split, b];
+        lines.insert(ix, vec![a, split]);
+        lines.rotate_left(ix + 1);
+    }
+}
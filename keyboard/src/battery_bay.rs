@@ -0,0 +1,233 @@
+use std::rc::Rc;
+
+use anyhow::anyhow;
+use geometry::{
+    decimal::Dec,
+    geometry::GeometryDyn,
+    indexes::geo_index::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId},
+    origin::Origin,
+    shapes::Rect,
+};
+use nalgebra::Vector3;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::keyboard_config::RightKeyboardConfig;
+
+/// How battery is held in the bay
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum BatteryRetention {
+    /// Two straps of `width` go around the cell through slots in the floor along long
+    /// sides of the bay
+    Straps { width: Dec },
+    /// Lid of `thickness` slides into grooves at the top of long walls through the
+    /// opening in +x end wall. See `battery_lid`.
+    SlidingLid { thickness: Dec },
+}
+
+/// Bay for pouch cell on top of the bottom plate. Origin is in the middle of the bay on
+/// the top surface of the plate, z axis looks up into the case, x axis goes along the
+/// cell. Cell lies in the pocket with foam around it, frame around the pocket is fused
+/// with the plate. Optional wire channel goes from the bay towards the first controller
+/// tray.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatteryBay {
+    pub(crate) origin: Origin,
+    pub(crate) length: Dec,
+    pub(crate) width: Dec,
+    pub(crate) thickness: Dec,
+    pub(crate) foam: Dec,
+    pub(crate) retention: BatteryRetention,
+    pub(crate) wire_channel: Option<Dec>,
+    pub(crate) clearance: Dec,
+}
+
+impl BatteryBay {
+    /// Bay for `length` x `width` x `thickness` cell, like 40 x 30 x 5mm of 603040, held
+    /// with 10mm straps
+    pub fn new(
+        origin: Origin,
+        length: impl Into<Dec>,
+        width: impl Into<Dec>,
+        thickness: impl Into<Dec>,
+    ) -> Self {
+        Self {
+            origin,
+            length: length.into(),
+            width: width.into(),
+            thickness: thickness.into(),
+            foam: 1.into(),
+            retention: BatteryRetention::Straps { width: 10.into() },
+            wire_channel: None,
+            clearance: dec!(0.2).into(),
+        }
+    }
+
+    /// Gap for foam tape on each side and on top of the cell. Pouch cells swell, so it
+    /// should not be zero.
+    pub fn foam(mut self, foam: impl Into<Dec>) -> Self {
+        self.foam = foam.into();
+        self
+    }
+
+    pub fn retention(mut self, retention: BatteryRetention) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Groove of `diameter` from the bay to the first controller tray, if there is one.
+    /// It is cut half into the plate, so diameter should be less than twice the bottom
+    /// thickness.
+    pub fn wire_channel(mut self, diameter: impl Into<Dec>) -> Self {
+        self.wire_channel = Some(diameter.into());
+        self
+    }
+
+    /// Gap between lid and its grooves
+    pub fn clearance(mut self, clearance: impl Into<Dec>) -> Self {
+        self.clearance = clearance.into();
+        self
+    }
+
+    fn wall(&self) -> Dec {
+        dec!(1.6).into()
+    }
+
+    /// How deep lid grooves go into long walls
+    fn groove(&self) -> Dec {
+        dec!(0.8).into()
+    }
+
+    /// Lip over lid grooves
+    fn lip(&self) -> Dec {
+        dec!(1.2).into()
+    }
+
+    fn pocket(&self) -> (Dec, Dec, Dec) {
+        (
+            self.length + self.foam * 2,
+            self.width + self.foam * 2,
+            self.thickness + self.foam,
+        )
+    }
+
+    fn height(&self) -> Dec {
+        let (_, _, depth) = self.pocket();
+        match self.retention {
+            BatteryRetention::Straps { .. } => depth,
+            BatteryRetention::SlidingLid { thickness } => {
+                depth + thickness + self.clearance + self.lip()
+            }
+        }
+    }
+
+    /// Frame around the pocket. It starts a bit under the top surface of the plate, so it
+    /// fuses with it.
+    pub(crate) fn material(&self) -> Rc<dyn GeometryDyn> {
+        let (length, width, _) = self.pocket();
+        let sink = Dec::from(dec!(0.5));
+        Rc::new(Rect::with_bottom_at(
+            self.origin.clone().offset_z(-sink),
+            length + self.wall() * 2,
+            width + self.wall() * 2,
+            self.height() + sink,
+        ))
+    }
+
+    /// Pocket, strap slots or lid grooves, and wire channel to `mcu`, which is origin of
+    /// controller tray
+    pub(crate) fn holes(
+        &self,
+        bottom_thickness: Dec,
+        mcu: Option<&Origin>,
+    ) -> Vec<Rc<dyn GeometryDyn>> {
+        let sw = Dec::from(dec!(0.1));
+        let (length, width, depth) = self.pocket();
+        let mut holes: Vec<Rc<dyn GeometryDyn>> = vec![Rc::new(Rect::with_bottom_at(
+            self.origin.clone(),
+            length,
+            width,
+            self.height() + sw,
+        ))];
+        match self.retention {
+            BatteryRetention::Straps { width: strap } => {
+                let slot = Dec::from(2);
+                for x in [-length / 4, length / 4] {
+                    for y in [-(width - slot) / 2, (width - slot) / 2] {
+                        holes.push(Rc::new(Rect::with_top_at(
+                            self.origin.clone().offset_x(x).offset_y(y).offset_z(sw),
+                            strap + self.clearance * 2,
+                            slot,
+                            bottom_thickness + sw * 2,
+                        )));
+                    }
+                }
+            }
+            BatteryRetention::SlidingLid { thickness } => {
+                // Groove goes through +x end wall, lid slides in from there
+                holes.push(Rc::new(Rect::with_bottom_at(
+                    self.origin
+                        .clone()
+                        .offset_x(self.wall() / 2 + sw)
+                        .offset_z(depth),
+                    length + self.wall() + sw * 2,
+                    width + self.groove() * 2,
+                    thickness + self.clearance,
+                )));
+            }
+        }
+        if let (Some(diameter), Some(mcu)) = (self.wire_channel, mcu) {
+            let to = self.origin.project(mcu.center) - self.origin.center;
+            let (x, y) = (to.dot(&self.origin.x()), to.dot(&self.origin.y()));
+            let run = to.magnitude();
+            let channel = self
+                .origin
+                .clone()
+                .rotate_axisangle(Vector3::z() * y.atan2(&x));
+            holes.push(Rc::new(Rect::with_bottom_at(
+                channel.offset_x(run / 2).offset_z(-diameter / 2),
+                run,
+                diameter,
+                diameter,
+            )));
+        }
+        holes
+    }
+
+    /// Lid, which slides into the grooves, in keyboard coordinates
+    fn lid(&self, thickness: Dec) -> Rect {
+        let (length, width, depth) = self.pocket();
+        Rect::with_bottom_at(
+            self.origin
+                .clone()
+                .offset_x(self.wall() / 2)
+                .offset_z(depth + self.clearance / 2),
+            length + self.wall(),
+            width + (self.groove() - self.clearance) * 2,
+            thickness,
+        )
+    }
+}
+
+impl RightKeyboardConfig {
+    /// Adds battery bay to bottom plate, see `BatteryBay`
+    pub fn add_battery_bay(mut self, bay: BatteryBay) -> Self {
+        self.battery_bay = Some(bay);
+        self
+    }
+
+    /// Sliding lid of battery bay. It is placed in keyboard coordinates, so it can be
+    /// checked in place.
+    pub fn battery_lid(&self, index: &mut GeoIndex) -> anyhow::Result<MeshId> {
+        let bay = self
+            .battery_bay
+            .as_ref()
+            .ok_or(anyhow!("Battery bay is not configured"))?;
+        let BatteryRetention::SlidingLid { thickness } = bay.retention else {
+            return Err(anyhow!("Battery bay has no sliding lid"));
+        };
+        let lid = index.new_mesh();
+        bay.lid(thickness).polygonize(lid.make_mut_ref(index), 0)?;
+        Ok(lid)
+    }
+}
@@ -1,9 +1,10 @@
-use geometry::decimal::Dec;
+use geometry::{decimal::Dec, origin::Origin, shapes::HelicalThread};
 use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
 
 use crate::bolt_builder::BoltBuilder;
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Bolt {
     pub(crate) head_diameter: Dec,
     pub(crate) diameter: Dec,
@@ -12,15 +13,78 @@ pub struct Bolt {
     pub(crate) height: Dec,
     pub(crate) thread_inner_diameter: Option<Dec>,
     pub(crate) nut: Option<Nut>,
+    pub(crate) thread: Option<Thread>,
 }
 
 impl Bolt {
     pub fn build() -> BoltBuilder {
         BoltBuilder::default()
     }
+
+    pub fn diameter(&self) -> Dec {
+        self.diameter
+    }
+
+    pub fn head_diameter(&self) -> Dec {
+        self.head_diameter
+    }
+
+    pub fn head_height(&self) -> Dec {
+        self.head_height
+    }
+
+    /// Threaded part of bolt itself, for printing bolts. Thread goes down from origin
+    /// and is thinner by thread tolerance.
+    pub fn thread_geometry(&self, origin: Origin) -> Option<HelicalThread> {
+        self.thread.as_ref().map(|thread| {
+            thread.geometry(origin, self.height, self.diameter / 2 - thread.tolerance)
+        })
+    }
+}
+
+/// Helical thread of bolt. When bolt has no nut, thread is cut right in the plastic
+/// instead of plain hole.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Thread {
+    pub(crate) pitch: Dec,
+    pub(crate) depth: Dec,
+    pub(crate) starts: usize,
+    pub(crate) tolerance: Dec,
+}
+
+impl Thread {
+    pub fn new(pitch: impl Into<Dec>, depth: impl Into<Dec>) -> Self {
+        Self {
+            pitch: pitch.into(),
+            depth: depth.into(),
+            starts: 1,
+            tolerance: dec!(0.15).into(),
+        }
+    }
+
+    /// Coarse metric thread for M2
+    pub fn m2() -> Self {
+        Self::new(dec!(0.4), dec!(0.25))
+    }
+
+    pub fn starts(mut self, starts: usize) -> Self {
+        self.starts = starts.max(1);
+        self
+    }
+
+    /// Radial gap between bolt and printed thread. Hole becomes wider by this value,
+    /// printed bolt becomes thinner.
+    pub fn tolerance(mut self, tolerance: impl Into<Dec>) -> Self {
+        self.tolerance = tolerance.into();
+        self
+    }
+
+    pub(crate) fn geometry(&self, top: Origin, height: Dec, radius: Dec) -> HelicalThread {
+        HelicalThread::with_top_at(top, height, radius, self.pitch, self.depth).starts(self.starts)
+    }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Nut {
     Hex { outer_diameter: Dec, height: Dec },
 }
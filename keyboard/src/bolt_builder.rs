@@ -1,10 +1,14 @@
 use geometry::decimal::Dec;
 
-use crate::{bolt::Nut, Bolt};
+use crate::{
+    bolt::{Nut, Thread},
+    Bolt,
+};
 
 #[derive(Default)]
 pub struct BoltBuilder {
     nut: Option<Nut>,
+    thread: Option<Thread>,
     diameter: Option<Dec>,
     thread_inner_diameter: Option<Dec>,
     height: Option<Dec>,
@@ -53,6 +57,12 @@ impl BoltBuilder {
         self
     }
 
+    /// Real thread geometry instead of plain cylinder for thread hole
+    pub fn thread(mut self, thread: Thread) -> Self {
+        self.thread = Some(thread);
+        self
+    }
+
     pub fn no_nut(mut self) -> Self {
         self.nut = None;
         self
@@ -65,6 +75,7 @@ impl BoltBuilder {
             height: self.height.expect("Bolt height is not specified"),
             thread_inner_diameter: self.thread_inner_diameter,
             nut: self.nut,
+            thread: self.thread,
         }
     }
 }
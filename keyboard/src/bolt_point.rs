@@ -1,9 +1,64 @@
-use geometry::{decimal::Dec, geometry::GeometryDyn, origin::Origin, shapes::Cylinder};
+use std::rc::Rc;
+
+use geometry::{
+    decimal::Dec,
+    geometry::GeometryDyn,
+    origin::Origin,
+    shapes::{Cone, Cylinder},
+};
+use nalgebra::Vector3;
 use num_traits::{One, Zero};
 use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::{bolt::Bolt, surface_quality::SurfaceQuality};
+
+/// Brass insert, which is melted into the thread part instead of hex nut. Thread part
+/// gets a boss with bore for the insert and a cone lead-in at its top, which centers
+/// the insert and takes molten plastic, pushed out of the bore.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct HeatSetInsert {
+    pub(crate) diameter: Dec,
+    pub(crate) length: Dec,
+    pub(crate) wall: Dec,
+    pub(crate) lead_in: Dec,
+}
+
+impl HeatSetInsert {
+    /// Insert, which goes into bore of `diameter`
+    pub fn new(diameter: impl Into<Dec>, length: impl Into<Dec>) -> Self {
+        Self {
+            diameter: diameter.into(),
+            length: length.into(),
+            wall: dec!(1.6).into(),
+            lead_in: dec!(0.5).into(),
+        }
+    }
+
+    /// Common M2 x 4mm insert
+    pub fn m2() -> Self {
+        Self::new(dec!(3.2), 4)
+    }
 
-use crate::bolt::Bolt;
+    /// Common M3 x 5.7mm insert
+    pub fn m3() -> Self {
+        Self::new(4, dec!(5.7))
+    }
 
+    /// Material around the bore
+    pub fn wall(mut self, wall: impl Into<Dec>) -> Self {
+        self.wall = wall.into();
+        self
+    }
+
+    /// How much wider lead-in is at its top, than the bore
+    pub fn lead_in(mut self, lead_in: impl Into<Dec>) -> Self {
+        self.lead_in = lead_in.into();
+        self
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct BoltPoint {
     pub(crate) origin: Origin,
     /// amount of material between head bottom surface and empty space
@@ -21,6 +76,12 @@ pub struct BoltPoint {
     pub(crate) radial_head_hole_extention: Dec,
 
     pub(crate) thread_hole_radius_plastic_modification: Dec,
+
+    /// Thread part holds heat-set insert instead of nut or thread in plastic
+    #[serde(default)]
+    pub(crate) insert: Option<HeatSetInsert>,
+
+    pub(crate) quality: SurfaceQuality,
 }
 
 // const INNER_THREAD_MULTIPLIER: rust_decimal::Decimal = dec!(1.25);
@@ -36,6 +97,8 @@ impl BoltPoint {
             radial_head_hole_extention: dec!(0.5).into(),
             thread_down_extension: 30.into(),
             thread_hole_radius_plastic_modification: Dec::from(1.5),
+            insert: None,
+            quality: SurfaceQuality::default(),
         }
     }
 
@@ -64,6 +127,13 @@ impl BoltPoint {
         self
     }
 
+    /// Boss with heat-set insert in thread part instead of nut pocket. Bolt gets plain
+    /// hole below the insert.
+    pub fn heat_set_insert(mut self, insert: HeatSetInsert) -> Self {
+        self.insert = Some(insert);
+        self
+    }
+
     ///  Space between origin point and position of nut and bolt is tighten
     fn nut_material_gap(&self) -> Dec {
         if let Some(nut) = self.bolt.nut.as_ref() {
@@ -96,7 +166,7 @@ impl BoltPoint {
         self.head_hole_radius() + self.radial_head_material_extention
     }
 
-    fn head_hole_radius(&self) -> Dec {
+    pub(crate) fn head_hole_radius(&self) -> Dec {
         (self.bolt.head_diameter / Dec::from(2)) + self.radial_head_hole_extention
     }
 
@@ -105,7 +175,9 @@ impl BoltPoint {
     }
 
     fn material_radius(&self) -> Dec {
-        let tail_radius = if let Some(nut) = self.bolt.nut.as_ref() {
+        let tail_radius = if let Some(insert) = self.insert {
+            insert.diameter / 2 + insert.wall
+        } else if let Some(nut) = self.bolt.nut.as_ref() {
             match nut {
                 crate::bolt::Nut::Hex { outer_diameter, .. } => {
                     *outer_diameter * Dec::from(dec!(1.1)) / 2
@@ -136,44 +208,71 @@ impl BoltPoint {
         radius * self.thread_hole_radius_plastic_modification
     }
 
+    /// Point is in material around the bolt, before it is cut by the case
+    pub(crate) fn in_material(&self, point: Vector3<Dec>) -> bool {
+        let axis = self.origin.z();
+        let d = point - self.origin.center;
+        let along = d.dot(&axis);
+        let radial = (d - axis * along).magnitude();
+        radial <= self.material_radius() + Dec::from(dec!(0.01))
+            && along <= self.bolt.head_height + self.head_up_extension
+            && along >= -self.bolt.height - self.thread_down_extension
+    }
+
+    pub(crate) fn quality(mut self, quality: SurfaceQuality) -> Self {
+        self.quality = quality;
+        self
+    }
+
     pub(crate) fn get_head_material(&self) -> impl GeometryDyn {
-        Cylinder::with_bottom_at(
-            self.origin.clone(),
-            self.bolt.head_height + self.head_up_extension,
-            self.material_radius(),
+        self.quality.cylinder(
+            Cylinder::with_bottom_at(
+                self.origin.clone(),
+                self.bolt.head_height + self.head_up_extension,
+                self.material_radius(),
+            )
+            .top_cap(false),
         )
-        .top_cap(false)
     }
 
     pub(crate) fn get_tail_material(&self) -> impl GeometryDyn {
-        Cylinder::with_top_at(
-            self.origin.clone(),
-            self.bolt.height + self.thread_down_extension,
-            self.material_radius(),
+        self.quality.cylinder(
+            Cylinder::with_top_at(
+                self.origin.clone(),
+                self.bolt.height + self.thread_down_extension,
+                self.material_radius(),
+            )
+            .bottom_cap(false),
         )
-        .bottom_cap(false)
     }
 
     pub(crate) fn get_head_hole(&self) -> impl GeometryDyn + Sized {
-        Cylinder::with_bottom_at(
-            self.origin.clone().offset_z(self.head_thread_material_gap),
-            self.bolt.head_height + self.head_up_extension,
-            self.head_hole_radius(),
+        self.quality.cylinder(
+            Cylinder::with_bottom_at(
+                self.origin.clone().offset_z(self.head_thread_material_gap),
+                self.bolt.head_height + self.head_up_extension,
+                self.head_hole_radius(),
+            )
+            .top_cap(false),
         )
-        .top_cap(false)
     }
 
     pub(crate) fn get_head_thread_hole(&self) -> impl GeometryDyn + Sized {
-        Cylinder::with_bottom_at(
-            self.origin.clone().offset_z(-dec!(0.1)),
-            self.head_up_extension,
-            self.head_thread_hole_radius(),
+        self.quality.cylinder(
+            Cylinder::with_bottom_at(
+                self.origin.clone().offset_z(-dec!(0.1)),
+                self.head_up_extension,
+                self.head_thread_hole_radius(),
+            )
+            .top_cap(false)
+            .bottom_cap(false),
         )
-        .top_cap(false)
-        .bottom_cap(false)
     }
 
     pub(crate) fn get_tail_nut_hole(&self) -> Option<impl GeometryDyn> {
+        if self.insert.is_some() {
+            return None;
+        }
         self.bolt.nut.as_ref().map(|nut| match nut {
             crate::bolt::Nut::Hex { outer_diameter, .. } => Cylinder::with_top_at(
                 self.origin.clone().offset_z(-self.nut_material_gap()),
@@ -185,19 +284,62 @@ impl BoltPoint {
         })
     }
 
-    pub(crate) fn get_tail_thread_hole(&self) -> impl GeometryDyn {
+    /// Bore for heat-set insert with lead-in, if bolt point has one
+    pub(crate) fn get_tail_insert_holes(&self) -> Vec<Rc<dyn GeometryDyn>> {
+        let sw = Dec::from(dec!(0.1));
+        let Some(insert) = self.insert else {
+            return Vec::new();
+        };
+        let radius = insert.diameter / 2;
+        let top = self.origin.clone().offset_z(sw);
+        vec![
+            Rc::new(self.quality.cylinder(Cylinder::with_top_at(
+                top.clone(),
+                insert.length + sw,
+                radius,
+            ))),
+            Rc::new(self.quality.cone(Cone::with_top_at(
+                top,
+                insert.lead_in + sw,
+                radius,
+                radius + insert.lead_in + sw,
+            ))),
+        ]
+    }
+
+    pub(crate) fn get_tail_thread_hole(&self) -> Rc<dyn GeometryDyn> {
+        let sw = Dec::from(dec!(0.1));
+        if self.insert.is_some() {
+            // Bolt goes through the insert, its end needs only a plain hole
+            return Rc::new(self.quality.cylinder(Cylinder::with_top_at(
+                self.origin.clone().offset_z(sw),
+                self.bolt_rest_height() + Dec::one(),
+                self.head_thread_hole_radius(),
+            )));
+        }
+        if let (None, Some(thread)) = (self.bolt.nut.as_ref(), self.bolt.thread.as_ref()) {
+            return Rc::new(thread.geometry(
+                self.origin.clone().offset_z(sw),
+                self.bolt_rest_height() - sw,
+                self.bolt.diameter / 2 + thread.tolerance,
+            ));
+        }
+
         let cut_addition = if self.bolt.nut.is_some() {
             Dec::one()
         } else {
             Dec::zero()
         };
 
-        let sw = Dec::from(dec!(0.1));
-        Cylinder::with_top_at(
-            self.origin.clone().offset_z(sw),
-            self.bolt_rest_height() - sw + cut_addition,
-            self.tail_thread_hole_radius(),
+        Rc::new(
+            self.quality.cylinder(
+                Cylinder::with_top_at(
+                    self.origin.clone().offset_z(sw),
+                    self.bolt_rest_height() - sw + cut_addition,
+                    self.tail_thread_hole_radius(),
+                )
+                .top_cap(false),
+            ),
         )
-        .top_cap(false)
     }
 }
@@ -0,0 +1,45 @@
+use geometry::decimal::Dec;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Flexible (TPU) ring around the case. Its profile is U-channel, open to the case:
+/// lower flange goes under the bottom plate, upper flange snaps into a groove, cut in
+/// the outside of hull walls at `height` above the table. Everything follows the table
+/// outline, shifted outside of walls.
+/// `lip + clearance` must be less, than wall thickness, so groove does not cut through.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Bumper {
+    pub(crate) height: Dec,
+    pub(crate) thickness: Dec,
+    pub(crate) lip: Dec,
+    pub(crate) clearance: Dec,
+}
+
+impl Bumper {
+    pub fn new(height: impl Into<Dec>) -> Self {
+        Self {
+            height: height.into(),
+            thickness: dec!(1.6).into(),
+            lip: dec!(1).into(),
+            clearance: dec!(0.2).into(),
+        }
+    }
+
+    /// Thickness of bumper side and both flanges
+    pub fn thickness(mut self, thickness: impl Into<Dec>) -> Self {
+        self.thickness = thickness.into();
+        self
+    }
+
+    /// How far flanges go inside from the outer face of walls
+    pub fn lip(mut self, lip: impl Into<Dec>) -> Self {
+        self.lip = lip.into();
+        self
+    }
+
+    /// Gap between bumper and case on every side
+    pub fn clearance(mut self, clearance: impl Into<Dec>) -> Self {
+        self.clearance = clearance.into();
+        self
+    }
+}
@@ -1,12 +1,21 @@
+use anyhow::anyhow;
 use geometry::{
     decimal::Dec,
     indexes::geo_index::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId},
     origin::Origin,
 };
-use nalgebra::Vector3;
+use itertools::Itertools;
+use nalgebra::{Vector2, Vector3};
 use rust_decimal_macros::dec;
-
-use crate::{button_builder::ButtonBuilder, chok_hotswap::ChokHotswap};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    button_builder::ButtonBuilder,
+    chok_hotswap::ChokHotswap,
+    encoder_mount::{EncoderModel, EncoderMount},
+    keycap::KeycapProfile,
+    mx_hotswap::MxHotswap,
+};
 
 #[derive(Clone, Debug, Default)]
 #[allow(unused)]
@@ -20,22 +29,61 @@ pub(crate) struct ButtonMount {
     pub(crate) around_button_padding: Dec,
 }
 
-#[derive(Clone, Debug, Copy)]
+/// Space around switch: hotswap socket with switch bottom below the plate and keycap
+/// above it
+#[derive(Clone, Debug)]
+pub(crate) struct SwitchEnvelope {
+    pub(crate) pocket_width: Dec,
+    pub(crate) pocket_height: Dec,
+    /// Down from the bottom of the plate
+    pub(crate) pocket_depth: Dec,
+    /// Up from the top of the plate, with pressed key
+    pub(crate) keycap_height: Dec,
+}
+
+/// Bore for backlight LED or light pipe under the switch. It goes from the top of the
+/// plate through the space under it, at `offset` from the switch center: MX switches
+/// have LED window at 5.08mm to the top.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct LedBore {
+    pub diameter: Dec,
+    pub offset: Vector2<Dec>,
+}
+
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ButtonMountKind {
     Chok,
-    Cherry,
+    /// Kailh Choc V2: cutout of Choc V1, but switch takes MX keycaps, which are higher
+    ChocV2,
+    /// Gateron low-profile switches: 14x14 cutout in 1.2mm plate
+    GateronLowProfile,
+    /// Cherry MX and compatible switches: 14x14 cutout, which holds switch by 1.5mm of
+    /// plate. With `clip_notches` cutout gets wider under these 1.5mm, so clips catch in
+    /// plate of any thickness; without them cutout goes straight through, so plate
+    /// should be 1.5mm thick.
+    Mx {
+        clip_notches: bool,
+    },
     Placeholder,
     ChokHotswapCustom,
+    MxHotswapCustom,
+    /// Rotary encoder instead of a switch, see `EncoderMount`
+    Encoder {
+        model: EncoderModel,
+        d_flat: bool,
+    },
 }
 
 impl ButtonMountKind {
     pub(crate) fn button_width(&self) -> Dec {
         match self {
-            ButtonMountKind::Chok => {
+            ButtonMountKind::Chok
+            | ButtonMountKind::ChocV2
+            | ButtonMountKind::GateronLowProfile
+            | ButtonMountKind::Mx { .. } => {
                 let ps = self.params();
                 ps.width + ps.around_button_padding
             }
-            ButtonMountKind::Cherry => todo!("Width cherry"),
             ButtonMountKind::Placeholder => {
                 let ps = self.params();
                 ps.width
@@ -44,16 +92,22 @@ impl ButtonMountKind {
                 let a = ChokHotswap::new();
                 a.width()
             }
+            ButtonMountKind::MxHotswapCustom => MxHotswap::new().width(),
+            ButtonMountKind::Encoder { model, d_flat } => {
+                EncoderMount::new(*model, *d_flat).width()
+            }
         }
     }
 
     pub(crate) fn button_height(&self) -> Dec {
         match self {
-            ButtonMountKind::Chok => {
+            ButtonMountKind::Chok
+            | ButtonMountKind::ChocV2
+            | ButtonMountKind::GateronLowProfile
+            | ButtonMountKind::Mx { .. } => {
                 let ps = self.params();
                 ps.height + ps.around_button_padding
             }
-            ButtonMountKind::Cherry => todo!("Width cherry"),
             ButtonMountKind::Placeholder => {
                 let ps = self.params();
                 ps.height
@@ -62,6 +116,10 @@ impl ButtonMountKind {
                 let a = ChokHotswap::new();
                 a.height()
             }
+            ButtonMountKind::MxHotswapCustom => MxHotswap::new().height(),
+            ButtonMountKind::Encoder { model, d_flat } => {
+                EncoderMount::new(*model, *d_flat).height()
+            }
         }
     }
 }
@@ -69,7 +127,7 @@ impl ButtonMountKind {
 impl ButtonMountKind {
     pub(crate) fn params(&self) -> ButtonMount {
         match self {
-            ButtonMountKind::Chok => ButtonMount {
+            ButtonMountKind::Chok | ButtonMountKind::ChocV2 => ButtonMount {
                 width: dec!(18).into(),
                 height: dec!(18).into(),
                 lock_width: dec!(13.8).into(),
@@ -78,18 +136,58 @@ impl ButtonMountKind {
                 lock_inner_padding: 1.into(),
                 around_button_padding: 1.into(),
             },
+            ButtonMountKind::GateronLowProfile => ButtonMount {
+                width: dec!(18).into(),
+                height: dec!(18).into(),
+                lock_width: 14.into(),
+                lock_height: 14.into(),
+                lock_depth: dec!(1.2).into(),
+                lock_inner_padding: 1.into(),
+                around_button_padding: 1.into(),
+            },
             ButtonMountKind::Placeholder => ButtonMount {
                 width: dec!(18).into(),
                 height: dec!(18).into(),
                 ..Default::default()
             },
-            ButtonMountKind::Cherry => todo!(),
-            ButtonMountKind::ChokHotswapCustom => unreachable!(),
+            ButtonMountKind::Mx { .. } => ButtonMount {
+                width: dec!(18.05).into(),
+                height: dec!(18.05).into(),
+                lock_width: 14.into(),
+                lock_height: 14.into(),
+                lock_depth: dec!(1.5).into(),
+                lock_inner_padding: 1.into(),
+                around_button_padding: 1.into(),
+            },
+            ButtonMountKind::ChokHotswapCustom
+            | ButtonMountKind::MxHotswapCustom
+            | ButtonMountKind::Encoder { .. } => unreachable!(),
+        }
+    }
+
+    /// Placeholder has no switch, but space above it is kept as for a Choc keycap
+    pub(crate) fn envelope(&self) -> SwitchEnvelope {
+        let (pocket, pocket_depth, keycap_height) = match self {
+            ButtonMountKind::Chok | ButtonMountKind::ChokHotswapCustom => (15, dec!(5), dec!(8)),
+            ButtonMountKind::ChocV2 => (15, dec!(5), dec!(9.5)),
+            ButtonMountKind::GateronLowProfile => (15, dec!(4.5), dec!(7.5)),
+            ButtonMountKind::Mx { .. } | ButtonMountKind::MxHotswapCustom => {
+                (16, dec!(8), dec!(12))
+            }
+            ButtonMountKind::Placeholder => (0, dec!(0), dec!(8)),
+            // Knob instead of keycap
+            ButtonMountKind::Encoder { .. } => (15, dec!(8), dec!(20)),
+        };
+        SwitchEnvelope {
+            pocket_width: pocket.into(),
+            pocket_height: pocket.into(),
+            pocket_depth: pocket_depth.into(),
+            keycap_height: keycap_height.into(),
         }
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Button {
     pub origin: Origin,
     pub(super) kind: ButtonMountKind,
@@ -103,6 +201,12 @@ pub struct Button {
     pub(crate) inner_right_bottom_edge: Vector3<Dec>,
     pub(crate) inner_left_top_edge: Vector3<Dec>,
     pub(crate) inner_left_bottom_edge: Vector3<Dec>,
+
+    #[serde(default)]
+    pub(crate) keycap: Option<KeycapProfile>,
+
+    #[serde(default)]
+    pub(crate) led_bore: Option<LedBore>,
 }
 
 impl Button {
@@ -113,10 +217,30 @@ impl Button {
         ButtonBuilder::chok_hotswap_custom()
     }
 
+    pub fn mx_hotswap_custom() -> ButtonBuilder {
+        ButtonBuilder::mx_hotswap_custom()
+    }
+
     pub fn placeholder() -> ButtonBuilder {
         ButtonBuilder::placeholder()
     }
 
+    pub fn mx(clip_notches: bool) -> ButtonBuilder {
+        ButtonBuilder::mx(clip_notches)
+    }
+
+    pub fn choc_v2() -> ButtonBuilder {
+        ButtonBuilder::choc_v2()
+    }
+
+    pub fn gateron_low_profile() -> ButtonBuilder {
+        ButtonBuilder::gateron_low_profile()
+    }
+
+    pub fn encoder(model: EncoderModel, d_flat: bool) -> ButtonBuilder {
+        ButtonBuilder::encoder(model, d_flat)
+    }
+
     pub(crate) fn inner_left_bottom(&self, thickness: Dec) -> Vector3<Dec> {
         let w = self.kind.button_width();
         let h = self.kind.button_height();
@@ -189,6 +313,42 @@ impl Button {
         right + top + up + self.origin.center
     }
 
+    pub(crate) fn lerp(&self, other: &Button, t: Dec) -> anyhow::Result<Button> {
+        if self.kind != other.kind {
+            return Err(anyhow!(
+                "Cannot blend buttons of different kinds: {:?} <> {:?}",
+                self.kind,
+                other.kind
+            ));
+        }
+        Ok(Button {
+            origin: self.origin.lerp(&other.origin, t),
+            kind: self.kind,
+            keycap: self.keycap,
+            led_bore: self.led_bore,
+            outer_right_top_edge: self
+                .outer_right_top_edge
+                .lerp(&other.outer_right_top_edge, t),
+            outer_right_bottom_edge: self
+                .outer_right_bottom_edge
+                .lerp(&other.outer_right_bottom_edge, t),
+            outer_left_top_edge: self.outer_left_top_edge.lerp(&other.outer_left_top_edge, t),
+            outer_left_bottom_edge: self
+                .outer_left_bottom_edge
+                .lerp(&other.outer_left_bottom_edge, t),
+            inner_right_top_edge: self
+                .inner_right_top_edge
+                .lerp(&other.inner_right_top_edge, t),
+            inner_right_bottom_edge: self
+                .inner_right_bottom_edge
+                .lerp(&other.inner_right_bottom_edge, t),
+            inner_left_top_edge: self.inner_left_top_edge.lerp(&other.inner_left_top_edge, t),
+            inner_left_bottom_edge: self
+                .inner_left_bottom_edge
+                .lerp(&other.inner_left_bottom_edge, t),
+        })
+    }
+
     pub fn pt(&self, v: Vector3<Dec>) -> Vector3<Dec> {
         self.origin.center + self.origin.x() * v.x + self.origin.y() * v.y + self.origin.z() * v.z
     }
@@ -215,7 +375,10 @@ impl Button {
                 mesh.add_polygon(&bottom)?;
                 Ok(mesh_id)
             }
-            ButtonMountKind::Chok => {
+            ButtonMountKind::Chok
+            | ButtonMountKind::ChocV2
+            | ButtonMountKind::GateronLowProfile
+            | ButtonMountKind::Mx { clip_notches: true } => {
                 let mesh_id = index.new_mesh();
                 let mut mesh = mesh_id.make_mut_ref(index);
                 let ps = self.kind.params();
@@ -403,12 +566,52 @@ impl Button {
                 Ok(mesh_id)
             }
 
+            ButtonMountKind::Mx {
+                clip_notches: false,
+            } => {
+                let mesh_id = index.new_mesh();
+                let mut mesh = mesh_id.make_mut_ref(index);
+                let ps = self.kind.params();
+                let outer_btn_width = ps.width + ps.around_button_padding;
+                let outer_btn_height = ps.height + ps.around_button_padding;
+                let at = |width: Dec, height: Dec, z: Dec, (x, y): (i64, i64)| {
+                    self.pt(Vector3::new(width / 2 * x, height / 2 * y, z))
+                };
+                let outer = |z, corner| at(outer_btn_width, outer_btn_height, z, corner);
+                let lock = |z, corner| at(ps.lock_width, ps.lock_height, z, corner);
+                let (top, bottom) = (thickness / 2, -thickness / 2);
+
+                // Corners go clockwise, as seen from above
+                let corners = [(1, 1), (1, -1), (-1, -1), (-1, 1)];
+                for (a, b) in corners.into_iter().circular_tuple_windows() {
+                    mesh.add_polygon(&[lock(top, a), lock(top, b), outer(top, b), outer(top, a)])?;
+                    mesh.add_polygon(&[
+                        outer(bottom, a),
+                        outer(bottom, b),
+                        lock(bottom, b),
+                        lock(bottom, a),
+                    ])?;
+                    mesh.add_polygon(&[
+                        lock(top, a),
+                        lock(bottom, a),
+                        lock(bottom, b),
+                        lock(top, b),
+                    ])?;
+                }
+                Ok(mesh_id)
+            }
+
             ButtonMountKind::ChokHotswapCustom => {
                 let mount = ChokHotswap::new();
 
                 mount.outer_mount(self.origin.clone(), index)
             }
-            _ => todo!("Implement mesh for chok and cherry"),
+            ButtonMountKind::MxHotswapCustom => {
+                MxHotswap::new().outer_mount(self.origin.clone(), index)
+            }
+            ButtonMountKind::Encoder { model, d_flat } => {
+                EncoderMount::new(model, d_flat).outer_mount(self.origin.clone(), thickness, index)
+            }
         }
     }
 }
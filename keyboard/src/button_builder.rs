@@ -1,14 +1,23 @@
 use geometry::{decimal::Dec, origin::Origin};
-use nalgebra::Vector3;
+use nalgebra::{Vector2, Vector3};
 use num_traits::{One, Zero};
 
-use crate::{button::Button, Angle, ButtonMountKind};
+use crate::{
+    button::{Button, LedBore},
+    encoder_mount::EncoderModel,
+    keycap::KeycapProfile,
+    Angle, ButtonMountKind,
+};
 
 pub struct ButtonBuilder {
     incline: Angle,
     additional_padding: Dec,
     depth: Dec,
     kind: ButtonMountKind,
+    keycap: Option<KeycapProfile>,
+    led_bore: Option<LedBore>,
+    offset: Vector3<Dec>,
+    rotations: Vec<(Vector3<Dec>, Angle)>,
 
     pub(crate) outer_right_top_edge: Vector3<Dec>,
     pub(crate) outer_right_bottom_edge: Vector3<Dec>,
@@ -27,6 +36,10 @@ impl Default for ButtonBuilder {
             additional_padding: Dec::zero(),
             depth: Default::default(),
             kind: ButtonMountKind::Placeholder,
+            keycap: None,
+            led_bore: None,
+            offset: Vector3::zeros(),
+            rotations: Vec::new(),
             outer_right_top_edge: Vector3::new(One::one(), One::one(), One::one()),
             outer_right_bottom_edge: Vector3::new(One::one(), One::one(), One::one()),
             outer_left_top_edge: Vector3::new(One::one(), One::one(), One::one()),
@@ -40,6 +53,13 @@ impl Default for ButtonBuilder {
 }
 
 impl ButtonBuilder {
+    pub(crate) fn with_kind(kind: ButtonMountKind) -> Self {
+        Self {
+            kind,
+            ..Default::default()
+        }
+    }
+
     pub fn chok() -> Self {
         Self {
             kind: ButtonMountKind::Chok,
@@ -54,6 +74,35 @@ impl ButtonBuilder {
         }
     }
 
+    pub fn mx(clip_notches: bool) -> Self {
+        Self {
+            kind: ButtonMountKind::Mx { clip_notches },
+            ..Default::default()
+        }
+    }
+
+    pub fn choc_v2() -> Self {
+        Self {
+            kind: ButtonMountKind::ChocV2,
+            ..Default::default()
+        }
+    }
+
+    pub fn gateron_low_profile() -> Self {
+        Self {
+            kind: ButtonMountKind::GateronLowProfile,
+            ..Default::default()
+        }
+    }
+
+    /// Rotary encoder in place of a key, see `EncoderMount`
+    pub fn encoder(model: EncoderModel, d_flat: bool) -> Self {
+        Self {
+            kind: ButtonMountKind::Encoder { model, d_flat },
+            ..Default::default()
+        }
+    }
+
     pub(crate) fn chok_hotswap_custom() -> ButtonBuilder {
         Self {
             kind: ButtonMountKind::ChokHotswapCustom,
@@ -61,6 +110,13 @@ impl ButtonBuilder {
         }
     }
 
+    pub(crate) fn mx_hotswap_custom() -> ButtonBuilder {
+        Self {
+            kind: ButtonMountKind::MxHotswapCustom,
+            ..Default::default()
+        }
+    }
+
     pub fn additional_padding(mut self, padding: Dec) -> Self {
         self.additional_padding = padding;
         self
@@ -76,6 +132,32 @@ impl ButtonBuilder {
         self
     }
 
+    /// Moves button from its place in column, along axes of the place
+    pub fn offset(mut self, offset: Vector3<Dec>) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Turns button around its center. Rotations are applied one after another, each
+    /// around axes, already turned by previous ones.
+    pub fn rotate(mut self, axis: Vector3<Dec>, angle: Angle) -> Self {
+        self.rotations.push((axis, angle));
+        self
+    }
+
+    /// Keycap for collision checks, by default it fits the switch
+    pub fn keycap(mut self, keycap: KeycapProfile) -> Self {
+        self.keycap = Some(keycap);
+        self
+    }
+
+    /// Bore for backlight LED of `diameter` at `offset` from the switch center, cut in
+    /// buttons hull
+    pub fn led_bore(mut self, diameter: Dec, offset: Vector2<Dec>) -> Self {
+        self.led_bore = Some(LedBore { diameter, offset });
+        self
+    }
+
     pub fn outer_left_top_edge(mut self, v: Vector3<Dec>) -> Self {
         self.outer_left_top_edge = v;
         self
@@ -118,10 +200,14 @@ impl ButtonBuilder {
 
     pub fn build(self) -> Button {
         let o = Origin::new()
+            .offset(self.offset)
             .offset_y(self.additional_padding)
             .offset_z(-self.depth);
         let x = o.x();
-        let o = o.rotate_axisangle(x * self.incline.rad());
+        let o = self.rotations.iter().fold(
+            o.rotate_axisangle(x * self.incline.rad()),
+            |o, (axis, angle)| o.rotate_axisangle(axis.normalize() * angle.rad()),
+        );
         let Self {
             outer_right_top_edge,
             outer_right_bottom_edge,
@@ -136,6 +222,8 @@ impl ButtonBuilder {
         Button {
             origin: o,
             kind: self.kind,
+            keycap: self.keycap,
+            led_bore: self.led_bore,
             outer_right_top_edge,
             outer_right_bottom_edge,
             outer_left_top_edge,
@@ -1,9 +1,22 @@
+use anyhow::anyhow;
 use geometry::{decimal::Dec, origin::Origin};
+use nalgebra::Vector3;
+use num_traits::Zero;
 
 use crate::{button_collections::ButtonsCollection, buttons_column::ButtonsColumn, Angle};
 
+/// Columns are placed on circle around pivot, one after another,
+/// instead of curvature and padding between columns
+pub(crate) struct ArcPlacement {
+    pub(crate) pivot: Vector3<Dec>,
+    pub(crate) radius: Dec,
+    pub(crate) start_angle: Angle,
+    pub(crate) step_angle: Angle,
+}
+
 #[derive(Default)]
 pub struct ButtonsCollectionBuilder {
+    arc: Option<ArcPlacement>,
     columns: Vec<ButtonsColumn>,
     padding: Dec,
     curvature: Angle,
@@ -13,10 +26,17 @@ pub struct ButtonsCollectionBuilder {
     height: Dec,
     position_shift_x: Dec,
     position_shift_y: Dec,
+    row_offsets: Vec<(usize, Dec, Dec)>,
 }
 
 impl ButtonsCollectionBuilder {
-    pub fn build(mut self) -> ButtonsCollection {
+    /// Panics, when collection is placed on arc of zero radius with padding, use
+    /// [`ButtonsCollectionBuilder::try_build`] to get an error instead
+    pub fn build(self) -> ButtonsCollection {
+        self.try_build().expect("Cannot place buttons collection")
+    }
+
+    pub fn try_build(mut self) -> anyhow::Result<ButtonsCollection> {
         let mut org = Origin::new();
         let x = org.x();
         let y = org.y();
@@ -29,6 +49,47 @@ impl ButtonsCollectionBuilder {
             .rotate_axisangle(x * self.plane_pitch.rad())
             .rotate_axisangle(z * self.plane_yaw.rad());
 
+        for (row, y, z) in &self.row_offsets {
+            for c in self.columns.iter_mut() {
+                c.offset_row(*row, *y, *z);
+            }
+        }
+
+        if let Some(arc) = self.arc.take() {
+            // Padding is an extra length of arc between columns, in direction of step
+            let mut step = arc.step_angle.rad();
+            if !self.padding.is_zero() {
+                if arc.radius <= Dec::zero() {
+                    return Err(anyhow!(
+                        "Padding between columns needs arc of positive radius, got {}",
+                        arc.radius
+                    ));
+                }
+                let padding = self.padding / arc.radius;
+                step += if step < Dec::zero() {
+                    -padding
+                } else {
+                    padding
+                };
+            }
+            for (ix, c) in self.columns.iter_mut().enumerate() {
+                let angle = arc.start_angle.rad() + step * Dec::from(ix);
+                // Column looks away from pivot and is tilted along its length, like
+                // columns with curvature are
+                let mut column_org = Origin::new()
+                    .offset(arc.pivot)
+                    .rotate_axisangle(z * angle)
+                    .offset_x(arc.radius)
+                    .rotate_axisangle(z * -Angle::from_deg(90).rad())
+                    .rotate_axisangle(y * -self.curvature.rad() * Dec::from(ix));
+                column_org.apply(&org);
+                c.apply_origin(&column_org);
+            }
+            return Ok(ButtonsCollection {
+                columns: self.columns,
+            });
+        }
+
         for c in self.columns.iter_mut() {
             c.apply_origin(&org);
             let two = Dec::from(2);
@@ -37,9 +98,14 @@ impl ButtonsCollectionBuilder {
                 .rotate_axisangle(y * -self.curvature.rad())
                 .offset_x(self.padding / two);
         }
-        ButtonsCollection {
+        Ok(ButtonsCollection {
             columns: self.columns,
-        }
+        })
+    }
+
+    pub(crate) fn arc(mut self, arc: ArcPlacement) -> Self {
+        self.arc = Some(arc);
+        self
     }
 
     pub fn column(mut self, column: ButtonsColumn) -> Self {
@@ -47,6 +113,14 @@ impl ButtonsCollectionBuilder {
         self
     }
 
+    /// Stagger of a row: button `row` of every column, counted from the bottom of column,
+    /// moves by `y` along the column and by `z` up from the plate. Offsets of the same row
+    /// add up.
+    pub fn row_offset(mut self, row: usize, y: impl Into<Dec>, z: impl Into<Dec>) -> Self {
+        self.row_offsets.push((row, y.into(), z.into()));
+        self
+    }
+
     pub fn padding(mut self, padding: impl Into<Dec>) -> Self {
         self.padding = padding.into();
         self
@@ -1,3 +1,4 @@
+use anyhow::anyhow;
 use geometry::{
     decimal::Dec,
     geometry::Geometry,
@@ -10,12 +11,18 @@ use geometry::{
     indexes::geo_index::mesh::MeshRefMut,
 };
 
+use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
+
 use crate::{
-    button::Button, button_collection_builder::ButtonsCollectionBuilder,
-    buttons_column::ButtonsColumn, next_and_peek::NextAndPeekBlank,
+    button::Button,
+    button_collection_builder::{ArcPlacement, ButtonsCollectionBuilder},
+    buttons_column::ButtonsColumn,
+    next_and_peek::NextAndPeekBlank,
+    Angle,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 #[allow(unused)]
 pub struct ButtonsCollection {
     pub(crate) columns: Vec<ButtonsColumn>,
@@ -26,12 +33,53 @@ impl ButtonsCollection {
         ButtonsCollectionBuilder::default()
     }
 
+    /// Places columns on arc around pivot, like thumb fan. First column is placed
+    /// at `start_angle`, each next one is rotated by `step_angle` further.
+    /// Columns are turned so they look away from pivot. Height, pitch and yaw of
+    /// collection still apply, and pivot is set in that plane. Padding adds that much
+    /// arc length between columns, curvature tilts each next column along its length
+    /// by that angle more.
+    pub fn arc_about(
+        pivot: Vector3<Dec>,
+        radius: impl Into<Dec>,
+        start_angle: Angle,
+        step_angle: Angle,
+    ) -> ButtonsCollectionBuilder {
+        ButtonsCollectionBuilder::default().arc(ArcPlacement {
+            pivot,
+            radius: radius.into(),
+            start_angle,
+            step_angle,
+        })
+    }
+
     pub(crate) fn empty() -> ButtonsCollection {
         Self {
             columns: Vec::new(),
         }
     }
 
+    pub(crate) fn lerp(
+        &self,
+        other: &ButtonsCollection,
+        t: Dec,
+    ) -> anyhow::Result<ButtonsCollection> {
+        if self.columns.len() != other.columns.len() {
+            return Err(anyhow!(
+                "Cannot blend collections with different amount of columns: {} <> {}",
+                self.columns.len(),
+                other.columns.len()
+            ));
+        }
+        let columns = self
+            .columns
+            .iter()
+            .zip(&other.columns)
+            .map(|(a, b)| a.lerp(b, t))
+            .collect::<anyhow::Result<_>>()?;
+        Ok(ButtonsCollection { columns })
+    }
+
     pub(crate) fn buttons(&self) -> impl DoubleEndedIterator<Item = &Button> {
         self.columns.iter().flat_map(|col| col.buttons())
     }
@@ -137,6 +185,28 @@ impl ButtonsCollection {
         Ok(())
     }
 
+    pub(crate) fn fill_columns_inner(
+        &self,
+        mesh: &mut MeshRefMut,
+        thickness: Dec,
+    ) -> anyhow::Result<()> {
+        for c in &self.columns {
+            c.filler_inner(mesh, thickness)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn fill_columns_outer(
+        &self,
+        mesh: &mut MeshRefMut,
+        thickness: Dec,
+    ) -> anyhow::Result<()> {
+        for c in &self.columns {
+            c.filler_outer(mesh, thickness)?;
+        }
+        Ok(())
+    }
+
     pub(crate) fn fill_between_columns_inner(
         &self,
         mesh: &mut MeshRefMut,
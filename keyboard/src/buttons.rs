@@ -1 +1,4 @@
 pub mod chok_hotswap;
+pub mod chok_hotswap_builder;
+pub mod encoder_mount;
+pub mod mx_hotswap;
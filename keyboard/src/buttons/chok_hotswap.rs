@@ -16,38 +16,45 @@ use itertools::Itertools;
 use num_traits::{One, Zero};
 use rust_decimal_macros::dec;
 
+use super::chok_hotswap_builder::ChokHotswapBuilder;
+
 #[allow(unused)]
 pub struct ChokHotswap {
     depth: Dec,
     pcb_thickness: Dec,
-    main_hole_radius: Dec,
-    side_hole_radius: Dec,
+    pub(super) main_hole_radius: Dec,
+    pub(super) side_hole_radius: Dec,
     side_hole_distance: Dec,
     near_pin_distance: [Dec; 2],
     far_pin_distance: [Dec; 2],
-    pcb_pin_diameter: Dec,
+    pub(super) pcb_pin_diameter: Dec,
     lock_to_bed_distance: Dec,
     total_switch_below_surface: Dec,
-    mount_width: Dec,
-    mount_height: Dec,
-    outer_mount_width: Dec,
-    outer_mount_height: Dec,
-    mount_lock_width: Dec,
-    mount_lock_height: Dec,
+    pub(super) mount_width: Dec,
+    pub(super) mount_height: Dec,
+    pub(super) outer_mount_width: Dec,
+    pub(super) outer_mount_height: Dec,
+    pub(super) mount_lock_width: Dec,
+    pub(super) mount_lock_height: Dec,
     mount_lock_depth: Dec,
-    hotswap_thickness: Dec,
+    pub(super) hotswap_thickness: Dec,
     bottom_mesh_button_holes_depth: Dec,
-    hotswap_rect_height: Dec,
-    hotswap_rect_width_far: Dec,
-    hotswap_rect_width_near: Dec,
+    pub(super) hotswap_rect_height: Dec,
+    pub(super) hotswap_rect_width_far: Dec,
+    pub(super) hotswap_rect_width_near: Dec,
     hotswap_chamfer_radius: Dec,
     bottom_mesh_screw_head_diameter: Dec,
     bottom_mesh_screw_thread_diameter: Dec,
     hs_ear_width: Dec,
     hs_ear_depth: Dec,
+    pub(super) frame_clearance: Dec,
 }
 
 impl ChokHotswap {
+    pub fn build() -> ChokHotswapBuilder {
+        ChokHotswapBuilder::default()
+    }
+
     pub fn height(&self) -> Dec {
         self.outer_mount_height
     }
@@ -85,6 +92,7 @@ impl ChokHotswap {
             bottom_mesh_screw_thread_diameter: Dec::from(dec!(1)),
             hs_ear_width: dec!(2.2).into(),
             hs_ear_depth: dec!(1.7).into(),
+            frame_clearance: Dec::zero(),
         }
     }
 
@@ -964,6 +972,8 @@ impl ChokHotswap {
         let upper_plane = index.new_mesh();
         let lower_plane = index.new_mesh();
         let total_depth = self.pcb_thickness + self.lock_to_bed_distance + self.mount_lock_depth;
+        let hole_width = self.mount_width + self.frame_clearance * 2;
+        let hole_height = self.mount_height + self.frame_clearance * 2;
         let top = center.offset_z(total_depth / 2);
         let bottom = top
             .clone()
@@ -990,8 +1000,8 @@ impl ChokHotswap {
         Rect::build()
             .align_z(Align::Pos)
             .origin(top.clone())
-            .width(self.mount_width)
-            .height(self.mount_height)
+            .width(hole_width)
+            .height(hole_height)
             .depth(total_depth * 10001 / 10000)
             .build()
             .polygonize(rect_hole.make_mut_ref(index), 0)?;
@@ -1025,13 +1035,9 @@ impl ChokHotswap {
 
         self.add_material(
             &Rect::build()
-                .origin(
-                    bottom
-                        .offset_z(Dec::one() / 100)
-                        .offset_y(-self.mount_width / 2),
-                )
+                .origin(bottom.offset_z(Dec::one() / 100).offset_y(-hole_width / 2))
                 .align_z(Align::Neg)
-                .width(self.mount_height * 105 / 100)
+                .width(hole_height * 105 / 100)
                 .height(2)
                 .depth(0.7)
                 .build(),
@@ -1043,8 +1049,8 @@ impl ChokHotswap {
             &Rect::build()
                 .origin(
                     top.clone()
-                        .offset_x(-self.mount_width / 2)
-                        .offset_y(-self.mount_height / 2), //.offset_z(-self.mount_lock_depth),
+                        .offset_x(-hole_width / 2)
+                        .offset_y(-hole_height / 2), //.offset_z(-self.mount_lock_depth),
                 )
                 .width(5)
                 .height(0.5)
@@ -0,0 +1,82 @@
+use geometry::decimal::Dec;
+
+use super::chok_hotswap::ChokHotswap;
+
+/// Dimensions of `ChokHotswap`, which depend on printer calibration and socket revision.
+/// Values, which are not set, stay as in `ChokHotswap::new`. Keys of
+/// `ButtonMountKind::ChokHotswapCustom` always use default dimensions.
+#[derive(Default)]
+pub struct ChokHotswapBuilder {
+    socket_depth: Option<Dec>,
+    center_hole_diameter: Option<Dec>,
+    side_hole_diameter: Option<Dec>,
+    pin_hole_diameter: Option<Dec>,
+    clearance: Option<Dec>,
+    wall: Option<Dec>,
+}
+
+impl ChokHotswapBuilder {
+    /// Depth of pocket for hotswap socket in bottom part
+    pub fn socket_depth(mut self, socket_depth: impl Into<Dec>) -> Self {
+        self.socket_depth = Some(socket_depth.into());
+        self
+    }
+
+    /// Holes for central pole and side pegs of the switch and for pins of the socket
+    pub fn pin_hole_diameters(
+        mut self,
+        center: impl Into<Dec>,
+        side: impl Into<Dec>,
+        pin: impl Into<Dec>,
+    ) -> Self {
+        self.center_hole_diameter = Some(center.into());
+        self.side_hole_diameter = Some(side.into());
+        self.pin_hole_diameter = Some(pin.into());
+        self
+    }
+
+    /// Gap on each side between the module and its frame in key plate, also added
+    /// around hotswap socket in its pocket
+    pub fn clearance(mut self, clearance: impl Into<Dec>) -> Self {
+        self.clearance = Some(clearance.into());
+        self
+    }
+
+    /// Thickness of module walls around the switch lock
+    pub fn wall(mut self, wall: impl Into<Dec>) -> Self {
+        self.wall = Some(wall.into());
+        self
+    }
+
+    pub fn build(self) -> ChokHotswap {
+        let mut hs = ChokHotswap::new();
+        let frame_width = hs.outer_mount_width - hs.mount_width;
+        let frame_height = hs.outer_mount_height - hs.mount_height;
+
+        if let Some(depth) = self.socket_depth {
+            hs.hotswap_thickness = depth;
+        }
+        if let Some(d) = self.center_hole_diameter {
+            hs.main_hole_radius = d / 2;
+        }
+        if let Some(d) = self.side_hole_diameter {
+            hs.side_hole_radius = d / 2;
+        }
+        if let Some(d) = self.pin_hole_diameter {
+            hs.pcb_pin_diameter = d;
+        }
+        if let Some(wall) = self.wall {
+            hs.mount_width = hs.mount_lock_width + wall * 2;
+            hs.mount_height = hs.mount_lock_height + wall * 2;
+        }
+        if let Some(clearance) = self.clearance {
+            hs.frame_clearance = clearance;
+            hs.hotswap_rect_height += clearance * 2;
+            hs.hotswap_rect_width_near += clearance * 2;
+            hs.hotswap_rect_width_far += clearance * 2;
+        }
+        hs.outer_mount_width = hs.mount_width + hs.frame_clearance * 2 + frame_width;
+        hs.outer_mount_height = hs.mount_height + hs.frame_clearance * 2 + frame_height;
+        hs
+    }
+}
@@ -0,0 +1,183 @@
+use std::{f64::consts::TAU, rc::Rc};
+
+use geometry::{
+    decimal::Dec,
+    geometry::GeometryDyn,
+    indexes::geo_index::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId},
+    origin::Origin,
+    shapes::{extrude, Rect},
+};
+use nalgebra::Vector2;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::keyboard_config::{KeyboardMesh, MaterialAddition, RightKeyboardConfig};
+
+/// Rotary encoder, which goes into `EncoderMount`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EncoderModel {
+    /// Alps EC11 and clones: 12mm body with M7 bushing
+    Ec11,
+    /// Alps EC12: 12mm body with 6mm bushing, lower than EC11
+    Ec12,
+}
+
+/// Mount for rotary encoder in place of a key. Bushing of the encoder goes up through
+/// the bore in the plate and is held by its nut, body hangs in a pocket under the plate.
+/// Pocket is open at the bottom and wider at the pin rows, so legs of the encoder and
+/// solder joints on them do not touch the mount. With `d_flat` bore has a flat, which
+/// keeps encoder with flatted bushing from turning. Zero is at the plate middle, at
+/// the shaft axis.
+pub struct EncoderMount {
+    d_flat: bool,
+    outer_width: Dec,
+    outer_height: Dec,
+    bushing_radius: Dec,
+    /// From the axis to the flat of bushing
+    flat: Dec,
+    body_width: Dec,
+    body_height: Dec,
+    body_depth: Dec,
+    /// Rows of pins along x: three signal pins in front, two switch pins at the back
+    pin_rows: [Dec; 2],
+    pin_row_width: Dec,
+    clearance: Dec,
+}
+
+impl EncoderMount {
+    pub fn new(model: EncoderModel, d_flat: bool) -> Self {
+        let ec11 = Self {
+            d_flat,
+            outer_width: 19.into(),
+            outer_height: 19.into(),
+            bushing_radius: dec!(3.5).into(),
+            flat: dec!(2.5).into(),
+            body_width: dec!(12.4).into(),
+            body_height: 12.into(),
+            body_depth: dec!(6.5).into(),
+            pin_rows: [dec!(-7.5).into(), 7.into()],
+            pin_row_width: 7.into(),
+            clearance: dec!(0.2).into(),
+        };
+        match model {
+            EncoderModel::Ec11 => ec11,
+            EncoderModel::Ec12 => Self {
+                bushing_radius: 3.into(),
+                flat: dec!(2.25).into(),
+                body_width: 12.into(),
+                body_depth: 5.into(),
+                pin_rows: [dec!(-6.5).into(), dec!(6.5).into()],
+                ..ec11
+            },
+        }
+    }
+
+    pub fn height(&self) -> Dec {
+        self.outer_height
+    }
+
+    pub fn width(&self) -> Dec {
+        self.outer_width
+    }
+
+    /// Round bore, cut by the flat, if it is needed
+    fn bore_profile(&self) -> Vec<Vector2<Dec>> {
+        let radius = self.bushing_radius + self.clearance;
+        let steps = 32;
+        let mut points = (0..steps)
+            .map(|step| {
+                let angle = TAU * step as f64 / steps as f64;
+                let y = radius * Dec::from(angle.sin()).round_dp(9);
+                Vector2::new(
+                    radius * Dec::from(angle.cos()).round_dp(9),
+                    if self.d_flat {
+                        y.min(self.flat + self.clearance)
+                    } else {
+                        y
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+        points.dedup();
+        points
+    }
+
+    /// Bore through plate of `thickness`, body pocket and pin clearance under it. `top`
+    /// is on the top of the plate at the shaft axis.
+    pub(crate) fn holes(&self, top: Origin, thickness: Dec) -> Vec<Rc<dyn GeometryDyn>> {
+        let sw = Dec::from(dec!(0.1));
+        let c = self.clearance;
+        let under = top.clone().offset_z(-thickness);
+        let [front, back] = self.pin_rows;
+        let pin_clearance = Dec::from(1);
+        vec![
+            Rc::new(extrude(
+                &self.bore_profile(),
+                -(thickness + sw * 2),
+                top.offset_z(sw),
+            )),
+            Rc::new(Rect::with_top_at(
+                under.clone().offset_z(sw),
+                self.body_width + c * 2,
+                self.body_height + c * 2,
+                self.body_depth + sw * 2,
+            )),
+            Rc::new(Rect::with_top_at(
+                under.offset_y((front + back) / 2).offset_z(sw),
+                self.pin_row_width + c * 2,
+                back - front + pin_clearance * 2,
+                self.body_depth + sw * 2,
+            )),
+        ]
+    }
+
+    fn material(&self, top: Origin, thickness: Dec) -> Rect {
+        Rect::with_top_at(
+            top,
+            self.outer_width,
+            self.outer_height,
+            thickness + self.body_depth,
+        )
+    }
+
+    /// Block in the key plate with bore and pocket
+    pub fn outer_mount(
+        &self,
+        center: Origin,
+        thickness: Dec,
+        index: &mut GeoIndex,
+    ) -> anyhow::Result<MeshId> {
+        let top = center.offset_z(thickness / 2);
+        let mount = index.new_mesh();
+        self.material(top.clone(), thickness)
+            .polygonize(mount.make_mut_ref(index), 0)?;
+        for hole in self.holes(top, thickness) {
+            let hole_mesh = index.new_mesh();
+            hole.polygonize(hole_mesh.make_mut_ref(index), 0)?;
+            RightKeyboardConfig::subtract_mesh(mount, hole_mesh, index)?;
+        }
+        Ok(mount)
+    }
+}
+
+impl RightKeyboardConfig {
+    /// Encoder mount outside of button columns: in a wall or on top of the case. Origin
+    /// is at the shaft axis on the outer surface, z axis looks out of the case. Mount
+    /// takes wall thickness of the case as plate thickness.
+    pub fn add_encoder(mut self, origin: Origin, model: EncoderModel, d_flat: bool) -> Self {
+        let mount = EncoderMount::new(model, d_flat);
+        let thickness = self.main_plane_thickness;
+        self.additional_material
+            .entry(KeyboardMesh::ButtonsHull)
+            .or_default()
+            .push((
+                MaterialAddition::InnerSurface,
+                Rc::new(mount.material(origin.clone(), thickness)),
+            ));
+        self.holes
+            .entry(KeyboardMesh::ButtonsHull)
+            .or_default()
+            .extend(mount.holes(origin, thickness));
+        self
+    }
+}
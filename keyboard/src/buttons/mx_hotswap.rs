@@ -0,0 +1,253 @@
+use geometry::{
+    decimal::Dec,
+    geometry::GeometryDyn,
+    indexes::geo_index::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId},
+    origin::Origin,
+    shapes::{extrude, Cylinder, Rect},
+};
+use nalgebra::Vector2;
+use num_traits::{One, Zero};
+use rust_decimal_macros::dec;
+
+use crate::keyboard_config::RightKeyboardConfig;
+
+/// Printable module for Kailh MX hotswap socket, which goes into the plate from above.
+/// Top part holds the switch: lock plate on top and bed, which plays role of PCB, at
+/// the bottom, joined by side walls. Bottom part keeps the socket under the bed and is
+/// screwed to it. Zero is at the top of the bed, at the switch axis.
+#[allow(unused)]
+pub struct MxHotswap {
+    pcb_thickness: Dec,
+    lock_to_bed_distance: Dec,
+    mount_width: Dec,
+    mount_height: Dec,
+    outer_mount_width: Dec,
+    outer_mount_height: Dec,
+    mount_lock_width: Dec,
+    mount_lock_height: Dec,
+    mount_lock_depth: Dec,
+    switch_body_width: Dec,
+    center_pole_radius: Dec,
+    peg_radius: Dec,
+    peg_distance: Dec,
+    pin_radius: Dec,
+    near_pin: [Dec; 2],
+    far_pin: [Dec; 2],
+    hotswap_thickness: Dec,
+    holder_bottom_thickness: Dec,
+    screw_hole_radius: Dec,
+    screw_tap_radius: Dec,
+    screws: [[Dec; 2]; 2],
+}
+
+impl MxHotswap {
+    pub fn height(&self) -> Dec {
+        self.outer_mount_height
+    }
+
+    pub fn width(&self) -> Dec {
+        self.outer_mount_width
+    }
+
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            mount_width: Dec::from(18),
+            mount_height: Dec::from(18),
+            outer_mount_width: dec!(19.05).into(),
+            outer_mount_height: dec!(19.05).into(),
+            mount_lock_width: Dec::from(14),
+            mount_lock_height: Dec::from(14),
+            mount_lock_depth: dec!(1.5).into(),
+            // MX switch stands 5mm above PCB
+            lock_to_bed_distance: dec!(3.5).into(),
+            switch_body_width: dec!(15.6).into(),
+            pcb_thickness: dec!(1.6).into(),
+            center_pole_radius: dec!(2.05).into(),
+            peg_radius: dec!(0.9).into(),
+            peg_distance: dec!(5.08).into(),
+            pin_radius: dec!(1).into(),
+            near_pin: [dec!(-3.81).into(), dec!(2.54).into()],
+            far_pin: [dec!(2.54).into(), dec!(5.08).into()],
+            hotswap_thickness: dec!(1.85).into(),
+            holder_bottom_thickness: Dec::from(1),
+            // M1 screws in front of the switch, heads go into space under the lock
+            screw_hole_radius: dec!(0.55).into(),
+            screw_tap_radius: dec!(0.45).into(),
+            screws: [
+                [dec!(-5.5).into(), dec!(-8).into()],
+                [dec!(5.5).into(), dec!(-8).into()],
+            ],
+        }
+    }
+
+    fn total_depth(&self) -> Dec {
+        self.pcb_thickness + self.lock_to_bed_distance + self.mount_lock_depth
+    }
+
+    fn cut(
+        &self,
+        hole: &dyn GeometryDyn,
+        in_mesh: MeshId,
+        index: &mut GeoIndex,
+    ) -> anyhow::Result<()> {
+        let hole_mesh = index.new_mesh();
+        hole.polygonize(hole_mesh.make_mut_ref(index), 0)?;
+        RightKeyboardConfig::subtract_mesh(in_mesh, hole_mesh, index)?;
+        Ok(())
+    }
+
+    /// Vertical hole through everything from `bottom` to `top`
+    fn drill(
+        &self,
+        [x, y]: [Dec; 2],
+        radius: Dec,
+        bottom: Dec,
+        top: Dec,
+        in_mesh: MeshId,
+        index: &mut GeoIndex,
+    ) -> anyhow::Result<()> {
+        let hole = Cylinder::with_bottom_at(
+            Origin::new().offset_x(x).offset_y(y).offset_z(bottom),
+            top - bottom,
+            radius,
+        )
+        .steps(32);
+        self.cut(&hole, in_mesh, index)
+    }
+
+    /// Switch holder: lock plate, side walls and bed with holes for switch pins and screws
+    pub fn top_mesh(&self, index: &mut GeoIndex) -> anyhow::Result<()> {
+        let zero = Origin::new();
+        let part = index.new_mesh();
+        let top = self.lock_to_bed_distance + self.mount_lock_depth;
+        Rect::with_top_at(
+            zero.clone().offset_z(top),
+            self.mount_width,
+            self.mount_height,
+            self.total_depth(),
+        )
+        .polygonize(part.make_mut_ref(index), 0)?;
+
+        // Space for switch body under the lock, open at front and back
+        self.cut(
+            &Rect::with_bottom_at(
+                zero.clone(),
+                self.switch_body_width,
+                self.mount_height * 2,
+                self.lock_to_bed_distance,
+            ),
+            part,
+            index,
+        )?;
+        self.cut(
+            &Rect::with_bottom_at(
+                zero.clone().offset_z(self.lock_to_bed_distance / 2),
+                self.mount_lock_width,
+                self.mount_lock_height,
+                top,
+            ),
+            part,
+            index,
+        )?;
+
+        let (bottom, up) = (-self.pcb_thickness * 2, Dec::from(1));
+        self.drill(
+            [0.into(), 0.into()],
+            self.center_pole_radius,
+            bottom,
+            up,
+            part,
+            index,
+        )?;
+        for x in [-self.peg_distance, self.peg_distance] {
+            self.drill([x, 0.into()], self.peg_radius, bottom, up, part, index)?;
+        }
+        for pin in [self.near_pin, self.far_pin] {
+            self.drill(pin, self.pin_radius, bottom, up, part, index)?;
+        }
+        for screw in self.screws {
+            self.drill(screw, self.screw_hole_radius, bottom, up, part, index)?;
+        }
+        Ok(())
+    }
+
+    /// Outline of the socket with solder pads, as seen from above
+    fn socket_outline(&self) -> Vec<Vector2<Dec>> {
+        [
+            (dec!(-6.0), dec!(0.3)),
+            (dec!(0.8), dec!(0.3)),
+            (dec!(0.8), dec!(2.8)),
+            (dec!(4.7), dec!(2.8)),
+            (dec!(4.7), dec!(3.8)),
+            (dec!(7.3), dec!(3.8)),
+            (dec!(7.3), dec!(6.3)),
+            (dec!(4.7), dec!(6.3)),
+            (dec!(4.7), dec!(7.4)),
+            (dec!(-1.6), dec!(7.4)),
+            (dec!(-1.6), dec!(4.9)),
+            (dec!(-6.0), dec!(4.9)),
+            (dec!(-6.0), dec!(3.8)),
+            (dec!(-7.4), dec!(3.8)),
+            (dec!(-7.4), dec!(1.3)),
+            (dec!(-6.0), dec!(1.3)),
+        ]
+        .map(|(x, y)| Vector2::new(x.into(), y.into()))
+        .to_vec()
+    }
+
+    /// Socket holder, screwed to the bed from below
+    pub fn bottom_mesh(&self, index: &mut GeoIndex) -> anyhow::Result<()> {
+        let zero = Origin::new().offset_z(-self.pcb_thickness);
+        let part = index.new_mesh();
+        let depth = self.hotswap_thickness + self.holder_bottom_thickness;
+        Rect::with_top_at(zero.clone(), self.mount_width, self.mount_height, depth)
+            .polygonize(part.make_mut_ref(index), 0)?;
+
+        self.cut(
+            &extrude(
+                &self.socket_outline(),
+                self.hotswap_thickness + Dec::one(),
+                zero.clone().offset_z(-self.hotswap_thickness),
+            ),
+            part,
+            index,
+        )?;
+
+        let (bottom, up) = (-self.pcb_thickness - depth * 2, Dec::zero());
+        self.drill(
+            [0.into(), 0.into()],
+            self.center_pole_radius,
+            bottom,
+            up,
+            part,
+            index,
+        )?;
+        for x in [-self.peg_distance, self.peg_distance] {
+            self.drill([x, 0.into()], self.peg_radius, bottom, up, part, index)?;
+        }
+        for screw in self.screws {
+            self.drill(screw, self.screw_tap_radius, bottom, up, part, index)?;
+        }
+        Ok(())
+    }
+
+    /// Frame in the key plate, which takes the module
+    pub fn outer_mount(&self, center: Origin, index: &mut GeoIndex) -> anyhow::Result<MeshId> {
+        let mount = index.new_mesh();
+        let total_depth = self.total_depth();
+        Rect::centered(
+            center.clone(),
+            self.outer_mount_width,
+            self.outer_mount_height,
+            total_depth,
+        )
+        .polygonize(mount.make_mut_ref(index), 0)?;
+        self.cut(
+            &Rect::centered(center, self.mount_width, self.mount_height, total_depth * 2),
+            mount,
+            index,
+        )?;
+        Ok(mount)
+    }
+}
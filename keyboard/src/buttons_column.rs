@@ -1,3 +1,4 @@
+use anyhow::anyhow;
 use geometry::{
     decimal::Dec,
     geometry::Geometry,
@@ -8,12 +9,13 @@ use geometry::{
     indexes::geo_index::mesh::MeshRefMut,
     origin::Origin,
 };
+use serde::{Deserialize, Serialize};
 
 use crate::{buttons_column_builder::ButtonsColumnBuilder, next_and_peek::NextAndPeekBlank};
 
 use super::button::Button;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ButtonsColumn {
     pub(super) buttons: Vec<Button>,
 }
@@ -35,6 +37,30 @@ impl ButtonsColumn {
         self.buttons.first().cloned()
     }
 
+    pub(crate) fn lerp(&self, other: &ButtonsColumn, t: Dec) -> anyhow::Result<ButtonsColumn> {
+        if self.buttons.len() != other.buttons.len() {
+            return Err(anyhow!(
+                "Cannot blend columns with different amount of buttons: {} <> {}",
+                self.buttons.len(),
+                other.buttons.len()
+            ));
+        }
+        let buttons = self
+            .buttons
+            .iter()
+            .zip(&other.buttons)
+            .map(|(a, b)| a.lerp(b, t))
+            .collect::<anyhow::Result<_>>()?;
+        Ok(ButtonsColumn { buttons })
+    }
+
+    /// Moves button `row`, counted from the bottom, along its own axes
+    pub(crate) fn offset_row(&mut self, row: usize, y: Dec, z: Dec) {
+        if let Some(b) = self.buttons.get_mut(row) {
+            b.origin = b.origin.clone().offset_y(y).offset_z(z);
+        }
+    }
+
     pub(crate) fn apply_origin(&mut self, origin: &Origin) {
         for b in self.buttons.iter_mut() {
             b.origin.apply(origin);
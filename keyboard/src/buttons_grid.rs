@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use geometry::decimal::Dec;
+use num_traits::Zero;
+
+use crate::{
+    angle::Angle, button::ButtonMountKind, button_builder::ButtonBuilder,
+    button_collection_builder::ButtonsCollectionBuilder, button_collections::ButtonsCollection,
+    buttons_column::ButtonsColumn,
+};
+
+/// Settings of one column of grid, which differ from the rest of columns
+#[derive(Clone, Copy, Default)]
+pub struct ColumnOverride {
+    rows: Option<usize>,
+    kind: Option<ButtonMountKind>,
+    curvature: Option<Angle>,
+    incline: Option<Angle>,
+    depth: Option<Dec>,
+    addition_column_padding: Option<Dec>,
+}
+
+impl ColumnOverride {
+    pub fn rows(mut self, rows: usize) -> Self {
+        self.rows = Some(rows);
+        self
+    }
+
+    pub fn kind(mut self, kind: ButtonMountKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    pub fn curvature(mut self, curvature: Angle) -> Self {
+        self.curvature = Some(curvature);
+        self
+    }
+
+    pub fn incline(mut self, incline: Angle) -> Self {
+        self.incline = Some(incline);
+        self
+    }
+
+    /// Depth of the column: positive goes up from the plate
+    pub fn depth(mut self, depth: impl Into<Dec>) -> Self {
+        self.depth = Some(depth.into());
+        self
+    }
+
+    pub fn addition_column_padding(mut self, padding: impl Into<Dec>) -> Self {
+        self.addition_column_padding = Some(padding.into());
+        self
+    }
+}
+
+/// Collection of `cols` columns with `rows` buttons of the same kind in each. Columns go
+/// left to right, gaps are between edges of buttons.
+pub struct ButtonsGrid {
+    rows: usize,
+    cols: usize,
+    kind: ButtonMountKind,
+    row_gap: Dec,
+    column_gap: Dec,
+    curvature: Angle,
+    overrides: HashMap<usize, ColumnOverride>,
+}
+
+impl ButtonsCollection {
+    /// Grid of buttons, see `ButtonsGrid`
+    pub fn grid(rows: usize, cols: usize, kind: ButtonMountKind) -> ButtonsGrid {
+        ButtonsGrid {
+            rows,
+            cols,
+            kind,
+            row_gap: 2.into(),
+            column_gap: 2.into(),
+            curvature: Angle::zero(),
+            overrides: HashMap::new(),
+        }
+    }
+}
+
+impl ButtonsGrid {
+    /// Gap between buttons of a column
+    pub fn row_gap(mut self, gap: impl Into<Dec>) -> Self {
+        self.row_gap = gap.into();
+        self
+    }
+
+    /// Gap between columns
+    pub fn column_gap(mut self, gap: impl Into<Dec>) -> Self {
+        self.column_gap = gap.into();
+        self
+    }
+
+    /// Curvature of all columns, which is not overridden
+    pub fn curvature(mut self, curvature: Angle) -> Self {
+        self.curvature = curvature;
+        self
+    }
+
+    /// Overrides settings of column `ix`, counted from the left
+    pub fn column(mut self, ix: usize, column: ColumnOverride) -> Self {
+        self.overrides.insert(ix, column);
+        self
+    }
+
+    /// Collection builder with all columns, so collection can be placed further
+    pub fn build(self) -> ButtonsCollectionBuilder {
+        let mut collection =
+            ButtonsCollection::build().padding(self.kind.button_width() + self.column_gap);
+        for ix in 0..self.cols {
+            let o = self.overrides.get(&ix).copied().unwrap_or_default();
+            let kind = o.kind.unwrap_or(self.kind);
+            let mut column = ButtonsColumn::build()
+                .padding(self.row_gap)
+                .curvature(o.curvature.unwrap_or(self.curvature));
+            for _ in 0..o.rows.unwrap_or(self.rows) {
+                column = column.main_button(ButtonBuilder::with_kind(kind).build());
+            }
+            if let Some(incline) = o.incline {
+                column = column.incline(incline);
+            }
+            if let Some(depth) = o.depth {
+                column = column.depth(depth);
+            }
+            if let Some(padding) = o.addition_column_padding {
+                column = column.addition_column_padding(padding);
+            }
+            collection = collection.column(column.build());
+        }
+        collection
+    }
+}
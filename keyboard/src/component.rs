@@ -0,0 +1,41 @@
+use geometry::{decimal::Dec, origin::Origin};
+use serde::{Deserialize, Serialize};
+
+/// Part, which lives inside of the case: controller, battery, sensor board. Origin is
+/// in the middle of the part bottom, z axis looks up, `width` goes along x axis and
+/// `height` along y. Part lies on its bottom, and must stay `clearance` away from case,
+/// switches and other parts on all other sides.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Component {
+    pub(crate) name: String,
+    pub(crate) origin: Origin,
+    pub(crate) width: Dec,
+    pub(crate) height: Dec,
+    pub(crate) depth: Dec,
+    pub(crate) clearance: Dec,
+}
+
+impl Component {
+    pub fn new(
+        name: impl Into<String>,
+        origin: Origin,
+        width: impl Into<Dec>,
+        height: impl Into<Dec>,
+        depth: impl Into<Dec>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            origin,
+            width: width.into(),
+            height: height.into(),
+            depth: depth.into(),
+            clearance: Dec::from(1),
+        }
+    }
+
+    /// Free space, left around the part
+    pub fn clearance(mut self, clearance: impl Into<Dec>) -> Self {
+        self.clearance = clearance.into();
+        self
+    }
+}
@@ -0,0 +1,127 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    rc::Rc,
+};
+
+use anyhow::anyhow;
+use geometry::{decimal::Dec, geometry::GeometryDyn, origin::Origin};
+
+use crate::{component::Component, keyboard_config::KeyboardMesh};
+
+/// Shape of a part, built at the origin, where part is placed
+pub(crate) type PartShape = Rc<dyn Fn(&Origin) -> Rc<dyn GeometryDyn>>;
+
+/// Reusable part: material and holes, it adds to keyboard meshes, space it takes inside
+/// of the case and named anchors to place it with. Everything is described in local
+/// coordinates of the part.
+#[derive(Clone, Default)]
+pub struct ComponentDefinition {
+    pub(crate) anchors: HashMap<String, Origin>,
+    pub(crate) material: Vec<(KeyboardMesh, PartShape)>,
+    pub(crate) holes: Vec<(KeyboardMesh, PartShape)>,
+    pub(crate) body: Option<Component>,
+}
+
+impl ComponentDefinition {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Named point of the part, which is put to target origin, when part is placed
+    pub fn anchor(mut self, name: impl Into<String>, origin: Origin) -> Self {
+        self.anchors.insert(name.into(), origin);
+        self
+    }
+
+    pub fn material<G: GeometryDyn + 'static>(
+        mut self,
+        on: KeyboardMesh,
+        shape: impl Fn(&Origin) -> G + 'static,
+    ) -> Self {
+        self.material.push((on, shape_rc(shape)));
+        self
+    }
+
+    pub fn hole<G: GeometryDyn + 'static>(
+        mut self,
+        on: KeyboardMesh,
+        shape: impl Fn(&Origin) -> G + 'static,
+    ) -> Self {
+        self.holes.push((on, shape_rc(shape)));
+        self
+    }
+
+    /// Box, taken by the part inside of the case. See `Component`.
+    pub fn body(
+        mut self,
+        width: impl Into<Dec>,
+        height: impl Into<Dec>,
+        depth: impl Into<Dec>,
+    ) -> Self {
+        self.body = Some(Component::new("", Origin::new(), width, height, depth));
+        self
+    }
+
+    /// Origin of the part, which puts `anchor` to `at`
+    pub(crate) fn placement(&self, anchor: &str, at: &Origin) -> anyhow::Result<Origin> {
+        let anchor = self
+            .anchors
+            .get(anchor)
+            .ok_or(anyhow!("No anchor {anchor} in component"))?;
+        let mut origin = anchor.inverse();
+        origin.apply(at);
+        Ok(origin)
+    }
+}
+
+fn shape_rc<G: GeometryDyn + 'static>(shape: impl Fn(&Origin) -> G + 'static) -> PartShape {
+    Rc::new(move |origin| Rc::new(shape(origin)) as Rc<dyn GeometryDyn>)
+}
+
+/// Parts, defined once and shared by many keyboards. Part is referenced as `name` for
+/// its latest version or `name@version` for exact one, so keyboards, made with older
+/// version of a part, are still built the same way.
+#[derive(Clone, Default)]
+pub struct ComponentLibrary {
+    definitions: HashMap<String, BTreeMap<u32, ComponentDefinition>>,
+}
+
+impl ComponentLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a version of the part. Same version, defined twice, is replaced.
+    pub fn define(
+        mut self,
+        name: impl Into<String>,
+        version: u32,
+        definition: ComponentDefinition,
+    ) -> Self {
+        self.definitions
+            .entry(name.into())
+            .or_default()
+            .insert(version, definition);
+        self
+    }
+
+    pub fn get(&self, reference: &str) -> anyhow::Result<&ComponentDefinition> {
+        let (name, version) = match reference.split_once('@') {
+            Some((name, version)) => (name, Some(version.parse::<u32>()?)),
+            None => (reference, None),
+        };
+        let versions = self
+            .definitions
+            .get(name)
+            .ok_or(anyhow!("No component {name} in library"))?;
+        match version {
+            Some(v) => versions
+                .get(&v)
+                .ok_or(anyhow!("Component {name} has no version {v}")),
+            None => versions
+                .values()
+                .next_back()
+                .ok_or(anyhow!("Component {name} has no versions")),
+        }
+    }
+}
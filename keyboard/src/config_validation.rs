@@ -0,0 +1,312 @@
+use std::fmt;
+
+use geometry::decimal::Dec;
+use itertools::Itertools;
+use nalgebra::Vector2;
+use num_traits::{Signed, Zero};
+
+use crate::{
+    button::Button,
+    keyboard_builder::KeyboardBuilder,
+    keyboard_config::RightKeyboardConfig,
+    switch_clearance::{KeyPosition, OrientedBox},
+};
+
+/// Problem of config, which makes its geometry impossible
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigError {
+    /// Key plates of two buttons go into each other
+    OverlappingButtons { a: KeyPosition, b: KeyPosition },
+    /// Walls from both sides of a notch of the outline meet, so there is less than wall
+    /// thickness for each of them. `gap` is the distance between their inner sides.
+    ThinWall { at: Vector2<Dec>, gap: Dec },
+    /// Bolt point, by index in order of adding, is not under the case
+    BoltOutsideOutline { bolt: usize, at: Vector2<Dec> },
+    /// Table outline crosses itself
+    OutlineSelfIntersection { at: Vector2<Dec> },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let point = |p: &Vector2<Dec>| format!("[{}, {}]", p.x.round_dp(2), p.y.round_dp(2));
+        match self {
+            ConfigError::OverlappingButtons { a, b } => {
+                write!(f, "Buttons {a:?} and {b:?} overlap")
+            }
+            ConfigError::ThinWall { at, gap } => write!(
+                f,
+                "Walls at {} are {} apart, it is too little for both of them",
+                point(at),
+                gap.round_dp(2)
+            ),
+            ConfigError::BoltOutsideOutline { bolt, at } => {
+                write!(f, "Bolt {bolt} at {} is outside of outline", point(at))
+            }
+            ConfigError::OutlineSelfIntersection { at } => {
+                write!(f, "Table outline crosses itself at {}", point(at))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// All problems, found in config by [`RightKeyboardConfig::validate`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigErrors(pub Vec<ConfigError>);
+
+impl fmt::Display for ConfigErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Config is not valid:")?;
+        for error in &self.0 {
+            write!(f, "\n  {error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigErrors {}
+
+/// Positive, when `b` is on the left of `o` -> `a`
+fn cross(o: Vector2<Dec>, a: Vector2<Dec>, b: Vector2<Dec>) -> Dec {
+    (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)
+}
+
+/// Crossing point of segments `a` and `b`, touching does not count
+fn crossing(
+    a: (Vector2<Dec>, Vector2<Dec>),
+    b: (Vector2<Dec>, Vector2<Dec>),
+) -> Option<Vector2<Dec>> {
+    let (d1, d2) = (cross(a.0, a.1, b.0), cross(a.0, a.1, b.1));
+    let (d3, d4) = (cross(b.0, b.1, a.0), cross(b.0, b.1, a.1));
+    let opposite = |x: Dec, y: Dec| {
+        (x > Dec::zero() && y < Dec::zero()) || (x < Dec::zero() && y > Dec::zero())
+    };
+    (opposite(d1, d2) && opposite(d3, d4)).then(|| a.0 + (a.1 - a.0) * (d3 / (d3 - d4)))
+}
+
+fn inside(polygon: &[Vector2<Dec>], p: Vector2<Dec>) -> bool {
+    polygon
+        .iter()
+        .circular_tuple_windows()
+        .filter(|(a, b)| {
+            (a.y > p.y) != (b.y > p.y) && p.x < a.x + (b.x - a.x) * (p.y - a.y) / (b.y - a.y)
+        })
+        .count()
+        % 2
+        == 1
+}
+
+impl RightKeyboardConfig {
+    /// Checks, which are cheap comparing to building meshes and find configs, which
+    /// would fail deep inside mesh operations or give broken case. Empty list means, that
+    /// nothing was found, not that the config is buildable.
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let mut errors = Vec::new();
+        let thickness = self.main_plane_thickness;
+
+        let keys = self.keys().collect_vec();
+        for ((a, button_a), (b, button_b)) in keys.iter().tuple_combinations() {
+            let plate = |button: &Button| {
+                OrientedBox::new(
+                    &button.origin,
+                    -thickness / 2,
+                    thickness / 2,
+                    button.kind.button_width(),
+                    button.kind.button_height(),
+                )
+            };
+            if plate(button_a).intersects(&plate(button_b)) {
+                errors.push(ConfigError::OverlappingButtons { a: *a, b: *b });
+            }
+        }
+
+        // Inner side of walls with outward normals
+        let samples = self
+            .wall_samples()
+            .into_iter()
+            .map(|(p, n)| {
+                let n = Vector2::new(n.x, n.y);
+                (Vector2::new(p.x, p.y) - n * thickness, n)
+            })
+            .collect_vec();
+        let mut along = vec![Dec::zero()];
+        for ((a, _), (b, _)) in samples.iter().circular_tuple_windows() {
+            let last = *along.last().expect("starts with zero");
+            along.push(last + (b - a).magnitude());
+        }
+        let perimeter = along.pop().unwrap_or_default();
+
+        // Points, which are close along outline, are parts of the same wall
+        let neighbourhood = thickness * Dec::from(4);
+        let mut thin: Vec<Vector2<Dec>> = Vec::new();
+        for ((i, (p, n)), (j, (q, m))) in samples.iter().enumerate().tuple_combinations() {
+            let d = (along[j] - along[i]).abs();
+            if d.min(perimeter - d) < neighbourhood {
+                continue;
+            }
+            let gap = (q - p).magnitude();
+            // Outer sides look at each other across the notch
+            if gap < thickness * Dec::from(2)
+                && (q - p).dot(n) > Dec::zero()
+                && (p - q).dot(m) > Dec::zero()
+            {
+                let at = (p + q) / Dec::from(2);
+                if thin.iter().all(|t| (t - at).magnitude() >= neighbourhood) {
+                    thin.push(at);
+                    errors.push(ConfigError::ThinWall { at, gap });
+                }
+            }
+        }
+
+        let polygon = samples.iter().map(|(p, _)| *p).collect_vec();
+        let segments = polygon
+            .iter()
+            .copied()
+            .circular_tuple_windows::<(_, _)>()
+            .collect_vec();
+        for (ix, a) in segments.iter().enumerate() {
+            for b in segments.iter().skip(ix + 2) {
+                if let Some(at) = crossing(*a, *b) {
+                    errors.push(ConfigError::OutlineSelfIntersection { at });
+                }
+            }
+        }
+
+        let outer = self.outline_on_table(0);
+        for (bolt, (_, _, point)) in self.bolts.iter().enumerate() {
+            let at = Vector2::new(point.origin.center.x, point.origin.center.y);
+            if !inside(&outer, at) {
+                errors.push(ConfigError::BoltOutsideOutline { bolt, at });
+            }
+        }
+        errors
+    }
+
+    /// Same config, if [`RightKeyboardConfig::validate`] finds nothing
+    pub fn validated(self) -> Result<Self, ConfigErrors> {
+        let errors = self.validate();
+        if errors.is_empty() {
+            Ok(self)
+        } else {
+            Err(ConfigErrors(errors))
+        }
+    }
+}
+
+impl KeyboardBuilder {
+    /// Builds config and validates it, see [`RightKeyboardConfig::validate`]
+    pub fn try_build(self) -> Result<RightKeyboardConfig, ConfigErrors> {
+        self.build().validated()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geometry::{
+        decimal::Dec,
+        hyper_path::{
+            hyper_line::HyperLine,
+            hyper_path::{HyperPath, Root},
+            hyper_point::SuperPoint,
+        },
+        origin::Origin,
+    };
+    use itertools::Itertools;
+    use nalgebra::Vector3;
+    use num_traits::Zero;
+
+    use crate::{
+        Bolt, BoltPoint, Button, ButtonsCollection, ButtonsColumn, KeyboardBuilder, KeyboardMesh,
+        RightKeyboardConfig,
+    };
+
+    use super::ConfigError;
+
+    /// Closed outline through `points`, which go clockwise
+    fn outline(points: &[(i32, i32)]) -> Root<SuperPoint<Dec>> {
+        let point = |(x, y): (i32, i32)| SuperPoint {
+            side_dir: Vector3::z(),
+            point: Vector3::new(Dec::from(x), Dec::from(y), Dec::zero()),
+        };
+        points
+            .iter()
+            .circular_tuple_windows()
+            .fold(Root::new(), |root, (a, b)| {
+                root.push_back(HyperLine::new_2(point(*a), point(*b)))
+            })
+    }
+
+    fn column() -> ButtonsColumn {
+        ButtonsColumn::build()
+            .main_button(Button::chok_hotswap_custom().build())
+            .build()
+    }
+
+    fn keyboard(columns_padding: i32, outline: Root<SuperPoint<Dec>>) -> KeyboardBuilder {
+        RightKeyboardConfig::build()
+            .wall_thickness(4)
+            .bottom_thickness(2)
+            .main(
+                ButtonsCollection::build()
+                    .column(column())
+                    .column(column())
+                    .padding(columns_padding)
+                    .height(20)
+                    .build(),
+            )
+            .table_outline(outline)
+    }
+
+    const SQUARE: [(i32, i32); 4] = [(-50, 50), (50, 50), (50, -50), (-50, -50)];
+
+    #[test]
+    fn separate_buttons_in_square_are_valid() {
+        assert_eq!(keyboard(25, outline(&SQUARE)).build().validate(), vec![]);
+    }
+
+    #[test]
+    fn buttons_in_the_same_place_overlap() {
+        let errors = keyboard(0, outline(&SQUARE)).build().validate();
+        assert!(
+            matches!(errors.as_slice(), [ConfigError::OverlappingButtons { .. }]),
+            "{errors:?}"
+        );
+    }
+
+    #[test]
+    fn bolt_outside_outline() {
+        let bolt = Bolt::build()
+            .m2()
+            .head_height(Dec::from(1.2))
+            .head_diameter(Dec::from(4.2))
+            .height(Dec::from(10))
+            .build();
+        let bolt = BoltPoint::new(bolt).origin(Origin::new().offset_x(80));
+        let errors = keyboard(25, outline(&SQUARE))
+            .add_bolt(KeyboardMesh::ButtonsHull, KeyboardMesh::Bottom, bolt)
+            .try_build()
+            .map(|_| ())
+            .expect_err("bolt is outside")
+            .0;
+        assert!(
+            matches!(
+                errors.as_slice(),
+                [ConfigError::BoltOutsideOutline { bolt: 0, .. }]
+            ),
+            "{errors:?}"
+        );
+    }
+
+    #[test]
+    fn crossed_outline() {
+        let bow_tie = [(-50, 50), (50, 50), (-50, -50), (50, -50)];
+        let errors = keyboard(25, outline(&bow_tie)).build().validate();
+        assert!(
+            errors
+                .iter()
+                .any(|e| matches!(e, ConfigError::OutlineSelfIntersection { .. })),
+            "{errors:?}"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use geometry::{
+    decimal::Dec,
+    shapes::{Align, Rect},
+};
+use itertools::Itertools;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::{button::Button, keyboard_config::RightKeyboardConfig, KeyPosition};
+
+/// Recess for sound dampening sheet (foam, silicone) in the bottom of key plate. Each key
+/// gets a pocket of sheet `thickness`, leaving `margin` of full plate on its edges, so
+/// plate stays connected to walls and neighbours. Sheet pieces have square holes for
+/// switch bodies. Thickness should leave enough plate for switch clips.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct DampeningPocket {
+    pub(crate) thickness: Dec,
+    pub(crate) margin: Dec,
+    pub(crate) switch_hole: Dec,
+    pub(crate) clearance: Dec,
+}
+
+impl DampeningPocket {
+    pub fn new(thickness: impl Into<Dec>) -> Self {
+        Self {
+            thickness: thickness.into(),
+            margin: dec!(1).into(),
+            switch_hole: dec!(14.5).into(),
+            clearance: dec!(0.2).into(),
+        }
+    }
+
+    /// Plate left around pocket of each key
+    pub fn margin(mut self, margin: impl Into<Dec>) -> Self {
+        self.margin = margin.into();
+        self
+    }
+
+    /// Size of square hole in the sheet for switch body
+    pub fn switch_hole(mut self, switch_hole: impl Into<Dec>) -> Self {
+        self.switch_hole = switch_hole.into();
+        self
+    }
+
+    /// Gap between sheet piece and pocket walls
+    pub fn clearance(mut self, clearance: impl Into<Dec>) -> Self {
+        self.clearance = clearance.into();
+        self
+    }
+
+    fn pocket_size(&self, button: &Button) -> (Dec, Dec) {
+        (
+            button.kind.button_width() - self.margin * 2,
+            button.kind.button_height() - self.margin * 2,
+        )
+    }
+
+    pub(crate) fn pocket(&self, button: &Button, plate_thickness: Dec) -> Rect {
+        let (width, height) = self.pocket_size(button);
+        // Pocket goes a bit below the plate to avoid coplanar polygons
+        let sw = Dec::from(dec!(0.1));
+        Rect::build()
+            .origin(
+                button
+                    .origin
+                    .clone()
+                    .offset_z(-plate_thickness / 2 + self.thickness),
+            )
+            .width(width)
+            .height(height)
+            .depth(self.thickness + sw)
+            .align_z(Align::Pos)
+            .build()
+    }
+
+    /// Square piece with a hole. Rows go from the bottom of image to the top, like on keyboard.
+    fn piece_path(
+        &self,
+        button: &Button,
+        key: KeyPosition,
+        column: usize,
+        rows: usize,
+        cell: Dec,
+    ) -> String {
+        let (w, h) = self.pocket_size(button);
+        let (w, h) = (w - self.clearance * 2, h - self.clearance * 2);
+        let cx = cell * Dec::from(column) + cell / 2;
+        let cy = cell * Dec::from(rows - key.row - 1) + cell / 2;
+        let square = |w: Dec, h: Dec| {
+            format!(
+                "M {} {} h {} v {} h {} z",
+                (cx - w / 2).round_dp(3),
+                (cy - h / 2).round_dp(3),
+                w.round_dp(3),
+                h.round_dp(3),
+                (-w).round_dp(3),
+            )
+        };
+        format!(
+            "<path fill=\"none\" fill-rule=\"evenodd\" stroke=\"black\" stroke-width=\"0.1\" d=\"{} {}\" />",
+            square(w, h),
+            square(self.switch_hole, self.switch_hole)
+        )
+    }
+}
+
+impl RightKeyboardConfig {
+    /// Cutting template for dampening sheet pieces, in millimeters. Pieces are laid out
+    /// like keys: one column of pieces for each column of keys, main cluster first.
+    pub fn dampening_template_svg(&self) -> anyhow::Result<String> {
+        let pocket = self
+            .dampening_pocket
+            .ok_or(anyhow::anyhow!("Dampening pocket is not configured"))?;
+        let gap = Dec::from(2);
+        let cell = self
+            .keys()
+            .map(|(_, b)| {
+                let (w, h) = pocket.pocket_size(b);
+                w.max(h)
+            })
+            .max()
+            .unwrap_or_default()
+            + gap;
+
+        let columns = self
+            .keys()
+            .map(|(k, _)| (k.cluster, k.column))
+            .dedup()
+            .collect_vec();
+        let rows = self.keys().map(|(k, _)| k.row + 1).max().unwrap_or(0);
+
+        let mut items = Vec::new();
+        for (key, button) in self.keys() {
+            let column = columns
+                .iter()
+                .position(|c| *c == (key.cluster, key.column))
+                .unwrap_or(0);
+            items.push(pocket.piece_path(button, key, column, rows, cell));
+        }
+
+        let width = cell * Dec::from(columns.len());
+        let height = cell * Dec::from(rows);
+        Ok([
+            format!("<svg viewBox=\"0 0 {width} {height}\" xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}mm\" height=\"{height}mm\">"),
+            items.join("\n"),
+            "</svg>".to_string(),
+        ]
+        .join("\n"))
+    }
+}
@@ -0,0 +1,176 @@
+use std::rc::Rc;
+
+use geometry::{
+    decimal::Dec,
+    geometry::GeometryDyn,
+    origin::Origin,
+    shapes::{Cylinder, Rect},
+};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::keyboard_config::RightKeyboardConfig;
+
+/// Window for small display module in the top surface. Origin is in the middle of the
+/// window on the outer surface, z axis looks out of the case, x axis goes along the long
+/// side of the display. Glass of the module goes into a pocket under thin panel with the
+/// window, its board rests on the shelf around the glass pocket. Where the board has
+/// mounting holes, shelf works as posts for M2 screws, which go through the board from
+/// inside of the case.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DisplayWindow {
+    pub(crate) origin: Origin,
+    pub(crate) board: (Dec, Dec),
+    pub(crate) board_thickness: Dec,
+    pub(crate) glass: (Dec, Dec),
+    pub(crate) glass_thickness: Dec,
+    pub(crate) window: (Dec, Dec),
+    pub(crate) screws: Option<(Dec, Dec)>,
+    pub(crate) panel_thickness: Dec,
+    pub(crate) clearance: Dec,
+}
+
+impl DisplayWindow {
+    /// 0.91" SSD1306 128x32 module. It has no mounting holes and is held by the pocket.
+    pub fn ssd1306_128x32(origin: Origin) -> Self {
+        Self {
+            origin,
+            board: (38.into(), 12.into()),
+            board_thickness: dec!(1.2).into(),
+            glass: (30.into(), dec!(11.5).into()),
+            glass_thickness: dec!(1.2).into(),
+            window: (dec!(22.5).into(), dec!(5.8).into()),
+            screws: None,
+            panel_thickness: 1.into(),
+            clearance: dec!(0.2).into(),
+        }
+    }
+
+    /// 0.96" SSD1306 128x64 module with M2 holes in corners
+    pub fn ssd1306_128x64(origin: Origin) -> Self {
+        Self {
+            board: (dec!(27.3).into(), dec!(27.8).into()),
+            glass: (dec!(26.7).into(), dec!(19.3).into()),
+            glass_thickness: dec!(1.5).into(),
+            window: (22.into(), 11.into()),
+            screws: Some((dec!(23.3).into(), dec!(23.8).into())),
+            ..Self::ssd1306_128x32(origin)
+        }
+    }
+
+    /// nice!view memory display. It has no mounting holes and is held by the pocket.
+    pub fn nice_view(origin: Origin) -> Self {
+        Self {
+            board: (36.into(), dec!(14.5).into()),
+            board_thickness: 1.into(),
+            glass: (dec!(33.5).into(), dec!(13.5).into()),
+            glass_thickness: 1.into(),
+            window: (27.into(), dec!(11.8).into()),
+            ..Self::ssd1306_128x32(origin)
+        }
+    }
+
+    /// Distance between centers of mounting holes along x and y
+    pub fn screws(mut self, x: impl Into<Dec>, y: impl Into<Dec>) -> Self {
+        self.screws = Some((x.into(), y.into()));
+        self
+    }
+
+    /// Visible part of the display
+    pub fn window(mut self, width: impl Into<Dec>, height: impl Into<Dec>) -> Self {
+        self.window = (width.into(), height.into());
+        self
+    }
+
+    /// Wall between outer surface and the glass
+    pub fn panel_thickness(mut self, panel_thickness: impl Into<Dec>) -> Self {
+        self.panel_thickness = panel_thickness.into();
+        self
+    }
+
+    /// Gap around glass and board
+    pub fn clearance(mut self, clearance: impl Into<Dec>) -> Self {
+        self.clearance = clearance.into();
+        self
+    }
+
+    fn wall(&self) -> Dec {
+        dec!(1.6).into()
+    }
+
+    /// Screw hole of M2 self-tapping screw
+    fn pilot_radius(&self) -> Dec {
+        dec!(0.8).into()
+    }
+
+    /// Material left between pilot holes and outer surface
+    fn skin(&self) -> Dec {
+        dec!(0.6).into()
+    }
+
+    fn shelf(&self) -> Dec {
+        self.panel_thickness + self.glass_thickness + self.clearance
+    }
+
+    /// Boss around the board, it goes from outer surface, so it fuses with it
+    pub(crate) fn material(&self) -> Rc<dyn GeometryDyn> {
+        let (width, height) = self.board;
+        Rc::new(Rect::with_top_at(
+            self.origin.clone(),
+            width + (self.clearance + self.wall()) * 2,
+            height + (self.clearance + self.wall()) * 2,
+            self.shelf() + self.board_thickness + self.clearance,
+        ))
+    }
+
+    /// Window through the panel, pockets for glass and board, and pilot holes for screws
+    pub(crate) fn holes(&self) -> Vec<Rc<dyn GeometryDyn>> {
+        let sw = Dec::from(dec!(0.1));
+        let c = self.clearance;
+        let ((window_width, window_height), (glass_width, glass_height)) =
+            (self.window, self.glass);
+        let (width, height) = self.board;
+        let mut holes: Vec<Rc<dyn GeometryDyn>> = vec![
+            Rc::new(Rect::with_top_at(
+                self.origin.clone().offset_z(sw),
+                window_width,
+                window_height,
+                self.panel_thickness + sw * 2,
+            )),
+            Rc::new(Rect::with_top_at(
+                self.origin.clone().offset_z(-self.panel_thickness),
+                glass_width + c * 2,
+                glass_height + c * 2,
+                self.glass_thickness + c + sw,
+            )),
+            Rc::new(Rect::with_top_at(
+                self.origin.clone().offset_z(-self.shelf()),
+                width + c * 2,
+                height + c * 2,
+                self.board_thickness + c + sw,
+            )),
+        ];
+        if let Some((x, y)) = self.screws {
+            for (dx, dy) in [(x, y), (-x, y), (-x, -y), (x, -y)] {
+                holes.push(Rc::new(Cylinder::with_top_at(
+                    self.origin
+                        .clone()
+                        .offset_x(dx / 2)
+                        .offset_y(dy / 2)
+                        .offset_z(-self.skin()),
+                    self.shelf() - self.skin() + sw,
+                    self.pilot_radius(),
+                )));
+            }
+        }
+        holes
+    }
+}
+
+impl RightKeyboardConfig {
+    /// Adds display window to buttons hull, see `DisplayWindow`
+    pub fn add_display_window(mut self, display: DisplayWindow) -> Self {
+        self.display_windows.push(display);
+        self
+    }
+}
@@ -0,0 +1,160 @@
+use geometry::{
+    decimal::Dec,
+    geometry::Geometry,
+    hyper_path::{
+        hyper_line::HyperLine,
+        hyper_path::{HyperPath, Root},
+        hyper_point::SuperPoint,
+        hyper_surface::{
+            dynamic_surface::DynamicSurface, primitive_dynamic_surface::PrimitiveSurface,
+        },
+    },
+    indexes::geo_index::{
+        geo_object::GeoObject,
+        index::GeoIndex,
+        mesh::{MeshId, MeshRefMut},
+    },
+};
+use nalgebra::Vector3;
+use num_traits::Zero;
+
+use crate::{
+    button_collections::ButtonsCollection, keyboard_config::RightKeyboardConfig,
+    next_and_peek::NextAndPeekBlank,
+};
+
+impl ButtonsCollection {
+    /// Closed line around buttons of this collection alone, clockwise from the top of the
+    /// right column
+    pub(crate) fn line_around_inner(&self, thickness: Dec) -> Root<SuperPoint<Dec>> {
+        self.right_line_inner(thickness)
+            .chain(self.bottom_line_inner(thickness).rev())
+            .chain(self.left_line_inner(thickness))
+            .chain(self.top_line_inner(thickness))
+            .chain(self.right_line_inner(thickness).take(1))
+            .next_and_peek(|n, p| HyperLine::new_2(*n, *p))
+            .fold(Root::new(), |hp, l| hp.push_back(l))
+    }
+
+    pub(crate) fn line_around_outer(&self, thickness: Dec) -> Root<SuperPoint<Dec>> {
+        self.right_line_outer(thickness)
+            .chain(self.bottom_line_outer(thickness).rev())
+            .chain(self.left_line_outer(thickness))
+            .chain(self.top_line_outer(thickness))
+            .chain(self.right_line_outer(thickness).take(1))
+            .next_and_peek(|n, p| HyperLine::new_2(*n, *p))
+            .fold(Root::new(), |hp, l| hp.push_back(l))
+    }
+}
+
+impl RightKeyboardConfig {
+    /// Main, thumb and named collections
+    pub(crate) fn collections(&self) -> impl Iterator<Item = &ButtonsCollection> {
+        [&self.main_buttons, &self.thumb_buttons]
+            .into_iter()
+            .chain(self.extra_collections.iter().map(|(_, c)| c))
+    }
+
+    /// Named collection, added with `KeyboardBuilder::collection`
+    pub fn collection(&self, name: &str) -> Option<&ButtonsCollection> {
+        self.extra_collections
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, c)| c)
+    }
+
+    /// Key plate of each named collection: buttons, fillers between them and the edge
+    /// around, like `key_plate` does for main and thumb collections
+    pub(crate) fn extra_collection_plates(
+        &self,
+        index: &mut GeoIndex,
+    ) -> anyhow::Result<Vec<MeshId>> {
+        let thickness = self.main_plane_thickness;
+        let mut plates = Vec::new();
+        for (_, collection) in &self.extra_collections {
+            let plate = self.collection_buttons(collection, index)?;
+            DynamicSurface::new(
+                collection.line_around_inner(thickness),
+                collection.line_around_outer(thickness),
+            )
+            .polygonize(&mut plate.make_mut_ref(index), self.quality.surface_steps)?;
+            plates.push(plate);
+        }
+        Ok(plates)
+    }
+
+    /// Hull of each named collection: its buttons and fillers with walls from the lines
+    /// around them straight down to the table, closed by ring on the table, like walls of
+    /// main and thumb collections go down to table outline
+    pub(crate) fn extra_collection_hulls(
+        &self,
+        index: &mut GeoIndex,
+    ) -> anyhow::Result<Vec<MeshId>> {
+        let thickness = self.main_plane_thickness;
+        let mut hulls = Vec::new();
+        for (_, collection) in &self.extra_collections {
+            let hull = self.collection_buttons(collection, index)?;
+            let inner = collection.line_around_inner(thickness);
+            let outer = collection.line_around_outer(thickness);
+            let (inner_table, outer_table) = (on_table(inner.clone()), on_table(outer.clone()));
+
+            let mut mesh = hull.make_mut_ref(index);
+            DynamicSurface::new(inner, inner_table.clone())
+                .polygonize(&mut mesh, self.quality.surface_steps)?;
+            DynamicSurface::new(outer_table.clone(), outer)
+                .polygonize(&mut mesh, self.quality.surface_steps)?;
+            self.connect_on_table(&mut mesh, inner_table, outer_table)?;
+            hulls.push(hull);
+        }
+        Ok(hulls)
+    }
+
+    /// Buttons of collection and fillers between them
+    fn collection_buttons(
+        &self,
+        collection: &ButtonsCollection,
+        index: &mut GeoIndex,
+    ) -> anyhow::Result<MeshId> {
+        let thickness = self.main_plane_thickness;
+        let plate = index.new_mesh();
+        for button in collection.buttons() {
+            let mesh = button.mesh(index, thickness)?;
+            index.move_all_polygons(mesh, plate);
+        }
+        let mut mesh = plate.make_mut_ref(index);
+        collection.fill_columns(&mut mesh, thickness)?;
+        collection.fill_between_columns_inner(&mut mesh, thickness)?;
+        collection.fill_between_columns_outer(&mut mesh, thickness)?;
+        Ok(plate)
+    }
+
+    /// Ring between bottoms of inner and outer walls
+    fn connect_on_table(
+        &self,
+        mesh: &mut MeshRefMut,
+        mut inner: Root<SuperPoint<Dec>>,
+        mut outer: Root<SuperPoint<Dec>>,
+    ) -> anyhow::Result<()> {
+        while inner.len() > 0 && outer.len() > 0 {
+            let (i, is) = inner.head_tail();
+            let (o, os) = outer.head_tail();
+            inner = is;
+            outer = os;
+            PrimitiveSurface(o.to_points(), i.to_points())
+                .polygonize(mesh, self.quality.surface_steps)?;
+        }
+        Ok(())
+    }
+}
+
+/// Line right under `line` on the table. Side directions look up, so walls leave the
+/// table vertically.
+fn on_table(line: Root<SuperPoint<Dec>>) -> Root<SuperPoint<Dec>> {
+    line.map(|l| {
+        l.map(|mut p| {
+            p.side_dir = Vector3::z() * (p.point.z / Dec::from(3));
+            p.point.z = Dec::zero();
+            p
+        })
+    })
+}
@@ -0,0 +1,55 @@
+use geometry::{decimal::Dec, indexes::geo_index::index::GeoIndex};
+use nalgebra::Vector3;
+
+use crate::keyboard_config::RightKeyboardConfig;
+
+/// Kind of case surface, which becomes physical group in FEM export
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SurfaceTag {
+    /// Key plates and surfaces between them, top and underside
+    Plate,
+    Wall,
+    /// Material around bolts
+    Boss,
+    /// Surfaces on the table or under it, which look down
+    Bottom,
+}
+
+impl SurfaceTag {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SurfaceTag::Plate => "plate",
+            SurfaceTag::Wall => "walls",
+            SurfaceTag::Boss => "bosses",
+            SurfaceTag::Bottom => "bottom",
+        }
+    }
+}
+
+impl RightKeyboardConfig {
+    /// Kind of surface by its center and normal, looking out of material. Surfaces,
+    /// which lean more than 60 degrees from horizontal, are walls.
+    pub fn surface_tag(&self, center: Vector3<f64>, normal: Vector3<f64>) -> SurfaceTag {
+        let point = center.map(Dec::from);
+        if self.bolts.iter().any(|(_, _, b)| b.in_material(point)) {
+            SurfaceTag::Boss
+        } else if normal.z.abs() < 0.5 {
+            SurfaceTag::Wall
+        } else if normal.z < 0.0 && center.z < 0.01 {
+            SurfaceTag::Bottom
+        } else {
+            SurfaceTag::Plate
+        }
+    }
+
+    /// Surface mesh of all meshes in `index` in Gmsh format, with physical groups named
+    /// by `SurfaceTag`, to simulate stiffness of the case
+    pub fn gmsh(&self, index: &GeoIndex) -> anyhow::Result<String> {
+        let topology = index.export_topology()?;
+        Ok(topology.gmsh(|_, polygon| {
+            self.surface_tag(topology.center(polygon), topology.normal(polygon))
+                .name()
+                .to_string()
+        }))
+    }
+}
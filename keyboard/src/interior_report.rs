@@ -0,0 +1,335 @@
+use anyhow::anyhow;
+use geometry::{
+    decimal::Dec,
+    geometry::GeometryDyn,
+    indexes::{
+        aabb::Aabb,
+        geo_index::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId},
+    },
+};
+use itertools::Itertools;
+use nalgebra::Vector3;
+use rust_decimal_macros::dec;
+
+use crate::{
+    button::ButtonMountKind,
+    component::Component,
+    keyboard_config::{KeyboardMesh, RightKeyboardConfig},
+    switch_clearance::OrientedBox,
+    KeyPosition,
+};
+
+/// Cavity is measured with vertical lines, going through centers of square cells
+/// of this size. Components are checked in points with the same spacing.
+const STEP: f64 = 1.0;
+
+/// Space inside of the case, in cubic millimeters
+#[derive(Clone, Copy, Debug)]
+pub struct InteriorReport {
+    /// Space between key plates, walls and the table
+    pub cavity: Dec,
+    /// Part of the cavity, taken by switch sockets and bolt bosses
+    pub occupied: Dec,
+    /// Space, left for components and wires
+    pub free: Dec,
+}
+
+type Interval = (f64, f64);
+
+/// Triangles of a mesh, prepared for fast checks along vertical lines
+struct Solid {
+    triangles: Vec<[Vector3<f64>; 3]>,
+}
+
+impl Solid {
+    fn new(index: &GeoIndex, mesh: MeshId) -> anyhow::Result<Self> {
+        let triangles = mesh
+            .make_ref(index)
+            .stl_triangles()?
+            .into_iter()
+            .map(|t| t.vertices.map(|v| Vector3::new(v[0], v[1], v[2]).cast()))
+            .collect();
+        Ok(Self { triangles })
+    }
+
+    fn polygonize(geometry: &dyn GeometryDyn, complexity: usize) -> anyhow::Result<Self> {
+        let mut index = scratch_index();
+        let mesh = index.new_mesh();
+        geometry.polygonize(mesh.make_mut_ref(&mut index), complexity)?;
+        Self::new(&index, mesh)
+    }
+
+    /// Heights, where vertical line through `x`, `y` crosses triangles, in ascending order
+    fn crossings(&self, x: f64, y: f64) -> Vec<f64> {
+        let mut crossings = self
+            .triangles
+            .iter()
+            .filter_map(|[a, b, c]| {
+                let (ab, ac) = (b - a, c - a);
+                let d = ab.x * ac.y - ac.x * ab.y;
+                if d.abs() < 1e-12 {
+                    return None;
+                }
+                let (px, py) = (x - a.x, y - a.y);
+                let u = (px * ac.y - ac.x * py) / d;
+                let v = (ab.x * py - px * ab.y) / d;
+                (u >= 0.0 && v >= 0.0 && u + v <= 1.0).then(|| a.z + ab.z * u + ac.z * v)
+            })
+            .collect_vec();
+        crossings.sort_by(f64::total_cmp);
+        crossings
+    }
+
+    /// Parts of vertical line, which are inside of closed solid
+    fn intervals(&self, x: f64, y: f64) -> Vec<Interval> {
+        self.crossings(x, y).into_iter().tuples().collect()
+    }
+
+    /// Parts of vertical line between the table and the surface, which is open to the table
+    fn intervals_above_table(&self, x: f64, y: f64) -> Vec<Interval> {
+        let mut crossings = self
+            .crossings(x, y)
+            .into_iter()
+            .filter(|z| *z > 0.0)
+            .collect_vec();
+        if crossings.len() % 2 == 1 {
+            crossings.insert(0, 0.0);
+        }
+        crossings.into_iter().tuples().collect()
+    }
+
+    /// Surface, open to the table, is treated as closed with the table
+    fn contains_above_table(&self, p: Vector3<f64>) -> bool {
+        p.z >= 0.0 && self.contains(p)
+    }
+
+    fn bounds(&self) -> (Vector3<f64>, Vector3<f64>) {
+        self.triangles.iter().flatten().fold(
+            (Vector3::repeat(f64::MAX), Vector3::repeat(f64::MIN)),
+            |(min, max), v| (min.inf(v), max.sup(v)),
+        )
+    }
+
+    /// Point is inside of closed solid, if it is crossed odd times above the point
+    fn contains(&self, p: Vector3<f64>) -> bool {
+        self.crossings(p.x, p.y)
+            .iter()
+            .filter(|z| **z > p.z)
+            .count()
+            % 2
+            == 1
+    }
+
+    /// Checks both ways, because boxes are only checked in points of their surface
+    fn hits(&self, b: &OrientedBox) -> bool {
+        surface_points(b).into_iter().any(|p| self.contains(p))
+            || self.triangles.iter().flatten().any(|p| box_contains(b, *p))
+    }
+}
+
+fn box_contains(b: &OrientedBox, p: Vector3<f64>) -> bool {
+    let d = p - b.center.map(f64::from);
+    b.axes
+        .iter()
+        .zip(b.half)
+        .all(|(axis, half)| d.dot(&axis.map(f64::from)).abs() <= f64::from(half))
+}
+
+/// Part of vertical line inside of a box
+fn box_interval(b: &OrientedBox, x: f64, y: f64) -> Option<Interval> {
+    let center = b.center.map(f64::from);
+    let (mut from, mut to) = (f64::MIN, f64::MAX);
+    for (axis, half) in b.axes.iter().zip(b.half) {
+        let axis = axis.map(f64::from);
+        let half = f64::from(half);
+        let offset = (x - center.x) * axis.x + (y - center.y) * axis.y - center.z * axis.z;
+        if axis.z.abs() < 1e-12 {
+            if offset.abs() > half {
+                return None;
+            }
+            continue;
+        }
+        let (a, b) = ((-half - offset) / axis.z, (half - offset) / axis.z);
+        from = from.max(a.min(b));
+        to = to.min(a.max(b));
+    }
+    (from < to).then_some((from, to))
+}
+
+fn union(mut intervals: Vec<Interval>) -> Vec<Interval> {
+    intervals.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut result: Vec<Interval> = Vec::new();
+    for (from, to) in intervals {
+        match result.last_mut() {
+            Some(last) if from <= last.1 => last.1 = last.1.max(to),
+            _ => result.push((from, to)),
+        }
+    }
+    result
+}
+
+fn length(intervals: &[Interval]) -> f64 {
+    intervals.iter().map(|(from, to)| to - from).sum()
+}
+
+/// Common length of two sets of intervals, each without overlaps inside
+fn overlap(a: &[Interval], b: &[Interval]) -> f64 {
+    a.iter()
+        .cartesian_product(b)
+        .map(|(a, b)| (a.1.min(b.1) - a.0.max(b.0)).max(0.0))
+        .sum()
+}
+
+/// Points on the surface of a box, not further than `STEP` from each other
+fn surface_points(b: &OrientedBox) -> Vec<Vector3<f64>> {
+    let center = b.center.map(f64::from);
+    let axes = b.axes.map(|a| a.map(f64::from));
+    let half = b.half.map(f64::from);
+    let counts = half.map(|h| ((2.0 * h / STEP).ceil() as usize).max(1) + 1);
+    let coordinate =
+        |a: usize, i: usize| -half[a] + 2.0 * half[a] * i as f64 / (counts[a] - 1) as f64;
+
+    (0..counts[0])
+        .cartesian_product(0..counts[1])
+        .cartesian_product(0..counts[2])
+        .filter(|((i, j), k)| {
+            [(*i, 0), (*j, 1), (*k, 2)]
+                .iter()
+                .any(|(i, a)| *i == 0 || *i == counts[*a] - 1)
+        })
+        .map(|((i, j), k)| {
+            center
+                + axes[0] * coordinate(0, i)
+                + axes[1] * coordinate(1, j)
+                + axes[2] * coordinate(2, k)
+        })
+        .collect()
+}
+
+fn scratch_index() -> GeoIndex {
+    GeoIndex::new(Aabb::from_points(&[
+        Vector3::new(Dec::from(-50), Dec::from(-50), Dec::from(-50)),
+        Vector3::new(Dec::from(50), Dec::from(50), Dec::from(50)),
+    ]))
+    .input_polygon_min_rib_length(dec!(0.05))
+    .points_precision(dec!(0.001))
+}
+
+impl Component {
+    fn body(&self) -> OrientedBox {
+        OrientedBox::new(
+            &self.origin,
+            Dec::from(0),
+            self.depth,
+            self.width,
+            self.height,
+        )
+    }
+
+    fn envelope(&self) -> OrientedBox {
+        OrientedBox::new(
+            &self.origin,
+            Dec::from(0),
+            self.depth + self.clearance,
+            self.width + self.clearance * 2,
+            self.height + self.clearance * 2,
+        )
+    }
+}
+
+impl RightKeyboardConfig {
+    /// Switch body with hotswap socket below the plate
+    fn sockets(&self) -> Vec<(KeyPosition, OrientedBox)> {
+        let bottom = -self.main_plane_thickness / 2;
+        self.keys()
+            .filter(|(_, b)| b.kind != ButtonMountKind::Placeholder)
+            .map(|(key, b)| {
+                let envelope = b.kind.envelope();
+                let socket = OrientedBox::new(
+                    &b.origin,
+                    bottom - envelope.pocket_depth,
+                    bottom,
+                    envelope.pocket_width,
+                    envelope.pocket_height,
+                );
+                (key, socket)
+            })
+            .collect()
+    }
+
+    /// Material of bolts, which goes inside of the case
+    fn bosses(&self) -> anyhow::Result<Vec<Solid>> {
+        [KeyboardMesh::ButtonsHull, KeyboardMesh::Bottom]
+            .iter()
+            .flat_map(|mesh| self.material_on(*mesh))
+            .map(|(_, material)| Solid::polygonize(material.as_ref(), self.quality.surface_steps))
+            .collect()
+    }
+
+    /// Measures space inside of the case and checks, that all components fit there.
+    /// Returns error with list of problems, if any of components does not fit, so it is
+    /// worth calling before building meshes.
+    pub fn interior_report(&self) -> anyhow::Result<InteriorReport> {
+        let mut index = scratch_index();
+        let cavity_surface = self.cavity_surface(&mut index)?;
+        let cavity = Solid::new(&index, cavity_surface)?;
+        let sockets = self.sockets();
+        let bosses = self.bosses()?;
+
+        let (min, max) = cavity.bounds();
+        let columns = ((max - min) / STEP).map(|s| s.ceil() as usize);
+        let (mut cavity_volume, mut occupied_volume) = (0.0, 0.0);
+        for (i, j) in (0..columns.x).cartesian_product(0..columns.y) {
+            let x = min.x + (i as f64 + 0.5) * STEP;
+            let y = min.y + (j as f64 + 0.5) * STEP;
+            let inside = cavity.intervals_above_table(x, y);
+            if inside.is_empty() {
+                continue;
+            }
+            let occupied = union(
+                sockets
+                    .iter()
+                    .filter_map(|(_, s)| box_interval(s, x, y))
+                    .chain(bosses.iter().flat_map(|b| b.intervals(x, y)))
+                    .collect(),
+            );
+            cavity_volume += length(&inside) * STEP * STEP;
+            occupied_volume += overlap(&inside, &occupied) * STEP * STEP;
+        }
+
+        let mut problems = Vec::new();
+        for (n, component) in self.components.iter().enumerate() {
+            let envelope = component.envelope();
+            if !surface_points(&envelope)
+                .into_iter()
+                .all(|p| cavity.contains_above_table(p))
+            {
+                problems.push(format!("{} goes out of the case", component.name));
+            }
+            for (key, socket) in &sockets {
+                if envelope.intersects(socket) {
+                    problems.push(format!("{} hits switch {key:?}", component.name));
+                }
+            }
+            if bosses.iter().any(|b| b.hits(&envelope)) {
+                problems.push(format!("{} hits bolt boss", component.name));
+            }
+            for other in self.components.iter().skip(n + 1) {
+                if envelope.intersects(&other.body())
+                    || other.envelope().intersects(&component.body())
+                {
+                    problems.push(format!("{} hits {}", component.name, other.name));
+                }
+            }
+        }
+        if !problems.is_empty() {
+            return Err(anyhow!("Components do not fit: {}", problems.join("; ")));
+        }
+
+        Ok(InteriorReport {
+            cavity: Dec::from(cavity_volume).round_dp(0),
+            occupied: Dec::from(occupied_volume).round_dp(0),
+            free: Dec::from(cavity_volume - occupied_volume).round_dp(0),
+        })
+    }
+}
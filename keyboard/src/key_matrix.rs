@@ -0,0 +1,286 @@
+use anyhow::anyhow;
+use geometry::decimal::Dec;
+use itertools::Itertools;
+use nalgebra::{Vector2, Vector3};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{keyboard_config::RightKeyboardConfig, switch_clearance::KeyPosition};
+
+/// Crossing of two row or two column wires costs as much as this length of wire, in
+/// millimeters: it means a jumper or wire, going around a switch
+const CROSSING_PENALTY: f64 = 10.0;
+
+/// Distance between keys in QMK and ZMK layouts, in millimeters
+const KEY_UNIT: f64 = 19.05;
+
+/// Improvement passes over all keys, search usually settles much earlier
+const MAX_PASSES: usize = 50;
+
+/// Row and column wire of a switch in the matrix, counted from zero
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MatrixPosition {
+    pub row: usize,
+    pub column: usize,
+}
+
+/// Assignment of physical keys to switch matrix. Each row wire goes through its keys
+/// from left to right, each column wire - from front to back.
+#[derive(Clone, Debug)]
+pub struct KeyMatrix {
+    pub rows: usize,
+    pub columns: usize,
+    pub keys: Vec<(KeyPosition, MatrixPosition)>,
+    /// Length of all row and column wires between key centers, in millimeters
+    pub wire_length: Dec,
+    /// Crossings of row wires with other rows and column wires with other columns, seen
+    /// from above
+    pub crossings: usize,
+    centers: Vec<Vector3<f64>>,
+}
+
+/// Wires of the matrix as chains of key indices
+pub(crate) struct Wires {
+    pub(crate) rows: Vec<Vec<usize>>,
+    pub(crate) columns: Vec<Vec<usize>>,
+}
+
+impl Wires {
+    fn new(
+        slots: &[MatrixPosition],
+        centers: &[Vector3<f64>],
+        rows: usize,
+        columns: usize,
+    ) -> Self {
+        let chain = |along: usize, wire: &dyn Fn(&MatrixPosition) -> bool| {
+            slots
+                .iter()
+                .enumerate()
+                .filter(|(_, s)| wire(s))
+                .map(|(ix, _)| ix)
+                .sorted_by(|a, b| centers[*a][along].total_cmp(&centers[*b][along]))
+                .collect_vec()
+        };
+        Self {
+            rows: (0..rows).map(|r| chain(0, &|s| s.row == r)).collect(),
+            columns: (0..columns).map(|c| chain(1, &|s| s.column == c)).collect(),
+        }
+    }
+
+    fn length(&self, centers: &[Vector3<f64>]) -> f64 {
+        self.rows
+            .iter()
+            .chain(&self.columns)
+            .flat_map(|w| w.iter().tuple_windows())
+            .map(|(a, b)| (centers[*b] - centers[*a]).norm())
+            .sum()
+    }
+
+    fn crossings(&self, centers: &[Vector3<f64>]) -> usize {
+        [&self.rows, &self.columns]
+            .into_iter()
+            .map(|wires| {
+                let segments = wires
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(w, keys)| {
+                        keys.iter().tuple_windows().map(move |(a, b)| (w, *a, *b))
+                    })
+                    .collect_vec();
+                segments
+                    .iter()
+                    .tuple_combinations()
+                    .filter(|((w1, a, b), (w2, c, d))| {
+                        w1 != w2
+                            && segments_cross(
+                                centers[*a].xy(),
+                                centers[*b].xy(),
+                                centers[*c].xy(),
+                                centers[*d].xy(),
+                            )
+                    })
+                    .count()
+            })
+            .sum()
+    }
+
+    fn cost(&self, centers: &[Vector3<f64>]) -> f64 {
+        self.length(centers) + self.crossings(centers) as f64 * CROSSING_PENALTY
+    }
+}
+
+/// Proper crossing of two segments, touching ends do not count
+fn segments_cross(a: Vector2<f64>, b: Vector2<f64>, c: Vector2<f64>, d: Vector2<f64>) -> bool {
+    let side = |p: Vector2<f64>, q: Vector2<f64>, r: Vector2<f64>| (q - p).perp(&(r - p));
+    let (d1, d2) = (side(a, b, c), side(a, b, d));
+    let (d3, d4) = (side(c, d, a), side(c, d, b));
+    d1 * d2 < 0.0 && d3 * d4 < 0.0
+}
+
+impl RightKeyboardConfig {
+    /// Assigns keys to switch matrix of `rows` x `columns` wires, so that wires are short
+    /// and do not cross each other. Starts from keys, split into rows from back to front,
+    /// and improves it by moving keys between matrix positions, while total cost goes down.
+    pub fn key_matrix(&self, rows: usize, columns: usize) -> anyhow::Result<KeyMatrix> {
+        let (positions, centers): (Vec<_>, Vec<_>) = self
+            .keys()
+            .map(|(key, button)| (key, button.origin.center.map(f64::from)))
+            .unzip();
+        if positions.len() > rows * columns {
+            return Err(anyhow!(
+                "{} keys do not fit into {rows} x {columns} matrix",
+                positions.len()
+            ));
+        }
+
+        let mut slots = vec![MatrixPosition { row: 0, column: 0 }; positions.len()];
+        let by_y = (0..positions.len())
+            .sorted_by(|a, b| centers[*b].y.total_cmp(&centers[*a].y))
+            .collect_vec();
+        for row in 0..rows {
+            let from = row * positions.len() / rows;
+            let to = (row + 1) * positions.len() / rows;
+            let row_keys = by_y[from..to]
+                .iter()
+                .sorted_by(|a, b| centers[**a].x.total_cmp(&centers[**b].x));
+            for (column, key) in row_keys.enumerate() {
+                slots[*key] = MatrixPosition { row, column };
+            }
+        }
+
+        let all_slots = (0..rows)
+            .cartesian_product(0..columns)
+            .map(|(row, column)| MatrixPosition { row, column })
+            .collect_vec();
+        let mut cost = Wires::new(&slots, &centers, rows, columns).cost(&centers);
+        for _ in 0..MAX_PASSES {
+            let mut improved = false;
+            for key in 0..slots.len() {
+                for target in &all_slots {
+                    let mut candidate = slots.clone();
+                    if let Some(other) = slots.iter().position(|s| s == target) {
+                        candidate[other] = slots[key];
+                    }
+                    candidate[key] = *target;
+                    let candidate_cost =
+                        Wires::new(&candidate, &centers, rows, columns).cost(&centers);
+                    if candidate_cost < cost - 1e-9 {
+                        slots = candidate;
+                        cost = candidate_cost;
+                        improved = true;
+                    }
+                }
+            }
+            if !improved {
+                break;
+            }
+        }
+
+        let wires = Wires::new(&slots, &centers, rows, columns);
+        Ok(KeyMatrix {
+            rows,
+            columns,
+            keys: positions.into_iter().zip(slots).collect(),
+            wire_length: Dec::from(wires.length(&centers)).round_dp(1),
+            crossings: wires.crossings(&centers),
+            centers,
+        })
+    }
+}
+
+impl KeyMatrix {
+    pub(crate) fn wires(&self) -> Wires {
+        let slots = self.keys.iter().map(|(_, s)| *s).collect_vec();
+        Wires::new(&slots, &self.centers, self.rows, self.columns)
+    }
+
+    /// Keys in layout units of 19.05mm: x goes to the right and y goes to the typist,
+    /// counted from the far left key
+    fn layout_units(&self) -> Vec<Vector2<f64>> {
+        let left = self.centers.iter().map(|c| c.x).fold(f64::MAX, f64::min);
+        let back = self.centers.iter().map(|c| c.y).fold(f64::MIN, f64::max);
+        self.centers
+            .iter()
+            .map(|c| Vector2::new(c.x - left, back - c.y) / KEY_UNIT)
+            .collect()
+    }
+
+    /// Top view of keys with row wires in red and column wires in blue, for hand wiring
+    pub fn wiring_svg(&self) -> String {
+        let margin = KEY_UNIT;
+        let points = self
+            .layout_units()
+            .into_iter()
+            .map(|p| p * KEY_UNIT + Vector2::new(margin, margin))
+            .collect_vec();
+        let width = points.iter().map(|p| p.x).fold(0.0, f64::max) + margin;
+        let height = points.iter().map(|p| p.y).fold(0.0, f64::max) + margin;
+
+        let wires = self.wires();
+        let polyline = |keys: &Vec<usize>, color: &str| {
+            let path = keys
+                .iter()
+                .map(|k| format!("{:.2},{:.2}", points[*k].x, points[*k].y))
+                .join(" ");
+            format!("<polyline points=\"{path}\" fill=\"none\" stroke=\"{color}\" stroke-width=\"0.8\"/>")
+        };
+        let keys = self.keys.iter().zip(&points).map(|((_, s), p)| {
+            format!(
+                "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"2\"/><text x=\"{:.2}\" y=\"{:.2}\" font-size=\"4\">R{}C{}</text>",
+                p.x,
+                p.y,
+                p.x + 2.5,
+                p.y - 2.5,
+                s.row,
+                s.column
+            )
+        }).join("\n");
+
+        [
+            format!("<svg viewBox=\"0 0 {width:.2} {height:.2}\" xmlns=\"http://www.w3.org/2000/svg\" width=\"{width:.2}mm\" height=\"{height:.2}mm\">"),
+            wires.rows.iter().map(|w| polyline(w, "red")).join("\n"),
+            wires.columns.iter().map(|w| polyline(w, "blue")).join("\n"),
+            keys,
+            "</svg>".to_string(),
+        ]
+        .join("\n")
+    }
+
+    /// `info.json` for QMK with matrix size and `LAYOUT`, keys go in config order
+    pub fn qmk_info_json(&self) -> anyhow::Result<String> {
+        let layout = self
+            .keys
+            .iter()
+            .zip(self.layout_units())
+            .map(|((_, s), p)| {
+                json!({
+                    "matrix": [s.row, s.column],
+                    "x": (p.x * 100.0).round() / 100.0,
+                    "y": (p.y * 100.0).round() / 100.0,
+                })
+            })
+            .collect_vec();
+        Ok(serde_json::to_string_pretty(&json!({
+            "matrix_size": { "rows": self.rows, "cols": self.columns },
+            "layouts": { "LAYOUT": { "layout": layout } },
+        }))?)
+    }
+
+    /// Devicetree node of ZMK matrix transform, keys go in config order
+    pub fn zmk_matrix_transform(&self) -> String {
+        let map = self
+            .keys
+            .iter()
+            .map(|(_, s)| format!("RC({},{})", s.row, s.column))
+            .join(" ");
+        format!(
+            "default_transform: keymap_transform_0 {{\n    \
+                 compatible = \"zmk,matrix-transform\";\n    \
+                 columns = <{}>;\n    \
+                 rows = <{}>;\n    \
+                 map = <{map}>;\n\
+             }};\n",
+            self.columns, self.rows
+        )
+    }
+}
@@ -0,0 +1,78 @@
+use geometry::origin::Origin;
+use itertools::Itertools;
+use nalgebra::{Quaternion, UnitQuaternion, Vector3};
+use serde::Serialize;
+
+use crate::{
+    keyboard_config::RightKeyboardConfig,
+    switch_clearance::{Cluster, KeyPosition},
+};
+
+/// Place of switch for PCB and firmware tools, in millimeters and degrees
+#[derive(Serialize)]
+struct KeyRecord {
+    cluster: Cluster,
+    column: usize,
+    row: usize,
+    center: [f64; 3],
+    normal: [f64; 3],
+    /// Roll, pitch and yaw: rotations around x, y and z, applied in this order
+    rotation: [f64; 3],
+}
+
+impl KeyRecord {
+    fn new(key: KeyPosition, origin: &Origin) -> Self {
+        let center = origin.center.map(f64::from);
+        let normal = origin.z().map(f64::from);
+        let q = origin.rotation.coords.map(f64::from);
+        let rotation = UnitQuaternion::from_quaternion(Quaternion::from(q));
+        let (roll, pitch, yaw) = rotation.euler_angles();
+        Self {
+            cluster: key.cluster,
+            column: key.column,
+            row: key.row,
+            center: center.into(),
+            normal: normal.into(),
+            rotation: Vector3::new(roll, pitch, yaw).map(f64::to_degrees).into(),
+        }
+    }
+}
+
+impl RightKeyboardConfig {
+    /// Final origins of all buttons: center is in the middle of key plate, z axis looks
+    /// up along switch axis, x axis goes along the row.
+    pub fn export_key_positions(&self) -> Vec<(KeyPosition, Origin)> {
+        self.keys()
+            .map(|(key, button)| (key, button.origin.clone()))
+            .collect()
+    }
+
+    /// Key positions as CSV with header: one line per key, angles in degrees
+    pub fn key_positions_csv(&self) -> String {
+        let lines = self
+            .export_key_positions()
+            .iter()
+            .map(|(key, origin)| KeyRecord::new(*key, origin))
+            .map(|r| {
+                [r.center, r.normal, r.rotation]
+                    .iter()
+                    .flatten()
+                    .map(|v| format!("{v:.4}"))
+                    .fold(
+                        format!("{:?},{},{}", r.cluster, r.column, r.row),
+                        |line, v| format!("{line},{v}"),
+                    )
+            })
+            .join("\n");
+        format!("cluster,column,row,x,y,z,nx,ny,nz,roll,pitch,yaw\n{lines}\n")
+    }
+
+    pub fn key_positions_json(&self) -> anyhow::Result<String> {
+        let records = self
+            .export_key_positions()
+            .iter()
+            .map(|(key, origin)| KeyRecord::new(*key, origin))
+            .collect_vec();
+        Ok(serde_json::to_string_pretty(&records)?)
+    }
+}
@@ -4,13 +4,27 @@ use geometry::{
     decimal::Dec,
     geometry::GeometryDyn,
     hyper_path::{hyper_path::Root, hyper_point::SuperPoint},
+    origin::Origin,
+    text::Text,
 };
+use num_traits::Signed;
+use rust_decimal_macros::dec;
 
 use crate::{
     bolt_point::BoltPoint,
+    bumper::Bumper,
     button_collections::ButtonsCollection,
+    component::Component,
+    component_library::ComponentLibrary,
+    dampening_pocket::DampeningPocket,
     hole::Hole,
     keyboard_config::{KeyboardMesh, MaterialAddition, RightKeyboardConfig},
+    origin_anchor::OriginAnchor,
+    pogo_dock::PogoDock,
+    rim_lip::RimLip,
+    surface_quality::SurfaceQuality,
+    tenting_adapter::TentingAdapter,
+    tenting_feet::TentingFeet,
 };
 
 #[derive(Default)]
@@ -18,31 +32,82 @@ use crate::{
 pub struct KeyboardBuilder {
     main: Option<ButtonsCollection>,
     thumb: Option<ButtonsCollection>,
+    extra_collections: Vec<(String, ButtonsCollection)>,
     table_outline: Option<Root<SuperPoint<Dec>>>,
-    //bolts: Vec<BoltPoint>,
+    auto_outline: Option<(Dec, Dec)>,
+    bolts: Vec<(KeyboardMesh, KeyboardMesh, BoltPoint)>,
     wall_thickness: Dec,
     bottom_thickness: Dec,
     wall_extension: Dec,
     //bottom_holes: Vec<Hole>,
     //main_holes: Vec<Hole>,
     holes: HashMap<KeyboardMesh, Vec<Rc<dyn GeometryDyn>>>,
+    embossed: HashMap<KeyboardMesh, Vec<Rc<dyn GeometryDyn>>>,
     material: HashMap<KeyboardMesh, Vec<(MaterialAddition, Rc<dyn GeometryDyn>)>>,
+    quality: SurfaceQuality,
+    rim_lip: Option<RimLip>,
+    bumper: Option<Bumper>,
+    dampening_pocket: Option<DampeningPocket>,
+    components: Vec<Component>,
+    seam_at: Option<Dec>,
+    tenting_adapter: Option<TentingAdapter>,
+    tenting_feet: Option<TentingFeet>,
+    origin_at: Option<OriginAnchor>,
+    pogo_dock: Option<PogoDock>,
 }
 
 impl KeyboardBuilder {
-    pub fn build(self) -> RightKeyboardConfig {
-        let main_buttons = self.main.unwrap_or(ButtonsCollection::empty());
-        let thumb_buttons = self.thumb.unwrap_or(ButtonsCollection::empty());
+    pub fn build(mut self) -> RightKeyboardConfig {
+        let main_buttons = self.main.take().unwrap_or(ButtonsCollection::empty());
+        let thumb_buttons = self.thumb.take().unwrap_or(ButtonsCollection::empty());
 
-        RightKeyboardConfig {
+        // Quality is applied here, so it does not depend on order of calls
+        let bolts = std::mem::take(&mut self.bolts)
+            .into_iter()
+            .map(|(head_on, thread_on, b)| (head_on, thread_on, b.quality(self.quality)))
+            .collect();
+
+        let (table_outline, auto_outline) = match (self.table_outline.take(), self.auto_outline) {
+            (Some(outline), _) => (outline, None),
+            (None, Some(auto)) => (Root::new(), Some(auto)),
+            (None, None) => panic!("Must have outline on the table"),
+        };
+
+        let config = RightKeyboardConfig {
             main_buttons,
             thumb_buttons,
+            extra_collections: self.extra_collections,
             bottom_thickness: self.bottom_thickness,
             main_plane_thickness: self.wall_thickness,
-            table_outline: self.table_outline.expect("Must have outline on the table"),
-            //bolt_points: self.bolts,
+            table_outline,
             holes: self.holes.into_iter().collect(),
+            embossed: self.embossed,
             additional_material: self.material,
+            quality: self.quality,
+            rim_lip: self.rim_lip,
+            bumper: self.bumper,
+            dampening_pocket: self.dampening_pocket,
+            components: self.components,
+            seam_at: self.seam_at,
+            tenting_adapter: self.tenting_adapter,
+            tenting_feet: self.tenting_feet,
+            pogo_dock: self.pogo_dock,
+            mcu_mounts: Vec::new(),
+            trrs_jacks: Vec::new(),
+            display_windows: Vec::new(),
+            battery_bay: None,
+            wrist_rest: None,
+            bolts,
+        };
+        let config = match auto_outline {
+            Some((margin, corner_radius)) => config
+                .auto_outline(margin, corner_radius)
+                .expect("Outline around buttons"),
+            None => config,
+        };
+        match self.origin_at {
+            Some(anchor) => config.origin_at(anchor),
+            None => config,
         }
     }
 
@@ -56,49 +121,141 @@ impl KeyboardBuilder {
         self
     }
 
+    /// Text on the surface of `on` mesh. `face` is a point on the surface, where text
+    /// starts, its z axis looks out of the material. Positive depth raises text above
+    /// the surface, negative engraves it.
+    pub fn emboss_label(
+        mut self,
+        on: KeyboardMesh,
+        face: Origin,
+        text: Text,
+        depth: impl Into<Dec>,
+    ) -> Self {
+        let depth = depth.into();
+        // Text goes a bit through the surface to avoid coplanar polygons
+        let sw = Dec::from(dec!(0.1));
+        if depth.is_negative() {
+            let text = text.origin(face.offset_z(sw)).depth(depth - sw);
+            save_index(&mut self.holes, on, rc(text));
+        } else {
+            let text = text.origin(face.offset_z(-sw)).depth(depth + sw);
+            save_index(&mut self.embossed, on, rc(text));
+        }
+        self
+    }
+
     pub fn add_bolt(
         mut self,
         head_on: KeyboardMesh,
         thread_on: KeyboardMesh,
         bolt_point: BoltPoint,
     ) -> Self {
-        let head_material = (
-            MaterialAddition::InnerSurface,
-            rc(bolt_point.get_head_material()),
-        );
-        let tail_material = (
-            MaterialAddition::InnerSurface,
-            rc(bolt_point.get_tail_material()),
-        );
-        save_index(&mut self.material, head_on, head_material);
-        save_index(&mut self.material, thread_on, tail_material);
-
-        save_index(&mut self.holes, head_on, rc(bolt_point.get_head_hole()));
-        save_index(
-            &mut self.holes,
-            head_on,
-            rc(bolt_point.get_head_thread_hole()),
-        );
-
-        if let Some(nut) = bolt_point.get_tail_nut_hole() {
-            save_index(&mut self.holes, thread_on, rc(nut));
-        }
+        self.bolts.push((head_on, thread_on, bolt_point));
+        self
+    }
+
+    /// Smoothness of all curved parts: walls, bolts and holes for them.
+    pub fn quality(mut self, quality: SurfaceQuality) -> Self {
+        self.quality = quality;
+        self
+    }
+
+    /// Groove in buttons hull and matching tongue on bottom plate
+    pub fn rim_lip(mut self, rim_lip: RimLip) -> Self {
+        self.rim_lip = Some(rim_lip);
+        self
+    }
+
+    /// Bumper ring around the case and groove for it in buttons hull
+    pub fn bumper(mut self, bumper: Bumper) -> Self {
+        self.bumper = Some(bumper);
+        self
+    }
+
+    /// Pockets for sound dampening sheet under key plates
+    pub fn dampening_pocket(mut self, dampening_pocket: DampeningPocket) -> Self {
+        self.dampening_pocket = Some(dampening_pocket);
+        self
+    }
+
+    /// Preferred place of layer seam on the outer wall, by table outline parameter:
+    /// integer part is number of outline line, fractional part - position on the line.
+    pub fn seam(mut self, at: impl Into<Dec>) -> Self {
+        self.seam_at = Some(at.into());
+        self
+    }
 
-        save_index(
-            &mut self.holes,
-            thread_on,
-            rc(bolt_point.get_tail_thread_hole()),
-        );
+    /// Plate for tenting hardware, bolted under bottom plate. See `tenting_adapter`.
+    pub fn tenting_adapter(mut self, tenting_adapter: TentingAdapter) -> Self {
+        self.tenting_adapter = Some(tenting_adapter);
+        self
+    }
 
-        // self.bolts.push(bolt_point);
+    /// Threaded bosses in bottom plate for screw-in feet. See `tenting_feet`.
+    pub fn tenting_feet(mut self, tenting_feet: TentingFeet) -> Self {
+        self.tenting_feet = Some(tenting_feet);
         self
     }
 
+    /// Spring pin dock for detachable module in the outer wall. See `pogo_plug`.
+    pub fn pogo_dock(mut self, pogo_dock: PogoDock) -> Self {
+        self.pogo_dock = Some(pogo_dock);
+        self
+    }
+
+    /// Moves layout so that `anchor` is at zero, see `RightKeyboardConfig::origin_at`
+    pub fn origin_at(mut self, anchor: OriginAnchor) -> Self {
+        self.origin_at = Some(anchor);
+        self
+    }
+
+    /// Part inside of the case, which is checked to fit by `interior_report`
+    pub fn component(mut self, component: Component) -> Self {
+        self.components.push(component);
+        self
+    }
+
+    /// Places part from `library` so, that its `anchor` is at `at`. Material and holes of
+    /// the part go to keyboard meshes, its body is checked by `interior_report`.
+    pub fn place_component(
+        mut self,
+        library: &ComponentLibrary,
+        reference: &str,
+        anchor: &str,
+        at: Origin,
+    ) -> anyhow::Result<Self> {
+        let definition = library.get(reference)?;
+        let origin = definition.placement(anchor, &at)?;
+        for (on, shape) in &definition.material {
+            save_index(
+                &mut self.material,
+                *on,
+                (MaterialAddition::InnerSurface, shape(&origin)),
+            );
+        }
+        for (on, shape) in &definition.holes {
+            save_index(&mut self.holes, *on, shape(&origin));
+        }
+        if let Some(mut body) = definition.body.clone() {
+            body.name = reference.to_string();
+            body.origin.apply(&origin);
+            self.components.push(body);
+        }
+        Ok(self)
+    }
+
     pub fn table_outline(mut self, hp: Root<SuperPoint<Dec>>) -> Self {
         self.table_outline = Some(hp);
         self
     }
 
+    /// Outline around buttons, see `RightKeyboardConfig::auto_outline`. It is used only
+    /// when there is no `table_outline`.
+    pub fn auto_outline(mut self, margin: impl Into<Dec>, corner_radius: impl Into<Dec>) -> Self {
+        self.auto_outline = Some((margin.into(), corner_radius.into()));
+        self
+    }
+
     pub fn bottom_thickness(mut self, bottom_thickness: impl Into<Dec>) -> Self {
         self.bottom_thickness = bottom_thickness.into();
         self
@@ -114,6 +271,23 @@ impl KeyboardBuilder {
         self
     }
 
+    /// Collection besides main and thumb ones, like inner index cluster or macro column,
+    /// moved to `origin`. It gets its own key plate, which is united with buttons hull, so
+    /// it must touch or cross the hull. Walls do not go around it, unless table outline
+    /// is made with `auto_outline`.
+    pub fn collection(
+        mut self,
+        name: impl Into<String>,
+        origin: Origin,
+        mut collection: ButtonsCollection,
+    ) -> Self {
+        for column in collection.columns.iter_mut() {
+            column.apply_origin(&origin);
+        }
+        self.extra_collections.push((name.into(), collection));
+        self
+    }
+
     pub fn wall_thickness(mut self, wall_thickness: impl Into<Dec>) -> Self {
         self.wall_thickness = wall_thickness.into();
         self
@@ -21,17 +21,24 @@ use geometry::{
         index::{GeoIndex, PolygonFilter},
         mesh::{MeshId, MeshRefMut},
     },
+    shapes::Cylinder,
 };
-use itertools::Itertools;
+use itertools::{EitherOrBoth, Itertools};
 use nalgebra::Vector3;
+use num_traits::Zero;
 use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    button_collections::ButtonsCollection, keyboard_builder::KeyboardBuilder,
-    next_and_peek::NextAndPeekBlank,
+    battery_bay::BatteryBay, bolt_point::BoltPoint, bumper::Bumper,
+    button_collections::ButtonsCollection, component::Component, dampening_pocket::DampeningPocket,
+    display_window::DisplayWindow, keyboard_builder::KeyboardBuilder, mcu::McuMount,
+    next_and_peek::NextAndPeekBlank, pogo_dock::PogoDock, rim_lip::RimLip,
+    surface_quality::SurfaceQuality, switch_clearance::Cluster, tenting_adapter::TentingAdapter,
+    tenting_feet::TentingFeet, trrs_jack::TrrsJack, wrist_rest::WristRest,
 };
 
-#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
 pub enum KeyboardMesh {
     ButtonsHull,
     Bottom,
@@ -46,17 +53,47 @@ pub enum MaterialAddition {
     Both,
 }
 
+/// Config can be saved and loaded with serde. Holes, labels and material, given as
+/// geometry, are not saved: they are code, not data, and must be added again.
 #[allow(clippy::type_complexity)]
+#[derive(Serialize, Deserialize)]
 pub struct RightKeyboardConfig {
     pub(crate) main_buttons: ButtonsCollection,
     pub(crate) thumb_buttons: ButtonsCollection,
+    /// Named collections besides main and thumb ones. Each has its own key plate, which
+    /// is united with buttons hull.
+    #[serde(default)]
+    pub(crate) extra_collections: Vec<(String, ButtonsCollection)>,
     pub(crate) table_outline: Root<SuperPoint<Dec>>,
     pub(crate) main_plane_thickness: Dec,
     pub(crate) bottom_thickness: Dec,
+    #[serde(skip)]
     pub(crate) additional_material:
         HashMap<KeyboardMesh, Vec<(MaterialAddition, Rc<dyn GeometryDyn>)>>,
 
+    #[serde(skip)]
     pub(crate) holes: HashMap<KeyboardMesh, Vec<Rc<dyn GeometryDyn>>>,
+    #[serde(skip)]
+    pub(crate) embossed: HashMap<KeyboardMesh, Vec<Rc<dyn GeometryDyn>>>,
+    /// Saved with config, so loaded one is rendered as smooth as it was designed
+    #[serde(default)]
+    pub(crate) quality: SurfaceQuality,
+    pub(crate) rim_lip: Option<RimLip>,
+    pub(crate) bumper: Option<Bumper>,
+    pub(crate) dampening_pocket: Option<DampeningPocket>,
+    pub(crate) components: Vec<Component>,
+    pub(crate) seam_at: Option<Dec>,
+    pub(crate) tenting_adapter: Option<TentingAdapter>,
+    pub(crate) tenting_feet: Option<TentingFeet>,
+    pub(crate) pogo_dock: Option<PogoDock>,
+    pub(crate) mcu_mounts: Vec<McuMount>,
+    pub(crate) trrs_jacks: Vec<TrrsJack>,
+    pub(crate) display_windows: Vec<DisplayWindow>,
+    pub(crate) battery_bay: Option<BatteryBay>,
+    pub(crate) wrist_rest: Option<WristRest>,
+    /// Bolts with meshes of their heads and threads. Holes and material for them are
+    /// made from bolt points when meshes are built.
+    pub(crate) bolts: Vec<(KeyboardMesh, KeyboardMesh, BoltPoint)>,
 }
 
 impl RightKeyboardConfig {
@@ -64,6 +101,68 @@ impl RightKeyboardConfig {
         KeyboardBuilder::default()
     }
 
+    /// Underside of bottom plate is this much under the table outline
+    pub fn bottom_thickness(&self) -> Dec {
+        self.bottom_thickness
+    }
+
+    /// Smoothness of all curved parts: walls, bolts and holes for them
+    pub fn quality(&self) -> SurfaceQuality {
+        self.quality
+    }
+
+    /// Same config rendered with other smoothness, e.g. draft preview of loaded one
+    pub fn with_quality(mut self, quality: SurfaceQuality) -> Self {
+        self.quality = quality;
+        self
+    }
+
+    /// Intermediate design between this config (`t = 0`) and `other` (`t = 1`).
+    /// Both configs must have same structure: amount of columns and buttons, button kinds
+    /// and outline lines. Holes, labels and additional material are taken from this config.
+    pub fn lerp(&self, other: &RightKeyboardConfig, t: impl Into<Dec>) -> anyhow::Result<Self> {
+        let t = t.into();
+        let lerp = |a: Dec, b: Dec| a + (b - a) * t;
+        Ok(Self {
+            main_buttons: self.main_buttons.lerp(&other.main_buttons, t)?,
+            thumb_buttons: self.thumb_buttons.lerp(&other.thumb_buttons, t)?,
+            extra_collections: self
+                .extra_collections
+                .iter()
+                .zip_longest(&other.extra_collections)
+                .map(|pair| match pair {
+                    EitherOrBoth::Both((name, a), (other_name, b)) if name == other_name => {
+                        Ok((name.clone(), a.lerp(b, t)?))
+                    }
+                    _ => Err(anyhow!(
+                        "Cannot blend configs with different named collections"
+                    )),
+                })
+                .collect::<anyhow::Result<_>>()?,
+            table_outline: self.table_outline.lerp(&other.table_outline, t)?,
+            main_plane_thickness: lerp(self.main_plane_thickness, other.main_plane_thickness),
+            bottom_thickness: lerp(self.bottom_thickness, other.bottom_thickness),
+            additional_material: self.additional_material.clone(),
+            holes: self.holes.clone(),
+            embossed: self.embossed.clone(),
+            quality: self.quality,
+            rim_lip: self.rim_lip,
+            bumper: self.bumper,
+            dampening_pocket: self.dampening_pocket,
+            components: self.components.clone(),
+            seam_at: self.seam_at,
+            tenting_adapter: self.tenting_adapter,
+            tenting_feet: self.tenting_feet.clone(),
+            pogo_dock: self.pogo_dock.clone(),
+            mcu_mounts: self.mcu_mounts.clone(),
+            trrs_jacks: self.trrs_jacks.clone(),
+            display_windows: self.display_windows.clone(),
+            battery_bay: self.battery_bay.clone(),
+            wrist_rest: self.wrist_rest.clone(),
+            bolts: self.bolts.clone(),
+        })
+    }
+
     fn right_line_inner(&self) -> impl Iterator<Item = SuperPoint<Dec>> + '_ {
         self.main_buttons
             .right_line_inner(self.main_plane_thickness)
@@ -363,7 +462,8 @@ impl RightKeyboardConfig {
             );
         }
 
-        DynamicSurface::new(around_buttons, outline).polygonize(&mut mesh, 8)?;
+        DynamicSurface::new(around_buttons, outline)
+            .polygonize(&mut mesh, self.quality.surface_steps)?;
         Ok(())
     }
 
@@ -382,11 +482,18 @@ impl RightKeyboardConfig {
             );
         }
 
-        DynamicSurface::new(outline, around_buttons).polygonize(&mut mesh, 8)?;
+        DynamicSurface::new(outline, around_buttons)
+            .polygonize(&mut mesh, self.quality.surface_steps)?;
         Ok(())
     }
 
     pub(crate) fn fill_between_collections(&self, mesh: &mut MeshRefMut) -> anyhow::Result<()> {
+        self.fill_between_collections_inner(mesh)?;
+        self.fill_between_collections_outer(mesh)?;
+        Ok(())
+    }
+
+    fn fill_between_collections_inner(&self, mesh: &mut MeshRefMut) -> anyhow::Result<()> {
         let right_line_inner = self
             .thumb_buttons
             .right_line_inner(self.main_plane_thickness)
@@ -400,6 +507,12 @@ impl RightKeyboardConfig {
             .next_and_peek(|a, b| HyperLine::new_2(*a, *b))
             .fold(Root::new(), |hp, l| hp.push_back(l));
 
+        DynamicSurface::new(right_line_inner, left_line_inner)
+            .polygonize(mesh, self.quality.surface_steps)?;
+        Ok(())
+    }
+
+    fn fill_between_collections_outer(&self, mesh: &mut MeshRefMut) -> anyhow::Result<()> {
         let right_line_outer = self
             .thumb_buttons
             .right_line_outer(self.main_plane_thickness)
@@ -413,8 +526,8 @@ impl RightKeyboardConfig {
             .next_and_peek(|a, b| HyperLine::new_2(*a, *b))
             .fold(Root::new(), |hp, l| hp.push_back(l));
 
-        DynamicSurface::new(right_line_inner, left_line_inner).polygonize(mesh, 8)?;
-        DynamicSurface::new(left_line_outer, right_line_outer).polygonize(mesh, 8)?;
+        DynamicSurface::new(left_line_outer, right_line_outer)
+            .polygonize(mesh, self.quality.surface_steps)?;
         Ok(())
     }
 
@@ -465,7 +578,8 @@ impl RightKeyboardConfig {
             outline = fs;
             shifted_outline = ss;
 
-            PrimitiveSurface(s.to_points(), f.to_points()).polygonize(&mut mesh, 8)?;
+            PrimitiveSurface(s.to_points(), f.to_points())
+                .polygonize(&mut mesh, self.quality.surface_steps)?;
             if outline.len() == 0 {
                 break;
             }
@@ -485,7 +599,8 @@ impl RightKeyboardConfig {
             line_one = fs;
             line_two = ss;
 
-            PrimitiveSurface(s.to_points(), f.to_points()).polygonize(&mut mesh, 8)?;
+            PrimitiveSurface(s.to_points(), f.to_points())
+                .polygonize(&mut mesh, self.quality.surface_steps)?;
             if line_one.len() == 0 {
                 break;
             }
@@ -494,6 +609,216 @@ impl RightKeyboardConfig {
         Ok(())
     }
 
+    /// Closed ring along table outline. Walls are shifted inside of outline by
+    /// `inner` and `outer` (negative - outside), ring spans from `bottom` to `top` by z.
+    fn outline_band(
+        &self,
+        index: &mut GeoIndex,
+        (inner, outer): (Dec, Dec),
+        (bottom, top): (Dec, Dec),
+    ) -> anyhow::Result<MeshId> {
+        self.outline_sweep(
+            index,
+            &[(outer, bottom), (outer, top), (inner, top), (inner, bottom)],
+        )
+    }
+
+    /// Closed ring along table outline with given profile. Each profile point is
+    /// (shift inside of outline, z). Profile goes counter-clockwise, when looking
+    /// along the outline, so it starts going up on the outer side.
+    fn outline_sweep(
+        &self,
+        index: &mut GeoIndex,
+        profile: &[(Dec, Dec)],
+    ) -> anyhow::Result<MeshId> {
+        let line = |(shift, z): (Dec, Dec)| {
+            let mut line = self.table_outline.clone().map(|l| {
+                l.shift_in_plane(Vector3::z(), shift).map(|mut t| {
+                    t.point += Vector3::z() * z;
+                    t
+                })
+            });
+            line.connect_ends_circular();
+            line
+        };
+
+        let ring = index.new_mesh();
+        for (from, to) in profile.iter().circular_tuple_windows() {
+            self.connect_two_lines(ring.make_mut_ref(index), line(*from), line(*to))?;
+        }
+        Ok(ring)
+    }
+
+    /// Groove in the bottom of hull walls
+    fn rim_groove(&self, hull: MeshId, index: &mut GeoIndex) -> anyhow::Result<()> {
+        let Some(lip) = self.rim_lip else {
+            return Ok(());
+        };
+        if lip.chamfer * 2 >= lip.width - lip.clearance * 2 {
+            return Err(anyhow!("Rim lip chamfer is wider, than its tongue"));
+        }
+        let middle = -self.main_plane_thickness / Dec::from(2);
+        let half_width = lip.width / Dec::from(2);
+        let (inner, outer) = (middle + half_width, middle - half_width);
+        let ch = lip.chamfer;
+        let groove = if ch.is_zero() {
+            self.outline_band(index, (inner, outer), (-lip.depth, lip.depth))?
+        } else {
+            self.outline_sweep(
+                index,
+                &[
+                    (outer - ch, -lip.depth),
+                    (outer - ch, Dec::zero()),
+                    (outer, ch),
+                    (outer, lip.depth),
+                    (inner, lip.depth),
+                    (inner, ch),
+                    (inner + ch, Dec::zero()),
+                    (inner + ch, -lip.depth),
+                ],
+            )?
+        };
+        Self::subtract_mesh(hull, groove, index)?;
+        Ok(())
+    }
+
+    /// Tongue on top of bottom plate, which fits into groove of hull
+    fn rim_tongue(&self, plate: MeshId, index: &mut GeoIndex) -> anyhow::Result<()> {
+        let Some(lip) = self.rim_lip else {
+            return Ok(());
+        };
+        let middle = -self.main_plane_thickness / Dec::from(2);
+        let half_width = lip.width / Dec::from(2) - lip.clearance;
+        let (inner, outer) = (middle + half_width, middle - half_width);
+        let (bottom, top) = (
+            -self.bottom_thickness / Dec::from(2),
+            lip.depth - lip.clearance,
+        );
+        let ch = lip.chamfer;
+        let tongue = if ch.is_zero() {
+            self.outline_band(index, (inner, outer), (bottom, top))?
+        } else {
+            self.outline_sweep(
+                index,
+                &[
+                    (outer, bottom),
+                    (outer, top - ch),
+                    (outer + ch, top),
+                    (inner - ch, top),
+                    (inner, top - ch),
+                    (inner, bottom),
+                ],
+            )?
+        };
+        Self::unite_mesh(plate, tongue, index)?;
+        Ok(())
+    }
+
+    fn bumper_params(&self) -> anyhow::Result<Bumper> {
+        let bumper = self.bumper.ok_or(anyhow!("Bumper is not configured"))?;
+        if bumper.lip + bumper.clearance >= self.main_plane_thickness {
+            return Err(anyhow!(
+                "Bumper lip with clearance must be thinner, than walls"
+            ));
+        }
+        Ok(bumper)
+    }
+
+    /// Groove in outer side of hull walls for upper flange of bumper
+    fn bumper_groove(&self, hull: MeshId, index: &mut GeoIndex) -> anyhow::Result<()> {
+        if self.bumper.is_none() {
+            return Ok(());
+        }
+        let bumper = self.bumper_params()?;
+        let groove = self.outline_band(
+            index,
+            (
+                bumper.lip + bumper.clearance - self.main_plane_thickness,
+                -self.main_plane_thickness - bumper.clearance,
+            ),
+            (
+                bumper.height - bumper.clearance,
+                bumper.height + bumper.thickness + bumper.clearance,
+            ),
+        )?;
+        Self::subtract_mesh(hull, groove, index)?;
+        Ok(())
+    }
+
+    /// Cuts dampening pockets in the bottom of key plates
+    fn dampening_pockets(&self, hull: MeshId, index: &mut GeoIndex) -> anyhow::Result<()> {
+        let Some(pocket) = self.dampening_pocket else {
+            return Ok(());
+        };
+        let cutter = index.new_mesh();
+        for (_, button) in self.keys() {
+            pocket
+                .pocket(button, self.main_plane_thickness)
+                .polygonize(cutter.make_mut_ref(index), 0)?;
+        }
+        Self::subtract_mesh(hull, cutter, index)?;
+        Ok(())
+    }
+
+    /// Bores for backlight LEDs of buttons, which have them
+    fn led_bores(&self, hull: MeshId, index: &mut GeoIndex) -> anyhow::Result<()> {
+        let sw = Dec::from(dec!(0.1));
+        let thickness = self.main_plane_thickness;
+        let bores = self
+            .keys()
+            .filter_map(|(_, button)| {
+                let led = button.led_bore?;
+                Some(Cylinder::with_top_at(
+                    button
+                        .origin
+                        .clone()
+                        .offset_x(led.offset.x)
+                        .offset_y(led.offset.y)
+                        .offset_z(thickness / 2 + sw),
+                    thickness + button.kind.envelope().pocket_depth + sw,
+                    led.diameter / 2,
+                ))
+            })
+            .collect_vec();
+        if bores.is_empty() {
+            return Ok(());
+        }
+        let cutter = index.new_mesh();
+        for bore in bores {
+            bore.polygonize(cutter.make_mut_ref(index), self.quality.surface_steps)?;
+        }
+        Self::subtract_mesh(hull, cutter, index)?;
+        Ok(())
+    }
+
+    /// Bumper ring, which snaps over assembled case. Separate part, printed of flexible
+    /// plastic.
+    pub fn bumper(&self, index: &mut GeoIndex) -> anyhow::Result<MeshId> {
+        let bumper = self.bumper_params()?;
+
+        let outer = -self.main_plane_thickness - bumper.clearance - bumper.thickness;
+        let side = -self.main_plane_thickness - bumper.clearance;
+        let lip = bumper.lip - self.main_plane_thickness;
+
+        let under_plate = -self.bottom_thickness - bumper.clearance;
+        let bottom = under_plate - bumper.thickness;
+        let top = bumper.height + bumper.thickness;
+
+        self.outline_sweep(
+            index,
+            &[
+                (outer, bottom),
+                (outer, top),
+                (lip, top),
+                (lip, bumper.height),
+                (side, bumper.height),
+                (side, under_plate),
+                (lip, under_plate),
+                (lip, bottom),
+            ],
+        )
+    }
+
     pub fn bottom_pad(&self, index: &mut GeoIndex) -> anyhow::Result<MeshId> {
         let plate_border = index.new_mesh();
 
@@ -544,11 +869,17 @@ impl RightKeyboardConfig {
 
         let bottom_plane = index.new_mesh();
         let poly = PolygonFromLineInPlane::new(outer_outline_lower, false);
-        poly.polygonize(&mut bottom_plane.make_mut_ref(index), 8)?;
+        poly.polygonize(
+            &mut bottom_plane.make_mut_ref(index),
+            self.quality.surface_steps,
+        )?;
 
         let top_plane = index.new_mesh();
         let poly = PolygonFromLineInPlane::new(inner_inside_extension_line, true);
-        poly.polygonize(&mut top_plane.make_mut_ref(index), 8)?;
+        poly.polygonize(
+            &mut top_plane.make_mut_ref(index),
+            self.quality.surface_steps,
+        )?;
 
         let inner_border = index.new_mesh();
         self.inner_wall_surface(inner_border.make_mut_ref(index))?;
@@ -580,10 +911,190 @@ impl RightKeyboardConfig {
             index.move_all_polygons(mat, plate_border);
         }
 
+        self.rim_tongue(plate_border, index)?;
+        self.apply_embossing(KeyboardMesh::Bottom, plate_border, index)?;
         self.apply_holes(KeyboardMesh::Bottom, plate_border, index)?;
         Ok(plate_border)
     }
 
+    /// Visible outer surface of buttons hull (plate between buttons and walls) as
+    /// separate shell of `thickness`. It lays on top of buttons hull, so it can be printed
+    /// with other plastic and glued over it. Has openings for buttons.
+    pub fn top_skin(
+        &self,
+        index: &mut GeoIndex,
+        thickness: impl Into<Dec>,
+    ) -> anyhow::Result<MeshId> {
+        let surface = index.new_mesh();
+
+        self.outer_wall_surface(surface.make_mut_ref(index))?;
+        {
+            let mut mesh = surface.make_mut_ref(index);
+            self.main_buttons
+                .fill_columns_outer(&mut mesh, self.main_plane_thickness)?;
+            self.thumb_buttons
+                .fill_columns_outer(&mut mesh, self.main_plane_thickness)?;
+            self.main_buttons
+                .fill_between_columns_outer(&mut mesh, self.main_plane_thickness)?;
+            self.thumb_buttons
+                .fill_between_columns_outer(&mut mesh, self.main_plane_thickness)?;
+            self.fill_between_collections_outer(&mut mesh)?;
+        }
+
+        // Negative thickness - skin grows outside of hull
+        let thickness: Dec = thickness.into();
+        let skin = index.thicken_surface(surface, -thickness)?;
+        for p in index.get_mesh_polygons(surface) {
+            p.make_mut_ref(index).remove();
+        }
+
+        self.apply_holes(KeyboardMesh::ButtonsHull, skin, index)?;
+        Ok(skin)
+    }
+
+    /// Key plate alone: buttons with their cutouts and fillers between them, closed along
+    /// the edge. No walls and no bottom, so it is fast to build and print for fit tests.
+    pub fn key_plate(&self, index: &mut GeoIndex) -> anyhow::Result<MeshId> {
+        let plate = index.new_mesh();
+        for button in self.buttons(index)? {
+            index.move_all_polygons(button, plate);
+        }
+        self.fill_between_buttons(plate.make_mut_ref(index))?;
+        DynamicSurface::new(
+            self.line_around_buttons_inner(),
+            self.line_around_buttons_outer(),
+        )
+        .polygonize(&mut plate.make_mut_ref(index), self.quality.surface_steps)?;
+        for extra in self.extra_collection_plates(index)? {
+            Self::unite_mesh(plate, extra, index)?;
+        }
+        Ok(plate)
+    }
+
+    /// Inner surface of buttons hull: inner side of walls, fillers between buttons and
+    /// bottoms of button plates. Together with the table it closes the cavity of the case.
+    pub(crate) fn cavity_surface(&self, index: &mut GeoIndex) -> anyhow::Result<MeshId> {
+        let surface = index.new_mesh();
+
+        self.inner_wall_surface(surface.make_mut_ref(index))?;
+        let mut mesh = surface.make_mut_ref(index);
+        self.main_buttons
+            .fill_columns_inner(&mut mesh, self.main_plane_thickness)?;
+        self.thumb_buttons
+            .fill_columns_inner(&mut mesh, self.main_plane_thickness)?;
+        self.main_buttons
+            .fill_between_columns_inner(&mut mesh, self.main_plane_thickness)?;
+        self.thumb_buttons
+            .fill_between_columns_inner(&mut mesh, self.main_plane_thickness)?;
+        self.fill_between_collections_inner(&mut mesh)?;
+
+        let thickness = self.main_plane_thickness;
+        for (_, button) in self
+            .keys()
+            .filter(|(key, _)| !matches!(key.cluster, Cluster::Named(_)))
+        {
+            mesh.add_polygon(&[
+                button.inner_right_top(thickness),
+                button.inner_right_bottom(thickness),
+                button.inner_left_bottom(thickness),
+                button.inner_left_top(thickness),
+            ])?;
+        }
+
+        Ok(surface)
+    }
+
+    /// Additional material of `mesh` and material around bolts, which go through it
+    pub(crate) fn material_on(
+        &self,
+        mesh: KeyboardMesh,
+    ) -> Vec<(MaterialAddition, Rc<dyn GeometryDyn>)> {
+        let mut material = self
+            .additional_material
+            .get(&mesh)
+            .cloned()
+            .unwrap_or_default();
+        for (head_on, thread_on, bolt_point) in &self.bolts {
+            if *head_on == mesh {
+                material.push((
+                    MaterialAddition::InnerSurface,
+                    Rc::new(bolt_point.get_head_material()),
+                ));
+            }
+            if *thread_on == mesh {
+                material.push((
+                    MaterialAddition::InnerSurface,
+                    Rc::new(bolt_point.get_tail_material()),
+                ));
+            }
+        }
+        if let (KeyboardMesh::ButtonsHull, Some(dock)) = (mesh, &self.pogo_dock) {
+            material.push((MaterialAddition::InnerSurface, dock.keyboard_material()));
+        }
+        if mesh == KeyboardMesh::ButtonsHull {
+            for mount in &self.mcu_mounts {
+                material.push((MaterialAddition::InnerSurface, mount.material()));
+            }
+            for jack in &self.trrs_jacks {
+                material.push((MaterialAddition::InnerSurface, jack.material()));
+            }
+            for display in &self.display_windows {
+                material.push((MaterialAddition::InnerSurface, display.material()));
+            }
+        }
+        if let (KeyboardMesh::Bottom, Some(bay)) = (mesh, &self.battery_bay) {
+            material.push((MaterialAddition::InnerSurface, bay.material()));
+        }
+        if let (KeyboardMesh::Bottom, Some(feet)) = (mesh, &self.tenting_feet) {
+            material.extend(
+                feet.material(self)
+                    .into_iter()
+                    .map(|m| (MaterialAddition::InnerSurface, m)),
+            );
+        }
+        material
+    }
+
+    /// Holes of `mesh` and holes for bolts, which go through it
+    fn holes_in(&self, mesh: KeyboardMesh) -> Vec<Rc<dyn GeometryDyn>> {
+        let mut holes = self.holes.get(&mesh).cloned().unwrap_or_default();
+        for (head_on, thread_on, bolt_point) in &self.bolts {
+            if *head_on == mesh {
+                holes.push(Rc::new(bolt_point.get_head_hole()));
+                holes.push(Rc::new(bolt_point.get_head_thread_hole()));
+            }
+            if *thread_on == mesh {
+                if let Some(nut) = bolt_point.get_tail_nut_hole() {
+                    holes.push(Rc::new(nut));
+                }
+                holes.extend(bolt_point.get_tail_insert_holes());
+                holes.push(bolt_point.get_tail_thread_hole());
+            }
+        }
+        if let (KeyboardMesh::ButtonsHull, Some(dock)) = (mesh, &self.pogo_dock) {
+            holes.extend(dock.keyboard_holes());
+        }
+        if mesh == KeyboardMesh::ButtonsHull {
+            for mount in &self.mcu_mounts {
+                holes.extend(mount.holes());
+            }
+            for jack in &self.trrs_jacks {
+                holes.extend(jack.holes());
+            }
+            for display in &self.display_windows {
+                holes.extend(display.holes());
+            }
+        }
+        if let (KeyboardMesh::Bottom, Some(bay)) = (mesh, &self.battery_bay) {
+            let mcu = self.mcu_mounts.first().map(|m| &m.origin);
+            holes.extend(bay.holes(self.bottom_thickness, mcu));
+        }
+        if let (KeyboardMesh::Bottom, Some(feet)) = (mesh, &self.tenting_feet) {
+            holes.extend(feet.holes(self));
+        }
+        holes
+    }
+
     pub fn pcb_mount(&self, _index: &mut GeoIndex) -> anyhow::Result<MeshId> {
         Err(anyhow!("not implemented"))
     }
@@ -596,19 +1107,15 @@ impl RightKeyboardConfig {
         index: &mut GeoIndex,
     ) -> anyhow::Result<Vec<MeshId>> {
         let material_polygons = self
-            .additional_material
-            .iter()
-            .flat_map(|mat| {
-                if to == *mat.0 {
-                    (*mat.1).clone()
-                } else {
-                    Vec::new()
-                }
-            })
+            .material_on(to)
+            .into_iter()
             .filter_map(|(addition, material)| {
                 let bolt_head_material = index.new_mesh();
                 material
-                    .polygonize(bolt_head_material.make_mut_ref(index), 0)
+                    .polygonize(
+                        bolt_head_material.make_mut_ref(index),
+                        self.quality.surface_steps,
+                    )
                     .ok()?;
 
                 match addition {
@@ -673,28 +1180,46 @@ impl RightKeyboardConfig {
         to_mesh: MeshId,
         index: &mut GeoIndex,
     ) -> anyhow::Result<()> {
-        for hole in self.holes.get(&holes).into_iter().flatten() {
+        for hole in self.holes_in(holes) {
             let hole_mesh = index.new_mesh();
-            hole.polygonize(hole_mesh.make_mut_ref(index), 0)?;
-
-            let to_remove = [
-                index.select_polygons(hole_mesh, to_mesh, PolygonFilter::Front),
-                index.select_polygons(to_mesh, hole_mesh, PolygonFilter::Back),
-            ]
-            .concat();
-            let to_flip = [index.select_polygons(hole_mesh, to_mesh, PolygonFilter::Back)].concat();
-            for p in to_remove {
-                p.make_mut_ref(index).remove();
-            }
+            hole.polygonize(hole_mesh.make_mut_ref(index), self.quality.surface_steps)?;
+            Self::subtract_mesh(to_mesh, hole_mesh, index)?;
+        }
+        Ok(())
+    }
 
-            for p in to_flip {
-                p.make_mut_ref(index).flip();
-            }
-            index.move_all_polygons(hole_mesh, to_mesh);
+    fn apply_embossing(
+        &self,
+        on: KeyboardMesh,
+        to_mesh: MeshId,
+        index: &mut GeoIndex,
+    ) -> anyhow::Result<()> {
+        for label in self.embossed.get(&on).into_iter().flatten() {
+            let label_mesh = index.new_mesh();
+            label.polygonize(label_mesh.make_mut_ref(index), self.quality.surface_steps)?;
+            Self::unite_mesh(to_mesh, label_mesh, index)?;
         }
         Ok(())
     }
 
+    fn unite_mesh(
+        to_mesh: MeshId,
+        material_mesh: MeshId,
+        index: &mut GeoIndex,
+    ) -> anyhow::Result<()> {
+        index.unite_mesh(to_mesh, material_mesh)?;
+        Ok(())
+    }
+
+    pub(crate) fn subtract_mesh(
+        to_mesh: MeshId,
+        hole_mesh: MeshId,
+        index: &mut GeoIndex,
+    ) -> anyhow::Result<()> {
+        index.subtract_mesh(to_mesh, hole_mesh)?;
+        Ok(())
+    }
+
     pub fn buttons_hull(&self, index: &mut GeoIndex) -> anyhow::Result<MeshId> {
         let inner_wall_surface = index.new_mesh();
         let outer_wall_surface = index.new_mesh();
@@ -731,9 +1256,18 @@ impl RightKeyboardConfig {
         for mesh_id in addition_material_polygons {
             index.move_all_polygons(mesh_id, hull);
         }
+        for extra in self.extra_collection_hulls(index)? {
+            Self::unite_mesh(hull, extra, index)?;
+        }
+
+        self.apply_embossing(KeyboardMesh::ButtonsHull, hull, index)?;
 
         println!("bolt holes");
         self.apply_holes(KeyboardMesh::ButtonsHull, hull, index)?;
+        self.rim_groove(hull, index)?;
+        self.bumper_groove(hull, index)?;
+        self.dampening_pockets(hull, index)?;
+        self.led_bores(hull, index)?;
         Ok(hull)
     }
 }
@@ -0,0 +1,531 @@
+use std::path::Path;
+
+use anyhow::anyhow;
+use geometry::{
+    decimal::Dec,
+    hyper_path::{
+        hyper_line::HyperLine,
+        hyper_path::{HyperPath, Root},
+        hyper_point::SuperPoint,
+        split_hyper_line::SplitHyperLine,
+    },
+    origin::Origin,
+    shapes::Cylinder,
+};
+use nalgebra::Vector3;
+use num_traits::Zero;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    angle::Angle,
+    bolt::Bolt,
+    bolt_point::{BoltPoint, HeatSetInsert},
+    bumper::Bumper,
+    button::{Button, ButtonMountKind, LedBore},
+    button_builder::ButtonBuilder,
+    button_collections::ButtonsCollection,
+    buttons_column::ButtonsColumn,
+    component::Component,
+    dampening_pocket::DampeningPocket,
+    hole::Hole,
+    keyboard_builder::KeyboardBuilder,
+    keyboard_config::{KeyboardMesh, RightKeyboardConfig},
+    keycap::KeycapProfile,
+    origin_anchor::OriginAnchor,
+    pogo_dock::PogoDock,
+    rim_lip::RimLip,
+    surface_quality::SurfaceQuality,
+    tenting_adapter::TentingAdapter,
+    tenting_feet::TentingFeet,
+};
+
+/// Keyboard, described in json file instead of code. All angles are in degrees, points
+/// and vectors are `[x, y, z]`. Missing values are left to defaults of builders.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct KeyboardFile {
+    pub main: CollectionSpec,
+    pub thumb: Option<CollectionSpec>,
+    /// Collections besides main and thumb
+    #[serde(default)]
+    pub collections: Vec<NamedCollectionSpec>,
+    pub wall_thickness: Option<Dec>,
+    pub bottom_thickness: Option<Dec>,
+    pub wall_extension: Option<Dec>,
+    #[serde(default)]
+    pub table_outline: Vec<OutlineLineSpec>,
+    /// Radius of fillets, which replace sharp joints of `table_outline`
+    pub outline_fillet: Option<Dec>,
+    /// Points of closed smooth outline, used when `table_outline` is empty
+    #[serde(default)]
+    pub outline_through: Vec<OutlinePointSpec>,
+    pub auto_outline: Option<AutoOutlineSpec>,
+    #[serde(default)]
+    pub bolts: Vec<BoltSpec>,
+    #[serde(default)]
+    pub holes: Vec<HoleSpec>,
+    #[serde(default)]
+    pub components: Vec<Component>,
+    pub quality: Option<SurfaceQuality>,
+    pub rim_lip: Option<RimLip>,
+    pub bumper: Option<Bumper>,
+    pub dampening_pocket: Option<DampeningPocket>,
+    pub tenting_adapter: Option<TentingAdapter>,
+    pub tenting_feet: Option<TentingFeet>,
+    pub seam: Option<Dec>,
+    pub origin_at: Option<OriginAnchor>,
+    pub pogo_dock: Option<PogoDock>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct CollectionSpec {
+    pub columns: Vec<ColumnSpec>,
+    pub padding: Option<Dec>,
+    pub height: Option<Dec>,
+    pub curvature: Option<Dec>,
+    pub first_column_angle: Option<Dec>,
+    pub plane_pitch: Option<Dec>,
+    pub plane_yaw: Option<Dec>,
+    pub position_shift_x: Option<Dec>,
+    pub position_shift_y: Option<Dec>,
+    #[serde(default)]
+    pub row_offsets: Vec<RowOffsetSpec>,
+}
+
+/// Stagger of button `row` in every column, counted from the bottom of column
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RowOffsetSpec {
+    pub row: usize,
+    #[serde(default)]
+    pub y: Dec,
+    #[serde(default)]
+    pub z: Dec,
+}
+
+/// Collection with a name, placed by `at`
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NamedCollectionSpec {
+    pub name: String,
+    #[serde(default)]
+    pub at: Vec<PlacementStep>,
+    pub buttons: CollectionSpec,
+}
+
+/// Column with `main` buttons and buttons, added on top and on bottom of them
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct ColumnSpec {
+    pub main: Vec<ButtonSpec>,
+    #[serde(default)]
+    pub top: Vec<ButtonSpec>,
+    #[serde(default)]
+    pub bottom: Vec<ButtonSpec>,
+    pub curvature: Option<Dec>,
+    pub incline: Option<Dec>,
+    pub padding: Option<Dec>,
+    pub depth: Option<Dec>,
+    pub addition_column_padding: Option<Dec>,
+}
+
+/// Button, `ChokHotswapCustom` if kind is not given. Edges are multipliers of
+/// wall sizes around the button.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct ButtonSpec {
+    pub kind: Option<ButtonMountKind>,
+    pub additional_padding: Option<Dec>,
+    pub depth: Option<Dec>,
+    pub incline: Option<Dec>,
+    pub outer_left_top_edge: Option<Vector3<Dec>>,
+    pub outer_left_bottom_edge: Option<Vector3<Dec>>,
+    pub outer_right_top_edge: Option<Vector3<Dec>>,
+    pub outer_right_bottom_edge: Option<Vector3<Dec>>,
+    pub inner_left_top_edge: Option<Vector3<Dec>>,
+    pub inner_left_bottom_edge: Option<Vector3<Dec>>,
+    pub inner_right_top_edge: Option<Vector3<Dec>>,
+    pub inner_right_bottom_edge: Option<Vector3<Dec>>,
+    pub keycap: Option<KeycapProfile>,
+    pub led_bore: Option<LedBore>,
+    /// Move of button from its place in column
+    pub offset: Option<Vector3<Dec>>,
+    /// Turns of button around its x, y and z axes, in this order
+    pub rotation: Option<Vector3<Dec>>,
+}
+
+/// Line of table outline: two points make straight line, four - a curve. With `arc`
+/// angle in degrees three points are start, center and end of circular arc. When `split`
+/// is given, line is split into parts with these weights.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct OutlineLineSpec {
+    pub points: Vec<SuperPoint<Dec>>,
+    pub arc: Option<Dec>,
+    #[serde(default)]
+    pub split: Vec<Dec>,
+}
+
+/// Point, which smooth outline goes through. Zero tension gives the roundest curve, one -
+/// sharp corner.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct OutlinePointSpec {
+    #[serde(flatten)]
+    pub at: SuperPoint<Dec>,
+    #[serde(default)]
+    pub tension: Dec,
+}
+
+/// Outline around buttons instead of `table_outline`
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AutoOutlineSpec {
+    pub margin: Dec,
+    pub corner_radius: Dec,
+}
+
+/// Step of placement: offset along axes of current origin or of the world, rotation
+/// around `axis` or by euler angles, or turn, which makes `z` look at `target`
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlacementStep {
+    Offset(Vector3<Dec>),
+    OffsetGlobal(Vector3<Dec>),
+    Rotate {
+        axis: Vector3<Dec>,
+        deg: Dec,
+    },
+    Euler {
+        roll: Dec,
+        pitch: Dec,
+        yaw: Dec,
+    },
+    LookAt {
+        target: Vector3<Dec>,
+        up: Vector3<Dec>,
+    },
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BoltSpec {
+    pub head_on: KeyboardMesh,
+    pub thread_on: KeyboardMesh,
+    pub bolt: Bolt,
+    pub at: Vec<PlacementStep>,
+    pub head_thread_material_gap: Option<Dec>,
+    pub radial_head_material_extention: Option<Dec>,
+    pub thread_hole_radius_plastic_modification: Option<Dec>,
+    pub heat_set_insert: Option<HeatSetInsert>,
+}
+
+/// Cylindrical hole, going down from the origin
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HoleSpec {
+    pub on: KeyboardMesh,
+    pub at: Vec<PlacementStep>,
+    pub height: Dec,
+    pub radius: Dec,
+    pub steps: Option<usize>,
+}
+
+fn placement(steps: &[PlacementStep]) -> Origin {
+    steps.iter().fold(Origin::new(), |origin, step| match step {
+        PlacementStep::Offset(v) => origin.offset_local(*v),
+        PlacementStep::OffsetGlobal(v) => origin.offset(*v),
+        PlacementStep::Rotate { axis, deg } => {
+            origin.rotate_axisangle(axis.normalize() * Angle::from_deg(*deg).rad())
+        }
+        PlacementStep::Euler { roll, pitch, yaw } => origin.rotate_euler(
+            Angle::from_deg(*roll).rad(),
+            Angle::from_deg(*pitch).rad(),
+            Angle::from_deg(*yaw).rad(),
+        ),
+        PlacementStep::LookAt { target, up } => Origin::look_at(origin.center, *target, *up),
+    })
+}
+
+impl ButtonSpec {
+    fn build(&self) -> anyhow::Result<Button> {
+        let mut b = match self.kind.unwrap_or(ButtonMountKind::ChokHotswapCustom) {
+            ButtonMountKind::Chok => ButtonBuilder::chok(),
+            ButtonMountKind::Placeholder => ButtonBuilder::placeholder(),
+            ButtonMountKind::ChokHotswapCustom => ButtonBuilder::chok_hotswap_custom(),
+            ButtonMountKind::MxHotswapCustom => ButtonBuilder::mx_hotswap_custom(),
+            ButtonMountKind::ChocV2 => ButtonBuilder::choc_v2(),
+            ButtonMountKind::GateronLowProfile => ButtonBuilder::gateron_low_profile(),
+            ButtonMountKind::Mx { clip_notches } => ButtonBuilder::mx(clip_notches),
+            ButtonMountKind::Encoder { model, d_flat } => ButtonBuilder::encoder(model, d_flat),
+        };
+        if let Some(padding) = self.additional_padding {
+            b = b.additional_padding(padding);
+        }
+        if let Some(depth) = self.depth {
+            b = b.depth(depth);
+        }
+        if let Some(incline) = self.incline {
+            b = b.incline(Angle::from_deg(incline));
+        }
+        if let Some(v) = self.outer_left_top_edge {
+            b = b.outer_left_top_edge(v);
+        }
+        if let Some(v) = self.outer_left_bottom_edge {
+            b = b.outer_left_bottom_edge(v);
+        }
+        if let Some(v) = self.outer_right_top_edge {
+            b = b.outer_right_top_edge(v);
+        }
+        if let Some(v) = self.outer_right_bottom_edge {
+            b = b.outer_right_bottom_edge(v);
+        }
+        if let Some(v) = self.inner_left_top_edge {
+            b = b.inner_left_top_edge(v);
+        }
+        if let Some(v) = self.inner_left_bottom_edge {
+            b = b.inner_left_bottom_edge(v);
+        }
+        if let Some(v) = self.inner_right_top_edge {
+            b = b.inner_right_top_edge(v);
+        }
+        if let Some(v) = self.inner_right_bottom_edge {
+            b = b.inner_right_bottom_edge(v);
+        }
+        if let Some(keycap) = self.keycap {
+            b = b.keycap(keycap);
+        }
+        if let Some(led) = self.led_bore {
+            b = b.led_bore(led.diameter, led.offset);
+        }
+        if let Some(offset) = self.offset {
+            b = b.offset(offset);
+        }
+        if let Some(rotation) = self.rotation {
+            for (axis, deg) in [
+                (Vector3::x(), rotation.x),
+                (Vector3::y(), rotation.y),
+                (Vector3::z(), rotation.z),
+            ] {
+                if !deg.is_zero() {
+                    b = b.rotate(axis, Angle::from_deg(deg));
+                }
+            }
+        }
+        Ok(b.build())
+    }
+}
+
+impl ColumnSpec {
+    fn build(&self) -> anyhow::Result<ButtonsColumn> {
+        let mut c = ButtonsColumn::build();
+        for button in &self.main {
+            c = c.main_button(button.build()?);
+        }
+        for button in &self.top {
+            c = c.add_on_top(button.build()?);
+        }
+        for button in &self.bottom {
+            c = c.add_on_bottom(button.build()?);
+        }
+        if let Some(curvature) = self.curvature {
+            c = c.curvature(Angle::from_deg(curvature));
+        }
+        if let Some(incline) = self.incline {
+            c = c.incline(Angle::from_deg(incline));
+        }
+        if let Some(padding) = self.padding {
+            c = c.padding(padding);
+        }
+        if let Some(depth) = self.depth {
+            c = c.depth(depth);
+        }
+        if let Some(padding) = self.addition_column_padding {
+            c = c.addition_column_padding(padding);
+        }
+        Ok(c.build())
+    }
+}
+
+impl CollectionSpec {
+    fn build(&self) -> anyhow::Result<ButtonsCollection> {
+        let mut c = ButtonsCollection::build();
+        for column in &self.columns {
+            c = c.column(column.build()?);
+        }
+        if let Some(padding) = self.padding {
+            c = c.padding(padding);
+        }
+        if let Some(height) = self.height {
+            c = c.height(height);
+        }
+        if let Some(curvature) = self.curvature {
+            c = c.curvature(Angle::from_deg(curvature));
+        }
+        if let Some(angle) = self.first_column_angle {
+            c = c.first_column_angle(Angle::from_deg(angle));
+        }
+        if let Some(pitch) = self.plane_pitch {
+            c = c.plane_pitch(Angle::from_deg(pitch));
+        }
+        if let Some(yaw) = self.plane_yaw {
+            c = c.plane_yaw(Angle::from_deg(yaw));
+        }
+        if let Some(x) = self.position_shift_x {
+            c = c.position_shift_x(x);
+        }
+        if let Some(y) = self.position_shift_y {
+            c = c.position_shift_y(y);
+        }
+        for offset in &self.row_offsets {
+            c = c.row_offset(offset.row, offset.y, offset.z);
+        }
+        c.try_build()
+    }
+}
+
+impl OutlineLineSpec {
+    fn build(&self) -> anyhow::Result<Vec<HyperLine<SuperPoint<Dec>>>> {
+        let line = match (self.points.as_slice(), self.arc) {
+            ([a, b], None) => HyperLine::new_2(*a, *b),
+            ([a, b, c, d], None) => HyperLine::new_4(*a, *b, *c, *d),
+            ([start, center, end], Some(sweep)) => {
+                HyperLine::new_arc(*start, *center, *end, Angle::from_deg(sweep).rad())
+            }
+            (points, None) => {
+                return Err(anyhow!(
+                    "Outline line must have 2 or 4 points, got {}",
+                    points.len()
+                ))
+            }
+            (points, Some(_)) => {
+                return Err(anyhow!(
+                    "Outline arc must have start, center and end points, got {}",
+                    points.len()
+                ))
+            }
+        };
+        if self.split.is_empty() {
+            Ok(vec![line])
+        } else {
+            Ok(line.split_by_weights(self.split.clone()))
+        }
+    }
+}
+
+impl KeyboardFile {
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Cannot read {}: {e}", path.display()))?;
+        Self::from_json(&json)
+    }
+
+    /// Builder with everything from the file, so it can be changed further in code
+    pub fn builder(&self) -> anyhow::Result<KeyboardBuilder> {
+        let mut k = RightKeyboardConfig::build().main(self.main.build()?);
+        match &self.auto_outline {
+            Some(auto) if self.table_outline.is_empty() && self.outline_through.is_empty() => {
+                k = k.auto_outline(auto.margin, auto.corner_radius);
+            }
+            _ => {
+                if self.table_outline.is_empty() && self.outline_through.is_empty() {
+                    return Err(anyhow!("Table outline is empty"));
+                }
+                let mut outline = if self.table_outline.is_empty() {
+                    Root::closed_spline(self.outline_through.iter().map(|p| (p.at, p.tension)))?
+                } else {
+                    Root::new()
+                };
+                for line in &self.table_outline {
+                    outline = outline.extend(line.build()?);
+                }
+                if let Some(radius) = self.outline_fillet {
+                    outline = outline.fillet_corners(radius);
+                }
+                k = k.table_outline(outline);
+            }
+        }
+        if let Some(thumb) = &self.thumb {
+            k = k.thumb(thumb.build()?);
+        }
+        for collection in &self.collections {
+            k = k.collection(
+                &collection.name,
+                placement(&collection.at),
+                collection.buttons.build()?,
+            );
+        }
+        if let Some(thickness) = self.wall_thickness {
+            k = k.wall_thickness(thickness);
+        }
+        if let Some(thickness) = self.bottom_thickness {
+            k = k.bottom_thickness(thickness);
+        }
+        if let Some(extension) = self.wall_extension {
+            k = k.wall_extension(extension);
+        }
+        for b in &self.bolts {
+            let mut point = BoltPoint::new(b.bolt.clone()).origin(placement(&b.at));
+            if let Some(gap) = b.head_thread_material_gap {
+                point = point.head_thread_material_gap(gap);
+            }
+            if let Some(extension) = b.radial_head_material_extention {
+                point = point.radial_head_material_extention(extension);
+            }
+            if let Some(modification) = b.thread_hole_radius_plastic_modification {
+                point = point.thread_hole_radius_plastic_modification(modification);
+            }
+            if let Some(insert) = b.heat_set_insert {
+                point = point.heat_set_insert(insert);
+            }
+            k = k.add_bolt(b.head_on, b.thread_on, point);
+        }
+        for h in &self.holes {
+            let mut cylinder = Cylinder::with_top_at(placement(&h.at), h.height, h.radius);
+            if let Some(steps) = h.steps {
+                cylinder = cylinder.steps(steps);
+            }
+            let hole = Hole::build().shape(cylinder).build()?;
+            k = match h.on {
+                KeyboardMesh::ButtonsHull => k.add_main_hole(hole),
+                KeyboardMesh::Bottom => k.add_bottom_hole(hole),
+                KeyboardMesh::PcbMount => {
+                    return Err(anyhow!("Holes in pcb mount are not supported"))
+                }
+            };
+        }
+        for component in &self.components {
+            k = k.component(component.clone());
+        }
+        if let Some(quality) = self.quality {
+            k = k.quality(quality);
+        }
+        if let Some(rim_lip) = self.rim_lip {
+            k = k.rim_lip(rim_lip);
+        }
+        if let Some(bumper) = self.bumper {
+            k = k.bumper(bumper);
+        }
+        if let Some(pocket) = self.dampening_pocket {
+            k = k.dampening_pocket(pocket);
+        }
+        if let Some(adapter) = self.tenting_adapter {
+            k = k.tenting_adapter(adapter);
+        }
+        if let Some(feet) = &self.tenting_feet {
+            k = k.tenting_feet(feet.clone());
+        }
+        if let Some(at) = self.seam {
+            k = k.seam(at);
+        }
+        if let Some(dock) = &self.pogo_dock {
+            k = k.pogo_dock(dock.clone());
+        }
+        if let Some(anchor) = self.origin_at {
+            k = k.origin_at(anchor);
+        }
+        Ok(k)
+    }
+}
+
+impl RightKeyboardConfig {
+    /// Keyboard from json file, see `KeyboardFile`
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Ok(KeyboardFile::load(path)?.builder()?.build())
+    }
+}
@@ -0,0 +1,181 @@
+use geometry::{
+    decimal::Dec,
+    indexes::{
+        aabb::Aabb,
+        geo_index::{
+            geo_object::GeoObject,
+            index::{GeoIndex, PolygonFilter},
+            mesh::MeshId,
+        },
+    },
+};
+use itertools::Itertools;
+use nalgebra::Vector3;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    button::{Button, ButtonMountKind},
+    keyboard_config::RightKeyboardConfig,
+    switch_clearance::KeyPosition,
+};
+
+/// Shape of keycap for collision checks: a frustum from `bottom` to `top` outline, which
+/// sits `rest` above the key plate and goes `travel` down, when key is pressed
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KeycapProfile {
+    /// Low profile cap for Kailh Choc switches, 17.5 x 16.5mm
+    Choc,
+    /// Cap of MX stem switches, 18 x 18mm, as a low row of cylindrical profile
+    Mx,
+}
+
+impl KeycapProfile {
+    /// Width and height of the skirt
+    fn bottom(&self) -> (Dec, Dec) {
+        match self {
+            KeycapProfile::Choc => (dec!(17.5).into(), dec!(16.5).into()),
+            KeycapProfile::Mx => (18.into(), 18.into()),
+        }
+    }
+
+    /// Width and height of the top surface
+    fn top(&self) -> (Dec, Dec) {
+        match self {
+            KeycapProfile::Choc => (16.into(), 15.into()),
+            KeycapProfile::Mx => (dec!(12.5).into(), 14.into()),
+        }
+    }
+
+    /// From the skirt to the top surface
+    fn height(&self) -> Dec {
+        match self {
+            KeycapProfile::Choc => dec!(3.5).into(),
+            KeycapProfile::Mx => 8.into(),
+        }
+    }
+
+    /// Skirt of released key above the top of key plate
+    fn rest(&self) -> Dec {
+        match self {
+            KeycapProfile::Choc => 3.into(),
+            KeycapProfile::Mx => dec!(6.5).into(),
+        }
+    }
+
+    fn travel(&self) -> Dec {
+        match self {
+            KeycapProfile::Choc => 3.into(),
+            KeycapProfile::Mx => 4.into(),
+        }
+    }
+
+    /// Cap of the same family as the switch
+    pub(crate) fn for_kind(kind: ButtonMountKind) -> Self {
+        match kind {
+            ButtonMountKind::Mx { .. } | ButtonMountKind::MxHotswapCustom => KeycapProfile::Mx,
+            _ => KeycapProfile::Choc,
+        }
+    }
+
+    /// Polygons of space, which keycap takes on its way from released to fully pressed
+    /// position. Polygons look out of the volume.
+    fn swept_volume(&self, button: &Button, thickness: Dec) -> Vec<Vec<Vector3<Dec>>> {
+        let o = &button.origin;
+        let ring = |(w, h): (Dec, Dec), z: Dec| {
+            [(-1, -1), (1, -1), (1, 1), (-1, 1)]
+                .map(|(x, y)| {
+                    o.center
+                        + o.x() * (w / 2 * Dec::from(x))
+                        + o.y() * (h / 2 * Dec::from(y))
+                        + o.z() * z
+                })
+                .to_vec()
+        };
+        let rest = thickness / 2 + self.rest();
+        let pressed = ring(self.bottom(), rest - self.travel());
+        let released = ring(self.bottom(), rest);
+        let top = ring(self.top(), rest + self.height());
+
+        let mut polygons = vec![pressed.iter().rev().copied().collect_vec(), top.clone()];
+        for (lower, upper) in [(&pressed, &released), (&released, &top)] {
+            for (a, b) in (0..4).circular_tuple_windows() {
+                polygons.push(vec![lower[a], lower[b], upper[b], upper[a]]);
+            }
+        }
+        polygons
+    }
+}
+
+impl Button {
+    /// Keycap, set with `ButtonBuilder::keycap` or the one, which fits the switch.
+    /// Placeholders and encoders have no keycap.
+    pub fn keycap(&self) -> Option<KeycapProfile> {
+        if matches!(
+            self.kind,
+            ButtonMountKind::Placeholder | ButtonMountKind::Encoder { .. }
+        ) {
+            None
+        } else {
+            Some(self.keycap.unwrap_or(KeycapProfile::for_kind(self.kind)))
+        }
+    }
+}
+
+fn bounds(points: &[Vector3<Dec>], axis: usize) -> (Dec, Dec) {
+    points
+        .iter()
+        .map(|p| p[axis])
+        .minmax()
+        .into_option()
+        .unwrap_or_default()
+}
+
+impl RightKeyboardConfig {
+    /// Pairs of keys, which caps touch each other somewhere on their travel. Swept
+    /// volumes of caps are intersected as meshes, so only neighbours, which bounding
+    /// boxes overlap, are checked.
+    pub fn check_keycap_collisions(&self) -> anyhow::Result<Vec<(KeyPosition, KeyPosition)>> {
+        let thickness = self.main_plane_thickness;
+        let caps = self
+            .keys()
+            .filter_map(|(key, button)| {
+                let cap = button.keycap()?.swept_volume(button, thickness);
+                let points = cap.iter().flatten().copied().collect_vec();
+                Some((key, cap, points))
+            })
+            .collect_vec();
+
+        let mut collisions = Vec::new();
+        for ((key, cap, points), (other_key, other_cap, other_points)) in
+            caps.iter().tuple_combinations()
+        {
+            let overlap = (0..3).all(|axis| {
+                let (min, max) = bounds(points, axis);
+                let (other_min, other_max) = bounds(other_points, axis);
+                min < other_max && other_min < max
+            });
+            if !overlap {
+                continue;
+            }
+            let all = points.iter().chain(other_points).copied().collect_vec();
+            let mut index = GeoIndex::new(Aabb::from_points(&all))
+                .input_polygon_min_rib_length(dec!(0.05))
+                .points_precision(dec!(0.001));
+            let a = Self::keycap_mesh(cap, &mut index)?;
+            let b = Self::keycap_mesh(other_cap, &mut index)?;
+            if !index.select_polygons(a, b, PolygonFilter::Back).is_empty() {
+                collisions.push((*key, *other_key));
+            }
+        }
+        Ok(collisions)
+    }
+
+    fn keycap_mesh(polygons: &[Vec<Vector3<Dec>>], index: &mut GeoIndex) -> anyhow::Result<MeshId> {
+        let mesh = index.new_mesh();
+        for p in polygons {
+            mesh.make_mut_ref(index).add_polygon(p)?;
+        }
+        Ok(mesh)
+    }
+}
@@ -0,0 +1,204 @@
+use anyhow::anyhow;
+use geometry::{decimal::Dec, origin::Origin};
+use itertools::Itertools;
+use nalgebra::Vector3;
+use serde_json::Value;
+
+use crate::{
+    angle::Angle, button::ButtonMountKind, button_builder::ButtonBuilder,
+    button_collection_builder::ButtonsCollectionBuilder, button_collections::ButtonsCollection,
+    buttons_column::ButtonsColumn,
+};
+
+/// Keys, which centers are closer, than this, in key units, go to the same column
+const COLUMN_TOLERANCE: f64 = 0.5;
+
+/// Most buttons, column can have
+const MAX_COLUMN_BUTTONS: usize = 6;
+
+/// Key of KLE layout: center and rotation. Units are keys, y goes down, as in KLE.
+#[derive(Clone, Copy, Debug)]
+struct KleKey {
+    x: f64,
+    y: f64,
+    angle: f64,
+}
+
+/// Keys of KLE json (raw data, as downloaded from keyboard-layout-editor.com). Only
+/// positions and rotations are read: labels, sizes and colors are ignored.
+fn parse_kle(json: &str) -> anyhow::Result<Vec<KleKey>> {
+    let rows: Vec<Value> = serde_json::from_str(json)?;
+    let (mut r, mut rx, mut ry) = (0.0, 0.0, 0.0);
+    let (mut x, mut y) = (0.0, 0.0);
+    let mut keys = Vec::new();
+    // First item may be metadata of the layout
+    for row in rows.iter().filter_map(|r| r.as_array()) {
+        let (mut w, mut h) = (1.0, 1.0);
+        for item in row {
+            match item {
+                Value::Object(props) => {
+                    let get = |name: &str| props.get(name).and_then(|v| v.as_f64());
+                    if let Some(angle) = get("r") {
+                        r = angle;
+                    }
+                    if let Some(v) = get("rx") {
+                        rx = v;
+                        (x, y) = (rx, ry);
+                    }
+                    if let Some(v) = get("ry") {
+                        ry = v;
+                        (x, y) = (rx, ry);
+                    }
+                    x += get("x").unwrap_or(0.0);
+                    y += get("y").unwrap_or(0.0);
+                    w = get("w").unwrap_or(w);
+                    h = get("h").unwrap_or(h);
+                }
+                Value::String(_) => {
+                    // Rotation is clockwise around rx, ry, as y goes down
+                    let (cx, cy) = (x + w / 2.0 - rx, y + h / 2.0 - ry);
+                    let (sin, cos) = r.to_radians().sin_cos();
+                    keys.push(KleKey {
+                        x: rx + cx * cos - cy * sin,
+                        y: ry + cx * sin + cy * cos,
+                        angle: r,
+                    });
+                    x += w;
+                    (w, h) = (1.0, 1.0);
+                }
+                other => return Err(anyhow!("Unexpected item in KLE row: {other}")),
+            }
+        }
+        y += 1.0;
+        x = rx;
+    }
+    Ok(keys)
+}
+
+impl ButtonsCollection {
+    /// Flat layout from keyboard-layout-editor json. Keys are grouped to columns by their
+    /// centers, columns go left to right, `unit` is the distance between key centers in
+    /// millimeters. Every button is placed exactly at its key center with its rotation,
+    /// so stagger and splay of the layout are kept. Curvature, height and angles of the
+    /// collection can be set on returned builder.
+    pub fn from_kle(
+        json: &str,
+        kind: ButtonMountKind,
+        unit: impl Into<Dec>,
+    ) -> anyhow::Result<ButtonsCollectionBuilder> {
+        let unit = unit.into();
+        let keys = parse_kle(json)?;
+        if keys.is_empty() {
+            return Err(anyhow!("KLE layout has no keys"));
+        }
+
+        let mut columns: Vec<Vec<KleKey>> = Vec::new();
+        for key in keys.into_iter().sorted_by(|a, b| a.x.total_cmp(&b.x)) {
+            match columns.last_mut() {
+                Some(column) if key.x - column[0].x < COLUMN_TOLERANCE => column.push(key),
+                _ => columns.push(vec![key]),
+            }
+        }
+        let (min_y, max_y) = columns
+            .iter()
+            .flatten()
+            .map(|k| k.y)
+            .fold((f64::MAX, f64::MIN), |(a, b), y| (a.min(y), b.max(y)));
+        let middle_y = (min_y + max_y) / 2.0;
+        let first_x = columns[0].iter().map(|k| k.x).sum::<f64>() / columns[0].len() as f64;
+
+        let mut collection = ButtonsCollection::build().padding(unit);
+        for (ix, column) in columns.iter().enumerate() {
+            if column.len() > MAX_COLUMN_BUTTONS {
+                return Err(anyhow!(
+                    "Column {ix} of KLE layout has {} keys, at most {MAX_COLUMN_BUTTONS} are supported",
+                    column.len()
+                ));
+            }
+            // Buttons of the column go from bottom to top, one unit apart around the middle
+            let slot_x = first_x + ix as f64;
+            let middle = (column.len() - 1) as f64 / 2.0;
+            let mut c = ButtonsColumn::build().padding(unit - kind.button_height());
+            for (slot, key) in column
+                .iter()
+                .sorted_by(|a, b| b.y.total_cmp(&a.y))
+                .enumerate()
+            {
+                let dx = Dec::from(key.x - slot_x) * unit;
+                let dy = Dec::from(middle_y - key.y - (slot as f64 - middle)) * unit;
+                let mut button = ButtonBuilder::with_kind(kind).build();
+                button.origin = Origin::new()
+                    .offset_x(dx)
+                    .offset_y(dy)
+                    .rotate_axisangle(Vector3::z() * Angle::from_deg(-key.angle).rad());
+                c = c.main_button(button);
+            }
+            collection = collection.column(c.build());
+        }
+        Ok(collection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{button::ButtonMountKind, ButtonsCollection};
+
+    use super::parse_kle;
+
+    /// Two rows with `x`, `y` and `w` modifiers and one key, rotated by 15 degrees
+    /// around (4, 3)
+    const LAYOUT: &str = r#"[
+        {"name": "test"},
+        ["Q", {"x": 0.25}, "W", {"w": 1.5}, "E"],
+        [{"y": 0.5}, "A", "S"],
+        [{"r": 15, "rx": 4, "ry": 3}, "T"]
+    ]"#;
+
+    #[test]
+    fn centers_of_kle_keys() {
+        let keys = parse_kle(LAYOUT).unwrap();
+
+        let (sin, cos) = 15f64.to_radians().sin_cos();
+        let expected = [
+            (0.5, 0.5, 0.0),
+            (1.75, 0.5, 0.0),
+            (3.0, 0.5, 0.0),
+            (0.5, 2.0, 0.0),
+            (1.5, 2.0, 0.0),
+            (
+                4.0 + 0.5 * cos - 0.5 * sin,
+                3.0 + 0.5 * sin + 0.5 * cos,
+                15.0,
+            ),
+        ];
+        assert_eq!(keys.len(), expected.len());
+        for (key, (x, y, angle)) in keys.iter().zip(expected) {
+            assert!((key.x - x).abs() < 1e-9, "{key:?}");
+            assert!((key.y - y).abs() < 1e-9, "{key:?}");
+            assert_eq!(key.angle, angle);
+        }
+    }
+
+    #[test]
+    fn kle_keys_grouped_to_columns() {
+        let collection = ButtonsCollection::from_kle(LAYOUT, ButtonMountKind::Chok, 19)
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        let sizes = collection
+            .columns
+            .iter()
+            .map(|c| c.buttons().count())
+            .collect::<Vec<_>>();
+        assert_eq!(sizes, vec![2, 2, 1, 1]);
+
+        // Rotated key keeps its rotation relative to the others
+        let x_of = |column: usize| {
+            let button = collection.columns[column].buttons().next().unwrap();
+            button.origin.x().map(f64::from)
+        };
+        let turn = x_of(0).dot(&x_of(3)).acos().to_degrees();
+        assert!((turn - 15.0).abs() < 1e-6, "{turn}");
+    }
+}
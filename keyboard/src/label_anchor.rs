@@ -0,0 +1,289 @@
+use anyhow::anyhow;
+use geometry::{
+    decimal::Dec,
+    hyper_path::{hyper_line::ShiftInPlane, hyper_path::HyperPath, line::GetT},
+    origin::Origin,
+};
+use itertools::Itertools;
+use nalgebra::{Vector2, Vector3};
+use num_traits::Zero;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::keyboard_config::RightKeyboardConfig;
+
+/// Points on each line of table outline, where walls are checked
+const SAMPLES: usize = 8;
+
+/// Side of the case, as seen from above: back is along y axis, right - along x
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Edge {
+    Front,
+    Back,
+    Left,
+    Right,
+}
+
+impl Edge {
+    fn dir(&self) -> Vector3<Dec> {
+        match self {
+            Edge::Front => -Vector3::y(),
+            Edge::Back => Vector3::y(),
+            Edge::Left => -Vector3::x(),
+            Edge::Right => Vector3::x(),
+        }
+    }
+}
+
+impl RightKeyboardConfig {
+    /// Outer wall points with outward normals, along the whole table outline
+    pub(crate) fn wall_samples(&self) -> Vec<(Vector3<Dec>, Vector3<Dec>)> {
+        let mut samples = Vec::new();
+        let mut outline = self.table_outline.clone();
+        for _ in 0..self.table_outline.len() {
+            let (line, tail) = outline.head_tail();
+            let outer = line
+                .clone()
+                .shift_in_plane(Vector3::z(), -self.main_plane_thickness)
+                .to_points();
+            let inner = line.to_points();
+            for i in 0..SAMPLES {
+                let t = Dec::from(i) / Dec::from(SAMPLES);
+                let (o, i) = (outer.get_t(t), inner.get_t(t));
+                let normal = Vector3::new(o.x - i.x, o.y - i.y, Dec::zero());
+                if normal.magnitude() > Dec::from(dec!(0.0001)) {
+                    samples.push((Vector3::new(o.x, o.y, Dec::zero()), normal.normalize()));
+                }
+            }
+            outline = tail;
+        }
+        samples
+    }
+
+    /// Outer wall outline at table level, moved out of the wall by `offset`. Parts,
+    /// which go under or around the case, are made from it.
+    pub fn outline_on_table(&self, offset: impl Into<Dec>) -> Vec<Vector2<Dec>> {
+        let offset = offset.into();
+        let mut outline = self
+            .wall_samples()
+            .into_iter()
+            .map(|(point, normal)| {
+                let p = point + normal * offset;
+                Vector2::new(p.x, p.y)
+            })
+            .collect_vec();
+        outline.dedup();
+        outline
+    }
+
+    /// Outer wall samples, which look to `edge` within 45 degrees, in order from left
+    /// to right for those, who look at the wall. If there are several such stripes, the
+    /// longest one is taken.
+    pub(crate) fn wall_stripe(
+        &self,
+        edge: Edge,
+    ) -> anyhow::Result<Vec<(Vector3<Dec>, Vector3<Dec>)>> {
+        let samples = self.wall_samples();
+        let cos_45 = Dec::from(dec!(0.7071));
+        let facing = samples
+            .iter()
+            .map(|(_, n)| n.dot(&edge.dir()) >= cos_45)
+            .collect_vec();
+
+        // Longest stripe of facing samples, which may go over start of outline
+        let len = samples.len();
+        let Some(start) = (0..len).find(|i| !facing[*i]) else {
+            return Err(anyhow!("All walls look to {edge:?}, outline is not closed"));
+        };
+        let mut stripe: Vec<usize> = Vec::new();
+        let mut current = Vec::new();
+        for i in (1..=len).map(|i| (start + i) % len) {
+            if facing[i] {
+                current.push(i);
+            } else {
+                if current.len() > stripe.len() {
+                    stripe = current;
+                }
+                current = Vec::new();
+            }
+        }
+        if stripe.is_empty() {
+            return Err(anyhow!("No wall looks to {edge:?}"));
+        }
+        // Stripe goes left to right for those, who look at the wall
+        let (first, last) = (samples[stripe[0]].0, samples[stripe[stripe.len() - 1]].0);
+        if (last - first).dot(&Vector3::z().cross(&edge.dir())) < Dec::zero() {
+            stripe.reverse();
+        }
+        Ok(stripe.into_iter().map(|i| samples[i]).collect())
+    }
+
+    /// Origin on outer wall at table level, for labels and logos: z axis looks out of the
+    /// wall, y axis goes up and x goes along the wall, so text reads left to right for
+    /// those, who look at the wall. Walls, which look to `edge` within 45 degrees, are
+    /// taken as one stripe and `t` from 0 to 1 goes along it from left to right.
+    /// `inset` moves origin into the wall.
+    pub fn label_anchor(
+        &self,
+        edge: Edge,
+        t: impl Into<Dec>,
+        inset: impl Into<Dec>,
+    ) -> anyhow::Result<Origin> {
+        let t = Ord::clamp(t.into(), Dec::zero(), Dec::from(1));
+        let stripe = self.wall_stripe(edge)?;
+
+        let lengths = stripe
+            .iter()
+            .tuple_windows()
+            .map(|((a, _), (b, _))| (*b - *a).magnitude())
+            .collect_vec();
+        let mut left = lengths.iter().fold(Dec::zero(), |a, l| a + *l) * t;
+        let (mut point, mut normal) = stripe[0];
+        for (((pa, na), (pb, nb)), l) in stripe.iter().copied().tuple_windows().zip(lengths) {
+            if left <= l {
+                let k = if l.is_zero() { Dec::zero() } else { left / l };
+                point = pa.lerp(&pb, k);
+                normal = na.lerp(&nb, k).normalize();
+                break;
+            }
+            left -= l;
+            (point, normal) = (pb, nb);
+        }
+
+        Ok(wall_origin(point, normal, inset.into()))
+    }
+
+    /// Origin on outer wall, which is the closest to `point` by x and y, at height of
+    /// `point`. Axes are the same, as of `label_anchor`, so bolts and holes along z go
+    /// through the wall. Unlike fixed coordinates, anchor stays on the wall, when outline
+    /// changes. `inset` moves origin into the wall.
+    pub fn wall_anchor(
+        &self,
+        point: Vector3<Dec>,
+        inset: impl Into<Dec>,
+    ) -> anyhow::Result<Origin> {
+        let flat = Vector3::new(point.x, point.y, Dec::zero());
+        let (on_wall, normal) = self
+            .wall_samples()
+            .into_iter()
+            .circular_tuple_windows()
+            .map(|((pa, na), (pb, nb))| {
+                let along = pb - pa;
+                let length = along.magnitude_squared();
+                let k = if length.is_zero() {
+                    Dec::zero()
+                } else {
+                    Ord::clamp((flat - pa).dot(&along) / length, Dec::zero(), Dec::from(1))
+                };
+                (pa.lerp(&pb, k), na.lerp(&nb, k).normalize())
+            })
+            .min_by_key(|(p, _)| (p - flat).magnitude_squared())
+            .ok_or(anyhow!("Table outline has no walls"))?;
+        Ok(wall_origin(
+            on_wall + Vector3::z() * point.z,
+            normal,
+            inset.into(),
+        ))
+    }
+}
+
+/// Origin in `point` of wall with outward `normal`: z looks out, y goes up and x goes along
+/// the wall from left to right for those, who look at it
+fn wall_origin(point: Vector3<Dec>, normal: Vector3<Dec>, inset: Dec) -> Origin {
+    let angle = Dec::from(f64::from(normal.x).atan2(-f64::from(normal.y)));
+    let quarter = Dec::from(Decimal::PI) / Dec::from(2);
+    Origin::new()
+        .offset(point - normal * inset)
+        .rotate_axisangle(Vector3::z() * angle)
+        .rotate_axisangle(Vector3::x() * quarter)
+}
+
+#[cfg(test)]
+mod tests {
+    use geometry::{
+        decimal::Dec,
+        hyper_path::{
+            hyper_line::HyperLine,
+            hyper_path::{HyperPath, Root},
+            hyper_point::SuperPoint,
+        },
+    };
+    use itertools::Itertools;
+    use nalgebra::Vector3;
+    use num_traits::Zero;
+
+    use crate::{Button, ButtonsCollection, ButtonsColumn, RightKeyboardConfig};
+
+    use super::Edge;
+
+    fn rounded(v: Vector3<Dec>) -> Vector3<Dec> {
+        v.map(|c| c.round_dp(6))
+    }
+
+    /// Keyboard with walls 4mm thick around square 100x100
+    fn keyboard() -> RightKeyboardConfig {
+        let point = |(x, y): (i32, i32)| SuperPoint {
+            side_dir: Vector3::z(),
+            point: Vector3::new(Dec::from(x), Dec::from(y), Dec::zero()),
+        };
+        let outline = [(-50, 50), (50, 50), (50, -50), (-50, -50)]
+            .into_iter()
+            .circular_tuple_windows()
+            .fold(Root::new(), |root, (a, b)| {
+                root.push_back(HyperLine::new_2(point(a), point(b)))
+            });
+        RightKeyboardConfig::build()
+            .wall_thickness(4)
+            .bottom_thickness(2)
+            .main(
+                ButtonsCollection::build()
+                    .column(
+                        ButtonsColumn::build()
+                            .main_button(Button::chok_hotswap_custom().build())
+                            .build(),
+                    )
+                    .height(20)
+                    .build(),
+            )
+            .table_outline(outline)
+            .build()
+    }
+
+    #[test]
+    fn wall_anchor_on_right_wall() {
+        let point = Vector3::new(Dec::from(80), Dec::from(10), Dec::from(5));
+
+        let origin = keyboard().wall_anchor(point, 1).unwrap();
+
+        assert_eq!(
+            rounded(origin.center),
+            Vector3::new(Dec::from(53), Dec::from(10), Dec::from(5))
+        );
+        assert_eq!(rounded(origin.z()), Vector3::x());
+        assert_eq!(rounded(origin.y()), Vector3::z());
+    }
+
+    #[test]
+    fn wall_anchor_on_front_wall() {
+        let point = Vector3::new(Dec::from(-20), Dec::from(-51), Dec::zero());
+
+        let origin = keyboard().wall_anchor(point, 0).unwrap();
+
+        assert_eq!(
+            rounded(origin.center),
+            Vector3::new(Dec::from(-20), Dec::from(-54), Dec::zero())
+        );
+        assert_eq!(rounded(origin.z()), -Vector3::y());
+        // Text on front wall reads along x
+        assert_eq!(rounded(origin.x()), Vector3::x());
+    }
+
+    #[test]
+    fn label_anchor_in_the_middle_of_back_wall() {
+        let origin = keyboard().label_anchor(Edge::Back, 0.5, 0).unwrap();
+
+        assert_eq!(rounded(origin.z()), Vector3::y());
+        assert_eq!(origin.center.y.round_dp(6), Dec::from(54));
+        assert_eq!(rounded(origin.x()), -Vector3::x());
+    }
+}
@@ -1,7 +1,11 @@
 mod angle;
+mod auto_bolts;
+mod auto_outline;
+mod battery_bay;
 mod bolt;
 mod bolt_builder;
 mod bolt_point;
+mod bumper;
 mod button;
 mod button_builder;
 mod button_collection_builder;
@@ -9,21 +13,92 @@ mod button_collections;
 mod buttons;
 mod buttons_column;
 mod buttons_column_builder;
+mod buttons_grid;
+mod component;
+mod component_library;
+mod config_validation;
+mod dampening_pocket;
+mod display_window;
+mod extra_collections;
+mod gmsh;
 mod hole;
 mod hole_builder;
+mod interior_report;
+mod key_matrix;
+mod key_positions;
 mod keyboard_builder;
 mod keyboard_config;
+mod keyboard_file;
+mod keycap;
+mod kle;
+mod label_anchor;
+mod mcu;
 mod next_and_peek;
+mod origin_anchor;
+mod outline_import;
+mod ping_report;
+mod plate_deflection;
+mod pogo_dock;
+mod power_controls;
+mod print_split;
+mod rim_lip;
+mod seam;
+mod surface_quality;
+mod switch_clearance;
+mod tenting_adapter;
+mod tenting_feet;
+mod trackball;
+mod trrs_jack;
+mod usb_c_cutout;
+mod wiring;
+mod wrist_rest;
 
 pub use angle::Angle;
+pub use battery_bay::{BatteryBay, BatteryRetention};
 pub use bolt::Bolt;
-pub use bolt_point::BoltPoint;
+pub use bolt::Thread;
+pub use bolt_point::{BoltPoint, HeatSetInsert};
+pub use bumper::Bumper;
 pub use button::Button;
 pub use button::ButtonMountKind;
+pub use button::LedBore;
 pub use button_builder::ButtonBuilder;
 pub use button_collections::ButtonsCollection;
 pub use buttons::*;
 pub use buttons_column::ButtonsColumn;
+pub use buttons_grid::{ButtonsGrid, ColumnOverride};
+pub use component::Component;
+pub use component_library::{ComponentDefinition, ComponentLibrary};
+pub use config_validation::{ConfigError, ConfigErrors};
+pub use dampening_pocket::DampeningPocket;
+pub use display_window::DisplayWindow;
+pub use gmsh::SurfaceTag;
 pub use hole::Hole;
+pub use interior_report::InteriorReport;
+pub use key_matrix::{KeyMatrix, MatrixPosition};
+pub use keyboard_builder::KeyboardBuilder;
 pub use keyboard_config::KeyboardMesh;
 pub use keyboard_config::RightKeyboardConfig;
+pub use keyboard_file::{
+    AutoOutlineSpec, BoltSpec, ButtonSpec, CollectionSpec, ColumnSpec, HoleSpec, KeyboardFile,
+    NamedCollectionSpec, OutlineLineSpec, PlacementStep, RowOffsetSpec,
+};
+pub use keycap::KeycapProfile;
+pub use label_anchor::Edge;
+pub use mcu::McuKind;
+pub use origin_anchor::OriginAnchor;
+pub use outline_import::OutlineImport;
+pub use ping_report::{KeyResonance, PlateMaterial};
+pub use plate_deflection::{KeyDeflection, PlateStiffness};
+pub use pogo_dock::PogoDock;
+pub use power_controls::{PowerSwitch, ResetButton};
+pub use print_split::{PrintSplit, SplitCut, SplitJoint};
+pub use rim_lip::RimLip;
+pub use surface_quality::SurfaceQuality;
+pub use switch_clearance::{BlockedSwitch, Cluster, KeyPosition, Obstacle, SwitchClearance};
+pub use tenting_adapter::{TentingAdapter, TentingHardware};
+pub use tenting_feet::{Corner, TentingFeet};
+pub use trackball::Trackball;
+pub use trrs_jack::TrrsJack;
+pub use wiring::Wiring;
+pub use wrist_rest::WristRest;
@@ -0,0 +1,187 @@
+use std::rc::Rc;
+
+use geometry::{decimal::Dec, geometry::GeometryDyn, origin::Origin, shapes::Rect};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::keyboard_config::RightKeyboardConfig;
+
+/// Controller board, which goes into a tray with `RightKeyboardConfig::add_mcu_mount`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum McuKind {
+    /// Sparkfun Pro Micro and clones with micro USB
+    ProMicro,
+    /// Elite-C with USB-C
+    EliteC,
+    /// nice!nano wireless controller with USB-C
+    NiceNano,
+}
+
+/// Sizes of controller board, in millimeters
+struct McuFootprint {
+    length: Dec,
+    width: Dec,
+    thickness: Dec,
+    /// Connector on top of the board at the front edge
+    connector: (Dec, Dec),
+    /// Overmold of cable plug, which goes into the wall
+    plug: (Dec, Dec),
+}
+
+impl McuKind {
+    fn footprint(&self) -> McuFootprint {
+        let usb_c: (Dec, Dec) = (9.into(), dec!(3.2).into());
+        let usb_c_plug: (Dec, Dec) = (dec!(12.5).into(), 7.into());
+        match self {
+            McuKind::ProMicro => McuFootprint {
+                length: 33.into(),
+                width: 18.into(),
+                thickness: dec!(1.6).into(),
+                connector: (dec!(7.5).into(), dec!(2.5).into()),
+                plug: (11.into(), dec!(7.5).into()),
+            },
+            McuKind::EliteC => McuFootprint {
+                length: 33.into(),
+                width: 18.into(),
+                thickness: dec!(1.6).into(),
+                connector: usb_c,
+                plug: usb_c_plug,
+            },
+            McuKind::NiceNano => McuFootprint {
+                length: dec!(33.3).into(),
+                width: 18.into(),
+                thickness: dec!(1.6).into(),
+                connector: usb_c,
+                plug: usb_c_plug,
+            },
+        }
+    }
+}
+
+/// Tray for controller at the inner side of the wall. Board drops into the tray between
+/// side rails: connector goes into the opening in the wall first, then back edge of the
+/// board snaps under the clip on flexible back wall of the tray. Header pins and solder
+/// joints go into the pocket under the board, board rests on ledges at its ends.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct McuMount {
+    pub(crate) origin: Origin,
+    pub(crate) kind: McuKind,
+    pub(crate) clearance: Dec,
+    pub(crate) header_clearance: Dec,
+}
+
+impl McuMount {
+    pub(crate) fn new(origin: Origin, kind: McuKind) -> Self {
+        Self {
+            origin,
+            kind,
+            clearance: dec!(0.2).into(),
+            header_clearance: 3.into(),
+        }
+    }
+
+    /// Box between `from` and `to` along y and `bottom` and `top` along z, centered
+    /// across the board
+    fn block(&self, (from, to): (Dec, Dec), (bottom, top): (Dec, Dec), width: Dec) -> Rect {
+        Rect::with_bottom_at(
+            self.origin
+                .clone()
+                .offset_y((from + to) / 2)
+                .offset_z(bottom),
+            width,
+            to - from,
+            top - bottom,
+        )
+    }
+
+    fn wall(&self) -> Dec {
+        dec!(1.6).into()
+    }
+
+    fn floor(&self) -> Dec {
+        dec!(1.2).into()
+    }
+
+    /// Height of the clip over the board
+    fn clip(&self) -> Dec {
+        dec!(1.2).into()
+    }
+
+    /// Gap behind the back wall, which lets it bend
+    fn relief(&self) -> Dec {
+        1.into()
+    }
+
+    fn back(&self) -> Dec {
+        let fp = self.kind.footprint();
+        -(fp.length + self.clearance + self.wall() * 2 + self.relief())
+    }
+
+    fn top(&self) -> Dec {
+        let fp = self.kind.footprint();
+        fp.thickness + self.clearance + self.clip()
+    }
+
+    /// Tray body. It goes a bit into the wall in front of the board, so that it fuses
+    /// with the wall.
+    pub(crate) fn material(&self) -> Rc<dyn GeometryDyn> {
+        let fp = self.kind.footprint();
+        let reach = self.clearance + Dec::from(3);
+        Rc::new(self.block(
+            (self.back(), reach),
+            (-(self.header_clearance + self.floor()), self.top()),
+            fp.width + self.clearance * 2 + self.wall() * 2,
+        ))
+    }
+
+    /// Board slot, clip undercut, header pocket, relief behind the clip and opening for
+    /// cable plug through the wall
+    pub(crate) fn holes(&self) -> Vec<Rc<dyn GeometryDyn>> {
+        let sw = Dec::from(dec!(0.1));
+        let fp = self.kind.footprint();
+        let c = self.clearance;
+        let width = fp.width + c * 2;
+        let board_back = -(fp.length + c);
+        let board_top = fp.thickness + c;
+        let overhang = Dec::from(dec!(0.6));
+        let ledge = Dec::from(1);
+        let clip_back = board_back - self.wall();
+        let (plug_width, plug_height) = fp.plug;
+        let plug_bottom = fp.thickness + fp.connector.1 / 2 - plug_height / 2;
+
+        vec![
+            Rc::new(self.block((board_back, c), (Dec::from(0), board_top), width)),
+            Rc::new(self.block(
+                (board_back + overhang, c),
+                (board_top - sw, self.top() + sw),
+                width,
+            )),
+            Rc::new(self.block(
+                (board_back + ledge, -ledge),
+                (-self.header_clearance, sw),
+                width,
+            )),
+            Rc::new(self.block(
+                (clip_back - self.relief(), clip_back),
+                (-self.header_clearance, self.top() + sw),
+                width,
+            )),
+            Rc::new(self.block(
+                (-sw, Dec::from(10)),
+                (plug_bottom - c, plug_bottom + plug_height + c),
+                plug_width + c * 2,
+            )),
+        ]
+    }
+}
+
+impl RightKeyboardConfig {
+    /// Adds tray for controller `kind` to buttons hull and opening for cable in the wall.
+    /// Origin is in the middle of the connector edge of the board on its bottom side, at
+    /// the inner wall surface. Its y axis looks out of the wall and z axis looks up, so
+    /// the board lies along -y.
+    pub fn add_mcu_mount(mut self, origin: Origin, kind: McuKind) -> Self {
+        self.mcu_mounts.push(McuMount::new(origin, kind));
+        self
+    }
+}
@@ -0,0 +1,93 @@
+use geometry::{
+    decimal::Dec,
+    hyper_path::{hyper_path::HyperPath, hyper_point::SuperPoint},
+};
+use itertools::Itertools;
+use nalgebra::{Vector2, Vector3};
+use num_traits::Zero;
+use serde::{Deserialize, Serialize};
+
+use crate::keyboard_config::RightKeyboardConfig;
+
+/// Point of keyboard, which is moved to zero of coordinates with
+/// `RightKeyboardConfig::origin_at`. Axes stay as they are: x goes to the right, y goes
+/// away from the typist and z goes up from the table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OriginAnchor {
+    /// Middle key of the first main column, the one next to thumb cluster
+    HomeKey,
+    /// Far left corner of the table outline bounding box
+    BackLeftCorner,
+    /// Center of area of the table outline
+    Centroid,
+}
+
+impl RightKeyboardConfig {
+    /// Moves buttons and table outline in table plane, so that `anchor` comes to zero.
+    /// Holes, bolts, components and other features are not moved: their coordinates
+    /// become relative to the anchor and stay in place, when layout changes.
+    /// Config without buttons or outline is left as is.
+    pub fn origin_at(mut self, anchor: OriginAnchor) -> Self {
+        let Some(anchor) = self.anchor_point(anchor) else {
+            return self;
+        };
+        let shift = Vector3::new(-anchor.x, -anchor.y, Dec::zero());
+
+        for collection in [&mut self.main_buttons, &mut self.thumb_buttons]
+            .into_iter()
+            .chain(self.extra_collections.iter_mut().map(|(_, c)| c))
+        {
+            for column in &mut collection.columns {
+                for button in &mut column.buttons {
+                    button.origin.center += shift;
+                }
+            }
+        }
+        self.table_outline = self.table_outline.map(|line| {
+            line.map(|p| SuperPoint {
+                point: p.point + shift,
+                side_dir: p.side_dir,
+            })
+        });
+        self
+    }
+
+    fn anchor_point(&self, anchor: OriginAnchor) -> Option<Vector2<Dec>> {
+        let outline = self
+            .wall_samples()
+            .into_iter()
+            .map(|(p, _)| p.xy())
+            .collect_vec();
+        match anchor {
+            OriginAnchor::HomeKey => {
+                let column = self.main_buttons.columns.first()?;
+                let button = column.buttons.get(column.buttons.len() / 2)?;
+                Some(button.origin.center.xy())
+            }
+            OriginAnchor::BackLeftCorner => {
+                let left = outline.iter().map(|p| p.x).min()?;
+                let back = outline.iter().map(|p| p.y).max()?;
+                Some(Vector2::new(left, back))
+            }
+            OriginAnchor::Centroid => {
+                // Shoelace formula over outer wall, samples are dense enough for this
+                let (area, moment) = outline
+                    .iter()
+                    .map(|p| p.map(f64::from))
+                    .circular_tuple_windows()
+                    .map(|(a, b)| {
+                        let cross = a.x * b.y - b.x * a.y;
+                        (cross, (a + b) * cross)
+                    })
+                    .fold((0.0, Vector2::zeros()), |(area, moment), (a, m)| {
+                        (area + a, moment + m)
+                    });
+                if area.abs() < f64::EPSILON {
+                    return None;
+                }
+                let center = moment / (3.0 * area);
+                Some(center.map(Dec::from))
+            }
+        }
+    }
+}
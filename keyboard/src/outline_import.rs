@@ -0,0 +1,508 @@
+use anyhow::anyhow;
+use geometry::{
+    decimal::Dec,
+    hyper_path::{
+        hyper_line::HyperLine,
+        hyper_path::{HyperPath, Root},
+        hyper_point::SuperPoint,
+    },
+};
+use itertools::Itertools;
+use nalgebra::{Vector2, Vector3};
+use num_traits::Zero;
+
+/// Line of drawing: two points make straight line, four - a cubic curve
+type DrawnLine = Vec<Vector2<f64>>;
+
+/// Table outline from a drawing of case silhouette. Only the first closed path of the
+/// drawing is taken, it becomes clockwise, as walls expect, and starts at the first
+/// point of the path, which is better to be next to the top of the right column.
+#[derive(Clone, Debug)]
+pub struct OutlineImport {
+    scale: Dec,
+    max_line_length: Option<Dec>,
+    side_dir: Dec,
+}
+
+impl Default for OutlineImport {
+    fn default() -> Self {
+        Self {
+            scale: 1.into(),
+            max_line_length: None,
+            side_dir: 20.into(),
+        }
+    }
+}
+
+impl OutlineImport {
+    /// Millimeters in one unit of the drawing
+    pub fn scale(mut self, scale: impl Into<Dec>) -> Self {
+        self.scale = scale.into();
+        self
+    }
+
+    /// Lines, which are longer, are split into equal parts. More lines make walls
+    /// follow buttons more closely.
+    pub fn max_line_length(mut self, length: impl Into<Dec>) -> Self {
+        self.max_line_length = Some(length.into());
+        self
+    }
+
+    /// Length of side direction of outline points: how far up walls leave the table
+    /// before they turn to buttons
+    pub fn side_dir(mut self, side_dir: impl Into<Dec>) -> Self {
+        self.side_dir = side_dir.into();
+        self
+    }
+
+    /// Outline from `d` attribute of the first `<path>` of svg document. Transforms of
+    /// elements are not applied and arcs are not supported: in Inkscape path must be
+    /// converted with "Object to Path" and transforms flattened. Y axis of svg goes
+    /// down, so it is flipped to look at the outline from above.
+    pub fn from_svg(&self, svg: &str) -> anyhow::Result<Root<SuperPoint<Dec>>> {
+        let start = svg
+            .find("<path")
+            .ok_or(anyhow!("There is no path in svg"))?;
+        let tag = &svg[start..];
+        let tag = &tag[..tag.find('>').unwrap_or(tag.len())];
+        let d = attribute(tag, "d").ok_or(anyhow!("Path of svg has no `d` attribute"))?;
+        let lines = parse_svg_path(d)?
+            .into_iter()
+            .map(|line| line.into_iter().map(|p| Vector2::new(p.x, -p.y)).collect())
+            .collect();
+        self.outline(lines)
+    }
+
+    /// Outline from the first LWPOLYLINE or POLYLINE entity of ascii dxf. Polyline is
+    /// closed, even if it is not marked so. Bulges (arcs of polyline) are not supported.
+    pub fn from_dxf(&self, dxf: &str) -> anyhow::Result<Root<SuperPoint<Dec>>> {
+        let points = parse_dxf_polyline(dxf)?;
+        let mut lines = points
+            .iter()
+            .tuple_windows()
+            .filter(|(a, b)| a != b)
+            .map(|(a, b)| vec![*a, *b])
+            .collect_vec();
+        if let (Some(first), Some(last)) = (points.first(), points.last()) {
+            if first != last {
+                lines.push(vec![*last, *first]);
+            }
+        }
+        self.outline(lines)
+    }
+
+    fn outline(&self, mut lines: Vec<DrawnLine>) -> anyhow::Result<Root<SuperPoint<Dec>>> {
+        if lines.len() < 3 {
+            return Err(anyhow!(
+                "Outline must have at least 3 lines, drawing has {}",
+                lines.len()
+            ));
+        }
+        // Shoelace by ends of lines is enough to tell the direction
+        let area = lines
+            .iter()
+            .map(|l| l[0].perp(&l[l.len() - 1]))
+            .sum::<f64>();
+        if area > 0.0 {
+            lines.reverse();
+            lines.iter_mut().for_each(|l| l.reverse());
+        }
+
+        let side_dir = Vector3::z() * self.side_dir;
+        let scale = f64::from(self.scale);
+        let outline = lines
+            .into_iter()
+            .flat_map(|line| {
+                let points = line
+                    .into_iter()
+                    .map(|p| SuperPoint {
+                        side_dir,
+                        point: Vector3::new(
+                            Dec::from(p.x * scale),
+                            Dec::from(p.y * scale),
+                            Dec::zero(),
+                        ),
+                    })
+                    .collect_vec();
+                let line = match points.as_slice() {
+                    [a, b, c, d] => HyperLine::new_4(*a, *b, *c, *d),
+                    _ => HyperLine::new_2(points[0], points[points.len() - 1]),
+                };
+                match self.max_line_length {
+                    Some(max) => line.split_by_max_length(max),
+                    None => vec![line],
+                }
+            })
+            .fold(Root::new(), |hp, l| hp.push_back(l));
+        Ok(outline)
+    }
+}
+
+/// Value of attribute `name` of xml `tag`
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let pattern = format!("{name}=");
+    tag.match_indices(&pattern)
+        .filter(|(ix, _)| {
+            tag[..*ix]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_whitespace())
+        })
+        .find_map(|(ix, _)| {
+            let value = &tag[ix + pattern.len()..];
+            let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+            let value = &value[1..];
+            value.find(quote).map(|end| &value[..end])
+        })
+}
+
+enum PathToken {
+    Command(char),
+    Number(f64),
+}
+
+/// Commands and numbers of svg path data
+fn tokenize_svg_path(d: &str) -> anyhow::Result<Vec<PathToken>> {
+    let mut tokens = Vec::new();
+    let chars = d.chars().collect_vec();
+    let mut ix = 0;
+    while ix < chars.len() {
+        let c = chars[ix];
+        if c.is_whitespace() || c == ',' {
+            ix += 1;
+        } else if c.is_ascii_alphabetic() {
+            tokens.push(PathToken::Command(c));
+            ix += 1;
+        } else {
+            let start = ix;
+            if c == '-' || c == '+' {
+                ix += 1;
+            }
+            let mut dot = false;
+            while ix < chars.len() && (chars[ix].is_ascii_digit() || (chars[ix] == '.' && !dot)) {
+                dot |= chars[ix] == '.';
+                ix += 1;
+            }
+            if ix < chars.len() && (chars[ix] == 'e' || chars[ix] == 'E') {
+                ix += 1;
+                if ix < chars.len() && (chars[ix] == '-' || chars[ix] == '+') {
+                    ix += 1;
+                }
+                while ix < chars.len() && chars[ix].is_ascii_digit() {
+                    ix += 1;
+                }
+            }
+            let number: String = chars[start..ix].iter().collect();
+            tokens.push(PathToken::Number(
+                number
+                    .parse::<f64>()
+                    .map_err(|_| anyhow!("Bad number `{number}` in svg path"))?,
+            ));
+        }
+    }
+    Ok(tokens)
+}
+
+/// Lines of the first subpath of svg path data. Quadratic curves become cubic, path is
+/// closed, even if it has no `z`.
+fn parse_svg_path(d: &str) -> anyhow::Result<Vec<DrawnLine>> {
+    let tokens = tokenize_svg_path(d)?;
+    let mut lines: Vec<DrawnLine> = Vec::new();
+    let mut tokens = tokens.into_iter().peekable();
+    let mut current = Vector2::<f64>::zeros();
+    let mut start = Vector2::zeros();
+    // Control point of previous curve for smooth curves
+    let mut cubic_control: Option<Vector2<f64>> = None;
+    let mut quad_control: Option<Vector2<f64>> = None;
+    let mut command = None;
+
+    'path: loop {
+        if let Some(PathToken::Command(c)) = tokens.peek() {
+            command = Some(*c);
+            tokens.next();
+        }
+        let Some(c) = command else {
+            return Err(anyhow!("Svg path does not start with a command"));
+        };
+        if tokens.peek().is_none() && !matches!(c, 'z' | 'Z') {
+            break;
+        }
+        let relative = c.is_ascii_lowercase();
+        let mut numbers = |count: usize| -> anyhow::Result<Vec<f64>> {
+            (0..count)
+                .map(|_| match tokens.next() {
+                    Some(PathToken::Number(n)) => Ok(n),
+                    _ => Err(anyhow!("Command `{c}` of svg path lacks numbers")),
+                })
+                .collect()
+        };
+        let point = |n: &[f64]| {
+            let p = Vector2::new(n[0], n[1]);
+            if relative {
+                current + p
+            } else {
+                p
+            }
+        };
+        let (mut next_cubic, mut next_quad) = (None, None);
+        match c.to_ascii_uppercase() {
+            'M' => {
+                if !lines.is_empty() {
+                    break 'path;
+                }
+                current = point(&numbers(2)?);
+                start = current;
+                // Pairs after moveto are lineto
+                command = Some(if relative { 'l' } else { 'L' });
+            }
+            'L' => {
+                let p = point(&numbers(2)?);
+                lines.push(vec![current, p]);
+                current = p;
+            }
+            'H' => {
+                let n = numbers(1)?[0];
+                let p = Vector2::new(if relative { current.x + n } else { n }, current.y);
+                lines.push(vec![current, p]);
+                current = p;
+            }
+            'V' => {
+                let n = numbers(1)?[0];
+                let p = Vector2::new(current.x, if relative { current.y + n } else { n });
+                lines.push(vec![current, p]);
+                current = p;
+            }
+            'C' | 'S' => {
+                let n = numbers(if c.eq_ignore_ascii_case(&'C') { 6 } else { 4 })?;
+                let (a, b, p) = if n.len() == 6 {
+                    (point(&n[0..2]), point(&n[2..4]), point(&n[4..6]))
+                } else {
+                    let a = cubic_control.map_or(current, |prev| current * 2.0 - prev);
+                    (a, point(&n[0..2]), point(&n[2..4]))
+                };
+                lines.push(vec![current, a, b, p]);
+                next_cubic = Some(b);
+                current = p;
+            }
+            'Q' | 'T' => {
+                let n = numbers(if c.eq_ignore_ascii_case(&'Q') { 4 } else { 2 })?;
+                let (q, p) = if n.len() == 4 {
+                    (point(&n[0..2]), point(&n[2..4]))
+                } else {
+                    let q = quad_control.map_or(current, |prev| current * 2.0 - prev);
+                    (q, point(&n[0..2]))
+                };
+                lines.push(vec![
+                    current,
+                    current + (q - current) * (2.0 / 3.0),
+                    p + (q - p) * (2.0 / 3.0),
+                    p,
+                ]);
+                next_quad = Some(q);
+                current = p;
+            }
+            'Z' => break 'path,
+            'A' => {
+                return Err(anyhow!(
+                    "Arcs of svg path are not supported, convert them to curves"
+                ))
+            }
+            other => return Err(anyhow!("Unknown command `{other}` in svg path")),
+        }
+        (cubic_control, quad_control) = (next_cubic, next_quad);
+    }
+
+    if current != start {
+        lines.push(vec![current, start]);
+    }
+    lines.retain(|l| l[0] != l[l.len() - 1] || l.len() == 4);
+    Ok(lines)
+}
+
+/// Points of the first polyline of ascii dxf
+fn parse_dxf_polyline(dxf: &str) -> anyhow::Result<Vec<Vector2<f64>>> {
+    let pairs = dxf
+        .lines()
+        .map(str::trim)
+        .tuples()
+        .map(|(code, value)| (code.parse::<i32>().unwrap_or(-1), value))
+        .collect_vec();
+    let entity = pairs
+        .iter()
+        .position(|(code, value)| *code == 0 && (*value == "LWPOLYLINE" || *value == "POLYLINE"))
+        .ok_or(anyhow!("There is no polyline in dxf"))?;
+    let heavy = pairs[entity].1 == "POLYLINE";
+
+    let mut points = Vec::new();
+    let mut x = None;
+    // Heavy polyline has its own point before vertices, which is not a vertex
+    let mut in_vertex = !heavy;
+    for (code, value) in &pairs[entity + 1..] {
+        match (code, *value) {
+            (0, "VERTEX") if heavy => in_vertex = true,
+            (0, _) => break,
+            _ if !in_vertex => {}
+            (10, v) => x = Some(v.parse::<f64>()?),
+            (20, v) => {
+                let x = x
+                    .take()
+                    .ok_or(anyhow!("Y of dxf polyline vertex goes before its X"))?;
+                points.push(Vector2::new(x, v.parse::<f64>()?));
+            }
+            (42, v) if v.parse::<f64>()? != 0.0 => {
+                return Err(anyhow!("Bulges of dxf polyline are not supported"));
+            }
+            _ => {}
+        }
+    }
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use geometry::{
+        decimal::Dec,
+        hyper_path::{
+            hyper_path::{HyperPath, IsLinear, Root},
+            hyper_point::SuperPoint,
+            line::GetT,
+        },
+    };
+    use nalgebra::Vector2;
+
+    use super::{parse_svg_path, OutlineImport};
+
+    /// Start and end of a line, rounded, and whether the line is straight
+    type Ends = ((Dec, Dec), (Dec, Dec), bool);
+
+    fn ends(mut outline: Root<SuperPoint<Dec>>) -> Vec<Ends> {
+        let mut ends = Vec::new();
+        for _ in 0..outline.len() {
+            let (line, tail) = outline.head_tail();
+            let linear = line.is_linear();
+            let line = line.to_points();
+            let (a, b) = (line.get_t(Dec::from(0)), line.get_t(Dec::from(1)));
+            ends.push((
+                (a.x.round_dp(6), a.y.round_dp(6)),
+                (b.x.round_dp(6), b.y.round_dp(6)),
+                linear,
+            ));
+            outline = tail;
+        }
+        ends
+    }
+
+    fn at(x: i32, y: i32) -> (Dec, Dec) {
+        (Dec::from(x), Dec::from(y))
+    }
+
+    #[test]
+    fn relative_lines_match_absolute() {
+        let absolute = parse_svg_path("M 5 5 L 15 5 L 15 15 L 5 15 Z").unwrap();
+
+        for d in [
+            "m 5 5 l 10 0 l 0 10 l -10 0 z",
+            "m 5 5 h 10 v 10 h -10 z",
+            // Pairs after moveto are lineto of the same case
+            "m5,5 10,0 0,10 -10,0z",
+        ] {
+            assert_eq!(parse_svg_path(d).unwrap(), absolute, "{d}");
+        }
+    }
+
+    #[test]
+    fn relative_curves_match_absolute() {
+        let absolute =
+            parse_svg_path("M 10 10 C 10 15 15 20 20 20 S 30 15 30 10 Q 30 0 20 0 T 10 0 Z")
+                .unwrap();
+        let relative =
+            parse_svg_path("m 10 10 c 0 5 5 10 10 10 s 10 -5 10 -10 q 0 -10 -10 -10 t -10 0 z")
+                .unwrap();
+
+        assert_eq!(relative, absolute);
+        assert_eq!(relative.len(), 5);
+        // Smooth curve reflects control point of previous one
+        assert_eq!(relative[1][1], Vector2::new(25.0, 20.0));
+        assert_eq!(
+            relative[4],
+            vec![Vector2::new(10.0, 0.0), Vector2::new(10.0, 10.0)]
+        );
+    }
+
+    #[test]
+    fn outline_from_svg_with_relative_path() {
+        let svg = r#"<svg><path id="case" d="m 0 0 h 10 v 10 h -10 z"/></svg>"#;
+
+        let outline = OutlineImport::default().from_svg(svg).unwrap();
+
+        // Y of svg is flipped, so the path is clockwise from above and keeps its order
+        assert_eq!(
+            ends(outline),
+            vec![
+                (at(0, 0), at(10, 0), true),
+                (at(10, 0), at(10, -10), true),
+                (at(10, -10), at(0, -10), true),
+                (at(0, -10), at(0, 0), true),
+            ]
+        );
+    }
+
+    /// Ascii dxf of group code and value pairs
+    fn dxf(pairs: &[(i32, &str)]) -> String {
+        pairs
+            .iter()
+            .map(|(code, value)| format!("{code}\n{value}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn outline_from_dxf_lwpolyline() {
+        let dxf = dxf(&[
+            (0, "SECTION"),
+            (2, "ENTITIES"),
+            (0, "LWPOLYLINE"),
+            (8, "0"),
+            (90, "4"),
+            (70, "1"),
+            (10, "0"),
+            (20, "0"),
+            (10, "10"),
+            (20, "0"),
+            (10, "10"),
+            (20, "10"),
+            (10, "0"),
+            (20, "10"),
+            (0, "ENDSEC"),
+            (0, "EOF"),
+        ]);
+
+        let outline = OutlineImport::default().scale(2).from_dxf(&dxf).unwrap();
+
+        // Polyline goes counterclockwise, so it is reversed
+        assert_eq!(
+            ends(outline),
+            vec![
+                (at(0, 0), at(0, 20), true),
+                (at(0, 20), at(20, 20), true),
+                (at(20, 20), at(20, 0), true),
+                (at(20, 0), at(0, 0), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn dxf_bulges_are_rejected() {
+        let dxf = dxf(&[
+            (0, "LWPOLYLINE"),
+            (10, "0"),
+            (20, "0"),
+            (42, "0.5"),
+            (10, "10"),
+            (20, "0"),
+            (0, "EOF"),
+        ]);
+
+        assert!(OutlineImport::default().from_dxf(&dxf).is_err());
+    }
+}
@@ -0,0 +1,122 @@
+use std::f64::consts::PI;
+
+use geometry::decimal::Dec;
+use nalgebra::Vector3;
+use rust_decimal_macros::dec;
+
+use crate::{keyboard_config::RightKeyboardConfig, plate_deflection::web_fraction, KeyPosition};
+
+/// First root of clamped round plate vibration, (lambda * a)^2
+const CLAMPED_PLATE_ROOT: f64 = 10.22;
+
+/// Ear is most sensitive to ping around this frequency, in Hz
+const PING_FREQUENCY: f64 = 2500.0;
+
+/// Width of sensitive band in decades
+const PING_BAND: f64 = 0.35;
+
+/// Loss factor, at which plate damps half of the ringing
+const HALF_DAMPING: f64 = 0.01;
+
+/// Presets of plate material for acoustic and stiffness estimations
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlateMaterial {
+    Pla,
+    Petg,
+    Abs,
+    Aluminum,
+}
+
+impl PlateMaterial {
+    /// Young's modulus, in N/mm2
+    pub fn modulus(&self) -> Dec {
+        match self {
+            PlateMaterial::Pla => 3500.into(),
+            PlateMaterial::Petg => 2100.into(),
+            PlateMaterial::Abs => 2200.into(),
+            PlateMaterial::Aluminum => 69000.into(),
+        }
+    }
+
+    pub fn poisson_ratio(&self) -> Dec {
+        match self {
+            PlateMaterial::Pla => dec!(0.35).into(),
+            PlateMaterial::Petg => dec!(0.4).into(),
+            PlateMaterial::Abs => dec!(0.35).into(),
+            PlateMaterial::Aluminum => dec!(0.33).into(),
+        }
+    }
+
+    /// In kg/m3
+    pub fn density(&self) -> Dec {
+        match self {
+            PlateMaterial::Pla => 1240.into(),
+            PlateMaterial::Petg => 1270.into(),
+            PlateMaterial::Abs => 1050.into(),
+            PlateMaterial::Aluminum => 2700.into(),
+        }
+    }
+
+    /// How much of vibration energy material turns to heat in one cycle
+    pub fn loss_factor(&self) -> Dec {
+        match self {
+            PlateMaterial::Pla => dec!(0.02).into(),
+            PlateMaterial::Petg => dec!(0.03).into(),
+            PlateMaterial::Abs => dec!(0.025).into(),
+            PlateMaterial::Aluminum => dec!(0.001).into(),
+        }
+    }
+}
+
+/// Expected resonance of plate under a key
+#[derive(Clone, Copy, Debug)]
+pub struct KeyResonance {
+    pub key: KeyPosition,
+    /// Distance from the key to the nearest support in table plane, in millimeters
+    pub span: Dec,
+    /// Fundamental frequency of plate around the key, in Hz
+    pub frequency: Dec,
+    /// From 0 to 1: how likely plate rings audibly after key bottoms out
+    pub risk: Dec,
+}
+
+impl RightKeyboardConfig {
+    /// Rough acoustic estimation: plate around each key is taken as a round plate,
+    /// clamped at the nearest wall or bolt, as in `plate_deflection`. Plates, which
+    /// ring near the most audible frequencies and are poorly damped by material, get
+    /// high risk: they are the first candidates for ribs or dampening pockets.
+    /// Keys go from the most risky one.
+    pub fn ping_report(&self, material: PlateMaterial) -> Vec<KeyResonance> {
+        let thickness = f64::from(self.main_plane_thickness);
+        let e = f64::from(material.modulus());
+        let nu = f64::from(material.poisson_ratio());
+        // Density in t/mm3, so that frequency comes in Hz with newtons and millimeters
+        let density = f64::from(material.density()) * 1e-12;
+        let damping = HALF_DAMPING / (HALF_DAMPING + f64::from(material.loss_factor()));
+
+        let mut keys = self
+            .keys()
+            .map(|(key, button)| {
+                let center: Vector3<f64> = button.origin.center.map(f64::from);
+                // Keys right on a support are taken as 1mm away, to keep frequency finite
+                let span = self.support_distance(center.xy()).max(1.0);
+                let web = web_fraction(button.kind);
+                // Cutouts take stiffness along the web, but mass over the whole square
+                let rigidity = e * thickness.powi(3) / (12.0 * (1.0 - nu * nu)) * web;
+                let mass = density * thickness * (1.0 - (1.0 - web).powi(2));
+                let frequency =
+                    CLAMPED_PLATE_ROOT / (2.0 * PI * span * span) * (rigidity / mass).sqrt();
+                let band = (frequency / PING_FREQUENCY).log10() / PING_BAND;
+                let risk = (-band * band / 2.0).exp() * damping;
+                KeyResonance {
+                    key,
+                    span: Dec::from(span).round_dp(1),
+                    frequency: Dec::from(frequency).round_dp(0),
+                    risk: Dec::from(risk).round_dp(2),
+                }
+            })
+            .collect::<Vec<_>>();
+        keys.sort_by_key(|k| std::cmp::Reverse(k.risk));
+        keys
+    }
+}
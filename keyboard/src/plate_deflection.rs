@@ -0,0 +1,146 @@
+use std::f64::consts::PI;
+
+use geometry::decimal::Dec;
+use itertools::Itertools;
+use nalgebra::{Vector2, Vector3};
+use rust_decimal_macros::dec;
+
+use crate::{
+    button::ButtonMountKind, keyboard_config::RightKeyboardConfig, ping_report::PlateMaterial,
+    KeyPosition,
+};
+
+/// Switch cutouts of all mounts are about this wide, in millimeters
+const SWITCH_CUTOUT: f64 = 14.0;
+
+/// Coarse model of key plate stiffness. Plate around each key is taken as a round plate,
+/// clamped at the nearest support (outer wall or bolt) and loaded in the center by the
+/// typing force. Switch cutouts weaken the plate in proportion to the material, left
+/// between them. Numbers are rough, but long unsupported spans stand out clearly.
+#[derive(Clone, Copy, Debug)]
+pub struct PlateStiffness {
+    modulus: Dec,
+    poisson_ratio: Dec,
+    load: Dec,
+    max_deflection: Dec,
+}
+
+/// Estimated plate deflection under a key
+#[derive(Clone, Copy, Debug)]
+pub struct KeyDeflection {
+    pub key: KeyPosition,
+    /// Distance from the key to the nearest support in table plane, in millimeters
+    pub span: Dec,
+    /// In millimeters
+    pub deflection: Dec,
+    /// Deflection is more, than allowed
+    pub excessive: bool,
+}
+
+/// Part of plate width, left between switch cutouts
+pub(crate) fn web_fraction(kind: ButtonMountKind) -> f64 {
+    if kind == ButtonMountKind::Placeholder {
+        1.0
+    } else {
+        1.0 - SWITCH_CUTOUT / f64::from(kind.button_width())
+    }
+}
+
+impl Default for PlateStiffness {
+    fn default() -> Self {
+        Self {
+            modulus: 3500.into(),
+            poisson_ratio: dec!(0.35).into(),
+            load: dec!(1.5).into(),
+            max_deflection: dec!(0.05).into(),
+        }
+    }
+}
+
+impl PlateStiffness {
+    /// PLA plate, pressed with 1.5N, allowed to bend by 0.05mm
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Young's modulus of plate material, in N/mm2
+    pub fn modulus(mut self, modulus: impl Into<Dec>) -> Self {
+        self.modulus = modulus.into();
+        self
+    }
+
+    /// Takes modulus and Poisson's ratio of the material
+    pub fn material(self, material: PlateMaterial) -> Self {
+        self.modulus(material.modulus())
+            .poisson_ratio(material.poisson_ratio())
+    }
+
+    pub fn poisson_ratio(mut self, poisson_ratio: impl Into<Dec>) -> Self {
+        self.poisson_ratio = poisson_ratio.into();
+        self
+    }
+
+    /// Force of a single key press, in newtons
+    pub fn load(mut self, load: impl Into<Dec>) -> Self {
+        self.load = load.into();
+        self
+    }
+
+    /// Deflection, after which key is reported, in millimeters
+    pub fn max_deflection(mut self, max_deflection: impl Into<Dec>) -> Self {
+        self.max_deflection = max_deflection.into();
+        self
+    }
+
+    /// Center deflection of clamped round plate under point load: P * a^2 / (16 * pi * D)
+    fn deflection(&self, span: f64, thickness: f64, material: f64) -> f64 {
+        let (e, nu) = (f64::from(self.modulus), f64::from(self.poisson_ratio));
+        let rigidity = e * thickness.powi(3) / (12.0 * (1.0 - nu * nu)) * material;
+        f64::from(self.load) * span * span / (16.0 * PI * rigidity)
+    }
+}
+
+impl RightKeyboardConfig {
+    /// Distance in table plane from point to the nearest outer wall or bolt
+    pub(crate) fn support_distance(&self, point: Vector2<f64>) -> f64 {
+        let walls = self
+            .wall_samples()
+            .into_iter()
+            .map(|(p, _)| Vector2::new(f64::from(p.x), f64::from(p.y)))
+            .circular_tuple_windows()
+            .map(|(a, b)| {
+                let ab = b - a;
+                let t = if ab.norm_squared() > 0.0 {
+                    ((point - a).dot(&ab) / ab.norm_squared()).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                (a + ab * t - point).norm()
+            });
+        let bolts = self.bolts.iter().map(|(_, _, b)| {
+            let c = b.origin.center;
+            (Vector2::new(f64::from(c.x), f64::from(c.y)) - point).norm()
+        });
+        walls.chain(bolts).fold(f64::MAX, f64::min)
+    }
+
+    /// Estimates, how much key plate bends under each key, see `PlateStiffness`.
+    /// Keys, which plate is far from walls and bolts, get `excessive` flag.
+    pub fn plate_deflection(&self, stiffness: &PlateStiffness) -> Vec<KeyDeflection> {
+        let thickness = f64::from(self.main_plane_thickness);
+        self.keys()
+            .map(|(key, button)| {
+                let center: Vector3<f64> = button.origin.center.map(f64::from);
+                let span = self.support_distance(center.xy());
+                let material = web_fraction(button.kind);
+                let deflection = Dec::from(stiffness.deflection(span, thickness, material));
+                KeyDeflection {
+                    key,
+                    span: Dec::from(span).round_dp(1),
+                    deflection: deflection.round_dp(3),
+                    excessive: deflection > stiffness.max_deflection,
+                }
+            })
+            .collect()
+    }
+}
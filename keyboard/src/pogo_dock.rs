@@ -0,0 +1,219 @@
+use std::rc::Rc;
+
+use anyhow::anyhow;
+use geometry::{
+    decimal::Dec,
+    geometry::GeometryDyn,
+    indexes::geo_index::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId},
+    origin::Origin,
+    shapes::{Cylinder, Rect},
+};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::keyboard_config::RightKeyboardConfig;
+
+/// Spring pin interface in the outer wall for a detachable module: numpad, wrist rest
+/// with battery and so on. Origin is in the middle of the dock on the outer wall surface,
+/// z axis looks out of the wall, x goes along the pin row, as `label_anchor` gives it.
+/// Keyboard gets a shallow recess with spring pins in the floor and a magnet at each
+/// end of the row, all backed by material inside of the wall. Module gets a plug, which
+/// fits the recess, with holes for target pins and magnets at the same places. Magnets
+/// both pull the parts together and center the plug in the recess.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PogoDock {
+    pub(crate) origin: Origin,
+    pub(crate) pins: usize,
+    pub(crate) pitch: Dec,
+    pub(crate) pin_diameter: Dec,
+    pub(crate) pin_length: Dec,
+    pub(crate) magnet_diameter: Dec,
+    pub(crate) magnet_depth: Dec,
+    pub(crate) recess_depth: Dec,
+    pub(crate) plug_length: Dec,
+    pub(crate) clearance: Dec,
+}
+
+impl PogoDock {
+    /// Five pins with 2.54mm pitch and two 5x2mm disc magnets
+    pub fn new(origin: Origin) -> Self {
+        Self {
+            origin,
+            pins: 5,
+            pitch: dec!(2.54).into(),
+            pin_diameter: dec!(1.5).into(),
+            pin_length: 5.into(),
+            magnet_diameter: 5.into(),
+            magnet_depth: 2.into(),
+            recess_depth: dec!(1.5).into(),
+            plug_length: 6.into(),
+            clearance: dec!(0.15).into(),
+        }
+    }
+
+    pub fn pins(mut self, pins: usize) -> Self {
+        self.pins = pins;
+        self
+    }
+
+    pub fn pitch(mut self, pitch: impl Into<Dec>) -> Self {
+        self.pitch = pitch.into();
+        self
+    }
+
+    /// Barrel of spring pin and its length, which goes into the keyboard
+    pub fn pin(mut self, diameter: impl Into<Dec>, length: impl Into<Dec>) -> Self {
+        self.pin_diameter = diameter.into();
+        self.pin_length = length.into();
+        self
+    }
+
+    /// Disc magnets, the same in both parts
+    pub fn magnet(mut self, diameter: impl Into<Dec>, depth: impl Into<Dec>) -> Self {
+        self.magnet_diameter = diameter.into();
+        self.magnet_depth = depth.into();
+        self
+    }
+
+    /// How deep plug of the module goes into the wall
+    pub fn recess_depth(mut self, recess_depth: impl Into<Dec>) -> Self {
+        self.recess_depth = recess_depth.into();
+        self
+    }
+
+    /// Length of module plug outside of the wall
+    pub fn plug_length(mut self, plug_length: impl Into<Dec>) -> Self {
+        self.plug_length = plug_length.into();
+        self
+    }
+
+    /// Gap between plug and recess and around pins and magnets
+    pub fn clearance(mut self, clearance: impl Into<Dec>) -> Self {
+        self.clearance = clearance.into();
+        self
+    }
+
+    /// Offsets of pins along the row
+    fn pin_offsets(&self) -> Vec<Dec> {
+        let first = -self.pitch * Dec::from(self.pins as i64 - 1) / 2;
+        (0..self.pins)
+            .map(|i| first + self.pitch * Dec::from(i as i64))
+            .collect()
+    }
+
+    /// Offset of magnet on the right end of the row
+    fn magnet_offset(&self) -> Dec {
+        self.pitch * Dec::from(self.pins as i64 - 1) / 2
+            + (self.pin_diameter + self.magnet_diameter) / 2
+            + Dec::from(2)
+    }
+
+    /// Size of recess in the wall
+    fn size(&self) -> (Dec, Dec) {
+        let margin = Dec::from(2);
+        (
+            (self.magnet_offset() + self.magnet_diameter / 2 + margin) * 2,
+            self.magnet_diameter.max(self.pin_diameter) + margin * 2,
+        )
+    }
+
+    /// Depth of material behind the recess floor
+    fn backing(&self) -> Dec {
+        self.recess_depth + self.pin_length.max(self.magnet_depth) + Dec::from(1)
+    }
+
+    /// Material inside of the wall, which carries pins and magnets
+    pub(crate) fn keyboard_material(&self) -> Rc<dyn GeometryDyn> {
+        let (width, height) = self.size();
+        let margin = Dec::from(2);
+        Rc::new(Rect::with_top_at(
+            self.origin.clone(),
+            width + margin * 2,
+            height + margin * 2,
+            self.backing(),
+        ))
+    }
+
+    /// Recess, pin holes, which go through the backing for wires, and magnet pockets
+    pub(crate) fn keyboard_holes(&self) -> Vec<Rc<dyn GeometryDyn>> {
+        let sw = Dec::from(dec!(0.1));
+        let (width, height) = self.size();
+        let floor = self.origin.clone().offset_z(-self.recess_depth);
+        let mut holes: Vec<Rc<dyn GeometryDyn>> = vec![Rc::new(Rect::with_top_at(
+            self.origin.clone().offset_z(sw),
+            width,
+            height,
+            self.recess_depth + sw,
+        ))];
+        for x in self.pin_offsets() {
+            holes.push(Rc::new(Cylinder::with_top_at(
+                floor.clone().offset_x(x).offset_z(sw),
+                self.backing() - self.recess_depth + sw * 2,
+                self.pin_diameter / 2 + self.clearance,
+            )));
+        }
+        for x in [-self.magnet_offset(), self.magnet_offset()] {
+            holes.push(Rc::new(Cylinder::with_top_at(
+                floor.clone().offset_x(x).offset_z(sw),
+                self.magnet_depth + self.clearance + sw,
+                self.magnet_diameter / 2 + self.clearance,
+            )));
+        }
+        holes
+    }
+
+    /// Plug of the module with holes for target pins, which go through it for wires, and
+    /// magnet pockets on its face
+    fn module_parts(&self) -> (Rect, Vec<Box<dyn GeometryDyn>>) {
+        let sw = Dec::from(dec!(0.1));
+        let (width, height) = self.size();
+        let face = self.origin.clone().offset_z(-self.recess_depth);
+        let plug = Rect::with_bottom_at(
+            face.clone(),
+            width - self.clearance * 2,
+            height - self.clearance * 2,
+            self.recess_depth + self.plug_length,
+        );
+        let length = self.recess_depth + self.plug_length;
+        let mut holes: Vec<Box<dyn GeometryDyn>> = Vec::new();
+        for x in self.pin_offsets() {
+            holes.push(Box::new(Cylinder::with_bottom_at(
+                face.clone().offset_x(x).offset_z(-sw),
+                length + sw * 2,
+                self.pin_diameter / 2 + self.clearance,
+            )));
+        }
+        for x in [-self.magnet_offset(), self.magnet_offset()] {
+            holes.push(Box::new(Cylinder::with_bottom_at(
+                face.clone().offset_x(x).offset_z(-sw),
+                self.magnet_depth + self.clearance + sw,
+                self.magnet_diameter / 2 + self.clearance,
+            )));
+        }
+        (plug, holes)
+    }
+}
+
+impl RightKeyboardConfig {
+    /// Plug for detachable module, which goes into pogo dock of the keyboard. It is
+    /// placed in keyboard coordinates, so it can be checked in place and then joined
+    /// with the module body.
+    pub fn pogo_plug(&self, index: &mut GeoIndex) -> anyhow::Result<MeshId> {
+        let dock = self
+            .pogo_dock
+            .as_ref()
+            .ok_or(anyhow!("Pogo dock is not configured"))?;
+        if dock.magnet_depth + dock.clearance >= dock.recess_depth + dock.plug_length {
+            return Err(anyhow!("Pogo plug is too short for magnets"));
+        }
+        let (plug, holes) = dock.module_parts();
+        let plug_mesh = index.new_mesh();
+        plug.polygonize(plug_mesh.make_mut_ref(index), 0)?;
+        for hole in holes {
+            let hole_mesh = index.new_mesh();
+            hole.polygonize(hole_mesh.make_mut_ref(index), self.quality.surface_steps)?;
+            Self::subtract_mesh(plug_mesh, hole_mesh, index)?;
+        }
+        Ok(plug_mesh)
+    }
+}
@@ -0,0 +1,203 @@
+use std::rc::Rc;
+
+use geometry::{
+    decimal::Dec,
+    geometry::GeometryDyn,
+    origin::Origin,
+    shapes::{Cylinder, Rect},
+};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::keyboard_config::{KeyboardMesh, RightKeyboardConfig};
+
+/// How deep pockets go into the case behind the panel
+fn depth() -> Dec {
+    10.into()
+}
+
+/// Access hole for tactile reset button, which sits behind the panel. Button is pressed
+/// with a pin or paperclip through the hole, so it is not pressed by accident.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ResetButton {
+    pub(crate) hole_diameter: Dec,
+    pub(crate) body: Dec,
+    pub(crate) panel_thickness: Dec,
+    pub(crate) clearance: Dec,
+}
+
+impl ResetButton {
+    /// 6 x 6mm tactile button behind 2mm hole
+    pub fn new() -> Self {
+        Self {
+            hole_diameter: 2.into(),
+            body: 6.into(),
+            panel_thickness: dec!(1.2).into(),
+            clearance: dec!(0.2).into(),
+        }
+    }
+
+    /// Diameter of the hole for the pin
+    pub fn hole_diameter(mut self, hole_diameter: impl Into<Dec>) -> Self {
+        self.hole_diameter = hole_diameter.into();
+        self
+    }
+
+    /// Side of square button body
+    pub fn body(mut self, body: impl Into<Dec>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Wall between outer surface and the button
+    pub fn panel_thickness(mut self, panel_thickness: impl Into<Dec>) -> Self {
+        self.panel_thickness = panel_thickness.into();
+        self
+    }
+
+    /// Gap around button body
+    pub fn clearance(mut self, clearance: impl Into<Dec>) -> Self {
+        self.clearance = clearance.into();
+        self
+    }
+
+    pub(crate) fn holes(&self, origin: Origin) -> Vec<Rc<dyn GeometryDyn>> {
+        let sw = Dec::from(dec!(0.1));
+        let pocket = self.body + self.clearance * 2;
+        vec![
+            Rc::new(Cylinder::with_top_at(
+                origin.clone().offset_z(sw),
+                self.panel_thickness + sw * 2,
+                self.hole_diameter / 2,
+            )),
+            Rc::new(Rect::with_top_at(
+                origin.offset_z(-self.panel_thickness),
+                pocket,
+                pocket,
+                depth(),
+            )),
+        ]
+    }
+}
+
+impl Default for ResetButton {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Pocket for slide power switch with a slot for its actuator. Actuator slides along x
+/// axis of origin, slot is long enough for the whole throw.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PowerSwitch {
+    pub(crate) body_width: Dec,
+    pub(crate) body_height: Dec,
+    pub(crate) actuator_width: Dec,
+    pub(crate) actuator_height: Dec,
+    pub(crate) throw: Dec,
+    pub(crate) panel_thickness: Dec,
+    pub(crate) clearance: Dec,
+}
+
+impl PowerSwitch {
+    /// SS12D00: 8.5 x 3.7mm body, 1.5 x 1.5mm actuator with 1.5mm throw
+    pub fn new() -> Self {
+        Self {
+            body_width: dec!(8.5).into(),
+            body_height: dec!(3.7).into(),
+            actuator_width: dec!(1.5).into(),
+            actuator_height: dec!(1.5).into(),
+            throw: dec!(1.5).into(),
+            panel_thickness: 1.into(),
+            clearance: dec!(0.2).into(),
+        }
+    }
+
+    /// Size of switch body across the panel
+    pub fn body(mut self, width: impl Into<Dec>, height: impl Into<Dec>) -> Self {
+        self.body_width = width.into();
+        self.body_height = height.into();
+        self
+    }
+
+    /// Size of actuator across the panel
+    pub fn actuator(mut self, width: impl Into<Dec>, height: impl Into<Dec>) -> Self {
+        self.actuator_width = width.into();
+        self.actuator_height = height.into();
+        self
+    }
+
+    /// How far actuator travels between positions
+    pub fn throw(mut self, throw: impl Into<Dec>) -> Self {
+        self.throw = throw.into();
+        self
+    }
+
+    /// Wall between outer surface and the switch body. Actuator must stick out of it.
+    pub fn panel_thickness(mut self, panel_thickness: impl Into<Dec>) -> Self {
+        self.panel_thickness = panel_thickness.into();
+        self
+    }
+
+    /// Gap around body and actuator
+    pub fn clearance(mut self, clearance: impl Into<Dec>) -> Self {
+        self.clearance = clearance.into();
+        self
+    }
+
+    pub(crate) fn holes(&self, origin: Origin) -> Vec<Rc<dyn GeometryDyn>> {
+        let sw = Dec::from(dec!(0.1));
+        vec![
+            Rc::new(Rect::with_top_at(
+                origin.clone().offset_z(sw),
+                self.actuator_width + self.throw + self.clearance * 2,
+                self.actuator_height + self.clearance * 2,
+                self.panel_thickness + sw * 2,
+            )),
+            Rc::new(Rect::with_top_at(
+                origin.offset_z(-self.panel_thickness),
+                self.body_width + self.clearance * 2,
+                self.body_height + self.clearance * 2,
+                depth(),
+            )),
+        ]
+    }
+}
+
+impl Default for PowerSwitch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RightKeyboardConfig {
+    /// Reset button access in `mesh`: walls of buttons hull or bottom plate. Origin is in
+    /// the middle of the hole on the outer surface, z axis looks out of the case.
+    pub fn add_reset_button(
+        mut self,
+        mesh: KeyboardMesh,
+        origin: Origin,
+        button: ResetButton,
+    ) -> Self {
+        self.holes
+            .entry(mesh)
+            .or_default()
+            .extend(button.holes(origin));
+        self
+    }
+
+    /// Power switch pocket in `mesh`: walls of buttons hull or bottom plate. Origin is in
+    /// the middle of the actuator slot on the outer surface, z axis looks out of the case.
+    pub fn add_power_switch(
+        mut self,
+        mesh: KeyboardMesh,
+        origin: Origin,
+        switch: PowerSwitch,
+    ) -> Self {
+        self.holes
+            .entry(mesh)
+            .or_default()
+            .extend(switch.holes(origin));
+        self
+    }
+}
@@ -0,0 +1,413 @@
+use std::rc::Rc;
+
+use anyhow::anyhow;
+use geometry::{
+    decimal::Dec,
+    geometry::GeometryDyn,
+    indexes::geo_index::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId},
+    origin::Origin,
+    shapes::{extrude, Cylinder, Rect},
+};
+use itertools::Itertools;
+use nalgebra::{Vector2, Vector3};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolt::{Bolt, Nut},
+    keyboard_config::{KeyboardMesh, RightKeyboardConfig},
+};
+
+type Shapes = Vec<Rc<dyn GeometryDyn>>;
+
+/// Alignment feature on the faces of a cut
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum SplitJoint {
+    /// Tenon of `length` grows from the positive piece `depth` into the negative one and
+    /// gets wider by 15 degree flanks. Mortise in the negative piece is open towards +y of
+    /// the cut, so pieces slide together along y.
+    Dovetail { width: Dec, depth: Dec, length: Dec },
+    /// Holes for dowel pin of `diameter` x `length`, half of it in each piece
+    Pins { diameter: Dec, length: Dec },
+}
+
+/// Cut by xy plane of `origin`, z axis looks into the positive piece. Joints and bolts
+/// are placed by x and y on the plane and must be where both pieces have material.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SplitCut {
+    pub(crate) origin: Origin,
+    pub(crate) joints: Vec<(Dec, Dec)>,
+    pub(crate) bolts: Vec<(Dec, Dec)>,
+}
+
+impl SplitCut {
+    pub fn new(origin: Origin) -> Self {
+        Self {
+            origin,
+            joints: Vec::new(),
+            bolts: Vec::new(),
+        }
+    }
+
+    pub fn joint(mut self, x: impl Into<Dec>, y: impl Into<Dec>) -> Self {
+        self.joints.push((x.into(), y.into()));
+        self
+    }
+
+    /// Bolt across the cut with bosses on both sides. Head bears on the positive boss,
+    /// nut - on the negative one.
+    pub fn bolt(mut self, x: impl Into<Dec>, y: impl Into<Dec>) -> Self {
+        self.bolts.push((x.into(), y.into()));
+        self
+    }
+}
+
+/// Splitting of a part, which does not fit the print bed, into pieces. Each cut
+/// divides space in two, so `n` cuts give up to `2^n` pieces, empty ones are dropped.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PrintSplit {
+    pub(crate) cuts: Vec<SplitCut>,
+    pub(crate) joint: SplitJoint,
+    pub(crate) bolt: Option<Bolt>,
+    pub(crate) boss_length: Dec,
+    pub(crate) clearance: Dec,
+    pub(crate) bed: Option<(Dec, Dec)>,
+}
+
+impl PrintSplit {
+    pub fn new(joint: SplitJoint) -> Self {
+        Self {
+            cuts: Vec::new(),
+            joint,
+            bolt: None,
+            boss_length: 6.into(),
+            clearance: dec!(0.15).into(),
+            bed: Some((180.into(), 180.into())),
+        }
+    }
+
+    pub fn cut(mut self, cut: SplitCut) -> Self {
+        self.cuts.push(cut);
+        self
+    }
+
+    /// Bolt for bolt points of cuts
+    pub fn bolt(mut self, bolt: Bolt) -> Self {
+        self.bolt = Some(bolt);
+        self
+    }
+
+    /// Length of each boss from the cut. Bolt must be longer, than two bosses and nut.
+    pub fn boss_length(mut self, boss_length: impl Into<Dec>) -> Self {
+        self.boss_length = boss_length.into();
+        self
+    }
+
+    pub fn clearance(mut self, clearance: impl Into<Dec>) -> Self {
+        self.clearance = clearance.into();
+        self
+    }
+
+    /// Pieces, which footprint on the table is bigger, than `width` x `height`, fail the
+    /// split
+    pub fn bed(mut self, width: impl Into<Dec>, height: impl Into<Dec>) -> Self {
+        self.bed = Some((width.into(), height.into()));
+        self
+    }
+
+    pub fn any_bed(mut self) -> Self {
+        self.bed = None;
+        self
+    }
+
+    fn wall(&self) -> Dec {
+        dec!(1.6).into()
+    }
+
+    /// Dovetail profile in xz plane of the cut: z goes into the negative piece. Profile
+    /// starts a bit inside of positive piece, so tenon fuses with it.
+    fn dovetail(&self, width: Dec, depth: Dec, grow: Dec) -> Vec<Vector2<Dec>> {
+        let sw = Dec::from(dec!(0.1));
+        // tan(15)
+        let flare = depth * Dec::from(dec!(0.2679));
+        let half = width / 2 + grow;
+        vec![
+            Vector2::new(-half, -sw),
+            Vector2::new(half, -sw),
+            Vector2::new(half + flare, depth + grow),
+            Vector2::new(-half - flare, depth + grow),
+        ]
+    }
+
+    /// Material and holes of joints and bolts of `cut` for the piece on `positive` side
+    fn features(
+        &self,
+        cut: &SplitCut,
+        positive: bool,
+        span: Dec,
+    ) -> anyhow::Result<(Shapes, Shapes)> {
+        let sw = Dec::from(dec!(0.1));
+        let c = self.clearance;
+        let quarter = Dec::pi() / Dec::from(2);
+        let mut material: Shapes = Vec::new();
+        let mut holes: Shapes = Vec::new();
+
+        for (x, y) in &cut.joints {
+            let at = cut.origin.clone().offset_x(*x).offset_y(*y);
+            match self.joint {
+                SplitJoint::Dovetail {
+                    width,
+                    depth,
+                    length,
+                } => {
+                    // Extrusion goes along y of the cut, its profile y goes against cut z
+                    let along = |from: Dec| {
+                        at.clone()
+                            .offset_y(from)
+                            .rotate_axisangle(Vector3::x() * -quarter)
+                    };
+                    if positive {
+                        material.push(Rc::new(extrude(
+                            &self.dovetail(width, depth, Dec::from(0)),
+                            length,
+                            along(-length / 2),
+                        )));
+                    } else {
+                        holes.push(Rc::new(extrude(
+                            &self.dovetail(width, depth, c),
+                            span,
+                            along(-length / 2 - c),
+                        )));
+                    }
+                }
+                SplitJoint::Pins { diameter, length } => {
+                    holes.push(Rc::new(Cylinder::with_bottom_at(
+                        at.offset_z(-length / 2 - c),
+                        length + c * 2,
+                        diameter / 2 + c,
+                    )));
+                }
+            }
+        }
+
+        if cut.bolts.is_empty() {
+            return Ok((material, holes));
+        }
+        let bolt = self
+            .bolt
+            .as_ref()
+            .ok_or(anyhow!("Cut has bolts, but split has no bolt"))?;
+        let nut = bolt
+            .nut
+            .as_ref()
+            .map(|Nut::Hex { outer_diameter, .. }| *outer_diameter);
+        let boss_radius = bolt.head_diameter.max(nut.unwrap_or_default()) / 2 + self.wall();
+        for (x, y) in &cut.bolts {
+            let at = cut.origin.clone().offset_x(*x).offset_y(*y);
+            let boss = if positive {
+                at.clone().offset_z(-sw)
+            } else {
+                at.clone().offset_z(-self.boss_length)
+            };
+            material.push(Rc::new(Cylinder::with_bottom_at(
+                boss,
+                self.boss_length + sw,
+                boss_radius,
+            )));
+            holes.push(Rc::new(Cylinder::with_bottom_at(
+                at.clone().offset_z(-self.boss_length - sw),
+                self.boss_length * 2 + sw * 2,
+                bolt.diameter / 2 + c,
+            )));
+            if let (
+                false,
+                Some(Nut::Hex {
+                    outer_diameter,
+                    height,
+                }),
+            ) = (positive, &bolt.nut)
+            {
+                holes.push(Rc::new(
+                    Cylinder::with_bottom_at(
+                        at.offset_z(-self.boss_length - sw),
+                        *height + c + sw,
+                        *outer_diameter / 2 + c,
+                    )
+                    .steps(6),
+                ));
+            }
+        }
+        Ok((material, holes))
+    }
+    /// Cuts `part` down to the piece on `sides` of the cuts: bit `i` set means the
+    /// positive side of cut `i`. Returns false, if nothing of `part` is left there.
+    fn cut_piece(
+        &self,
+        part: MeshId,
+        sides: usize,
+        span: Dec,
+        steps: usize,
+        index: &mut GeoIndex,
+    ) -> anyhow::Result<bool> {
+        let mut other_side: Shapes = Vec::new();
+        let mut material = Vec::new();
+        let mut holes = Vec::new();
+        for (ix, cut) in self.cuts.iter().enumerate() {
+            let positive = sides & (1 << ix) != 0;
+            other_side.push(Rc::new(if positive {
+                Rect::with_top_at(cut.origin.clone(), span, span, span)
+            } else {
+                Rect::with_bottom_at(cut.origin.clone(), span, span, span)
+            }));
+            let (m, h) = self.features(cut, positive, span)?;
+            material.extend(m);
+            holes.extend(h);
+        }
+
+        for hole in other_side {
+            RightKeyboardConfig::subtract_geometry(part, hole.as_ref(), steps, index)?;
+        }
+        if index.get_mesh_polygons(part).is_empty() {
+            return Ok(false);
+        }
+        for m in material {
+            let material_mesh = index.new_mesh();
+            m.polygonize(material_mesh.make_mut_ref(index), steps)?;
+            index.unite_mesh(part, material_mesh)?;
+        }
+        for hole in holes {
+            RightKeyboardConfig::subtract_geometry(part, hole.as_ref(), steps, index)?;
+        }
+        Ok(true)
+    }
+}
+
+impl RightKeyboardConfig {
+    /// Builds `mesh` once for each piece of `split`, each piece in its own index, which
+    /// `make_index` makes. Pieces stay in keyboard coordinates, so they can be checked
+    /// together.
+    pub fn split_for_printing(
+        &self,
+        mesh: KeyboardMesh,
+        split: &PrintSplit,
+        make_index: impl Fn() -> GeoIndex,
+    ) -> anyhow::Result<Vec<GeoIndex>> {
+        if split.cuts.is_empty() {
+            return Err(anyhow!("Print split has no cuts"));
+        }
+        let outline = self.outline_on_table(0);
+        let extent = |v: Vec<Dec>| {
+            v.into_iter()
+                .minmax()
+                .into_option()
+                .map(|(min, max)| max - min)
+                .unwrap_or_default()
+        };
+        let span = (extent(outline.iter().map(|p| p.x).collect())
+            + extent(outline.iter().map(|p| p.y).collect()))
+            * 2
+            + Dec::from(200);
+
+        let mut pieces = Vec::new();
+        for sides in 0..(1usize << split.cuts.len()) {
+            let mut index = make_index();
+            let part = match mesh {
+                KeyboardMesh::ButtonsHull => self.buttons_hull(&mut index)?,
+                KeyboardMesh::Bottom => self.bottom_pad(&mut index)?,
+                KeyboardMesh::PcbMount => self.pcb_mount(&mut index)?,
+            };
+
+            if !split.cut_piece(part, sides, span, self.quality.surface_steps, &mut index)? {
+                continue;
+            }
+
+            if let Some((width, height)) = split.bed {
+                let triangles = index.stl_triangles()?;
+                let footprint = |axis: usize| {
+                    triangles
+                        .iter()
+                        .flat_map(|t| t.vertices.iter().map(move |v| f64::from(v[axis])))
+                        .minmax()
+                        .into_option()
+                        .map(|(min, max)| Dec::from(max - min))
+                        .unwrap_or_default()
+                };
+                let (x, y) = (footprint(0), footprint(1));
+                let fits = |a: Dec, b: Dec| a <= width && b <= height;
+                if !fits(x, y) && !fits(y, x) {
+                    return Err(anyhow!(
+                        "Piece {} of split is {} x {}, it does not fit {width} x {height} bed",
+                        pieces.len() + 1,
+                        x.round_dp(1),
+                        y.round_dp(1)
+                    ));
+                }
+            }
+            pieces.push(index);
+        }
+        Ok(pieces)
+    }
+
+    fn subtract_geometry(
+        part: MeshId,
+        hole: &dyn GeometryDyn,
+        steps: usize,
+        index: &mut GeoIndex,
+    ) -> anyhow::Result<()> {
+        let hole_mesh = index.new_mesh();
+        hole.polygonize(hole_mesh.make_mut_ref(index), steps)?;
+        Self::subtract_mesh(part, hole_mesh, index)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geometry::{
+        decimal::Dec,
+        geometry::GeometryDyn,
+        indexes::{
+            aabb::Aabb,
+            geo_index::{geo_object::GeoObject, index::GeoIndex},
+        },
+        origin::Origin,
+        shapes::Rect,
+    };
+    use nalgebra::Vector3;
+    use rust_decimal_macros::dec;
+
+    use super::{PrintSplit, SplitCut, SplitJoint};
+
+    fn index() -> GeoIndex {
+        GeoIndex::new(Aabb::from_points(&[
+            Vector3::new(Dec::from(-200), Dec::from(-200), Dec::from(-200)),
+            Vector3::new(Dec::from(200), Dec::from(200), Dec::from(200)),
+        ]))
+        .points_precision(dec!(0.001))
+    }
+
+    #[test]
+    fn box_split_in_two_valid_halves() {
+        let split = PrintSplit::new(SplitJoint::Pins {
+            diameter: 3.into(),
+            length: 8.into(),
+        })
+        .cut(SplitCut::new(Origin::new().offset_z(3)).joint(0, 0));
+
+        for sides in 0..2 {
+            let mut index = index();
+            let part = index.new_mesh();
+            Rect::centered(Origin::new(), 20.into(), 20.into(), 20.into())
+                .polygonize(part.make_mut_ref(&mut index), 0)
+                .unwrap();
+
+            let left = split
+                .cut_piece(part, sides, Dec::from(100), 8, &mut index)
+                .unwrap();
+
+            assert!(left);
+            let report = part.make_ref(&index).validate();
+            assert!(report.is_valid(), "piece {sides}: {report:?}");
+        }
+    }
+}
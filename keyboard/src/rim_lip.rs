@@ -0,0 +1,43 @@
+use geometry::decimal::Dec;
+use num_traits::Zero;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Tongue-and-groove joint along the parting line of buttons hull and bottom plate.
+/// Groove is cut in the bottom of hull walls, tongue grows from the bottom plate,
+/// both follow the table outline in the middle of the wall.
+/// Width should be less, than wall thickness. Lip is made of outline offsets, so
+/// on sharp inner corners of outline wide lip may intersect itself. With chamfer, top
+/// edges of the tongue and mouth of the groove are cut at 45 degrees, so parts find
+/// each other, when the case is closed.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct RimLip {
+    pub(crate) width: Dec,
+    pub(crate) depth: Dec,
+    pub(crate) clearance: Dec,
+    #[serde(default)]
+    pub(crate) chamfer: Dec,
+}
+
+impl RimLip {
+    pub fn new(width: impl Into<Dec>, depth: impl Into<Dec>) -> Self {
+        Self {
+            width: width.into(),
+            depth: depth.into(),
+            clearance: dec!(0.2).into(),
+            chamfer: Dec::zero(),
+        }
+    }
+
+    /// Gap between tongue and groove on each side and on top
+    pub fn clearance(mut self, clearance: impl Into<Dec>) -> Self {
+        self.clearance = clearance.into();
+        self
+    }
+
+    /// Size of 45 degree chamfer on tongue top edges and groove mouth
+    pub fn chamfer(mut self, chamfer: impl Into<Dec>) -> Self {
+        self.chamfer = chamfer.into();
+        self
+    }
+}
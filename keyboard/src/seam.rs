@@ -0,0 +1,31 @@
+use geometry::{
+    hyper_path::{hyper_line::ShiftInPlane, hyper_path::HyperPath, line::GetT},
+    indexes::geo_index::three_mf::Seam,
+};
+use nalgebra::{ComplexField, Vector3};
+
+use crate::keyboard_config::RightKeyboardConfig;
+
+impl RightKeyboardConfig {
+    /// Seam on outer wall of buttons hull, where it was asked with `KeyboardBuilder::seam`
+    pub fn seam(&self) -> Option<Seam> {
+        let at = self.seam_at?;
+        let lines = self.table_outline.len();
+        let line_ix = i128::from(at.floor()).rem_euclid(lines as i128) as usize;
+        let t = at - at.floor();
+
+        let mut outline = self.table_outline.clone();
+        for _ in 0..line_ix {
+            outline = outline.head_tail().1;
+        }
+        let (line, _) = outline.head_tail();
+        let outer = line
+            .clone()
+            .shift_in_plane(Vector3::z(), -self.main_plane_thickness)
+            .to_points()
+            .get_t(t);
+        let inner = line.to_points().get_t(t);
+
+        Some(Seam::new(outer, outer - inner))
+    }
+}
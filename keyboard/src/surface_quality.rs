@@ -0,0 +1,86 @@
+use geometry::{
+    decimal::Dec,
+    shapes::{Cone, Cylinder, Sphere},
+};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// How smooth curved parts of keyboard are rendered.
+/// One value for the whole keyboard instead of steps in every generator.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct SurfaceQuality {
+    pub(crate) surface_steps: usize,
+    pub(crate) max_chord_error: Option<Dec>,
+}
+
+impl Default for SurfaceQuality {
+    /// Keeps amount of steps, which each generator uses by itself
+    fn default() -> Self {
+        Self {
+            surface_steps: 8,
+            max_chord_error: None,
+        }
+    }
+}
+
+impl SurfaceQuality {
+    /// Fast and rough, good for previews
+    pub fn draft() -> Self {
+        Self {
+            surface_steps: 4,
+            max_chord_error: Some(dec!(0.2).into()),
+        }
+    }
+
+    pub fn normal() -> Self {
+        Self {
+            surface_steps: 8,
+            max_chord_error: Some(dec!(0.05).into()),
+        }
+    }
+
+    /// Slow, for final print
+    pub fn fine() -> Self {
+        Self {
+            surface_steps: 16,
+            max_chord_error: Some(dec!(0.01).into()),
+        }
+    }
+
+    /// Amount of steps for curved surfaces between outline and buttons
+    pub fn surface_steps(mut self, surface_steps: usize) -> Self {
+        self.surface_steps = surface_steps.max(1);
+        self
+    }
+
+    /// Maximal distance between ideal round shape and its polygons
+    pub fn max_chord_error(mut self, max_chord_error: impl Into<Dec>) -> Self {
+        self.max_chord_error = Some(max_chord_error.into());
+        self
+    }
+
+    pub(crate) fn cylinder(&self, cylinder: Cylinder) -> Cylinder {
+        if let Some(e) = self.max_chord_error {
+            cylinder.max_chord_error(e)
+        } else {
+            cylinder
+        }
+    }
+
+    pub(crate) fn cone(&self, cone: Cone) -> Cone {
+        if let Some(e) = self.max_chord_error {
+            cone.max_chord_error(e)
+        } else {
+            cone
+        }
+    }
+
+    /// Sphere with `max_chord_error`, if it is set. Otherwise it is left as given.
+    pub(crate) fn sphere(&self, sphere: Sphere) -> Sphere {
+        if let Some(e) = self.max_chord_error {
+            sphere.max_chord_error(e)
+        } else {
+            sphere
+        }
+    }
+}
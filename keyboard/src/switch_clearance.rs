@@ -0,0 +1,224 @@
+use geometry::{decimal::Dec, origin::Origin};
+use nalgebra::Vector3;
+use num_traits::{Signed, Zero};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::{button::Button, keyboard_config::RightKeyboardConfig};
+
+/// Group of keys, key belongs to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Cluster {
+    Main,
+    Thumb,
+    /// Collection, added with `KeyboardBuilder::collection`, by order of adding
+    Named(usize),
+}
+
+/// Place of key in the config: columns and rows are counted from zero, in order they were added
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct KeyPosition {
+    pub cluster: Cluster,
+    pub column: usize,
+    pub row: usize,
+}
+
+/// Space, needed above a switch to open its top housing in place. Tool goes along the
+/// switch axis from the top of the plate, so the envelope is a box `width` x `height`
+/// centered on the switch and `depth` high. Neighbour keys occupy their footprint from
+/// the bottom of the plate up to top of their keycaps, which height depends on mount kind
+/// unless `keycap_height` is given.
+#[derive(Clone, Copy, Debug)]
+pub struct SwitchClearance {
+    width: Dec,
+    height: Dec,
+    depth: Dec,
+    keycap_height: Option<Dec>,
+}
+
+/// Something, which stops tool from reaching a switch
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Obstacle {
+    /// Neighbour key with its keycap
+    Key(KeyPosition),
+    /// Switch is tilted so much, that envelope goes below the table
+    Table,
+}
+
+/// Key, which switch cannot be serviced in place
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockedSwitch {
+    pub key: KeyPosition,
+    pub obstacle: Obstacle,
+}
+
+impl Default for SwitchClearance {
+    fn default() -> Self {
+        Self {
+            width: dec!(15).into(),
+            height: dec!(15).into(),
+            depth: dec!(10).into(),
+            keycap_height: None,
+        }
+    }
+}
+
+impl SwitchClearance {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Size of envelope along button x axis
+    pub fn width(mut self, width: impl Into<Dec>) -> Self {
+        self.width = width.into();
+        self
+    }
+
+    /// Size of envelope along button y axis
+    pub fn height(mut self, height: impl Into<Dec>) -> Self {
+        self.height = height.into();
+        self
+    }
+
+    /// How far above the plate tool must go
+    pub fn depth(mut self, depth: impl Into<Dec>) -> Self {
+        self.depth = depth.into();
+        self
+    }
+
+    /// Top of switch with keycap above the plate, the same for all kinds of mounts
+    pub fn keycap_height(mut self, keycap_height: impl Into<Dec>) -> Self {
+        self.keycap_height = Some(keycap_height.into());
+        self
+    }
+
+    fn envelope(&self, button: &Button, thickness: Dec) -> OrientedBox {
+        OrientedBox::new(
+            &button.origin,
+            thickness / 2,
+            thickness / 2 + self.depth,
+            self.width,
+            self.height,
+        )
+    }
+
+    fn occupied(&self, button: &Button, thickness: Dec) -> OrientedBox {
+        OrientedBox::new(
+            &button.origin,
+            -thickness / 2,
+            thickness / 2
+                + self
+                    .keycap_height
+                    .unwrap_or(button.kind.envelope().keycap_height),
+            button.kind.button_width(),
+            button.kind.button_height(),
+        )
+    }
+}
+
+pub(crate) struct OrientedBox {
+    pub(crate) center: Vector3<Dec>,
+    pub(crate) axes: [Vector3<Dec>; 3],
+    pub(crate) half: [Dec; 3],
+}
+
+impl OrientedBox {
+    /// Box on origin axes, from `bottom` to `top` along its z
+    pub(crate) fn new(o: &Origin, bottom: Dec, top: Dec, width: Dec, height: Dec) -> Self {
+        Self {
+            center: o.center + o.z() * ((bottom + top) / 2),
+            axes: [o.x(), o.y(), o.z()],
+            half: [width / 2, height / 2, (top - bottom) / 2],
+        }
+    }
+
+    pub(crate) fn corners(&self) -> impl Iterator<Item = Vector3<Dec>> + '_ {
+        (0..8).map(|i| {
+            (0..3).fold(self.center, |c, a| {
+                let sign = if i & (1 << a) == 0 { -1 } else { 1 };
+                c + self.axes[a] * self.half[a] * Dec::from(sign)
+            })
+        })
+    }
+
+    fn radius_along(&self, axis: &Vector3<Dec>) -> Dec {
+        (0..3)
+            .map(|a| self.axes[a].dot(axis).abs() * self.half[a])
+            .sum()
+    }
+
+    /// Separating axis test. Boxes, which only touch, do not intersect.
+    pub(crate) fn intersects(&self, other: &OrientedBox) -> bool {
+        let mut axes = self.axes.to_vec();
+        axes.extend(other.axes);
+        for a in &self.axes {
+            for b in &other.axes {
+                axes.push(a.cross(b));
+            }
+        }
+        let distance = other.center - self.center;
+        !axes
+            .iter()
+            .filter(|a| !a.magnitude_squared().is_zero())
+            .any(|a| distance.dot(a).abs() >= self.radius_along(a) + other.radius_along(a))
+    }
+}
+
+impl RightKeyboardConfig {
+    pub(crate) fn keys(&self) -> impl Iterator<Item = (KeyPosition, &Button)> {
+        [
+            (Cluster::Main, &self.main_buttons),
+            (Cluster::Thumb, &self.thumb_buttons),
+        ]
+        .into_iter()
+        .chain(
+            self.extra_collections
+                .iter()
+                .enumerate()
+                .map(|(ix, (_, c))| (Cluster::Named(ix), c)),
+        )
+        .flat_map(|(cluster, collection)| {
+            collection
+                .columns
+                .iter()
+                .enumerate()
+                .flat_map(move |(column, c)| {
+                    c.buttons().enumerate().map(move |(row, button)| {
+                        (
+                            KeyPosition {
+                                cluster,
+                                column,
+                                row,
+                            },
+                            button,
+                        )
+                    })
+                })
+        })
+    }
+
+    /// Finds keys, which switches cannot be opened in place, because tool envelope
+    /// hits neighbour keys or the table.
+    pub fn check_switch_clearance(&self, clearance: &SwitchClearance) -> Vec<BlockedSwitch> {
+        let thickness = self.main_plane_thickness;
+        let mut blocked = Vec::new();
+        for (key, button) in self.keys() {
+            let envelope = clearance.envelope(button, thickness);
+            if envelope.corners().any(|c| c.z < Dec::zero()) {
+                blocked.push(BlockedSwitch {
+                    key,
+                    obstacle: Obstacle::Table,
+                });
+            }
+            for (other_key, other) in self.keys().filter(|(k, _)| *k != key) {
+                if envelope.intersects(&clearance.occupied(other, thickness)) {
+                    blocked.push(BlockedSwitch {
+                        key,
+                        obstacle: Obstacle::Key(other_key),
+                    });
+                }
+            }
+        }
+        blocked
+    }
+}
@@ -0,0 +1,238 @@
+use anyhow::anyhow;
+use geometry::{
+    decimal::Dec,
+    geometry::GeometryDyn,
+    indexes::geo_index::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId},
+    origin::Origin,
+    shapes::{Cylinder, Rect},
+};
+use itertools::Itertools;
+use nalgebra::Vector3;
+use num_traits::Signed;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolt::{Bolt, Nut},
+    keyboard_config::{KeyboardMesh, RightKeyboardConfig},
+};
+
+/// Off-the-shelf hardware, which holds keyboard tented
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum TentingHardware {
+    /// Tripod or 1/4" flange screw, going up through the plate into 1/4"-20 hex nut,
+    /// trapped in a pocket on the top side
+    Tripod,
+    /// Metal plate of folding phone stand, glued into recess in the underside
+    PhoneStandPlate { width: Dec, height: Dec, depth: Dec },
+    /// Suction feet, which studs go into blind holes in corners of the underside
+    SuctionFeet { stud_diameter: Dec, depth: Dec },
+}
+
+/// Plate, bolted under the bottom plate with bolts of the case, which carries mount for
+/// tenting hardware. Bolts go through the plate, their nuts or heads sink into pockets on
+/// the underside. Plate covers all bolts of the bottom plate with `margin` around them.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct TentingAdapter {
+    pub(crate) hardware: TentingHardware,
+    pub(crate) thickness: Dec,
+    pub(crate) margin: Dec,
+    pub(crate) clearance: Dec,
+}
+
+impl TentingAdapter {
+    pub fn new(hardware: TentingHardware) -> Self {
+        Self {
+            hardware,
+            thickness: 8.into(),
+            margin: 6.into(),
+            clearance: dec!(0.2).into(),
+        }
+    }
+
+    pub fn tripod() -> Self {
+        Self::new(TentingHardware::Tripod)
+    }
+
+    pub fn phone_stand_plate(width: impl Into<Dec>, height: impl Into<Dec>) -> Self {
+        Self::new(TentingHardware::PhoneStandPlate {
+            width: width.into(),
+            height: height.into(),
+            depth: 1.into(),
+        })
+    }
+
+    pub fn suction_feet(stud_diameter: impl Into<Dec>) -> Self {
+        Self::new(TentingHardware::SuctionFeet {
+            stud_diameter: stud_diameter.into(),
+            depth: 4.into(),
+        })
+    }
+
+    pub fn thickness(mut self, thickness: impl Into<Dec>) -> Self {
+        self.thickness = thickness.into();
+        self
+    }
+
+    /// Plate around outermost bolts
+    pub fn margin(mut self, margin: impl Into<Dec>) -> Self {
+        self.margin = margin.into();
+        self
+    }
+
+    /// Gap between hardware (bolts, nuts, studs) and holes for it
+    pub fn clearance(mut self, clearance: impl Into<Dec>) -> Self {
+        self.clearance = clearance.into();
+        self
+    }
+
+    /// Through hole for a bolt with pocket for its nut or head on the underside
+    fn bolt_hole(&self, at: Origin, bolt: &Bolt) -> anyhow::Result<Vec<Box<dyn GeometryDyn>>> {
+        let sw = Dec::from(dec!(0.1));
+        let (pocket_radius, pocket_depth, steps) = match &bolt.nut {
+            Some(Nut::Hex {
+                outer_diameter,
+                height,
+            }) => (*outer_diameter / 2, *height, 6),
+            None => (bolt.head_diameter / 2, bolt.head_height, 10),
+        };
+        let pocket_depth = pocket_depth + self.clearance;
+        if pocket_depth >= self.thickness {
+            return Err(anyhow!("Tenting adapter is too thin for bolt pockets"));
+        }
+        Ok(vec![
+            Box::new(Cylinder::with_top_at(
+                at.clone().offset_z(sw),
+                self.thickness + sw * 2,
+                bolt.diameter / 2 + self.clearance,
+            )),
+            Box::new(
+                Cylinder::with_bottom_at(
+                    at.offset_z(-self.thickness - sw),
+                    pocket_depth + sw,
+                    pocket_radius + self.clearance,
+                )
+                .steps(steps),
+            ),
+        ])
+    }
+
+    /// Mount for hardware. `top` is the middle of plate top, `size` - size of the plate.
+    fn hardware_holes(&self, top: Origin, size: (Dec, Dec)) -> Vec<Box<dyn GeometryDyn>> {
+        let sw = Dec::from(dec!(0.1));
+        let bottom = top.clone().offset_z(-self.thickness - sw);
+        match self.hardware {
+            TentingHardware::Tripod => {
+                // 1/4"-20 hex nut is 7/16" across flats
+                let nut_radius = Dec::from(dec!(6.42));
+                let nut_height = Dec::from(dec!(5.6)) + self.clearance;
+                vec![
+                    Box::new(Cylinder::with_top_at(
+                        top.clone().offset_z(sw),
+                        self.thickness + sw * 2,
+                        Dec::from(dec!(3.175)) + self.clearance,
+                    )),
+                    Box::new(
+                        Cylinder::with_top_at(
+                            top.offset_z(sw),
+                            nut_height + sw,
+                            nut_radius + self.clearance,
+                        )
+                        .steps(6),
+                    ),
+                ]
+            }
+            TentingHardware::PhoneStandPlate {
+                width,
+                height,
+                depth,
+            } => vec![Box::new(Rect::with_bottom_at(
+                bottom,
+                width + self.clearance * 2,
+                height + self.clearance * 2,
+                depth + sw,
+            ))],
+            TentingHardware::SuctionFeet {
+                stud_diameter,
+                depth,
+            } => {
+                let (dx, dy) = ((size.0 - self.margin) / 2, (size.1 - self.margin) / 2);
+                [(-dx, -dy), (dx, -dy), (dx, dy), (-dx, dy)]
+                    .into_iter()
+                    .map(|(x, y)| {
+                        Box::new(Cylinder::with_bottom_at(
+                            bottom.clone().offset_x(x).offset_y(y),
+                            depth + sw,
+                            stud_diameter / 2 + self.clearance,
+                        )) as Box<dyn GeometryDyn>
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+impl RightKeyboardConfig {
+    /// Points, where bolts of bottom plate go out of its underside
+    pub fn bottom_bolt_pattern(&self) -> Vec<(Vector3<Dec>, &Bolt)> {
+        let underside = -self.bottom_thickness;
+        self.bolts
+            .iter()
+            .filter(|(head_on, thread_on, _)| {
+                *head_on == KeyboardMesh::Bottom || *thread_on == KeyboardMesh::Bottom
+            })
+            .map(|(_, _, b)| (&b.origin, &b.bolt))
+            .filter(|(origin, _)| origin.z().z.abs() > Dec::from(dec!(0.01)))
+            .map(|(origin, bolt)| {
+                let axis = origin.z();
+                let t = (underside - origin.center.z) / axis.z;
+                (origin.center + axis * t, bolt)
+            })
+            .collect()
+    }
+
+    /// Adapter plate for tenting hardware. Separate part, which is bolted to the
+    /// underside of bottom plate.
+    pub fn tenting_adapter(&self, index: &mut GeoIndex) -> anyhow::Result<MeshId> {
+        let adapter = self
+            .tenting_adapter
+            .ok_or(anyhow!("Tenting adapter is not configured"))?;
+        let pattern = self.bottom_bolt_pattern();
+        let Some((min_x, max_x)) = pattern.iter().map(|(p, _)| p.x).minmax().into_option() else {
+            return Err(anyhow!("Bottom plate has no bolts for tenting adapter"));
+        };
+        let (min_y, max_y) = pattern
+            .iter()
+            .map(|(p, _)| p.y)
+            .minmax()
+            .into_option()
+            .unwrap_or_default();
+
+        let size = (
+            max_x - min_x + adapter.margin * 2,
+            max_y - min_y + adapter.margin * 2,
+        );
+        let top = Origin::new()
+            .offset_x((min_x + max_x) / 2)
+            .offset_y((min_y + max_y) / 2)
+            .offset_z(-self.bottom_thickness);
+        let plate = index.new_mesh();
+        Rect::with_top_at(top.clone(), size.0, size.1, adapter.thickness)
+            .polygonize(plate.make_mut_ref(index), 0)?;
+
+        let mut holes = adapter.hardware_holes(top.clone(), size);
+        for (point, bolt) in pattern {
+            let at = Origin::new()
+                .offset_x(point.x)
+                .offset_y(point.y)
+                .offset_z(top.center.z);
+            holes.extend(adapter.bolt_hole(at, bolt)?);
+        }
+        for hole in holes {
+            let hole_mesh = index.new_mesh();
+            hole.polygonize(hole_mesh.make_mut_ref(index), self.quality.surface_steps)?;
+            Self::subtract_mesh(plate, hole_mesh, index)?;
+        }
+        Ok(plate)
+    }
+}
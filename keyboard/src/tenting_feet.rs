@@ -0,0 +1,190 @@
+use std::rc::Rc;
+
+use anyhow::anyhow;
+use geometry::{
+    decimal::Dec,
+    geometry::GeometryDyn,
+    indexes::geo_index::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId},
+    origin::Origin,
+    shapes::Cylinder,
+};
+use nalgebra::Vector3;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolt::{Bolt, Thread},
+    keyboard_config::RightKeyboardConfig,
+};
+
+/// Corner of the case, as seen from above: back is along y axis, right - along x
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Corner {
+    FrontLeft,
+    FrontRight,
+    BackLeft,
+    BackRight,
+}
+
+impl Corner {
+    fn dir(&self) -> Vector3<Dec> {
+        let (x, y) = match self {
+            Corner::FrontLeft => (-1, -1),
+            Corner::FrontRight => (1, -1),
+            Corner::BackLeft => (-1, 1),
+            Corner::BackRight => (1, 1),
+        };
+        Vector3::new(x.into(), y.into(), 0.into())
+    }
+}
+
+/// Screw-in feet under the bottom plate. Each of `corners` gets a boss on the bottom
+/// plate with thread of `bolt` cut into it from the underside. Feet are round posts of
+/// `lengths` with threaded studs, so tent angle is set by feet, which are screwed in.
+/// Bolt must have a thread, its height is the length of studs.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TentingFeet {
+    pub(crate) bolt: Bolt,
+    pub(crate) corners: Vec<Corner>,
+    pub(crate) lengths: Vec<Dec>,
+    pub(crate) foot_diameter: Dec,
+    pub(crate) inset: Dec,
+}
+
+impl TentingFeet {
+    /// Feet of 5, 10 and 15mm under both left corners, which lifts inner side of the
+    /// right half
+    pub fn new(bolt: Bolt) -> Self {
+        Self {
+            bolt,
+            corners: vec![Corner::FrontLeft, Corner::BackLeft],
+            lengths: vec![5.into(), 10.into(), 15.into()],
+            foot_diameter: 12.into(),
+            inset: 8.into(),
+        }
+    }
+
+    pub fn corners(mut self, corners: Vec<Corner>) -> Self {
+        self.corners = corners;
+        self
+    }
+
+    /// One foot of each length is made by `tenting_feet`
+    pub fn lengths(mut self, lengths: Vec<Dec>) -> Self {
+        self.lengths = lengths;
+        self
+    }
+
+    pub fn foot_diameter(mut self, foot_diameter: impl Into<Dec>) -> Self {
+        self.foot_diameter = foot_diameter.into();
+        self
+    }
+
+    /// Distance from outer wall to the axis of boss
+    pub fn inset(mut self, inset: impl Into<Dec>) -> Self {
+        self.inset = inset.into();
+        self
+    }
+
+    fn wall(&self) -> Dec {
+        dec!(2.4).into()
+    }
+
+    /// Material over the blind thread hole
+    fn skin(&self) -> Dec {
+        1.into()
+    }
+
+    fn thread(&self) -> anyhow::Result<&Thread> {
+        self.bolt
+            .thread
+            .as_ref()
+            .ok_or(anyhow!("Bolt of tenting feet has no thread"))
+    }
+
+    /// Boss centers on the underside of bottom plate, z axis looks up
+    fn bosses(&self, config: &RightKeyboardConfig) -> Vec<Origin> {
+        let samples = config.wall_samples();
+        self.corners
+            .iter()
+            .filter_map(|corner| {
+                samples
+                    .iter()
+                    .max_by_key(|(point, _)| point.dot(&corner.dir()))
+                    .map(|(point, normal)| {
+                        Origin::new()
+                            .offset(point - normal * self.inset)
+                            .offset_z(-config.bottom_thickness)
+                    })
+            })
+            .collect()
+    }
+
+    pub(crate) fn material(&self, config: &RightKeyboardConfig) -> Vec<Rc<dyn GeometryDyn>> {
+        let radius = self.bolt.diameter / 2 + self.wall();
+        self.bosses(config)
+            .into_iter()
+            .map(|at| -> Rc<dyn GeometryDyn> {
+                Rc::new(config.quality.cylinder(Cylinder::with_bottom_at(
+                    at,
+                    self.bolt.height + self.skin(),
+                    radius,
+                )))
+            })
+            .collect()
+    }
+
+    pub(crate) fn holes(&self, config: &RightKeyboardConfig) -> Vec<Rc<dyn GeometryDyn>> {
+        let sw = Dec::from(dec!(0.1));
+        let Ok(thread) = self.thread() else {
+            return Vec::new();
+        };
+        self.bosses(config)
+            .into_iter()
+            .map(|at| -> Rc<dyn GeometryDyn> {
+                Rc::new(thread.geometry(
+                    at.offset_z(self.bolt.height),
+                    self.bolt.height + sw,
+                    self.bolt.diameter / 2 + thread.tolerance,
+                ))
+            })
+            .collect()
+    }
+}
+
+impl RightKeyboardConfig {
+    /// Feet of all lengths in a row along x axis, stud up, standing at zero height
+    pub fn tenting_feet(&self, index: &mut GeoIndex) -> anyhow::Result<Vec<MeshId>> {
+        let feet = self
+            .tenting_feet
+            .as_ref()
+            .ok_or(anyhow!("Tenting feet are not configured"))?;
+        let thread = feet.thread()?;
+        let pitch = feet.foot_diameter * Dec::from(dec!(1.5));
+        let mut meshes = Vec::new();
+        for (ix, length) in feet.lengths.iter().enumerate() {
+            let top = Origin::new()
+                .offset_x(pitch * Dec::from(ix))
+                .offset_z(*length);
+            let foot = index.new_mesh();
+            self.quality
+                .cylinder(Cylinder::with_top_at(
+                    top.clone(),
+                    *length,
+                    feet.foot_diameter / 2,
+                ))
+                .polygonize(foot.make_mut_ref(index), self.quality.surface_steps)?;
+            let stud = index.new_mesh();
+            thread
+                .geometry(
+                    top.offset_z(feet.bolt.height),
+                    feet.bolt.height,
+                    feet.bolt.diameter / 2 - thread.tolerance,
+                )
+                .polygonize(stud.make_mut_ref(index), 0)?;
+            index.unite_mesh(foot, stud)?;
+            meshes.push(foot);
+        }
+        Ok(meshes)
+    }
+}
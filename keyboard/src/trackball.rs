@@ -0,0 +1,213 @@
+use std::rc::Rc;
+
+use geometry::{
+    decimal::Dec,
+    geometry::GeometryDyn,
+    origin::Origin,
+    shapes::{Cylinder, Rect, Sphere},
+};
+use nalgebra::Vector3;
+use rust_decimal_macros::dec;
+
+use crate::{
+    keyboard_config::{KeyboardMesh, MaterialAddition, RightKeyboardConfig},
+    surface_quality::SurfaceQuality,
+};
+
+/// Housing for trackball in buttons hull. Origin is in the center of the ball, z axis
+/// looks up: part of the ball above the outer surface sticks out of the case. Ball lies
+/// in spherical cup on three bearing balls, pressed into seats in the cup wall below
+/// the equator. PMW3360 sensor board is screwed to the post under the cup and looks at
+/// the ball through the window in the bottom of the cup. Bottom plate gets an opening
+/// under the sensor board, so that the board can be reached with the case closed.
+#[derive(Clone, Debug)]
+pub struct Trackball {
+    pub(crate) origin: Origin,
+    pub(crate) ball_diameter: Dec,
+    pub(crate) gap: Dec,
+    pub(crate) wall: Dec,
+    pub(crate) bearing_diameter: Dec,
+    pub(crate) bearing_elevation: Dec,
+    pub(crate) sensor_distance: Dec,
+    pub(crate) lens: (Dec, Dec),
+    pub(crate) board: (Dec, Dec),
+    pub(crate) screw_spacing: Dec,
+    pub(crate) clearance: Dec,
+}
+
+impl Trackball {
+    /// 34mm ball on 2.5mm static bearing balls, PMW3360 with LM19-LSI lens
+    pub fn new(origin: Origin) -> Self {
+        Self {
+            origin,
+            ball_diameter: 34.into(),
+            gap: 1.into(),
+            wall: 4.into(),
+            bearing_diameter: dec!(2.5).into(),
+            bearing_elevation: 30.into(),
+            sensor_distance: dec!(7.4).into(),
+            lens: (19.into(), 21.into()),
+            board: (34.into(), 22.into()),
+            screw_spacing: 24.into(),
+            clearance: dec!(0.2).into(),
+        }
+    }
+
+    pub fn ball_diameter(mut self, ball_diameter: impl Into<Dec>) -> Self {
+        self.ball_diameter = ball_diameter.into();
+        self
+    }
+
+    /// Space between the ball and the cup. Bearing balls stick out of their seats by it.
+    pub fn gap(mut self, gap: impl Into<Dec>) -> Self {
+        self.gap = gap.into();
+        self
+    }
+
+    /// Thickness of the cup. It must take bearing seats.
+    pub fn wall(mut self, wall: impl Into<Dec>) -> Self {
+        self.wall = wall.into();
+        self
+    }
+
+    /// Bearing balls of `diameter` with centers `elevation` degrees below the equator
+    pub fn bearings(mut self, diameter: impl Into<Dec>, elevation: impl Into<Dec>) -> Self {
+        self.bearing_diameter = diameter.into();
+        self.bearing_elevation = elevation.into();
+        self
+    }
+
+    /// From the top of sensor board to the ball surface, by the lens datasheet
+    pub fn sensor_distance(mut self, sensor_distance: impl Into<Dec>) -> Self {
+        self.sensor_distance = sensor_distance.into();
+        self
+    }
+
+    /// Sensor board and distance between its mounting holes along x
+    pub fn board(
+        mut self,
+        width: impl Into<Dec>,
+        height: impl Into<Dec>,
+        screw_spacing: impl Into<Dec>,
+    ) -> Self {
+        self.board = (width.into(), height.into());
+        self.screw_spacing = screw_spacing.into();
+        self
+    }
+
+    fn radius(&self) -> Dec {
+        self.ball_diameter / 2
+    }
+
+    /// Top of sensor board, down from the ball center
+    fn board_top(&self) -> Dec {
+        self.radius() + self.sensor_distance
+    }
+
+    /// Origin in the center of the ball, which z axis looks to the bearing number `ix`
+    fn bearing_axis(&self, ix: usize) -> Origin {
+        let azimuth = Dec::two_pi() * Dec::from(ix) / Dec::from(3);
+        let elevation = self.bearing_elevation * Dec::pi() / Dec::from(180);
+        self.origin
+            .clone()
+            .rotate_axisangle(Vector3::z() * azimuth)
+            .rotate_axisangle(Vector3::y() * (Dec::pi() / Dec::from(2) + elevation))
+    }
+
+    /// Sphere around the ball center. It is fine by default, as small bearing gap must
+    /// not be eaten by polygons.
+    fn sphere(&self, radius: Dec, quality: &SurfaceQuality) -> Sphere {
+        quality.sphere(
+            Sphere::centered(self.origin.clone(), radius)
+                .segments(32)
+                .rings(16),
+        )
+    }
+
+    /// Cup around the ball and post for sensor board under it
+    pub(crate) fn material(&self, quality: &SurfaceQuality) -> Vec<Rc<dyn GeometryDyn>> {
+        let (width, height) = self.board;
+        let post_top = self.radius() + self.gap;
+        vec![
+            Rc::new(self.sphere(self.radius() + self.gap + self.wall, quality)),
+            Rc::new(Rect::with_bottom_at(
+                self.origin.clone().offset_z(-self.board_top()),
+                width,
+                height,
+                self.board_top() - post_top + self.wall,
+            )),
+        ]
+    }
+
+    /// Cup cavity, bearing seats, sensor window and pilot holes for board screws
+    pub(crate) fn holes(&self, quality: &SurfaceQuality) -> Vec<Rc<dyn GeometryDyn>> {
+        let sw = Dec::from(dec!(0.1));
+        let c = self.clearance;
+        let (lens_width, lens_height) = self.lens;
+        let board = self.origin.clone().offset_z(-self.board_top());
+        let mut holes: Vec<Rc<dyn GeometryDyn>> = vec![
+            Rc::new(self.sphere(self.radius() + self.gap, quality)),
+            Rc::new(Rect::with_bottom_at(
+                board.clone().offset_z(-sw),
+                lens_width + c * 2,
+                lens_height + c * 2,
+                self.sensor_distance + self.gap + sw * 2,
+            )),
+        ];
+        for ix in 0..3 {
+            holes.push(Rc::new(Cylinder::with_bottom_at(
+                self.bearing_axis(ix).offset_z(self.radius()),
+                self.bearing_diameter + c,
+                self.bearing_diameter / 2 + c / 2,
+            )));
+        }
+        for x in [-self.screw_spacing / 2, self.screw_spacing / 2] {
+            holes.push(Rc::new(Cylinder::with_bottom_at(
+                board.clone().offset_x(x).offset_z(-sw),
+                Dec::from(5) + sw,
+                dec!(0.8).into(),
+            )));
+        }
+        holes
+    }
+
+    /// Opening in bottom plate under the sensor board. Bottom plate lies on the table,
+    /// so the opening goes through everything around zero height.
+    pub(crate) fn bottom_opening(&self, bottom_thickness: Dec) -> Rc<dyn GeometryDyn> {
+        let (width, height) = self.board;
+        let margin = Dec::from(2);
+        let center = self.origin.center;
+        Rc::new(Rect::centered(
+            Origin::new().offset(Vector3::new(center.x, center.y, Dec::from(0))),
+            width + margin * 2,
+            height + margin * 2,
+            (bottom_thickness + Dec::from(2)) * 2,
+        ))
+    }
+}
+
+impl RightKeyboardConfig {
+    /// Adds trackball housing to buttons hull and opening for its sensor to bottom
+    /// plate, see `Trackball`
+    pub fn add_trackball(mut self, trackball: Trackball) -> Self {
+        let quality = self.quality;
+        self.additional_material
+            .entry(KeyboardMesh::ButtonsHull)
+            .or_default()
+            .extend(
+                trackball
+                    .material(&quality)
+                    .into_iter()
+                    .map(|m| (MaterialAddition::InnerSurface, m)),
+            );
+        self.holes
+            .entry(KeyboardMesh::ButtonsHull)
+            .or_default()
+            .extend(trackball.holes(&quality));
+        self.holes
+            .entry(KeyboardMesh::Bottom)
+            .or_default()
+            .push(trackball.bottom_opening(self.bottom_thickness));
+        self
+    }
+}
@@ -0,0 +1,114 @@
+use std::rc::Rc;
+
+use geometry::{
+    decimal::Dec,
+    geometry::GeometryDyn,
+    origin::Origin,
+    shapes::{Cylinder, Rect},
+};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::keyboard_config::RightKeyboardConfig;
+
+/// Socket for PJ-320A style 3.5mm TRRS jack, which connects split halves. Origin is on
+/// the barrel axis on the outer wall surface, z axis looks out of the wall, y axis looks
+/// up, as `label_anchor` gives it. Barrel goes through the panel, body of the jack sits
+/// in a boss inside of the wall. Jack is pushed into the boss from below, through the
+/// slot for its pins, and lips at the sides of the slot hold it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TrrsJack {
+    pub(crate) origin: Origin,
+    pub(crate) panel_thickness: Dec,
+    pub(crate) clearance: Dec,
+    pub(crate) barrel_diameter: Dec,
+    pub(crate) body_width: Dec,
+    pub(crate) body_height: Dec,
+    pub(crate) body_length: Dec,
+}
+
+impl TrrsJack {
+    /// PJ-320A: 5mm barrel, 6 x 5 x 12mm body
+    pub fn new(origin: Origin) -> Self {
+        Self {
+            origin,
+            panel_thickness: dec!(1.5).into(),
+            clearance: dec!(0.2).into(),
+            barrel_diameter: 5.into(),
+            body_width: 6.into(),
+            body_height: 5.into(),
+            body_length: 12.into(),
+        }
+    }
+
+    /// Wall between outer surface and the body of the jack. Barrel of PJ-320A is 2mm
+    /// long, so plug does not reach the contacts through thicker panel.
+    pub fn panel_thickness(mut self, panel_thickness: impl Into<Dec>) -> Self {
+        self.panel_thickness = panel_thickness.into();
+        self
+    }
+
+    /// Gap around barrel and body
+    pub fn clearance(mut self, clearance: impl Into<Dec>) -> Self {
+        self.clearance = clearance.into();
+        self
+    }
+
+    fn wall(&self) -> Dec {
+        dec!(1.6).into()
+    }
+
+    /// How far lips of the slot go under the body on each side
+    fn lip(&self) -> Dec {
+        dec!(0.4).into()
+    }
+
+    fn pocket(&self) -> (Dec, Dec, Dec) {
+        (
+            self.body_width + self.clearance * 2,
+            self.body_height + self.clearance * 2,
+            self.body_length + self.clearance,
+        )
+    }
+
+    /// Boss around the body, it goes from outer surface of the wall, so it fuses with it
+    pub(crate) fn material(&self) -> Rc<dyn GeometryDyn> {
+        let (width, height, length) = self.pocket();
+        Rc::new(Rect::with_top_at(
+            self.origin.clone(),
+            width + self.wall() * 2,
+            height + self.wall() * 2,
+            self.panel_thickness + length + self.wall(),
+        ))
+    }
+
+    /// Barrel hole through the panel, pocket for the body and slot in the bottom of the
+    /// boss for pins
+    pub(crate) fn holes(&self) -> Vec<Rc<dyn GeometryDyn>> {
+        let sw = Dec::from(dec!(0.1));
+        let (width, height, length) = self.pocket();
+        let body = self.origin.clone().offset_z(-self.panel_thickness);
+        vec![
+            Rc::new(Cylinder::with_top_at(
+                self.origin.clone().offset_z(sw),
+                self.panel_thickness + sw * 2,
+                self.barrel_diameter / 2 + self.clearance,
+            )),
+            Rc::new(Rect::with_top_at(body.clone(), width, height, length)),
+            Rc::new(Rect::with_top_at(
+                body.offset_y(-(height + self.wall()) / 2),
+                width - self.lip() * 2,
+                self.wall() + sw * 2,
+                length,
+            )),
+        ]
+    }
+}
+
+impl RightKeyboardConfig {
+    /// Adds TRRS jack socket to buttons hull, see `TrrsJack`
+    pub fn add_trrs_jack(mut self, jack: TrrsJack) -> Self {
+        self.trrs_jacks.push(jack);
+        self
+    }
+}
@@ -0,0 +1,95 @@
+use std::{f64::consts::FRAC_PI_2, rc::Rc};
+
+use geometry::{decimal::Dec, geometry::GeometryDyn, origin::Origin, shapes::extrude};
+use nalgebra::Vector2;
+use rust_decimal_macros::dec;
+
+use crate::keyboard_config::{KeyboardMesh, RightKeyboardConfig};
+
+/// Shell of USB-C receptacle by the standard, 8.94 x 3.26mm with round ends
+fn receptacle() -> (Dec, Dec) {
+    (dec!(8.94).into(), dec!(3.26).into())
+}
+
+/// Gap around receptacle shell on each side
+fn tolerance() -> Dec {
+    dec!(0.25).into()
+}
+
+/// Wall, which is left around the opening. Plug must reach the receptacle through it,
+/// so it is thinner, than usual walls.
+fn panel() -> Dec {
+    dec!(1.2).into()
+}
+
+/// Pocket behind the panel for receptacle body and edge of its board
+fn relief() -> (Dec, Dec) {
+    (12.into(), 7.into())
+}
+
+/// How deep holes go into the case from the outer surface
+fn depth() -> Dec {
+    15.into()
+}
+
+/// Rectangle `width` x `height` with corners of `radius`, centered at zero. With radius
+/// of half height, short sides become round.
+fn rounded_rect(width: Dec, height: Dec, radius: Dec, steps: usize) -> Vec<Vector2<Dec>> {
+    let (w, h) = (width / 2 - radius, height / 2 - radius);
+    let corners = [(w, h), (-w, h), (-w, -h), (w, -h)];
+    let steps = steps.max(1);
+    let mut points = corners
+        .into_iter()
+        .enumerate()
+        .flat_map(|(ix, (x, y))| {
+            (0..=steps).map(move |step| {
+                let angle = FRAC_PI_2 * (ix as f64 + step as f64 / steps as f64);
+                Vector2::new(
+                    x + radius * Dec::from(angle.cos()).round_dp(9),
+                    y + radius * Dec::from(angle.sin()).round_dp(9),
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+    // Corners meet, where straight sides have zero length
+    points.dedup();
+    if points.first() == points.last() {
+        points.pop();
+    }
+    points
+}
+
+impl RightKeyboardConfig {
+    /// Opening for USB-C receptacle in buttons hull. Origin is in the middle of the
+    /// opening on the outer wall surface, z axis looks out of the wall and x axis goes
+    /// along the long side of receptacle, as `label_anchor` gives it. Opening goes
+    /// through the wall, pocket from inside leaves thin panel around it, so the plug
+    /// reaches the receptacle.
+    pub fn add_usb_c_cutout(mut self, origin: Origin) -> Self {
+        let sw = Dec::from(dec!(0.1));
+        let steps = self.quality.surface_steps;
+        let (width, height) = receptacle();
+        let opening = rounded_rect(
+            width + tolerance() * 2,
+            height + tolerance() * 2,
+            height / 2 + tolerance(),
+            steps,
+        );
+        let (relief_width, relief_height) = relief();
+        let pocket = rounded_rect(relief_width, relief_height, 1.into(), steps);
+
+        let holes: [Rc<dyn GeometryDyn>; 2] = [
+            Rc::new(extrude(
+                &opening,
+                -(depth() + sw),
+                origin.clone().offset_z(sw),
+            )),
+            Rc::new(extrude(&pocket, -depth(), origin.offset_z(-panel()))),
+        ];
+        self.holes
+            .entry(KeyboardMesh::ButtonsHull)
+            .or_default()
+            .extend(holes);
+        self
+    }
+}
@@ -0,0 +1,132 @@
+use std::rc::Rc;
+
+use anyhow::anyhow;
+use geometry::{
+    decimal::Dec,
+    geometry::GeometryDyn,
+    origin::Origin,
+    shapes::{Cylinder, Rect},
+};
+use nalgebra::Vector3;
+use num_traits::Zero;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    key_matrix::KeyMatrix,
+    keyboard_config::{KeyboardMesh, RightKeyboardConfig},
+};
+
+/// Grooves for hand wiring on the underside of key plate. Row wires lie in half-round
+/// grooves right at the underside. Column wires cross them, so their grooves go deeper
+/// by wire diameter and `bridge_clearance`, and wires do not touch at crossings.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Wiring {
+    pub(crate) channel_diameter: Dec,
+    pub(crate) bridge_clearance: Dec,
+}
+
+impl Wiring {
+    /// Channels for 22 AWG hookup wire
+    pub fn new() -> Self {
+        Self {
+            channel_diameter: dec!(1.2).into(),
+            bridge_clearance: dec!(0.4).into(),
+        }
+    }
+
+    pub fn channel_diameter(mut self, channel_diameter: impl Into<Dec>) -> Self {
+        self.channel_diameter = channel_diameter.into();
+        self
+    }
+
+    /// Gap between row and column wires, where they cross
+    pub fn bridge_clearance(mut self, bridge_clearance: impl Into<Dec>) -> Self {
+        self.bridge_clearance = bridge_clearance.into();
+        self
+    }
+
+    /// How far axis of column groove goes into the plate from the underside
+    fn column_depth(&self) -> Dec {
+        self.channel_diameter + self.bridge_clearance
+    }
+
+    /// Groove from `from` to `to` with axis `depth` into the plate. `from` is a point on
+    /// the underside, its z axis looks into the plate. Groove goes in the plane of
+    /// `from`, so it ends near `to`, when keys are tilted differently.
+    fn groove(&self, from: &Origin, to: Vector3<Dec>, depth: Dec) -> Vec<Rc<dyn GeometryDyn>> {
+        let sw = Dec::from(dec!(0.1));
+        let quarter = Dec::pi() / Dec::from(2);
+        let to = from.project(to) - from.center;
+        let (x, y) = (to.dot(&from.x()), to.dot(&from.y()));
+        let run = to.magnitude();
+        let along = from
+            .clone()
+            .rotate_axisangle(Vector3::z() * y.atan2(&x))
+            .offset_z(depth);
+        let mut groove: Vec<Rc<dyn GeometryDyn>> = vec![Rc::new(Cylinder::with_bottom_at(
+            along.clone().rotate_axisangle(Vector3::y() * quarter),
+            run,
+            self.channel_diameter / 2,
+        ))];
+        if !depth.is_zero() {
+            // Slot, through which wire is pressed into deep groove
+            groove.push(Rc::new(Rect::with_top_at(
+                along.offset_x(run / 2),
+                run,
+                self.channel_diameter,
+                depth + sw,
+            )));
+        }
+        groove
+    }
+}
+
+impl Default for Wiring {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RightKeyboardConfig {
+    /// Cuts grooves for row and column wires of `matrix` into buttons hull. Wires go
+    /// between key centers in the same order, as `KeyMatrix::wiring_svg` shows them.
+    pub fn add_wiring(mut self, matrix: &KeyMatrix, wiring: Wiring) -> anyhow::Result<Self> {
+        let thickness = self.main_plane_thickness;
+        if wiring.column_depth() + wiring.channel_diameter / 2 >= thickness {
+            return Err(anyhow!(
+                "Column grooves of {} go through {thickness} plate",
+                wiring.column_depth() + wiring.channel_diameter / 2
+            ));
+        }
+        let undersides = matrix
+            .keys
+            .iter()
+            .map(|(position, _)| {
+                self.keys()
+                    .find(|(key, _)| key == position)
+                    .map(|(_, button)| button.origin.clone().offset_z(-thickness / 2))
+                    .ok_or(anyhow!("Key {position:?} of matrix is not in config"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let wires = matrix.wires();
+        let mut holes = Vec::new();
+        for (chains, depth) in [
+            (&wires.rows, Dec::from(0)),
+            (&wires.columns, wiring.column_depth()),
+        ] {
+            for chain in chains {
+                for pair in chain.windows(2) {
+                    let (from, to) = (&undersides[pair[0]], &undersides[pair[1]]);
+                    holes.extend(wiring.groove(from, to.center, depth));
+                }
+            }
+        }
+        self.holes
+            .entry(KeyboardMesh::ButtonsHull)
+            .or_default()
+            .extend(holes);
+        Ok(self)
+    }
+}
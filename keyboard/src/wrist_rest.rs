@@ -0,0 +1,189 @@
+use std::rc::Rc;
+
+use anyhow::anyhow;
+use geometry::{
+    decimal::Dec,
+    geometry::GeometryDyn,
+    indexes::geo_index::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId},
+    origin::Origin,
+    shapes::{extrude, Cylinder},
+};
+use nalgebra::Vector2;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    keyboard_config::{KeyboardMesh, RightKeyboardConfig},
+    label_anchor::Edge,
+};
+
+/// Wrist rest in front of the case. Its outline is the front stripe of table outline,
+/// moved out of the wall by `gap` and extended towards the typist by `depth`, so its
+/// back face follows the front wall of the case. Optional disc magnets in the back face
+/// and in the front wall at the same places keep the rest aligned with the case.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WristRest {
+    pub(crate) depth: Dec,
+    pub(crate) gap: Dec,
+    pub(crate) height: Option<Dec>,
+    pub(crate) magnets: usize,
+    pub(crate) magnet_diameter: Dec,
+    pub(crate) magnet_depth: Dec,
+    pub(crate) clearance: Dec,
+}
+
+impl WristRest {
+    /// 80mm deep rest, 1mm from the case, as high as the lowest key, without magnets
+    pub fn new() -> Self {
+        Self {
+            depth: 80.into(),
+            gap: 1.into(),
+            height: None,
+            magnets: 0,
+            magnet_diameter: 6.into(),
+            magnet_depth: 2.into(),
+            clearance: dec!(0.15).into(),
+        }
+    }
+
+    pub fn depth(mut self, depth: impl Into<Dec>) -> Self {
+        self.depth = depth.into();
+        self
+    }
+
+    /// Space between the front wall of the case and the rest
+    pub fn gap(mut self, gap: impl Into<Dec>) -> Self {
+        self.gap = gap.into();
+        self
+    }
+
+    /// Height of the rest over the table. By default it is the height of the lowest key
+    /// plate underside.
+    pub fn height(mut self, height: impl Into<Dec>) -> Self {
+        self.height = Some(height.into());
+        self
+    }
+
+    /// `count` disc magnets of `diameter` x `depth`, evenly spread along the front wall
+    /// at half height of the rest
+    pub fn magnets(
+        mut self,
+        count: usize,
+        diameter: impl Into<Dec>,
+        depth: impl Into<Dec>,
+    ) -> Self {
+        self.magnets = count;
+        self.magnet_diameter = diameter.into();
+        self.magnet_depth = depth.into();
+        self
+    }
+
+    fn resolved_height(&self, config: &RightKeyboardConfig) -> anyhow::Result<Dec> {
+        if let Some(height) = self.height {
+            return Ok(height);
+        }
+        config
+            .collections()
+            .flat_map(|c| c.buttons())
+            .map(|b| b.origin.center.z - config.main_plane_thickness / 2)
+            .min()
+            .filter(|h| *h > Dec::from(0))
+            .ok_or(anyhow!("Cannot take wrist rest height from keys, set it"))
+    }
+
+    /// Origins of magnet pockets on the outer surface of the front wall, z axis looks
+    /// out of the wall. Pockets are placed by the wall at table level, so on leaning
+    /// walls they should be kept low.
+    fn magnet_origins(&self, config: &RightKeyboardConfig) -> anyhow::Result<Vec<Origin>> {
+        let height = self.resolved_height(config)?;
+        (1..=self.magnets)
+            .map(|i| {
+                let t = Dec::from(i) / Dec::from(self.magnets + 1);
+                Ok(config.label_anchor(Edge::Front, t, 0)?.offset_y(height / 2))
+            })
+            .collect()
+    }
+
+    /// Outline of the rest on the table
+    fn profile(&self, config: &RightKeyboardConfig) -> anyhow::Result<Vec<Vector2<Dec>>> {
+        let mut back = config
+            .wall_stripe(Edge::Front)?
+            .into_iter()
+            .map(|(point, normal)| {
+                let p = point + normal * self.gap;
+                Vector2::new(p.x, p.y)
+            })
+            .collect::<Vec<_>>();
+        back.dedup();
+        let front = back
+            .iter()
+            .rev()
+            .map(|p| Vector2::new(p.x, p.y - self.depth))
+            .collect::<Vec<_>>();
+        Ok(back.into_iter().chain(front).collect())
+    }
+}
+
+impl Default for WristRest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RightKeyboardConfig {
+    /// Adds magnet pockets for wrist rest to the front wall of buttons hull and keeps
+    /// the rest for `wrist_rest`
+    pub fn add_wrist_rest(mut self, rest: WristRest) -> anyhow::Result<Self> {
+        let sw = Dec::from(dec!(0.1));
+        let c = rest.clearance;
+        if rest.magnet_depth + c >= self.main_plane_thickness && rest.magnets > 0 {
+            return Err(anyhow!("Wrist rest magnets go through the front wall"));
+        }
+        let pockets = rest
+            .magnet_origins(&self)?
+            .into_iter()
+            .map(|origin| -> Rc<dyn GeometryDyn> {
+                Rc::new(Cylinder::with_top_at(
+                    origin.offset_z(sw),
+                    rest.magnet_depth + c + sw,
+                    rest.magnet_diameter / 2 + c,
+                ))
+            })
+            .collect::<Vec<_>>();
+        self.holes
+            .entry(KeyboardMesh::ButtonsHull)
+            .or_default()
+            .extend(pockets);
+        self.wrist_rest = Some(rest);
+        Ok(self)
+    }
+
+    /// Wrist rest with magnet pockets in its back face. It is placed in keyboard
+    /// coordinates, in front of the case.
+    pub fn wrist_rest(&self, index: &mut GeoIndex) -> anyhow::Result<MeshId> {
+        let sw = Dec::from(dec!(0.1));
+        let rest = self
+            .wrist_rest
+            .as_ref()
+            .ok_or(anyhow!("Wrist rest is not configured"))?;
+        let c = rest.clearance;
+        let height = rest.resolved_height(self)?;
+        if rest.magnet_diameter + c * 2 >= height && rest.magnets > 0 {
+            return Err(anyhow!("Wrist rest is too low for magnets"));
+        }
+        let mesh = index.new_mesh();
+        extrude(&rest.profile(self)?, height, Origin::new())
+            .polygonize(mesh.make_mut_ref(index), 0)?;
+        for origin in rest.magnet_origins(self)? {
+            let pocket = Cylinder::with_bottom_at(
+                origin.offset_z(rest.gap - sw),
+                rest.magnet_depth + c + sw,
+                rest.magnet_diameter / 2 + c,
+            );
+            let pocket_mesh = index.new_mesh();
+            pocket.polygonize(pocket_mesh.make_mut_ref(index), self.quality.surface_steps)?;
+            Self::subtract_mesh(mesh, pocket_mesh, index)?;
+        }
+        Ok(mesh)
+    }
+}
@@ -6,4 +6,91 @@ use clap::Parser;
 pub struct Command {
     #[arg(long)]
     pub output_path: PathBuf,
+
+    /// Json file with keyboard layout, instead of the built-in one
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+
+    /// Export top skin of given thickness as separate model
+    #[arg(long)]
+    pub top_skin: Option<f64>,
+
+    /// Json file with keyboard layout of the same structure to blend design with
+    #[arg(long, requires = "morph")]
+    pub morph_to: Option<PathBuf>,
+
+    /// How far to blend design to `--morph-to` one: 0 - as is, 1 - the other design
+    #[arg(long, requires = "morph_to")]
+    pub morph: Option<f64>,
+
+    /// Export TPU bumper of given height as separate model and cut groove for it in the case
+    #[arg(long)]
+    pub bumper: Option<f64>,
+
+    /// Export plate, bolted under bottom plate, with nut pocket for tripod screw
+    #[arg(long)]
+    pub tripod_adapter: bool,
+
+    /// Cut pockets for dampening sheet of given thickness under key plates and export
+    /// cutting template for the sheet
+    #[arg(long)]
+    pub dampening: Option<f64>,
+
+    /// Mirror all parts to get left half of keyboard
+    #[arg(long)]
+    pub left: bool,
+
+    /// Report switches, which top housing cannot be opened without disassembly
+    #[arg(long)]
+    pub check_clearance: bool,
+
+    /// Report pairs of keys, which keycaps hit each other on their travel
+    #[arg(long)]
+    pub keycap_collisions: bool,
+
+    /// Print free volume inside of the case and check, that components fit there
+    #[arg(long)]
+    pub interior_report: bool,
+
+    /// Report keys, which plate bends too much under typing load
+    #[arg(long)]
+    pub deflection: bool,
+
+    /// Rank keys by risk of plate ping for PLA plate
+    #[arg(long)]
+    pub ping: bool,
+
+    /// Write positions of all keys as csv and json for PCB and firmware tools
+    #[arg(long)]
+    pub key_positions: bool,
+
+    /// Assign keys to switch matrix of given rows and columns with the shortest wiring,
+    /// write wiring diagram and matrix for QMK and ZMK
+    #[arg(long, num_args = 2, value_names = ["ROWS", "COLUMNS"])]
+    pub matrix: Option<Vec<usize>>,
+
+    /// Write outlines of the case, cut at these heights, as svg and dxf
+    #[arg(long, num_args = 1.., value_name = "HEIGHT")]
+    pub section: Vec<f64>,
+
+    /// Also write stl files next to scad ones
+    #[arg(long)]
+    pub stl: bool,
+
+    /// Also write surface mesh of right case in Gmsh format for FEM
+    #[arg(long)]
+    pub gmsh: bool,
+
+    /// Also write 3mf file of the case
+    #[arg(long)]
+    pub three_mf: bool,
+
+    /// Paint layer seam in 3mf at this table outline parameter
+    #[arg(long)]
+    pub seam: Option<f64>,
+
+    /// Print diagnostics of mesh operations, filtered like `RUST_LOG`, e.g.
+    /// `geometry=debug`. Needs `tracing` feature.
+    #[arg(long)]
+    pub log: Option<String>,
 }
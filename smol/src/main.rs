@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use nalgebra::Vector3;
 use num_traits::{One, Zero};
 use rust_decimal_macros::dec;
@@ -17,11 +19,13 @@ use geometry::{
         geo_index::{face::FaceId, index::GeoIndex},
     },
     origin::Origin,
+    planar::plane::Plane,
     shapes::Cylinder,
 };
 use keyboard::{
-    Angle, Bolt, BoltPoint, Button, ButtonsCollection, ButtonsColumn, Hole, KeyboardMesh,
-    RightKeyboardConfig,
+    Angle, Bolt, BoltPoint, Bumper, Button, ButtonsCollection, ButtonsColumn, DampeningPocket,
+    Hole, KeyboardBuilder, KeyboardFile, KeyboardMesh, PlateMaterial, PlateStiffness,
+    RightKeyboardConfig, SwitchClearance, TentingAdapter,
 };
 
 mod cli;
@@ -29,6 +33,10 @@ mod cli;
 fn main() -> Result<(), anyhow::Error> {
     let cli = cli::Command::parse();
 
+    if let Some(filter) = &cli.log {
+        geometry::log::init(filter)?;
+    }
+
     let m2_10_nut = Bolt::build()
         .m2()
         .head_height(Dec::from(1.2))
@@ -52,285 +60,370 @@ fn main() -> Result<(), anyhow::Error> {
         .height(Dec::from(8))
         .build();
 
-    let keyboard = RightKeyboardConfig::build()
-        .wall_thickness(4)
-        .bottom_thickness(2)
-        .add_bolt(
-            KeyboardMesh::ButtonsHull,
-            KeyboardMesh::Bottom,
-            BoltPoint::new(m2_10_nut.clone())
-                .head_thread_material_gap(2)
-                .radial_head_material_extention(dec!(1))
-                .origin(
-                    Origin::new()
-                        .offset_x(-15)
-                        .offset_y(dec!(3))
-                        .offset_z(dec!(15))
-                        .rotate_axisangle(
-                            Vector3::new(Dec::one(), Dec::one(), Dec::zero()).normalize()
-                                * Angle::from_deg(-75).rad(),
-                        )
-                        .rotate_axisangle(Vector3::x() * Angle::from_deg(30).rad())
-                        .offset_y(5)
-                        .offset_z(-10),
-                ),
-        )
-        .add_bolt(
-            KeyboardMesh::ButtonsHull,
-            KeyboardMesh::Bottom,
-            BoltPoint::new(m2_10)
-                .thread_hole_radius_plastic_modification(dec!(1.6))
-                .radial_head_material_extention(dec!(1))
-                .head_thread_material_gap(4)
-                .origin(
-                    Origin::new()
-                        .offset_x(17)
-                        .offset_y(-dec!(11.5))
-                        .offset_z(dec!(0.4))
-                        .rotate_axisangle(Vector3::x() * Angle::from_deg(20).rad())
-                        .offset_z(dec!(2.5)),
-                ),
-        )
-        .add_bolt(
-            KeyboardMesh::ButtonsHull,
-            KeyboardMesh::Bottom,
-            BoltPoint::new(m1_8)
-                .radial_head_material_extention(dec!(1))
-                .thread_hole_radius_plastic_modification(1.8)
-                .head_thread_material_gap(4)
-                .origin(
-                    Origin::new()
-                        .offset_x(15)
-                        .offset_y(dec!(11.5))
-                        .offset_z(dec!(0.4))
-                        .rotate_axisangle(Vector3::x() * Angle::from_deg(-15).rad())
-                        .offset_y(dec!(-1) + dec!(0.6))
-                        .offset_z(2),
-                ),
-        )
-        .main(
-            ButtonsCollection::build()
-                .column(
-                    ButtonsColumn::build()
-                        .main_button(
-                            Button::chok_hotswap_custom()
-                                .outer_left_bottom_edge(Vector3::new(
-                                    Dec::one(),
-                                    Dec::from(2),
-                                    Dec::one(),
-                                ))
-                                .build(),
-                        )
-                        .main_button(
-                            Button::chok_hotswap_custom()
-                                .outer_right_top_edge(Vector3::new(
-                                    Dec::one(),
-                                    Dec::from(10),
-                                    Dec::one(),
-                                ))
-                                .outer_left_top_edge(Vector3::new(
-                                    Dec::from(10),
-                                    Dec::from(10),
-                                    Dec::one(),
-                                ))
-                                .build(),
-                        )
-                        .curvature(Angle::from_deg(Dec::from(10)))
-                        .padding(Dec::from(2))
-                        .build(),
-                )
-                .column(
-                    ButtonsColumn::build()
-                        .main_button(
-                            Button::chok_hotswap_custom()
-                                .outer_left_top_edge(Vector3::new(
-                                    Dec::from(2),
-                                    Dec::from(2),
-                                    Dec::one(),
-                                ))
-                                .build(),
-                        )
-                        .curvature(Angle::from_deg(Dec::from(10)))
-                        .padding(Dec::from(2))
-                        .build(),
-                )
-                .padding(25)
-                .first_column_angle(Angle::from_deg(30))
-                .plane_pitch(Angle::from_deg(-7))
-                .height(20)
-                .curvature(Angle::from_deg(Dec::from(10)))
-                .build(),
-        )
-        .thumb(
-            ButtonsCollection::build()
-                .column(
-                    ButtonsColumn::build()
-                        .main_button(
-                            Button::chok_hotswap_custom()
-                                .outer_left_top_edge(Vector3::new(
-                                    Dec::one(),
-                                    Dec::from(10),
-                                    Dec::one(),
-                                ))
-                                .outer_right_top_edge(Vector3::new(
-                                    Dec::from(10),
-                                    Dec::from(10),
-                                    Dec::one(),
-                                ))
-                                .outer_right_bottom_edge(Vector3::new(
-                                    Dec::from(2),
-                                    Dec::from(2),
-                                    Dec::one(),
-                                ))
-                                .build(),
-                        )
-                        .build(),
-                )
-                .height(Dec::from(15))
-                .padding(Dec::from(18))
-                .position_shift_x(Dec::from(-20))
-                .position_shift_y(Dec::from(-15))
-                .first_column_angle(Angle::from_deg(Dec::from(-85)))
-                .curvature(Angle::from_deg(Dec::from(-9)))
-                .plane_pitch(Angle::from_deg(Dec::from(25)))
-                .plane_yaw(Angle::from_deg(Dec::from(-15)))
-                .build(),
-        )
-        .table_outline(
-            Root::new() // right
-                .push_back(HyperLine::new_2(
-                    SuperPoint {
-                        side_dir: Vector3::z() * Dec::from(1),
-                        point: Vector3::new(Dec::from(30), Dec::from(10), Dec::zero()),
-                    },
-                    SuperPoint {
-                        side_dir: Vector3::z() * Dec::from(1),
-                        point: Vector3::new(Dec::from(30), -Dec::from(10), Dec::zero()),
-                    },
-                ))
-                .push_back(HyperLine::new_2(
-                    // corner
-                    SuperPoint {
-                        side_dir: Vector3::z() * Dec::from(1),
-                        point: Vector3::new(Dec::from(30), -Dec::from(10), Dec::zero()),
-                    },
-                    SuperPoint {
-                        side_dir: Vector3::z() * Dec::from(1),
-                        point: Vector3::new(Dec::from(20), -Dec::from(15), Dec::zero()),
-                    },
-                ))
-                .extend(
-                    HyperLine::new_2(
+    let keyboard = if let Some(path) = &cli.config {
+        with_options(&cli, KeyboardFile::load(path)?.builder()?).build()
+    } else {
+        let keyboard = RightKeyboardConfig::build()
+            .wall_thickness(4)
+            .bottom_thickness(2)
+            .add_bolt(
+                KeyboardMesh::ButtonsHull,
+                KeyboardMesh::Bottom,
+                BoltPoint::new(m2_10_nut.clone())
+                    .head_thread_material_gap(2)
+                    .radial_head_material_extention(dec!(1))
+                    .origin(
+                        Origin::new()
+                            .offset_x(-15)
+                            .offset_y(dec!(3))
+                            .offset_z(dec!(15))
+                            .rotate_axisangle(
+                                Vector3::new(Dec::one(), Dec::one(), Dec::zero()).normalize()
+                                    * Angle::from_deg(-75).rad(),
+                            )
+                            .rotate_axisangle(Vector3::x() * Angle::from_deg(30).rad())
+                            .offset_y(5)
+                            .offset_z(-10),
+                    ),
+            )
+            .add_bolt(
+                KeyboardMesh::ButtonsHull,
+                KeyboardMesh::Bottom,
+                BoltPoint::new(m2_10.clone())
+                    .thread_hole_radius_plastic_modification(dec!(1.6))
+                    .radial_head_material_extention(dec!(1))
+                    .head_thread_material_gap(4)
+                    .origin(
+                        Origin::new()
+                            .offset_x(17)
+                            .offset_y(-dec!(11.5))
+                            .offset_z(dec!(0.4))
+                            .rotate_axisangle(Vector3::x() * Angle::from_deg(20).rad())
+                            .offset_z(dec!(2.5)),
+                    ),
+            )
+            .add_bolt(
+                KeyboardMesh::ButtonsHull,
+                KeyboardMesh::Bottom,
+                BoltPoint::new(m1_8.clone())
+                    .radial_head_material_extention(dec!(1))
+                    .thread_hole_radius_plastic_modification(1.8)
+                    .head_thread_material_gap(4)
+                    .origin(
+                        Origin::new()
+                            .offset_x(15)
+                            .offset_y(dec!(11.5))
+                            .offset_z(dec!(0.4))
+                            .rotate_axisangle(Vector3::x() * Angle::from_deg(-15).rad())
+                            .offset_y(dec!(-1) + dec!(0.6))
+                            .offset_z(2),
+                    ),
+            )
+            .main(
+                ButtonsCollection::build()
+                    .column(
+                        ButtonsColumn::build()
+                            .main_button(
+                                Button::chok_hotswap_custom()
+                                    .outer_left_bottom_edge(Vector3::new(
+                                        Dec::one(),
+                                        Dec::from(2),
+                                        Dec::one(),
+                                    ))
+                                    .build(),
+                            )
+                            .main_button(
+                                Button::chok_hotswap_custom()
+                                    .outer_right_top_edge(Vector3::new(
+                                        Dec::one(),
+                                        Dec::from(10),
+                                        Dec::one(),
+                                    ))
+                                    .outer_left_top_edge(Vector3::new(
+                                        Dec::from(10),
+                                        Dec::from(10),
+                                        Dec::one(),
+                                    ))
+                                    .build(),
+                            )
+                            .curvature(Angle::from_deg(Dec::from(10)))
+                            .padding(Dec::from(2))
+                            .build(),
+                    )
+                    .column(
+                        ButtonsColumn::build()
+                            .main_button(
+                                Button::chok_hotswap_custom()
+                                    .outer_left_top_edge(Vector3::new(
+                                        Dec::from(2),
+                                        Dec::from(2),
+                                        Dec::one(),
+                                    ))
+                                    .build(),
+                            )
+                            .curvature(Angle::from_deg(Dec::from(10)))
+                            .padding(Dec::from(2))
+                            .build(),
+                    )
+                    .padding(25)
+                    .first_column_angle(Angle::from_deg(30))
+                    .plane_pitch(Angle::from_deg(-7))
+                    .height(20)
+                    .curvature(Angle::from_deg(Dec::from(10)))
+                    .build(),
+            )
+            .thumb(
+                ButtonsCollection::build()
+                    .column(
+                        ButtonsColumn::build()
+                            .main_button(
+                                Button::chok_hotswap_custom()
+                                    .outer_left_top_edge(Vector3::new(
+                                        Dec::one(),
+                                        Dec::from(10),
+                                        Dec::one(),
+                                    ))
+                                    .outer_right_top_edge(Vector3::new(
+                                        Dec::from(10),
+                                        Dec::from(10),
+                                        Dec::one(),
+                                    ))
+                                    .outer_right_bottom_edge(Vector3::new(
+                                        Dec::from(2),
+                                        Dec::from(2),
+                                        Dec::one(),
+                                    ))
+                                    .build(),
+                            )
+                            .build(),
+                    )
+                    .height(Dec::from(15))
+                    .padding(Dec::from(18))
+                    .position_shift_x(Dec::from(-20))
+                    .position_shift_y(Dec::from(-15))
+                    .first_column_angle(Angle::from_deg(Dec::from(-85)))
+                    .curvature(Angle::from_deg(Dec::from(-9)))
+                    .plane_pitch(Angle::from_deg(Dec::from(25)))
+                    .plane_yaw(Angle::from_deg(Dec::from(-15)))
+                    .build(),
+            )
+            .table_outline(
+                Root::new() // right
+                    .push_back(HyperLine::new_2(
                         SuperPoint {
                             side_dir: Vector3::z() * Dec::from(1),
-                            point: Vector3::new(Dec::from(20), -Dec::from(15), Dec::zero()),
+                            point: Vector3::new(Dec::from(30), Dec::from(10), Dec::zero()),
                         },
                         SuperPoint {
                             side_dir: Vector3::z() * Dec::from(1),
-                            point: Vector3::new(-Dec::from(5), -Dec::from(30), Dec::zero()),
+                            point: Vector3::new(Dec::from(30), -Dec::from(10), Dec::zero()),
                         },
-                    )
-                    .split_by_weights([Dec::from(2), Dec::from(dec!(0.5)), Dec::from(2)].to_vec()),
-                )
-                .push_back(HyperLine::new_2(
-                    // corner
-                    SuperPoint {
-                        side_dir: Vector3::z() * Dec::from(1),
-                        point: Vector3::new(-Dec::from(5), -Dec::from(30), Dec::zero()),
-                    },
-                    SuperPoint {
-                        side_dir: Vector3::z() * Dec::from(dec!(0.01)),
-                        point: Vector3::new(-Dec::from(10), -Dec::from(24), Dec::zero()),
-                    },
-                ))
-                .extend(
-                    HyperLine::new_2(
+                    ))
+                    .push_back(HyperLine::new_2(
+                        // corner
                         SuperPoint {
-                            side_dir: Vector3::z() * Dec::from(dec!(0.01)),
-                            point: Vector3::new(-Dec::from(10), -Dec::from(24), Dec::zero()),
+                            side_dir: Vector3::z() * Dec::from(1),
+                            point: Vector3::new(Dec::from(30), -Dec::from(10), Dec::zero()),
                         },
                         SuperPoint {
-                            side_dir: Vector3::z() * Dec::from(dec!(0.01)),
-                            point: Vector3::new(-Dec::from(12), -Dec::from(10), Dec::zero()),
+                            side_dir: Vector3::z() * Dec::from(1),
+                            point: Vector3::new(Dec::from(20), -Dec::from(15), Dec::zero()),
                         },
+                    ))
+                    .extend(
+                        HyperLine::new_2(
+                            SuperPoint {
+                                side_dir: Vector3::z() * Dec::from(1),
+                                point: Vector3::new(Dec::from(20), -Dec::from(15), Dec::zero()),
+                            },
+                            SuperPoint {
+                                side_dir: Vector3::z() * Dec::from(1),
+                                point: Vector3::new(-Dec::from(5), -Dec::from(30), Dec::zero()),
+                            },
+                        )
+                        .split_by_weights(
+                            [Dec::from(2), Dec::from(dec!(0.5)), Dec::from(2)].to_vec(),
+                        ),
                     )
-                    .split_by([Dec::from(dec!(0.1)), Dec::from(dec!(0.2))].to_vec()),
-                )
-                .extend(
-                    HyperLine::new_4(
-                        SuperPoint {
-                            side_dir: Vector3::z() * Dec::from(dec!(0.01)),
-                            point: Vector3::new(-Dec::from(12), -Dec::from(10), Dec::zero()),
-                        },
-                        SuperPoint {
-                            side_dir: Vector3::z() * Dec::from(3),
-                            point: Vector3::new(-Dec::from(12), Dec::from(20), Dec::zero()),
-                        },
+                    .push_back(HyperLine::new_2(
+                        // corner
                         SuperPoint {
-                            side_dir: Vector3::z() * Dec::from(2),
-                            point: Vector3::new(Dec::from(10), Dec::from(20), Dec::zero()),
+                            side_dir: Vector3::z() * Dec::from(1),
+                            point: Vector3::new(-Dec::from(5), -Dec::from(30), Dec::zero()),
                         },
                         SuperPoint {
-                            side_dir: Vector3::z() * Dec::from(1),
-                            point: Vector3::new(Dec::from(30), Dec::from(10), Dec::zero()),
+                            side_dir: Vector3::z() * Dec::from(dec!(0.01)),
+                            point: Vector3::new(-Dec::from(10), -Dec::from(24), Dec::zero()),
                         },
+                    ))
+                    .extend(
+                        HyperLine::new_2(
+                            SuperPoint {
+                                side_dir: Vector3::z() * Dec::from(dec!(0.01)),
+                                point: Vector3::new(-Dec::from(10), -Dec::from(24), Dec::zero()),
+                            },
+                            SuperPoint {
+                                side_dir: Vector3::z() * Dec::from(dec!(0.01)),
+                                point: Vector3::new(-Dec::from(12), -Dec::from(10), Dec::zero()),
+                            },
+                        )
+                        .split_by([Dec::from(dec!(0.1)), Dec::from(dec!(0.2))].to_vec()),
                     )
-                    .split_by_weights(
-                        [
-                            Dec::from(dec!(1)),
-                            Dec::from(dec!(10)),
-                            Dec::from(dec!(10)),
-                            Dec::from(dec!(10)),
-                            Dec::from(dec!(10)),
-                            Dec::from(dec!(10)),
-                            Dec::from(dec!(10)),
-                        ]
-                        .to_vec(),
+                    .extend(
+                        HyperLine::new_4(
+                            SuperPoint {
+                                side_dir: Vector3::z() * Dec::from(dec!(0.01)),
+                                point: Vector3::new(-Dec::from(12), -Dec::from(10), Dec::zero()),
+                            },
+                            SuperPoint {
+                                side_dir: Vector3::z() * Dec::from(3),
+                                point: Vector3::new(-Dec::from(12), Dec::from(20), Dec::zero()),
+                            },
+                            SuperPoint {
+                                side_dir: Vector3::z() * Dec::from(2),
+                                point: Vector3::new(Dec::from(10), Dec::from(20), Dec::zero()),
+                            },
+                            SuperPoint {
+                                side_dir: Vector3::z() * Dec::from(1),
+                                point: Vector3::new(Dec::from(30), Dec::from(10), Dec::zero()),
+                            },
+                        )
+                        .split_by_weights(
+                            [
+                                Dec::from(dec!(1)),
+                                Dec::from(dec!(10)),
+                                Dec::from(dec!(10)),
+                                Dec::from(dec!(10)),
+                                Dec::from(dec!(10)),
+                                Dec::from(dec!(10)),
+                                Dec::from(dec!(10)),
+                            ]
+                            .to_vec(),
+                        ),
                     ),
-                ),
-        )
-        .add_main_hole(
-            Hole::build()
-                .shape(
-                    Cylinder::with_top_at(
-                        Origin::new()
-                            .offset_y(-25)
-                            .offset_z(15)
-                            .rotate_axisangle(Vector3::x() * Angle::from_deg(67).rad())
-                            .rotate_axisangle(Vector3::y() * Angle::from_deg(20).rad())
-                            .offset_z(1),
-                        5,
-                        4,
+            )
+            .add_main_hole(
+                Hole::build()
+                    .shape(
+                        Cylinder::with_top_at(
+                            Origin::new()
+                                .offset_y(-25)
+                                .offset_z(15)
+                                .rotate_axisangle(Vector3::x() * Angle::from_deg(67).rad())
+                                .rotate_axisangle(Vector3::y() * Angle::from_deg(20).rad())
+                                .offset_z(1),
+                            5,
+                            4,
+                        )
+                        .top_cap(false)
+                        .bottom_cap(false)
+                        .steps(16),
                     )
-                    .top_cap(false)
-                    .bottom_cap(false)
-                    .steps(16),
-                )
-                .build()?,
-        )
-        .add_main_hole(
-            Hole::build()
-                .shape(
-                    Cylinder::with_top_at(
-                        Origin::new()
-                            .offset_y(-25)
-                            .offset_z(15)
-                            .rotate_axisangle(Vector3::x() * Angle::from_deg(67).rad())
-                            .rotate_axisangle(Vector3::y() * Angle::from_deg(20).rad())
-                            //.offset_y(-4)
-                            .offset_z(-1),
-                        5,
-                        5.5,
+                    .build()?,
+            )
+            .add_main_hole(
+                Hole::build()
+                    .shape(
+                        Cylinder::with_top_at(
+                            Origin::new()
+                                .offset_y(-25)
+                                .offset_z(15)
+                                .rotate_axisangle(Vector3::x() * Angle::from_deg(67).rad())
+                                .rotate_axisangle(Vector3::y() * Angle::from_deg(20).rad())
+                                //.offset_y(-4)
+                                .offset_z(-1),
+                            5,
+                            5.5,
+                        )
+                        //.top_cap(false)
+                        .bottom_cap(false)
+                        .steps(6),
                     )
-                    //.top_cap(false)
-                    .bottom_cap(false)
-                    .steps(6),
-                )
-                .build()?,
-        )
-        .build();
+                    .build()?,
+            );
+        with_options(&cli, keyboard).build()
+    };
+    let keyboard = if let (Some(path), Some(t)) = (&cli.morph_to, cli.morph) {
+        let other = with_options(&cli, KeyboardFile::load(path)?.builder()?).build();
+        keyboard.lerp(&other, t)?
+    } else {
+        keyboard
+    }
+    .validated()?;
+
+    if cli.check_clearance {
+        for blocked in keyboard.check_switch_clearance(&SwitchClearance::new()) {
+            println!(
+                "switch {:?} is blocked by {:?}",
+                blocked.key, blocked.obstacle
+            );
+        }
+    }
+
+    if cli.keycap_collisions {
+        for (a, b) in keyboard.check_keycap_collisions()? {
+            println!("keycaps of {a:?} and {b:?} collide");
+        }
+    }
+
+    if cli.interior_report {
+        let report = keyboard.interior_report()?;
+        println!(
+            "cavity: {} mm3, occupied: {} mm3, free: {} mm3",
+            report.cavity, report.occupied, report.free
+        );
+    }
+
+    if cli.deflection {
+        for key in keyboard.plate_deflection(&PlateStiffness::new()) {
+            if key.excessive {
+                println!(
+                    "plate under {:?} bends by {} mm, nearest support is {} mm away",
+                    key.key, key.deflection, key.span
+                );
+            }
+        }
+    }
+
+    if cli.ping {
+        for key in keyboard.ping_report(PlateMaterial::Pla) {
+            println!(
+                "{:?}: ping risk {}, {} Hz, nearest support is {} mm away",
+                key.key, key.risk, key.frequency, key.span
+            );
+        }
+    }
 
     std::fs::create_dir_all(&cli.output_path)?;
+    if cli.dampening.is_some() {
+        std::fs::write(
+            cli.output_path.join("dampening.svg"),
+            keyboard.dampening_template_svg()?,
+        )?;
+    }
+    if cli.key_positions {
+        std::fs::write(
+            cli.output_path.join("key_positions.csv"),
+            keyboard.key_positions_csv(),
+        )?;
+        std::fs::write(
+            cli.output_path.join("key_positions.json"),
+            keyboard.key_positions_json()?,
+        )?;
+    }
+    if let Some([rows, columns]) = cli.matrix.as_deref() {
+        let matrix = keyboard.key_matrix(*rows, *columns)?;
+        println!(
+            "matrix wiring: {} mm of wire, {} crossings",
+            matrix.wire_length, matrix.crossings
+        );
+        std::fs::write(cli.output_path.join("wiring.svg"), matrix.wiring_svg())?;
+        std::fs::write(cli.output_path.join("info.json"), matrix.qmk_info_json()?)?;
+        std::fs::write(
+            cli.output_path.join("matrix_transform.dtsi"),
+            matrix.zmk_matrix_transform(),
+        )?;
+    }
     println!("create main");
     let mut main = GeoIndex::new(Aabb::from_points(&[
         Vector3::new(Dec::from(-51), Dec::from(-51), Dec::from(-51)),
@@ -393,6 +486,79 @@ fn main() -> Result<(), anyhow::Error> {
     .points_precision(dec!(0.001));
 
     keyboard.buttons_hull(&mut main).unwrap();
+    if cli.gmsh {
+        std::fs::write(cli.output_path.join("main.msh"), keyboard.gmsh(&main)?)?;
+    }
+    if cli.left {
+        main.mirror_x()?;
+    }
+    for height in &cli.section {
+        let plane = Plane::new(Dec::zero(), Dec::zero(), Dec::one(), Dec::from(*height));
+        for mesh in main.meshes() {
+            let section = main.slice(*mesh, &plane)?;
+            let name = format!("section-{height}-{}", mesh.0);
+            std::fs::write(cli.output_path.join(format!("{name}.svg")), section.svg())?;
+            std::fs::write(cli.output_path.join(format!("{name}.dxf")), section.dxf())?;
+        }
+    }
+
+    if let Some(thickness) = cli.top_skin {
+        let mut skin = GeoIndex::new(Aabb::from_points(&[
+            Vector3::new(Dec::from(-51), Dec::from(-51), Dec::from(-51)),
+            Vector3::new(Dec::from(50), Dec::from(50), Dec::from(50)),
+        ]))
+        .input_polygon_min_rib_length(dec!(0.05))
+        .points_precision(dec!(0.001));
+        keyboard.top_skin(&mut skin, thickness)?;
+        if cli.left {
+            skin.mirror_x()?;
+        }
+
+        let scad = skin.scad();
+        let top_skin = format!("translate(v=[0, 0, 0]) {{ {scad} }};");
+        std::fs::write(cli.output_path.join("top_skin.scad"), top_skin)?;
+        if cli.stl {
+            write_stl(&skin, cli.output_path.join("top_skin.stl"))?;
+        }
+    }
+    if cli.bumper.is_some() {
+        let mut bumper = GeoIndex::new(Aabb::from_points(&[
+            Vector3::new(Dec::from(-51), Dec::from(-51), Dec::from(-51)),
+            Vector3::new(Dec::from(50), Dec::from(50), Dec::from(50)),
+        ]))
+        .input_polygon_min_rib_length(dec!(0.05))
+        .points_precision(dec!(0.001));
+        keyboard.bumper(&mut bumper)?;
+        if cli.left {
+            bumper.mirror_x()?;
+        }
+
+        let scad = bumper.scad();
+        let bumper_scad = format!("translate(v=[0, 0, 0]) {{ {scad} }};");
+        std::fs::write(cli.output_path.join("bumper.scad"), bumper_scad)?;
+        if cli.stl {
+            write_stl(&bumper, cli.output_path.join("bumper.stl"))?;
+        }
+    }
+    if cli.tripod_adapter {
+        let mut adapter = GeoIndex::new(Aabb::from_points(&[
+            Vector3::new(Dec::from(-51), Dec::from(-51), Dec::from(-51)),
+            Vector3::new(Dec::from(50), Dec::from(50), Dec::from(50)),
+        ]))
+        .input_polygon_min_rib_length(dec!(0.05))
+        .points_precision(dec!(0.001));
+        keyboard.tenting_adapter(&mut adapter)?;
+        if cli.left {
+            adapter.mirror_x()?;
+        }
+
+        let scad = adapter.scad();
+        let adapter_scad = format!("translate(v=[0, 0, 0]) {{ {scad} }};");
+        std::fs::write(cli.output_path.join("tenting_adapter.scad"), adapter_scad)?;
+        if cli.stl {
+            write_stl(&adapter, cli.output_path.join("tenting_adapter.stl"))?;
+        }
+    }
     //println!("create bottom");
     //keyboard.bottom_pad(&mut bottom).unwrap();
     //let chok = ChokHotswap::new();
@@ -413,6 +579,45 @@ fn main() -> Result<(), anyhow::Error> {
     std::fs::write(main_all, button_hull)?;
     std::fs::write(chok_hw_top, chok_hotswap_top)?;
     std::fs::write(chok_hw_bottom, chok_hotswap_bottom)?;
+    if cli.stl {
+        write_stl(&main, cli.output_path.join("main.stl"))?;
+    }
+    if cli.three_mf {
+        let seam = keyboard
+            .seam()
+            .map(|s| if cli.left { s.mirror_x() } else { s });
+        std::fs::write(
+            cli.output_path.join("main.3mf"),
+            main.three_mf(seam.as_ref())?,
+        )?;
+    }
 
     Ok(())
 }
+
+fn write_stl(index: &GeoIndex, path: PathBuf) -> anyhow::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    stl_io::write_stl(&mut file, index.stl_triangles()?.iter())?;
+    Ok(())
+}
+
+/// Parts and features, requested from command line
+fn with_options(cli: &cli::Command, keyboard: KeyboardBuilder) -> KeyboardBuilder {
+    let keyboard = match cli.bumper {
+        Some(height) => keyboard.bumper(Bumper::new(height)),
+        None => keyboard,
+    };
+    let keyboard = if cli.tripod_adapter {
+        keyboard.tenting_adapter(TentingAdapter::tripod())
+    } else {
+        keyboard
+    };
+    let keyboard = match cli.seam {
+        Some(at) => keyboard.seam(at),
+        None => keyboard,
+    };
+    match cli.dampening {
+        Some(thickness) => keyboard.dampening_pocket(DampeningPocket::new(thickness)),
+        None => keyboard,
+    }
+}
@@ -1,7 +1,34 @@
+use std::path::PathBuf;
+
 use clap::Parser;
 
 #[derive(Parser)]
 pub struct Command {
+    /// Stl file of the stand
+    #[arg(long)]
+    pub output_path: PathBuf,
+
+    /// Json file with keyboard layout
+    #[arg(long)]
+    pub config: PathBuf,
+
+    /// Tenting angle in degrees: inner (left) side of right half goes up
+    #[arg(long, default_value_t = 15.0)]
+    pub tent: f64,
+
+    /// Tilt angle in degrees: back side goes up, negative values lift the front
+    #[arg(long, default_value_t = 0.0)]
+    pub tilt: f64,
+
+    /// Height of the stand under the lowest point of the case
+    #[arg(long, default_value_t = 8.0)]
+    pub min_height: f64,
+
+    /// Height of lips around the case
+    #[arg(long, default_value_t = 3.0)]
+    pub lip_height: f64,
+
+    /// Stand for left half, mirrored along x
     #[arg(long)]
-    pub output_path: String,
+    pub left: bool,
 }
@@ -0,0 +1,143 @@
+use anyhow::anyhow;
+use geometry::{
+    decimal::Dec,
+    geometry::GeometryDyn,
+    indexes::geo_index::{geo_object::GeoObject, index::GeoIndex, mesh::MeshId},
+    origin::Origin,
+    shapes::{extrude, Cylinder, Rect},
+};
+use itertools::Itertools;
+use keyboard::{Angle, RightKeyboardConfig};
+use nalgebra::{ComplexField, Vector3};
+use num_traits::Zero;
+use rust_decimal_macros::dec;
+
+/// Wedge under the case, which holds it tented and tilted. Its top follows the underside
+/// of bottom plate, its bottom is the table plane, turned by `tent` around y axis and by
+/// `tilt` around x axis. Lips around the outline keep the case in place, bolts of bottom
+/// plate go through the stand and their heads sink into pockets on the table side.
+/// Stand is built in keyboard coordinates, so it can be checked under the case.
+pub struct TentingStand {
+    pub tent: Angle,
+    pub tilt: Angle,
+    pub min_height: Dec,
+    pub lip_height: Dec,
+    pub lip_wall: Dec,
+    /// Material between bolt head pockets and the case
+    pub boss: Dec,
+    pub clearance: Dec,
+}
+
+impl TentingStand {
+    pub fn new(tent: Angle, tilt: Angle) -> Self {
+        Self {
+            tent,
+            tilt,
+            min_height: 8.into(),
+            lip_height: 3.into(),
+            lip_wall: 2.into(),
+            boss: 3.into(),
+            clearance: dec!(0.2).into(),
+        }
+    }
+
+    /// Slopes of the table plane along x and y under the case
+    fn slopes(&self) -> (Dec, Dec) {
+        let slope = |a: &Angle| a.rad().sin() / a.rad().cos();
+        (slope(&self.tent), slope(&self.tilt))
+    }
+
+    /// Whole stand as mesh of `index`
+    pub fn mesh(
+        &self,
+        keyboard: &RightKeyboardConfig,
+        index: &mut GeoIndex,
+    ) -> anyhow::Result<MeshId> {
+        let sw = Dec::from(dec!(0.1));
+        let c = self.clearance;
+        let underside = -keyboard.bottom_thickness();
+        let outline = keyboard.outline_on_table(c);
+        let outer = keyboard.outline_on_table(c + self.lip_wall);
+        let (Some((min_x, max_x)), Some((min_y, max_y))) = (
+            outer.iter().map(|p| p.x).minmax().into_option(),
+            outer.iter().map(|p| p.y).minmax().into_option(),
+        ) else {
+            return Err(anyhow!("Keyboard has no outline on the table"));
+        };
+
+        // Inner side of the right half goes up by tent, back goes up by positive tilt
+        let (tent, tilt) = self.slopes();
+        let corners = [
+            (min_x, min_y),
+            (min_x, max_y),
+            (max_x, min_y),
+            (max_x, max_y),
+        ];
+        let rise = |x: Dec, y: Dec| (max_x - x) * tent + (y - min_y) * tilt;
+        let lowest = corners
+            .iter()
+            .map(|(x, y)| rise(*x, *y))
+            .min()
+            .unwrap_or_default();
+        let height = |x: Dec, y: Dec| self.min_height + rise(x, y) - lowest;
+        let depth = corners
+            .iter()
+            .map(|(x, y)| height(*x, *y))
+            .max()
+            .unwrap_or_default()
+            + sw;
+
+        let stand = index.new_mesh();
+        extrude(
+            &outer,
+            depth + self.lip_height,
+            Origin::new().offset_z(underside - depth),
+        )
+        .polygonize(stand.make_mut_ref(index), 0)?;
+
+        let mut holes: Vec<Box<dyn GeometryDyn>> = vec![Box::new(extrude(
+            &outline,
+            self.lip_height + sw,
+            Origin::new().offset_z(underside),
+        ))];
+
+        // Everything under the table plane goes away
+        let normal = Vector3::new(-tent, tilt, Dec::from(1)).normalize();
+        let axis = Vector3::z().cross(&normal);
+        let mut table =
+            Origin::new().offset(Vector3::new(max_x, min_y, underside - height(max_x, min_y)));
+        if !axis.magnitude().is_zero() {
+            table = table.rotate_axisangle(axis.normalize() * normal.z.acos());
+        }
+        let span = (max_x - min_x + max_y - min_y + depth) * 4;
+        holes.push(Box::new(Rect::with_top_at(table, span, span, span)));
+
+        for (point, bolt) in keyboard.bottom_bolt_pattern() {
+            let at = Origin::new().offset(point);
+            let pocket = height(point.x, point.y) - self.boss;
+            if pocket < bolt.head_height() + c {
+                return Err(anyhow!(
+                    "Stand is too low for bolt head at {:?}",
+                    (point.x, point.y)
+                ));
+            }
+            holes.push(Box::new(Cylinder::with_top_at(
+                at.clone().offset_z(sw),
+                depth + sw * 2,
+                bolt.diameter() / 2 + c,
+            )));
+            holes.push(Box::new(Cylinder::with_top_at(
+                at.offset_z(-self.boss),
+                depth,
+                bolt.head_diameter() / 2 + c,
+            )));
+        }
+
+        for hole in holes {
+            let hole_mesh = index.new_mesh();
+            hole.polygonize(hole_mesh.make_mut_ref(index), 0)?;
+            index.subtract_mesh(stand, hole_mesh)?;
+        }
+        Ok(stand)
+    }
+}